@@ -1,6 +1,8 @@
 mod parser;
 mod seek_sequence;
 mod standalone_executable;
+mod transaction;
+mod unified_diff;
 
 use std::collections::HashMap;
 use std::path::Path;
@@ -15,6 +17,8 @@ pub use parser::ParseError;
 use parser::ParseError::*;
 use parser::UpdateFileChunk;
 pub use parser::parse_patch;
+pub use seek_sequence::MatchFuzz;
+use serde::Serialize;
 use similar::TextDiff;
 use thiserror::Error;
 use tree_sitter::LanguageError;
@@ -25,6 +29,7 @@ use tree_sitter::StreamingIterator;
 use tree_sitter_bash::LANGUAGE as BASH;
 
 pub use standalone_executable::main;
+use transaction::FileTransaction;
 
 /// Detailed instructions for gpt-4.1 on how to use the `apply_patch` tool.
 pub const APPLY_PATCH_TOOL_INSTRUCTIONS: &str = include_str!("../apply_patch_tool_instructions.md");
@@ -127,6 +132,16 @@ pub fn maybe_parse_apply_patch(argv: &[String]) -> MaybeApplyPatch {
 pub enum ApplyPatchFileChange {
     Add {
         content: String,
+        /// Unix permission bits for the new file, if the patch sets them.
+        mode: Option<u32>,
+    },
+    AddBinary {
+        content: Vec<u8>,
+        mode: Option<u32>,
+    },
+    AddSymlink {
+        /// Where the link points, as written in the patch.
+        target: PathBuf,
     },
     Delete {
         content: String,
@@ -136,6 +151,8 @@ pub enum ApplyPatchFileChange {
         move_path: Option<PathBuf>,
         /// new_content that will result after the unified_diff is applied.
         new_content: String,
+        /// New Unix permission bits, if the patch changes them.
+        mode: Option<u32>,
     },
 }
 
@@ -198,7 +215,13 @@ impl ApplyPatchAction {
 + {content}
 *** End Patch"#,
         );
-        let changes = HashMap::from([(path.to_path_buf(), ApplyPatchFileChange::Add { content })]);
+        let changes = HashMap::from([(
+            path.to_path_buf(),
+            ApplyPatchFileChange::Add {
+                content,
+                mode: None,
+            },
+        )]);
         #[expect(clippy::expect_used)]
         Self {
             changes,
@@ -255,11 +278,36 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
             for hunk in hunks {
                 let path = hunk.resolve_path(&effective_cwd);
                 match hunk {
-                    Hunk::AddFile { contents, .. } => {
-                        changes.insert(path, ApplyPatchFileChange::Add { content: contents });
+                    Hunk::AddFile { contents, mode, .. } => {
+                        changes.insert(
+                            path,
+                            ApplyPatchFileChange::Add {
+                                content: contents,
+                                mode,
+                            },
+                        );
+                    }
+                    Hunk::AddBinaryFile { contents, mode, .. } => {
+                        changes.insert(
+                            path,
+                            ApplyPatchFileChange::AddBinary {
+                                content: contents,
+                                mode,
+                            },
+                        );
+                    }
+                    Hunk::AddSymlink { target, .. } => {
+                        changes.insert(path, ApplyPatchFileChange::AddSymlink { target });
                     }
                     Hunk::DeleteFile { .. } => {
-                        let content = match std::fs::read_to_string(&path) {
+                        // Binary files and symlinks have no text to show for
+                        // the deletion.
+                        let content = match std::fs::symlink_metadata(&path) {
+                            Ok(meta) if meta.file_type().is_symlink() => Ok(String::new()),
+                            _ => std::fs::read(&path)
+                                .map(|bytes| String::from_utf8(bytes).unwrap_or_default()),
+                        };
+                        let content = match content {
                             Ok(content) => content,
                             Err(e) => {
                                 return MaybeApplyPatchVerified::CorrectnessError(
@@ -273,12 +321,34 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                         changes.insert(path, ApplyPatchFileChange::Delete { content });
                     }
                     Hunk::UpdateFile {
-                        move_path, chunks, ..
+                        move_path,
+                        mode,
+                        chunks,
+                        ..
                     } => {
+                        let update = if chunks.is_empty() {
+                            // Only the mode changes; the file may not even be text.
+                            std::fs::read(&path)
+                                .map(|bytes| ApplyPatchFileUpdate {
+                                    unified_diff: String::new(),
+                                    content: String::from_utf8(bytes).unwrap_or_default(),
+                                })
+                                .map_err(|e| {
+                                    ApplyPatchError::IoError(IoError {
+                                        context: format!(
+                                            "Failed to read file to update {}",
+                                            path.display()
+                                        ),
+                                        source: e,
+                                    })
+                                })
+                        } else {
+                            unified_diff_from_chunks(&path, &chunks)
+                        };
                         let ApplyPatchFileUpdate {
                             unified_diff,
                             content: contents,
-                        } = match unified_diff_from_chunks(&path, &chunks) {
+                        } = match update {
                             Ok(diff) => diff,
                             Err(e) => {
                                 return MaybeApplyPatchVerified::CorrectnessError(e);
@@ -290,6 +360,7 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                                 unified_diff,
                                 move_path: move_path.map(|p| cwd.join(p)),
                                 new_content: contents,
+                                mode,
                             },
                         );
                     }
@@ -510,7 +581,7 @@ pub fn apply_hunks(
     let _existing_paths: Vec<&Path> = hunks
         .iter()
         .filter_map(|hunk| match hunk {
-            Hunk::AddFile { .. } => {
+            Hunk::AddFile { .. } | Hunk::AddBinaryFile { .. } | Hunk::AddSymlink { .. } => {
                 // The file is being added, so it doesn't exist yet.
                 None
             }
@@ -557,6 +628,7 @@ pub fn apply_hunks(
 /// Applies each parsed patch hunk to the filesystem.
 /// Returns an error if any of the changes could not be applied.
 /// Tracks file paths affected by applying a patch.
+#[derive(Debug, Clone)]
 pub struct AffectedPaths {
     pub added: Vec<PathBuf>,
     pub modified: Vec<PathBuf>,
@@ -565,71 +637,181 @@ pub struct AffectedPaths {
 
 /// Apply the hunks to the filesystem, returning which files were added, modified, or deleted.
 /// Returns an error if the patch could not be applied.
+///
+/// The patch is applied atomically: the new contents of every file are
+/// computed before anything is written, and if writing any file fails, the
+/// files already written are restored.
 fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
+    stage_hunks(hunks)?.commit()
+}
+
+/// A patch whose hunks were all matched against the filesystem and whose
+/// changes are computed, but not written yet.
+pub struct StagedPatch {
+    transaction: FileTransaction,
+    affected: AffectedPaths,
+    files: Vec<FileCheck>,
+}
+
+/// How one hunk of a patch matched, as reported by `apply_patch --check`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileCheck {
+    pub action: FileAction,
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_path: Option<PathBuf>,
+    /// Where each chunk of an `Update File` hunk matched, in patch order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<ChunkMatch>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileAction {
+    Add,
+    Delete,
+    Update,
+}
+
+/// Where a chunk of an `Update File` hunk was found in the original file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ChunkMatch {
+    /// 1-based line where the chunk's old lines start, or where its new
+    /// lines are inserted.
+    pub line: usize,
+    /// The most lenient comparison needed to locate the chunk, including its
+    /// `@@` context line.
+    pub fuzz: MatchFuzz,
+}
+
+impl StagedPatch {
+    pub fn affected(&self) -> &AffectedPaths {
+        &self.affected
+    }
+
+    pub fn files(&self) -> &[FileCheck] {
+        &self.files
+    }
+
+    /// A git-style unified diff from what is on disk to the staged result.
+    pub fn unified_diff(&self) -> String {
+        self.transaction.unified_diff()
+    }
+
+    /// Write all changes to disk, or none of them.
+    pub fn commit(self) -> anyhow::Result<AffectedPaths> {
+        self.transaction.commit()?;
+        Ok(self.affected)
+    }
+}
+
+/// Match every hunk against the filesystem and compute the resulting file
+/// contents without writing anything. Returns an error if any hunk does not
+/// apply.
+pub fn stage_hunks(hunks: &[Hunk]) -> anyhow::Result<StagedPatch> {
     if hunks.is_empty() {
         anyhow::bail!("No files were modified.");
     }
 
+    let mut transaction = FileTransaction::default();
     let mut added: Vec<PathBuf> = Vec::new();
     let mut modified: Vec<PathBuf> = Vec::new();
     let mut deleted: Vec<PathBuf> = Vec::new();
+    let mut files: Vec<FileCheck> = Vec::new();
+    let check = |action: FileAction, path: &Path| FileCheck {
+        action,
+        path: path.to_path_buf(),
+        move_path: None,
+        chunks: Vec::new(),
+    };
     for hunk in hunks {
         match hunk {
-            Hunk::AddFile { path, contents } => {
-                if let Some(parent) = path.parent()
-                    && !parent.as_os_str().is_empty()
-                {
-                    std::fs::create_dir_all(parent).with_context(|| {
-                        format!("Failed to create parent directories for {}", path.display())
-                    })?;
-                }
-                std::fs::write(path, contents)
-                    .with_context(|| format!("Failed to write file {}", path.display()))?;
+            Hunk::AddFile {
+                path,
+                contents,
+                mode,
+            } => {
+                transaction.write(path, contents.as_str(), *mode);
+                added.push(path.clone());
+                files.push(check(FileAction::Add, path));
+            }
+            Hunk::AddBinaryFile {
+                path,
+                contents,
+                mode,
+            } => {
+                transaction.write(path, contents.as_slice(), *mode);
                 added.push(path.clone());
+                files.push(check(FileAction::Add, path));
+            }
+            Hunk::AddSymlink { path, target } => {
+                transaction.symlink(path, target);
+                added.push(path.clone());
+                files.push(check(FileAction::Add, path));
             }
             Hunk::DeleteFile { path } => {
-                std::fs::remove_file(path)
-                    .with_context(|| format!("Failed to delete file {}", path.display()))?;
+                if !transaction.is_file(path) {
+                    anyhow::bail!("Failed to delete file {}: no such file", path.display());
+                }
+                transaction.delete(path);
                 deleted.push(path.clone());
+                files.push(check(FileAction::Delete, path));
             }
             Hunk::UpdateFile {
                 path,
                 move_path,
+                mode,
                 chunks,
             } => {
-                let AppliedPatch { new_contents, .. } =
-                    derive_new_contents_from_chunks(path, chunks)?;
+                let read_error = |err| {
+                    ApplyPatchError::IoError(IoError {
+                        context: format!("Failed to read file to update {}", path.display()),
+                        source: err,
+                    })
+                };
+                // Without chunks only the mode changes, so the contents are
+                // kept byte for byte (and need not be text).
+                let (new_contents, chunk_matches) = if chunks.is_empty() {
+                    (transaction.read(path).map_err(read_error)?, Vec::new())
+                } else {
+                    let original_contents = transaction.read_to_string(path).map_err(read_error)?;
+                    let AppliedPatch {
+                        new_contents,
+                        chunk_matches,
+                        ..
+                    } = derive_new_contents_from_text(original_contents, path, chunks)?;
+                    (new_contents.into_bytes(), chunk_matches)
+                };
+                files.push(FileCheck {
+                    move_path: move_path.clone(),
+                    chunks: chunk_matches,
+                    ..check(FileAction::Update, path)
+                });
                 if let Some(dest) = move_path {
-                    if let Some(parent) = dest.parent()
-                        && !parent.as_os_str().is_empty()
-                    {
-                        std::fs::create_dir_all(parent).with_context(|| {
-                            format!("Failed to create parent directories for {}", dest.display())
-                        })?;
-                    }
-                    std::fs::write(dest, new_contents)
-                        .with_context(|| format!("Failed to write file {}", dest.display()))?;
-                    std::fs::remove_file(path)
-                        .with_context(|| format!("Failed to remove original {}", path.display()))?;
+                    transaction.move_file(path, dest, new_contents, *mode);
                     modified.push(dest.clone());
                 } else {
-                    std::fs::write(path, new_contents)
-                        .with_context(|| format!("Failed to write file {}", path.display()))?;
+                    transaction.write(path, new_contents, *mode);
                     modified.push(path.clone());
                 }
             }
         }
     }
-    Ok(AffectedPaths {
-        added,
-        modified,
-        deleted,
+    Ok(StagedPatch {
+        transaction,
+        affected: AffectedPaths {
+            added,
+            modified,
+            deleted,
+        },
+        files,
     })
 }
 
 struct AppliedPatch {
     original_contents: String,
     new_contents: String,
+    chunk_matches: Vec<ChunkMatch>,
 }
 
 /// Return *only* the new file contents (joined into a single `String`) after
@@ -647,7 +829,16 @@ fn derive_new_contents_from_chunks(
             }));
         }
     };
+    derive_new_contents_from_text(original_contents, path, chunks)
+}
 
+/// Like `derive_new_contents_from_chunks`, but for contents that were
+/// already read (or staged by an earlier hunk of the same patch).
+fn derive_new_contents_from_text(
+    original_contents: String,
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+) -> std::result::Result<AppliedPatch, ApplyPatchError> {
     let mut original_lines: Vec<String> = original_contents
         .split('\n')
         .map(|s| s.to_string())
@@ -659,44 +850,61 @@ fn derive_new_contents_from_chunks(
         original_lines.pop();
     }
 
-    let replacements = compute_replacements(&original_lines, path, chunks)?;
+    // A file without a final newline keeps it that way as long as its last
+    // line survives the patch unchanged.
+    let unterminated_last_line = if original_contents.ends_with('\n') {
+        None
+    } else {
+        original_lines.last().cloned()
+    };
+
+    let (replacements, chunk_matches) = compute_replacements(&original_lines, path, chunks)?;
     let new_lines = apply_replacements(original_lines, &replacements);
     let mut new_lines = new_lines;
-    if !new_lines.last().is_some_and(|s| s.is_empty()) {
+    let keeps_unterminated_last_line =
+        unterminated_last_line.is_some() && new_lines.last() == unterminated_last_line.as_ref();
+    if !keeps_unterminated_last_line && !new_lines.last().is_some_and(|s| s.is_empty()) {
         new_lines.push(String::new());
     }
     let new_contents = new_lines.join("\n");
     Ok(AppliedPatch {
         original_contents,
         new_contents,
+        chunk_matches,
     })
 }
 
+/// A `(start_index, old_len, new_lines)` edit to the lines of a file.
+type Replacement = (usize, usize, Vec<String>);
+
 /// Compute a list of replacements needed to transform `original_lines` into the
-/// new lines, given the patch `chunks`. Each replacement is returned as
-/// `(start_index, old_len, new_lines)`.
+/// new lines, given the patch `chunks`, along with where each chunk matched.
 fn compute_replacements(
     original_lines: &[String],
     path: &Path,
     chunks: &[UpdateFileChunk],
-) -> std::result::Result<Vec<(usize, usize, Vec<String>)>, ApplyPatchError> {
-    let mut replacements: Vec<(usize, usize, Vec<String>)> = Vec::new();
+) -> std::result::Result<(Vec<Replacement>, Vec<ChunkMatch>), ApplyPatchError> {
+    let mut replacements: Vec<Replacement> = Vec::new();
+    let mut chunk_matches: Vec<ChunkMatch> = Vec::with_capacity(chunks.len());
     let mut line_index: usize = 0;
 
-    for chunk in chunks {
+    for (chunk_index, chunk) in chunks.iter().enumerate() {
+        let hunk_label = format!("hunk {} of {}", chunk_index + 1, chunks.len());
+        let mut fuzz = MatchFuzz::Exact;
         // If a chunk has a `change_context`, we use seek_sequence to find it, then
         // adjust our `line_index` to continue from there.
         if let Some(ctx_line) = &chunk.change_context {
-            if let Some(idx) = seek_sequence::seek_sequence(
+            if let Some((idx, context_fuzz)) = seek_sequence::seek_sequence(
                 original_lines,
                 std::slice::from_ref(ctx_line),
                 line_index,
                 false,
             ) {
                 line_index = idx + 1;
+                fuzz = context_fuzz;
             } else {
                 return Err(ApplyPatchError::ComputeReplacements(format!(
-                    "Failed to find context '{}' in {}",
+                    "Failed to find context '{}' for {hunk_label} in {}",
                     ctx_line,
                     path.display()
                 )));
@@ -712,6 +920,10 @@ fn compute_replacements(
                 original_lines.len()
             };
             replacements.push((insertion_idx, 0, chunk.new_lines.clone()));
+            chunk_matches.push(ChunkMatch {
+                line: insertion_idx + 1,
+                fuzz,
+            });
             continue;
         }
 
@@ -748,12 +960,21 @@ fn compute_replacements(
             );
         }
 
-        if let Some(start_idx) = found {
+        if let Some((start_idx, lines_fuzz)) = found {
             replacements.push((start_idx, pattern.len(), new_slice.to_vec()));
+            chunk_matches.push(ChunkMatch {
+                line: start_idx + 1,
+                fuzz: fuzz.max(lines_fuzz),
+            });
             line_index = start_idx + pattern.len();
         } else {
+            let after_context = chunk
+                .change_context
+                .as_ref()
+                .map(|ctx| format!(" after '@@ {ctx}'"))
+                .unwrap_or_default();
             return Err(ApplyPatchError::ComputeReplacements(format!(
-                "Failed to find expected lines in {}:\n{}",
+                "Failed to find expected lines for {hunk_label}{after_context} in {}:\n{}",
                 path.display(),
                 chunk.old_lines.join("\n"),
             )));
@@ -762,15 +983,12 @@ fn compute_replacements(
 
     replacements.sort_by(|(lhs_idx, _, _), (rhs_idx, _, _)| lhs_idx.cmp(rhs_idx));
 
-    Ok(replacements)
+    Ok((replacements, chunk_matches))
 }
 
-/// Apply the `(start_index, old_len, new_lines)` replacements to `original_lines`,
-/// returning the modified file contents as a vector of lines.
-fn apply_replacements(
-    mut lines: Vec<String>,
-    replacements: &[(usize, usize, Vec<String>)],
-) -> Vec<String> {
+/// Apply the replacements to `original_lines`, returning the modified file
+/// contents as a vector of lines.
+fn apply_replacements(mut lines: Vec<String>, replacements: &[Replacement]) -> Vec<String> {
     // We must apply replacements in descending order so that earlier replacements
     // don't shift the positions of later ones.
     for (start_idx, old_len, new_segment) in replacements.iter().rev() {
@@ -815,6 +1033,7 @@ pub fn unified_diff_from_chunks_with_context(
     let AppliedPatch {
         original_contents,
         new_contents,
+        ..
     } = derive_new_contents_from_chunks(path, chunks)?;
     let text_diff = TextDiff::from_lines(&original_contents, &new_contents);
     let unified_diff = text_diff.unified_diff().context_radius(context).to_string();
@@ -831,6 +1050,14 @@ pub fn print_summary(
     out: &mut impl std::io::Write,
 ) -> std::io::Result<()> {
     writeln!(out, "Success. Updated the following files:")?;
+    write_affected_paths(affected, out)
+}
+
+/// Write one `A`/`M`/`D` line per affected path.
+pub(crate) fn write_affected_paths(
+    affected: &AffectedPaths,
+    out: &mut impl std::io::Write,
+) -> std::io::Result<()> {
     for path in &affected.added {
         writeln!(out, "A {}", path.display())?;
     }
@@ -880,6 +1107,7 @@ mod tests {
         vec![Hunk::AddFile {
             path: PathBuf::from("foo"),
             contents: "hi\n".to_string(),
+            mode: None,
         }]
     }
 
@@ -941,7 +1169,8 @@ mod tests {
                     hunks,
                     vec![Hunk::AddFile {
                         path: PathBuf::from("foo"),
-                        contents: "hi\n".to_string()
+                        contents: "hi\n".to_string(),
+                        mode: None,
                     }]
                 );
             }
@@ -966,7 +1195,8 @@ mod tests {
                     hunks,
                     vec![Hunk::AddFile {
                         path: PathBuf::from("foo"),
-                        contents: "hi\n".to_string()
+                        contents: "hi\n".to_string(),
+                        mode: None,
                     }]
                 );
             }
@@ -999,7 +1229,8 @@ PATCH"#,
                     hunks,
                     vec![Hunk::AddFile {
                         path: PathBuf::from("foo"),
-                        contents: "hi\n".to_string()
+                        contents: "hi\n".to_string(),
+                        mode: None,
                     }]
                 );
             }
@@ -1145,6 +1376,145 @@ PATCH"#,
         assert_eq!(contents, "foo\nbaz\n");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_modes_symlinks_and_binary_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        let existing = dir.path().join("tool.py");
+        let blob = dir.path().join("blob.bin");
+        let link = dir.path().join("latest");
+        fs::write(&existing, "print('hi')\n").unwrap();
+        let patch = wrap_patch(&format!(
+            r#"*** Add File: {}
+*** Set Mode: 755
++#!/bin/sh
+*** Update File: {}
+*** Set Mode: 0700
+*** Add Binary File: {}
++AAEC/w==
+*** Add Symlink: {} -> run.sh"#,
+            script.display(),
+            existing.display(),
+            blob.display(),
+            link.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode(&script), 0o755);
+        assert_eq!(mode(&existing), 0o700);
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "print('hi')\n");
+        assert_eq!(fs::read(&blob).unwrap(), vec![0, 1, 2, 255]);
+        assert_eq!(fs::read_link(&link).unwrap(), PathBuf::from("run.sh"));
+        assert_eq!(fs::read_to_string(&link).unwrap(), "#!/bin/sh\n");
+    }
+
+    #[test]
+    fn test_failed_hunk_leaves_all_files_unchanged() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first.txt");
+        let second = dir.path().join("second.txt");
+        let added = dir.path().join("added.txt");
+        fs::write(&first, "one\n").unwrap();
+        fs::write(&second, "alpha\nbeta\n").unwrap();
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+-one
++ONE
+*** Add File: {}
++new
+*** Update File: {}
+@@
+ alpha
+-beta
++BETA
+@@ fn missing
+-gamma
++GAMMA"#,
+            first.display(),
+            added.display(),
+            second.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let result = apply_patch(&patch, &mut stdout, &mut stderr);
+        assert!(result.is_err());
+        let stderr_str = String::from_utf8(stderr).unwrap();
+        assert_eq!(
+            stderr_str,
+            format!(
+                "Failed to find context 'fn missing' for hunk 2 of 2 in {}\n",
+                second.display()
+            )
+        );
+        assert_eq!(fs::read_to_string(&first).unwrap(), "one\n");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "alpha\nbeta\n");
+        assert!(!added.exists());
+    }
+
+    #[test]
+    fn test_update_of_file_added_in_same_patch() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("fresh.txt");
+        let patch = wrap_patch(&format!(
+            r#"*** Add File: {}
++draft
+*** Update File: {}
+@@
+-draft
++final"#,
+            path.display(),
+            path.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "final\n");
+    }
+
+    #[test]
+    fn test_git_diff_updates_and_renames_files() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src.txt");
+        let dst = dir.path().join("dst.txt");
+        fs::write(&src, "one\ntwo\nthree\n").unwrap();
+        let patch = format!(
+            r#"diff --git a/{src} b/{dst}
+similarity index 80%
+rename from {src}
+rename to {dst}
+--- a/{src}
++++ b/{dst}
+@@ -1,3 +1,3 @@
+ one
+-two
++TWO
+ three
+"#,
+            src = src.display(),
+            dst = dst.display()
+        );
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        let stdout_str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            stdout_str,
+            format!(
+                "Success. Updated the following files:\nM {}\n",
+                dst.display()
+            )
+        );
+        assert!(!src.exists());
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "one\nTWO\nthree\n");
+    }
+
     #[test]
     fn test_update_file_hunk_can_move_file() {
         let dir = tempdir().unwrap();
@@ -1267,6 +1637,30 @@ PATCH"#,
         assert_eq!(contents, "a\nB\nc\nd\nE\nf\ng\n");
     }
 
+    #[test]
+    fn test_update_keeps_missing_final_newline() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("no_newline.txt");
+        fs::write(&path, "a\nb\nc").unwrap();
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+ a
+-b
++B
+ c
+*** End of File"#,
+            path.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "a\nB\nc");
+    }
+
     #[test]
     fn test_pure_addition_chunk_followed_by_removal() {
         let dir = tempdir().unwrap();
@@ -1596,6 +1990,7 @@ g
                         .to_string(),
                         move_path: None,
                         new_content: "updated session directory content\n".to_string(),
+                        mode: None,
                     },
                 )]),
                 patch: argv[1].clone(),
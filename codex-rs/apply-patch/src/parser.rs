@@ -7,30 +7,45 @@
 //! begin_patch: "*** Begin Patch" LF
 //! end_patch: "*** End Patch" LF?
 //!
-//! hunk: add_hunk | delete_hunk | update_hunk
-//! add_hunk: "*** Add File: " filename LF add_line+
+//! hunk: add_hunk | add_binary_hunk | add_symlink_hunk | delete_hunk | update_hunk
+//! add_hunk: "*** Add File: " filename LF set_mode? add_line+
+//! add_binary_hunk: "*** Add Binary File: " filename LF set_mode? add_line+
+//! add_symlink_hunk: "*** Add Symlink: " /(.+) -> (.+)/ LF
 //! delete_hunk: "*** Delete File: " filename LF
-//! update_hunk: "*** Update File: " filename LF change_move? change?
+//! update_hunk: "*** Update File: " filename LF change_move? set_mode? change?
 //! filename: /(.+)/
 //! add_line: "+" /(.+)/ LF -> line
 //!
+//! set_mode: "*** Set Mode: " /[0-7]{3,6}/ LF
 //! change_move: "*** Move to: " filename LF
 //! change: (change_context | change_line)+ eof_line?
 //! change_context: ("@@" | "@@ " /(.+)/) LF
 //! change_line: ("+" | "-" | " ") /(.+)/ LF
 //! eof_line: "*** End of File" LF
 //!
+//! The add lines of an `add_binary_hunk` hold the base64-encoded file contents.
+//! An `update_hunk` without any `change` is only valid if it sets the mode.
+//!
 //! The parser below is a little more lenient than the explicit spec and allows for
 //! leading/trailing whitespace around patch markers.
+//!
+//! Standard unified diffs and git diffs are accepted as well; see the
+//! `unified_diff` module.
 use crate::ApplyPatchArgs;
+use crate::unified_diff;
 use std::path::Path;
 use std::path::PathBuf;
 
+use base64::Engine;
 use thiserror::Error;
 
 const BEGIN_PATCH_MARKER: &str = "*** Begin Patch";
 const END_PATCH_MARKER: &str = "*** End Patch";
 const ADD_FILE_MARKER: &str = "*** Add File: ";
+const ADD_BINARY_FILE_MARKER: &str = "*** Add Binary File: ";
+const ADD_SYMLINK_MARKER: &str = "*** Add Symlink: ";
+const SYMLINK_TARGET_SEPARATOR: &str = " -> ";
+const SET_MODE_MARKER: &str = "*** Set Mode: ";
 const DELETE_FILE_MARKER: &str = "*** Delete File: ";
 const UPDATE_FILE_MARKER: &str = "*** Update File: ";
 const MOVE_TO_MARKER: &str = "*** Move to: ";
@@ -38,6 +53,11 @@ const EOF_MARKER: &str = "*** End of File";
 const CHANGE_CONTEXT_MARKER: &str = "@@ ";
 const EMPTY_CHANGE_CONTEXT_MARKER: &str = "@@";
 
+/// Largest decoded size accepted for an `*** Add Binary File:` hunk. Binary
+/// contents are inlined in the patch as base64, so anything bigger is better
+/// produced by running a command.
+pub const MAX_BINARY_FILE_BYTES: usize = 1024 * 1024;
+
 /// Currently, the only OpenAI model that knowingly requires lenient parsing is
 /// gpt-4.1. While we could try to require everyone to pass in a strictness
 /// param when invoking apply_patch, it is a pain to thread it through all of
@@ -61,6 +81,19 @@ pub enum Hunk {
     AddFile {
         path: PathBuf,
         contents: String,
+        /// Unix permission bits for the new file, e.g. `0o755`.
+        mode: Option<u32>,
+    },
+    AddBinaryFile {
+        path: PathBuf,
+        contents: Vec<u8>,
+        mode: Option<u32>,
+    },
+    AddSymlink {
+        path: PathBuf,
+        /// Where the link points, exactly as written in the patch (relative
+        /// targets are relative to the directory holding the link).
+        target: PathBuf,
     },
     DeleteFile {
         path: PathBuf,
@@ -68,6 +101,8 @@ pub enum Hunk {
     UpdateFile {
         path: PathBuf,
         move_path: Option<PathBuf>,
+        /// New Unix permission bits, if the patch changes them.
+        mode: Option<u32>,
 
         /// Chunks should be in order, i.e. the `change_context` of one chunk
         /// should occur later in the file than the previous chunk.
@@ -79,6 +114,8 @@ impl Hunk {
     pub fn resolve_path(&self, cwd: &Path) -> PathBuf {
         match self {
             Hunk::AddFile { path, .. } => cwd.join(path),
+            Hunk::AddBinaryFile { path, .. } => cwd.join(path),
+            Hunk::AddSymlink { path, .. } => cwd.join(path),
             Hunk::DeleteFile { path } => cwd.join(path),
             Hunk::UpdateFile { path, .. } => cwd.join(path),
         }
@@ -153,6 +190,13 @@ enum ParseMode {
 
 fn parse_patch_text(patch: &str, mode: ParseMode) -> Result<ApplyPatchArgs, ParseError> {
     let lines: Vec<&str> = patch.trim().lines().collect();
+    if let Some(diff_lines) = unified_diff_lines(&lines, &mode) {
+        return Ok(ApplyPatchArgs {
+            hunks: unified_diff::parse_unified_diff(diff_lines)?,
+            patch: diff_lines.join("\n"),
+            workdir: None,
+        });
+    }
     let lines: &[&str] = match check_patch_boundaries_strict(&lines) {
         Ok(()) => &lines,
         Err(e) => match mode {
@@ -182,6 +226,25 @@ fn parse_patch_text(patch: &str, mode: ParseMode) -> Result<ApplyPatchArgs, Pars
     })
 }
 
+/// Returns the lines of a standard unified or git diff, if `lines` holds one
+/// instead of a `*** Begin Patch` patch. In lenient mode the diff may be
+/// wrapped in heredoc markers, as described in [`ParseMode::Lenient`].
+fn unified_diff_lines<'a>(lines: &'a [&'a str], mode: &ParseMode) -> Option<&'a [&'a str]> {
+    if unified_diff::is_unified_diff(lines) {
+        return Some(lines);
+    }
+    match (mode, lines) {
+        (ParseMode::Lenient, [first, inner @ .., last])
+            if (first == &"<<EOF" || first == &"<<'EOF'" || first == &"<<\"EOF\"")
+                && last.ends_with("EOF")
+                && unified_diff::is_unified_diff(inner) =>
+        {
+            Some(inner)
+        }
+        _ => None,
+    }
+}
+
 /// Checks the start and end lines of the patch text for `apply_patch`,
 /// returning an error if they do not match the expected markers.
 fn check_patch_boundaries_strict(lines: &[&str]) -> Result<(), ParseError> {
@@ -247,9 +310,9 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
     let first_line = lines[0].trim();
     if let Some(path) = first_line.strip_prefix(ADD_FILE_MARKER) {
         // Add File
+        let (mode, mut parsed_lines) = parse_set_mode(&lines[1..], line_number + 1)?;
         let mut contents = String::new();
-        let mut parsed_lines = 1;
-        for add_line in &lines[1..] {
+        for add_line in &lines[parsed_lines..] {
             if let Some(line_to_add) = add_line.strip_prefix('+') {
                 contents.push_str(line_to_add);
                 contents.push('\n');
@@ -262,9 +325,62 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             AddFile {
                 path: PathBuf::from(path),
                 contents,
+                mode,
             },
             parsed_lines,
         ));
+    } else if let Some(path) = first_line.strip_prefix(ADD_BINARY_FILE_MARKER) {
+        // Add Binary File
+        let (mode, mut parsed_lines) = parse_set_mode(&lines[1..], line_number + 1)?;
+        let mut encoded = String::new();
+        for add_line in &lines[parsed_lines..] {
+            if let Some(line_to_add) = add_line.strip_prefix('+') {
+                encoded.push_str(line_to_add.trim());
+                parsed_lines += 1;
+            } else {
+                break;
+            }
+        }
+        let contents = base64::engine::general_purpose::STANDARD
+            .decode(&encoded)
+            .map_err(|err| InvalidHunkError {
+                message: format!("Invalid base64 contents for binary file '{path}': {err}"),
+                line_number,
+            })?;
+        if contents.len() > MAX_BINARY_FILE_BYTES {
+            return Err(InvalidHunkError {
+                message: format!(
+                    "Binary file '{path}' is {} bytes, more than the {MAX_BINARY_FILE_BYTES} bytes allowed in a patch",
+                    contents.len()
+                ),
+                line_number,
+            });
+        }
+        return Ok((
+            AddBinaryFile {
+                path: PathBuf::from(path),
+                contents,
+                mode,
+            },
+            parsed_lines,
+        ));
+    } else if let Some(link) = first_line.strip_prefix(ADD_SYMLINK_MARKER) {
+        // Add Symlink
+        let Some((path, target)) = link.split_once(SYMLINK_TARGET_SEPARATOR) else {
+            return Err(InvalidHunkError {
+                message: format!(
+                    "Expected '*** Add Symlink: {{path}} -> {{target}}', got: '{first_line}'"
+                ),
+                line_number,
+            });
+        };
+        return Ok((
+            AddSymlink {
+                path: PathBuf::from(path.trim()),
+                target: PathBuf::from(target.trim()),
+            },
+            1,
+        ));
     } else if let Some(path) = first_line.strip_prefix(DELETE_FILE_MARKER) {
         // Delete File
         return Ok((
@@ -288,6 +404,10 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             parsed_lines += 1;
         }
 
+        let (mode, mode_lines) = parse_set_mode(remaining_lines, line_number + parsed_lines)?;
+        remaining_lines = &remaining_lines[mode_lines..];
+        parsed_lines += mode_lines;
+
         let mut chunks = Vec::new();
         // NOTE: we need to know to stop once we reach the next special marker header.
         while !remaining_lines.is_empty() {
@@ -312,7 +432,7 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             remaining_lines = &remaining_lines[chunk_lines..]
         }
 
-        if chunks.is_empty() {
+        if chunks.is_empty() && mode.is_none() {
             return Err(InvalidHunkError {
                 message: format!("Update file hunk for path '{path}' is empty"),
                 line_number,
@@ -323,6 +443,7 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             UpdateFile {
                 path: PathBuf::from(path),
                 move_path: move_path.map(PathBuf::from),
+                mode,
                 chunks,
             },
             parsed_lines,
@@ -331,12 +452,42 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
 
     Err(InvalidHunkError {
         message: format!(
-            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Delete File: {{path}}', '*** Update File: {{path}}'"
+            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Add Binary File: {{path}}', '*** Add Symlink: {{path}} -> {{target}}', '*** Delete File: {{path}}', '*** Update File: {{path}}'"
         ),
         line_number,
     })
 }
 
+/// Parses an optional `*** Set Mode: <octal>` line at the start of `lines`.
+/// Returns the mode and the number of lines consumed (0 or 1).
+fn parse_set_mode(lines: &[&str], line_number: usize) -> Result<(Option<u32>, usize), ParseError> {
+    let Some(mode) = lines
+        .first()
+        .and_then(|line| line.trim().strip_prefix(SET_MODE_MARKER))
+    else {
+        return Ok((None, 0));
+    };
+    let mode = mode.trim();
+    match parse_octal_mode(mode) {
+        Some(mode) => Ok((Some(mode), 1)),
+        None => Err(InvalidHunkError {
+            message: format!(
+                "Invalid file mode '{mode}', expected octal permissions such as 644 or 755"
+            ),
+            line_number,
+        }),
+    }
+}
+
+/// Parses octal permission bits as written by `chmod` (`755`, `0755`) or git
+/// (`100755`), keeping only the permission bits.
+fn parse_octal_mode(mode: &str) -> Option<u32> {
+    if !(3..=6).contains(&mode.len()) {
+        return None;
+    }
+    u32::from_str_radix(mode, 8).ok().map(|mode| mode & 0o7777)
+}
+
 fn parse_update_file_chunk(
     lines: &[&str],
     line_number: usize,
@@ -486,7 +637,8 @@ fn test_parse_patch() {
         vec![
             AddFile {
                 path: PathBuf::from("path/add.py"),
-                contents: "abc\ndef\n".to_string()
+                contents: "abc\ndef\n".to_string(),
+                mode: None,
             },
             DeleteFile {
                 path: PathBuf::from("path/delete.py")
@@ -494,6 +646,7 @@ fn test_parse_patch() {
             UpdateFile {
                 path: PathBuf::from("path/update.py"),
                 move_path: Some(PathBuf::from("path/update2.py")),
+                mode: None,
                 chunks: vec![UpdateFileChunk {
                     change_context: Some("def f():".to_string()),
                     old_lines: vec!["    pass".to_string()],
@@ -521,6 +674,7 @@ fn test_parse_patch() {
             UpdateFile {
                 path: PathBuf::from("file.py"),
                 move_path: None,
+                mode: None,
                 chunks: vec![UpdateFileChunk {
                     change_context: None,
                     old_lines: vec![],
@@ -530,7 +684,8 @@ fn test_parse_patch() {
             },
             AddFile {
                 path: PathBuf::from("other.py"),
-                contents: "content\n".to_string()
+                contents: "content\n".to_string(),
+                mode: None,
             }
         ]
     );
@@ -551,6 +706,7 @@ fn test_parse_patch() {
         vec![UpdateFile {
             path: PathBuf::from("file2.py"),
             move_path: None,
+            mode: None,
             chunks: vec![UpdateFileChunk {
                 change_context: None,
                 old_lines: vec!["import foo".to_string()],
@@ -571,6 +727,7 @@ fn test_parse_patch_lenient() {
     let expected_patch = vec![UpdateFile {
         path: PathBuf::from("file2.py"),
         move_path: None,
+        mode: None,
         chunks: vec![UpdateFileChunk {
             change_context: None,
             old_lines: vec!["import foo".to_string()],
@@ -653,7 +810,8 @@ fn test_parse_one_hunk() {
         parse_one_hunk(&["bad"], 234),
         Err(InvalidHunkError {
             message: "'bad' is not a valid hunk header. \
-            Valid hunk headers: '*** Add File: {path}', '*** Delete File: {path}', '*** Update File: {path}'".to_string(),
+            Valid hunk headers: '*** Add File: {path}', '*** Add Binary File: {path}', \
+            '*** Add Symlink: {path} -> {target}', '*** Delete File: {path}', '*** Update File: {path}'".to_string(),
             line_number: 234
         })
     );
@@ -739,3 +897,60 @@ fn test_update_file_chunk() {
         ))
     );
 }
+
+#[test]
+fn test_parse_modes_symlinks_and_binary_files() {
+    assert_eq!(
+        parse_patch_text(
+            "*** Begin Patch\n\
+             *** Add File: bin/run.sh\n\
+             *** Set Mode: 755\n\
+             +#!/bin/sh\n\
+             *** Add Binary File: assets/blob.bin\n\
+             +AAEC/w==\n\
+             *** Add Symlink: latest -> releases/v2\n\
+             *** Update File: tool.py\n\
+             *** Set Mode: 100755\n\
+             *** End Patch",
+            ParseMode::Strict
+        )
+        .unwrap()
+        .hunks,
+        vec![
+            AddFile {
+                path: PathBuf::from("bin/run.sh"),
+                contents: "#!/bin/sh\n".to_string(),
+                mode: Some(0o755),
+            },
+            AddBinaryFile {
+                path: PathBuf::from("assets/blob.bin"),
+                contents: vec![0, 1, 2, 255],
+                mode: None,
+            },
+            AddSymlink {
+                path: PathBuf::from("latest"),
+                target: PathBuf::from("releases/v2"),
+            },
+            UpdateFile {
+                path: PathBuf::from("tool.py"),
+                move_path: None,
+                mode: Some(0o755),
+                chunks: Vec::new(),
+            },
+        ]
+    );
+    assert_eq!(
+        parse_patch_text(
+            "*** Begin Patch\n\
+             *** Update File: tool.py\n\
+             *** Set Mode: rwx\n\
+             *** End Patch",
+            ParseMode::Strict
+        ),
+        Err(InvalidHunkError {
+            message: "Invalid file mode 'rwx', expected octal permissions such as 644 or 755"
+                .to_string(),
+            line_number: 3,
+        })
+    );
+}
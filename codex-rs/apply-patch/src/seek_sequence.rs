@@ -1,9 +1,37 @@
+use serde::Serialize;
+
+/// How leniently lines had to be compared for [`seek_sequence`] to find a
+/// match, from strictest to most lenient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchFuzz {
+    Exact,
+    /// Trailing whitespace was ignored.
+    TrailingWhitespace,
+    /// Leading and trailing whitespace was ignored.
+    Whitespace,
+    /// Whitespace was ignored and Unicode punctuation was normalised to ASCII.
+    Punctuation,
+}
+
+impl std::fmt::Display for MatchFuzz {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MatchFuzz::Exact => "exact",
+            MatchFuzz::TrailingWhitespace => "trailing_whitespace",
+            MatchFuzz::Whitespace => "whitespace",
+            MatchFuzz::Punctuation => "punctuation",
+        };
+        f.write_str(name)
+    }
+}
+
 /// Attempt to find the sequence of `pattern` lines within `lines` beginning at or after `start`.
-/// Returns the starting index of the match or `None` if not found. Matches are attempted with
-/// decreasing strictness: exact match, then ignoring trailing whitespace, then ignoring leading
-/// and trailing whitespace. When `eof` is true, we first try starting at the end-of-file (so that
-/// patterns intended to match file endings are applied at the end), and fall back to searching
-/// from `start` if needed.
+/// Returns the starting index of the match and how lenient the match was, or `None` if not
+/// found. Matches are attempted with decreasing strictness: exact match, then ignoring trailing
+/// whitespace, then ignoring leading and trailing whitespace. When `eof` is true, we first try
+/// starting at the end-of-file (so that patterns intended to match file endings are applied at
+/// the end), and fall back to searching from `start` if needed.
 ///
 /// Special cases handled defensively:
 ///  • Empty `pattern` → returns `Some(start)` (no-op match)
@@ -14,9 +42,9 @@ pub(crate) fn seek_sequence(
     pattern: &[String],
     start: usize,
     eof: bool,
-) -> Option<usize> {
+) -> Option<(usize, MatchFuzz)> {
     if pattern.is_empty() {
-        return Some(start);
+        return Some((start, MatchFuzz::Exact));
     }
 
     // When the pattern is longer than the available input there is no possible
@@ -34,7 +62,7 @@ pub(crate) fn seek_sequence(
     // Exact match first.
     for i in search_start..=lines.len().saturating_sub(pattern.len()) {
         if lines[i..i + pattern.len()] == *pattern {
-            return Some(i);
+            return Some((i, MatchFuzz::Exact));
         }
     }
     // Then rstrip match.
@@ -47,7 +75,7 @@ pub(crate) fn seek_sequence(
             }
         }
         if ok {
-            return Some(i);
+            return Some((i, MatchFuzz::TrailingWhitespace));
         }
     }
     // Finally, trim both sides to allow more lenience.
@@ -60,7 +88,7 @@ pub(crate) fn seek_sequence(
             }
         }
         if ok {
-            return Some(i);
+            return Some((i, MatchFuzz::Whitespace));
         }
     }
 
@@ -102,7 +130,7 @@ pub(crate) fn seek_sequence(
             }
         }
         if ok {
-            return Some(i);
+            return Some((i, MatchFuzz::Punctuation));
         }
     }
 
@@ -111,6 +139,7 @@ pub(crate) fn seek_sequence(
 
 #[cfg(test)]
 mod tests {
+    use super::MatchFuzz;
     use super::seek_sequence;
 
     fn to_vec(strings: &[&str]) -> Vec<String> {
@@ -121,7 +150,10 @@ mod tests {
     fn test_exact_match_finds_sequence() {
         let lines = to_vec(&["foo", "bar", "baz"]);
         let pattern = to_vec(&["bar", "baz"]);
-        assert_eq!(seek_sequence(&lines, &pattern, 0, false), Some(1));
+        assert_eq!(
+            seek_sequence(&lines, &pattern, 0, false),
+            Some((1, MatchFuzz::Exact))
+        );
     }
 
     #[test]
@@ -129,7 +161,10 @@ mod tests {
         let lines = to_vec(&["foo   ", "bar\t\t"]);
         // Pattern omits trailing whitespace.
         let pattern = to_vec(&["foo", "bar"]);
-        assert_eq!(seek_sequence(&lines, &pattern, 0, false), Some(0));
+        assert_eq!(
+            seek_sequence(&lines, &pattern, 0, false),
+            Some((0, MatchFuzz::TrailingWhitespace))
+        );
     }
 
     #[test]
@@ -137,7 +172,20 @@ mod tests {
         let lines = to_vec(&["    foo   ", "   bar\t"]);
         // Pattern omits any additional whitespace.
         let pattern = to_vec(&["foo", "bar"]);
-        assert_eq!(seek_sequence(&lines, &pattern, 0, false), Some(0));
+        assert_eq!(
+            seek_sequence(&lines, &pattern, 0, false),
+            Some((0, MatchFuzz::Whitespace))
+        );
+    }
+
+    #[test]
+    fn test_punctuation_match_normalises_unicode_dashes() {
+        let lines = to_vec(&["let range = 1\u{2013}10;"]);
+        let pattern = to_vec(&["let range = 1-10;"]);
+        assert_eq!(
+            seek_sequence(&lines, &pattern, 0, false),
+            Some((0, MatchFuzz::Punctuation))
+        );
     }
 
     #[test]
@@ -1,33 +1,164 @@
+use std::ffi::OsString;
 use std::io::Read;
 use std::io::Write;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::AffectedPaths;
+use crate::FileAction;
+use crate::FileCheck;
+
+const USAGE: &str = "Usage: apply_patch [--check | --dry-run] [--diff] [--json] [--cwd DIR] 'PATCH'
+       echo 'PATCH' | apply_patch [OPTIONS]
+
+Options:
+  --check      Verify that every hunk applies and report where each chunk matched
+  --dry-run    Compute the changes without writing any files
+  --diff       Print a unified diff of the resulting changes
+  --json       Print a single JSON object instead of text
+  --cwd DIR    Resolve relative paths in the patch against DIR";
 
 pub fn main() -> ! {
     let exit_code = run_main();
     std::process::exit(exit_code);
 }
 
+/// Options of the standalone executable. Without any of them, the patch is
+/// simply applied.
+#[derive(Debug, Default, PartialEq)]
+struct CliOptions {
+    check: bool,
+    dry_run: bool,
+    diff: bool,
+    json: bool,
+    cwd: Option<PathBuf>,
+    patch: Option<String>,
+}
+
+impl CliOptions {
+    fn needs_report(&self) -> bool {
+        self.check || self.dry_run || self.diff || self.json
+    }
+
+    fn mode(&self) -> &'static str {
+        if self.check {
+            "check"
+        } else if self.dry_run {
+            "dry_run"
+        } else {
+            "apply"
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ArgsError {
+    Help,
+    InvalidUtf8,
+    Usage(String),
+}
+
+/// Options are only recognized by their exact names, so a patch argument
+/// that happens to start with `-` (such as a `--- a/file` unified diff) is
+/// still taken as the patch.
+fn parse_args(args: impl IntoIterator<Item = OsString>) -> Result<CliOptions, ArgsError> {
+    let mut options = CliOptions::default();
+    let mut args = args.into_iter();
+    let mut only_positional = false;
+    while let Some(arg) = args.next() {
+        let arg = arg.into_string().map_err(|_| ArgsError::InvalidUtf8)?;
+        if !only_positional {
+            match arg.as_str() {
+                "--check" => {
+                    options.check = true;
+                    continue;
+                }
+                "--dry-run" => {
+                    options.dry_run = true;
+                    continue;
+                }
+                "--diff" => {
+                    options.diff = true;
+                    continue;
+                }
+                "--json" => {
+                    options.json = true;
+                    continue;
+                }
+                "-h" | "--help" => return Err(ArgsError::Help),
+                "--" => {
+                    only_positional = true;
+                    continue;
+                }
+                "--cwd" => {
+                    let dir = args.next().ok_or_else(|| {
+                        ArgsError::Usage("--cwd requires a directory.".to_string())
+                    })?;
+                    options.cwd = Some(PathBuf::from(dir));
+                    continue;
+                }
+                _ => {
+                    if let Some(dir) = arg.strip_prefix("--cwd=") {
+                        options.cwd = Some(PathBuf::from(dir));
+                        continue;
+                    }
+                }
+            }
+        }
+        if options.patch.is_some() {
+            return Err(ArgsError::Usage(
+                "apply_patch accepts exactly one argument.".to_string(),
+            ));
+        }
+        options.patch = Some(arg);
+    }
+    if options.check && options.dry_run {
+        return Err(ArgsError::Usage(
+            "--check and --dry-run cannot be combined.".to_string(),
+        ));
+    }
+    Ok(options)
+}
+
 /// We would prefer to return `std::process::ExitCode`, but its `exit_process()`
 /// method is still a nightly API and we want main() to return !.
 pub fn run_main() -> i32 {
-    // Expect either one argument (the full apply_patch payload) or read it from stdin.
-    let mut args = std::env::args_os();
-    let _argv0 = args.next();
-
-    let patch_arg = match args.next() {
-        Some(arg) => match arg.into_string() {
-            Ok(s) => s,
-            Err(_) => {
-                eprintln!("Error: apply_patch requires a UTF-8 PATCH argument.");
-                return 1;
-            }
-        },
+    let options = match parse_args(std::env::args_os().skip(1)) {
+        Ok(options) => options,
+        Err(ArgsError::Help) => {
+            println!("{USAGE}");
+            return 0;
+        }
+        Err(ArgsError::InvalidUtf8) => {
+            eprintln!("Error: apply_patch requires a UTF-8 PATCH argument.");
+            return 1;
+        }
+        Err(ArgsError::Usage(message)) => {
+            eprintln!("Error: {message}\n{USAGE}");
+            return 2;
+        }
+    };
+
+    if let Some(cwd) = &options.cwd
+        && let Err(err) = std::env::set_current_dir(cwd)
+    {
+        eprintln!(
+            "Error: Failed to change to directory {}.\n{err}",
+            cwd.display()
+        );
+        return 1;
+    }
+
+    let patch_arg = match &options.patch {
+        Some(patch) => patch.clone(),
         None => {
             // No argument provided; attempt to read the patch from stdin.
             let mut buf = String::new();
             match std::io::stdin().read_to_string(&mut buf) {
                 Ok(_) => {
                     if buf.is_empty() {
-                        eprintln!("Usage: apply_patch 'PATCH'\n       echo 'PATCH' | apply-patch");
+                        eprintln!("{USAGE}");
                         return 2;
                     }
                     buf
@@ -40,14 +171,13 @@ pub fn run_main() -> i32 {
         }
     };
 
-    // Refuse extra args to avoid ambiguity.
-    if args.next().is_some() {
-        eprintln!("Error: apply_patch accepts exactly one argument.");
-        return 2;
-    }
-
     let mut stdout = std::io::stdout();
     let mut stderr = std::io::stderr();
+    if options.needs_report() {
+        let exit_code = run_with_report(&patch_arg, &options, &mut stdout, &mut stderr);
+        let _ = stdout.flush();
+        return exit_code;
+    }
     match crate::apply_patch(&patch_arg, &mut stdout, &mut stderr) {
         Ok(()) => {
             // Flush to ensure output ordering when used in pipelines.
@@ -57,3 +187,182 @@ pub fn run_main() -> i32 {
         Err(_) => 1,
     }
 }
+
+/// What `--check`, `--dry-run` and `--diff` found out about the patch.
+struct Report {
+    files: Vec<FileCheck>,
+    affected: AffectedPaths,
+    diff: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonReport {
+    ok: bool,
+    mode: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    files: Vec<FileCheck>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diff: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn build_report(patch: &str, options: &CliOptions) -> anyhow::Result<Report> {
+    let args = crate::parse_patch(patch)?;
+    let staged = crate::stage_hunks(&args.hunks)?;
+    let files = staged.files().to_vec();
+    let diff = options.diff.then(|| staged.unified_diff());
+    let affected = if options.check || options.dry_run {
+        staged.affected().clone()
+    } else {
+        staged.commit()?
+    };
+    Ok(Report {
+        files,
+        affected,
+        diff,
+    })
+}
+
+fn run_with_report(
+    patch: &str,
+    options: &CliOptions,
+    stdout: &mut impl Write,
+    stderr: &mut impl Write,
+) -> i32 {
+    let result = build_report(patch, options);
+    let exit_code = if result.is_ok() { 0 } else { 1 };
+
+    if options.json {
+        let report = match result {
+            Ok(report) => JsonReport {
+                ok: true,
+                mode: options.mode(),
+                files: report.files,
+                diff: report.diff,
+                error: None,
+            },
+            Err(err) => JsonReport {
+                ok: false,
+                mode: options.mode(),
+                files: Vec::new(),
+                diff: None,
+                error: Some(format!("{err:#}")),
+            },
+        };
+        return match serde_json::to_string(&report) {
+            Ok(json) => match writeln!(stdout, "{json}") {
+                Ok(()) => exit_code,
+                Err(_) => 1,
+            },
+            Err(err) => {
+                let _ = writeln!(stderr, "Error: Failed to serialize report.\n{err}");
+                1
+            }
+        };
+    }
+
+    let report = match result {
+        Ok(report) => report,
+        Err(err) => {
+            let _ = writeln!(stderr, "{err:#}");
+            return exit_code;
+        }
+    };
+    match write_text_report(&report, options, stdout) {
+        Ok(()) => exit_code,
+        Err(_) => 1,
+    }
+}
+
+fn write_text_report(
+    report: &Report,
+    options: &CliOptions,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    if options.check {
+        writeln!(
+            out,
+            "Check passed. The patch applies to the following files:"
+        )?;
+        for file in &report.files {
+            let marker = match file.action {
+                FileAction::Add => "A",
+                FileAction::Delete => "D",
+                FileAction::Update => "M",
+            };
+            match &file.move_path {
+                Some(dest) => writeln!(
+                    out,
+                    "{marker} {} -> {}",
+                    file.path.display(),
+                    dest.display()
+                )?,
+                None => writeln!(out, "{marker} {}", file.path.display())?,
+            }
+            for (index, chunk) in file.chunks.iter().enumerate() {
+                writeln!(
+                    out,
+                    "  chunk {}: line {} ({})",
+                    index + 1,
+                    chunk.line,
+                    chunk.fuzz
+                )?;
+            }
+        }
+    } else if options.dry_run {
+        writeln!(out, "Dry run. Would update the following files:")?;
+        crate::write_affected_paths(&report.affected, out)?;
+    } else {
+        crate::print_summary(&report.affected, out)?;
+    }
+    if let Some(diff) = &report.diff {
+        write!(out, "{diff}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn parses_flags_and_keeps_diffs_as_the_patch() {
+        assert_eq!(
+            parse_args(args(&[
+                "--check",
+                "--json",
+                "--cwd",
+                "/repo",
+                "--- a\n+++ b\n"
+            ])),
+            Ok(CliOptions {
+                check: true,
+                json: true,
+                cwd: Some(PathBuf::from("/repo")),
+                patch: Some("--- a\n+++ b\n".to_string()),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["--dry-run", "--diff", "--cwd=src"])),
+            Ok(CliOptions {
+                dry_run: true,
+                diff: true,
+                cwd: Some(PathBuf::from("src")),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["one", "two"])),
+            Err(ArgsError::Usage(
+                "apply_patch accepts exactly one argument.".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,783 @@
+//! Applies the file changes of a patch as a single transaction.
+//!
+//! Changes are first staged in memory, so later hunks see the results of
+//! earlier ones (e.g. an `Update File` of a path added by the same patch).
+//! Committing then happens in two steps:
+//!
+//! 1. Every new file body (or symlink) is written to a temporary file next to
+//!    its target. Nothing visible has changed yet, so a failure here only has
+//!    to clean up the temporary files.
+//! 2. The temporary files are renamed over their targets and deleted files
+//!    are removed. If any of these steps fails, the files that were already
+//!    replaced or removed are restored from the contents and permissions
+//!    read before the commit started.
+//!
+//! File modes and symlinks are only supported on Unix; elsewhere modes are
+//! ignored and adding a symlink fails.
+
+use std::collections::HashMap;
+use std::fs;
+use std::fs::Permissions;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use similar::TextDiff;
+
+/// Git modes used in the headers of [`FileTransaction::unified_diff`].
+const GIT_REGULAR_FILE: u32 = 0o100000;
+const GIT_SYMLINK: u32 = 0o120000;
+const DEFAULT_PERMISSIONS: u32 = 0o644;
+
+/// Staged file changes, keyed by path.
+#[derive(Debug, Default)]
+pub(crate) struct FileTransaction {
+    /// Paths in the order they were first touched.
+    order: Vec<PathBuf>,
+    /// Final state of each touched path; `None` deletes the file.
+    staged: HashMap<PathBuf, Option<StagedFile>>,
+}
+
+/// The final state of a path that is written by the transaction.
+#[derive(Debug, Clone)]
+enum StagedFile {
+    Contents {
+        bytes: Vec<u8>,
+        /// Permission bits to set; `None` keeps those of the file it replaces.
+        mode: Option<u32>,
+    },
+    Symlink {
+        target: PathBuf,
+    },
+}
+
+impl FileTransaction {
+    /// Contents of `path` as seen by this transaction: the staged contents if
+    /// an earlier change touched it, otherwise what is on disk.
+    pub(crate) fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        match self.staged.get(path) {
+            Some(Some(StagedFile::Contents { bytes, .. })) => Ok(bytes.clone()),
+            Some(Some(StagedFile::Symlink { .. })) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "file is a symlink added earlier in the patch",
+            )),
+            Some(None) => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "file is deleted earlier in the patch",
+            )),
+            None => fs::read(path),
+        }
+    }
+
+    /// Like [`FileTransaction::read`], for files that must be UTF-8 text.
+    pub(crate) fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+
+    /// Whether `path` is a file as seen by this transaction.
+    pub(crate) fn is_file(&self, path: &Path) -> bool {
+        match self.staged.get(path) {
+            Some(staged) => staged.is_some(),
+            None => fs::metadata(path).is_ok_and(|meta| meta.is_file()),
+        }
+    }
+
+    /// Stage new contents for `path`. A `mode` of `None` keeps the mode an
+    /// earlier change in the patch set, or else that of the file on disk.
+    pub(crate) fn write(&mut self, path: &Path, contents: impl Into<Vec<u8>>, mode: Option<u32>) {
+        let mode = mode.or_else(|| match self.staged.get(path) {
+            Some(Some(StagedFile::Contents { mode, .. })) => *mode,
+            _ => None,
+        });
+        self.stage(
+            path,
+            Some(StagedFile::Contents {
+                bytes: contents.into(),
+                mode,
+            }),
+        );
+    }
+
+    /// Stage `contents` at `to` and delete `from`. A `mode` of `None` carries
+    /// over the mode of `from`, staged or on disk.
+    pub(crate) fn move_file(
+        &mut self,
+        from: &Path,
+        to: &Path,
+        contents: impl Into<Vec<u8>>,
+        mode: Option<u32>,
+    ) {
+        let mode = mode.or_else(|| self.mode_of(from));
+        self.write(to, contents, mode);
+        self.delete(from);
+    }
+
+    /// Permission bits `path` would have after the staged changes, if it is a
+    /// regular file.
+    fn mode_of(&self, path: &Path) -> Option<u32> {
+        match self.staged.get(path) {
+            Some(Some(StagedFile::Contents {
+                mode: Some(mode), ..
+            })) => Some(*mode),
+            Some(Some(StagedFile::Symlink { .. })) | Some(None) => None,
+            Some(Some(StagedFile::Contents { mode: None, .. })) | None => fs::metadata(path)
+                .ok()
+                .filter(fs::Metadata::is_file)
+                .map(|meta| permission_bits(&meta)),
+        }
+    }
+
+    pub(crate) fn symlink(&mut self, path: &Path, target: &Path) {
+        self.stage(
+            path,
+            Some(StagedFile::Symlink {
+                target: target.to_path_buf(),
+            }),
+        );
+    }
+
+    pub(crate) fn delete(&mut self, path: &Path) {
+        self.stage(path, None);
+    }
+
+    fn stage(&mut self, path: &Path, file: Option<StagedFile>) {
+        if self.staged.insert(path.to_path_buf(), file).is_none() {
+            self.order.push(path.to_path_buf());
+        }
+    }
+
+    /// A git-style unified diff from what is on disk to the staged state of
+    /// every touched path.
+    pub(crate) fn unified_diff(&self) -> String {
+        let mut out = String::new();
+        for path in &self.order {
+            let Some(staged) = self.staged.get(path) else {
+                continue;
+            };
+            let old = DiffSide::from_disk(path);
+            let new = staged
+                .as_ref()
+                .map(|staged| DiffSide::from_staged(staged, old.as_ref()));
+            write_file_diff(&mut out, path, old.as_ref(), new.as_ref());
+        }
+        out
+    }
+
+    /// Write all staged changes to disk, or none of them.
+    pub(crate) fn commit(mut self) -> anyhow::Result<()> {
+        let mut prepared: Vec<PreparedChange> = Vec::with_capacity(self.order.len());
+        let mut created_dirs: Vec<PathBuf> = Vec::new();
+        for path in std::mem::take(&mut self.order) {
+            let Some(contents) = self.staged.remove(&path) else {
+                continue;
+            };
+            // Deleting a file that was added earlier in the same patch
+            // leaves nothing to do on disk.
+            if contents.is_none() && fs::symlink_metadata(&path).is_err() {
+                continue;
+            }
+            match prepare_change(path, contents, &mut created_dirs) {
+                Ok(change) => prepared.push(change),
+                Err(err) => {
+                    discard(&prepared, &created_dirs);
+                    return Err(err);
+                }
+            }
+        }
+
+        for (index, change) in prepared.iter().enumerate() {
+            if let Err(err) = change.apply() {
+                let failed = restore(&prepared[..index]);
+                discard(&prepared[index..], &created_dirs);
+                let message = format!("{err:#}");
+                return Err(match failed {
+                    None => err.context(format!(
+                        "{message}\nRolled back changes to {index} file(s); no files were modified."
+                    )),
+                    Some(restore_err) => err.context(format!(
+                        "{message}\nRollback failed, files may be partially modified: {restore_err:#}"
+                    )),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One side of a file diff: its bytes (a symlink's target) and git mode.
+struct DiffSide {
+    bytes: Vec<u8>,
+    mode: u32,
+}
+
+impl DiffSide {
+    fn from_disk(path: &Path) -> Option<Self> {
+        let meta = fs::symlink_metadata(path).ok()?;
+        if meta.file_type().is_symlink() {
+            let target = fs::read_link(path).ok()?;
+            return Some(Self {
+                bytes: target.to_string_lossy().into_owned().into_bytes(),
+                mode: GIT_SYMLINK,
+            });
+        }
+        if !meta.is_file() {
+            return None;
+        }
+        Some(Self {
+            bytes: fs::read(path).ok()?,
+            mode: GIT_REGULAR_FILE | permission_bits(&meta),
+        })
+    }
+
+    fn from_staged(staged: &StagedFile, old: Option<&DiffSide>) -> Self {
+        match staged {
+            StagedFile::Contents { bytes, mode } => {
+                let mode = match (mode, old) {
+                    (Some(mode), _) => GIT_REGULAR_FILE | mode,
+                    (None, Some(old)) if old.mode != GIT_SYMLINK => old.mode,
+                    (None, _) => GIT_REGULAR_FILE | DEFAULT_PERMISSIONS,
+                };
+                Self {
+                    bytes: bytes.clone(),
+                    mode,
+                }
+            }
+            StagedFile::Symlink { target } => Self {
+                bytes: target.to_string_lossy().into_owned().into_bytes(),
+                mode: GIT_SYMLINK,
+            },
+        }
+    }
+}
+
+#[cfg(unix)]
+fn permission_bits(meta: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o777
+}
+
+#[cfg(not(unix))]
+fn permission_bits(_meta: &fs::Metadata) -> u32 {
+    DEFAULT_PERMISSIONS
+}
+
+fn write_file_diff(out: &mut String, path: &Path, old: Option<&DiffSide>, new: Option<&DiffSide>) {
+    if let (Some(old), Some(new)) = (old, new)
+        && old.bytes == new.bytes
+        && old.mode == new.mode
+    {
+        return;
+    }
+    let name = path.display();
+    let old_name = old.map_or_else(|| "/dev/null".to_string(), |_| format!("a/{name}"));
+    let new_name = new.map_or_else(|| "/dev/null".to_string(), |_| format!("b/{name}"));
+    match (old, new) {
+        (None, None) => return,
+        (None, Some(new)) => {
+            out.push_str(&format!("diff --git a/{name} b/{name}\n"));
+            out.push_str(&format!("new file mode {:o}\n", new.mode));
+        }
+        (Some(old), None) => {
+            out.push_str(&format!("diff --git a/{name} b/{name}\n"));
+            out.push_str(&format!("deleted file mode {:o}\n", old.mode));
+        }
+        (Some(old), Some(new)) => {
+            out.push_str(&format!("diff --git a/{name} b/{name}\n"));
+            if old.mode != new.mode {
+                out.push_str(&format!("old mode {:o}\n", old.mode));
+                out.push_str(&format!("new mode {:o}\n", new.mode));
+            }
+        }
+    }
+
+    let old_bytes = old.map_or(&[][..], |side| side.bytes.as_slice());
+    let new_bytes = new.map_or(&[][..], |side| side.bytes.as_slice());
+    if old_bytes == new_bytes {
+        return;
+    }
+    match (
+        std::str::from_utf8(old_bytes),
+        std::str::from_utf8(new_bytes),
+    ) {
+        (Ok(old_text), Ok(new_text)) => {
+            let diff = TextDiff::from_lines(old_text, new_text);
+            out.push_str(&diff.unified_diff().header(&old_name, &new_name).to_string());
+        }
+        _ => out.push_str(&format!("Binary files {old_name} and {new_name} differ\n")),
+    }
+}
+
+/// A staged change whose new contents, if any, are already on disk in a
+/// temporary file.
+struct PreparedChange {
+    /// The file that is replaced or removed. When writing through a symlink
+    /// this is the file the link points to, so the link itself is preserved.
+    target: PathBuf,
+    /// Temporary file (or symlink) holding the new contents; `None` deletes
+    /// `target`.
+    temp: Option<PathBuf>,
+    /// What was at `target` before the commit, used for rollback. `None`
+    /// when nothing existed there.
+    original: Option<Original>,
+}
+
+enum Original {
+    File {
+        bytes: Vec<u8>,
+        /// Restored along with the contents, since the file is recreated.
+        permissions: Permissions,
+    },
+    Symlink(PathBuf),
+}
+
+impl Original {
+    fn read_file(path: &Path, meta: &fs::Metadata) -> std::io::Result<Self> {
+        Ok(Self::File {
+            bytes: fs::read(path)?,
+            permissions: meta.permissions(),
+        })
+    }
+}
+
+impl PreparedChange {
+    fn apply(&self) -> anyhow::Result<()> {
+        match &self.temp {
+            Some(temp) => fs::rename(temp, &self.target)
+                .with_context(|| format!("Failed to write file {}", self.target.display())),
+            None => fs::remove_file(&self.target)
+                .with_context(|| format!("Failed to delete file {}", self.target.display())),
+        }
+    }
+
+    fn undo(&self) -> anyhow::Result<()> {
+        let restore = || -> std::io::Result<()> {
+            // The new contents may be a symlink, which must not be written
+            // through, so always remove them first.
+            if fs::symlink_metadata(&self.target).is_ok() {
+                fs::remove_file(&self.target)?;
+            }
+            match &self.original {
+                Some(Original::File { bytes, permissions }) => {
+                    fs::write(&self.target, bytes)?;
+                    fs::set_permissions(&self.target, permissions.clone())
+                }
+                Some(Original::Symlink(link_target)) => create_symlink(link_target, &self.target),
+                None => Ok(()),
+            }
+        };
+        restore().with_context(|| format!("Failed to restore {}", self.target.display()))
+    }
+}
+
+fn prepare_change(
+    path: PathBuf,
+    file: Option<StagedFile>,
+    created_dirs: &mut Vec<PathBuf>,
+) -> anyhow::Result<PreparedChange> {
+    let (bytes, mode) = match file {
+        None => return prepare_delete(path),
+        Some(StagedFile::Symlink { target }) => {
+            return prepare_symlink(path, &target, created_dirs);
+        }
+        Some(StagedFile::Contents { bytes, mode }) => (bytes, mode),
+    };
+
+    let target = match fs::symlink_metadata(&path) {
+        Ok(meta) if meta.file_type().is_symlink() => fs::canonicalize(&path)
+            .with_context(|| format!("Failed to resolve symlink {}", path.display()))?,
+        _ => path,
+    };
+    let existing = fs::metadata(&target).ok();
+
+    let mut permissions: Option<Permissions> = None;
+    let mut original = None;
+    if let Some(meta) = existing.as_ref().filter(|meta| meta.is_file()) {
+        // Renaming over a read-only file would succeed, so check up front that
+        // the file could be written in place.
+        fs::OpenOptions::new()
+            .write(true)
+            .open(&target)
+            .with_context(|| format!("Failed to write file {}", target.display()))?;
+        original = Some(
+            Original::read_file(&target, meta)
+                .with_context(|| format!("Failed to read file {}", target.display()))?,
+        );
+        permissions = Some(meta.permissions());
+    }
+    if let Some(mode_permissions) = mode.and_then(permissions_from_mode) {
+        permissions = Some(mode_permissions);
+    }
+
+    ensure_parent_dirs(&target, created_dirs)?;
+
+    let temp = temp_path_for(&target);
+    let write_temp = || -> std::io::Result<()> {
+        fs::write(&temp, &bytes)?;
+        if let Some(permissions) = permissions {
+            fs::set_permissions(&temp, permissions)?;
+        }
+        Ok(())
+    };
+    if let Err(err) = write_temp() {
+        let _ = fs::remove_file(&temp);
+        return Err(err).with_context(|| format!("Failed to write file {}", target.display()));
+    }
+
+    Ok(PreparedChange {
+        target,
+        temp: Some(temp),
+        original,
+    })
+}
+
+fn prepare_delete(path: PathBuf) -> anyhow::Result<PreparedChange> {
+    let original = match fs::symlink_metadata(&path) {
+        Ok(meta) if meta.file_type().is_symlink() => Original::Symlink(
+            fs::read_link(&path)
+                .with_context(|| format!("Failed to delete file {}", path.display()))?,
+        ),
+        Ok(meta) if meta.is_file() => Original::read_file(&path, &meta)
+            .with_context(|| format!("Failed to delete file {}", path.display()))?,
+        _ => anyhow::bail!("Failed to delete file {}: no such file", path.display()),
+    };
+    Ok(PreparedChange {
+        target: path,
+        temp: None,
+        original: Some(original),
+    })
+}
+
+/// Creates the new symlink under a temporary name, to be renamed over `path`.
+/// Unlike file writes, an existing symlink at `path` is replaced rather than
+/// followed.
+fn prepare_symlink(
+    path: PathBuf,
+    link_target: &Path,
+    created_dirs: &mut Vec<PathBuf>,
+) -> anyhow::Result<PreparedChange> {
+    let original = match fs::symlink_metadata(&path) {
+        Ok(meta) if meta.file_type().is_symlink() => Some(Original::Symlink(
+            fs::read_link(&path)
+                .with_context(|| format!("Failed to read symlink {}", path.display()))?,
+        )),
+        Ok(meta) if meta.is_file() => Some(
+            Original::read_file(&path, &meta)
+                .with_context(|| format!("Failed to read file {}", path.display()))?,
+        ),
+        Ok(_) => anyhow::bail!(
+            "Failed to create symlink {}: a directory exists at that path",
+            path.display()
+        ),
+        Err(_) => None,
+    };
+
+    ensure_parent_dirs(&path, created_dirs)?;
+
+    let temp = temp_path_for(&path);
+    create_symlink(link_target, &temp)
+        .with_context(|| format!("Failed to create symlink {}", path.display()))?;
+
+    Ok(PreparedChange {
+        target: path,
+        temp: Some(temp),
+        original,
+    })
+}
+
+fn ensure_parent_dirs(path: &Path, created_dirs: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        create_parent_dirs(parent, created_dirs).with_context(|| {
+            format!("Failed to create parent directories for {}", path.display())
+        })?;
+    }
+    Ok(())
+}
+
+/// Permissions for Unix permission bits `mode`. Other platforms have no
+/// equivalent, so the mode is ignored there.
+#[cfg(unix)]
+fn permissions_from_mode(mode: u32) -> Option<Permissions> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn permissions_from_mode(_mode: u32) -> Option<Permissions> {
+    None
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &Path, _link: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "symlinks are only supported on Unix",
+    ))
+}
+
+/// Like `create_dir_all`, but records every directory it creates so they can
+/// be removed again on rollback.
+fn create_parent_dirs(dir: &Path, created_dirs: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let missing: Vec<&Path> = dir
+        .ancestors()
+        .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+        .collect();
+    for ancestor in missing.into_iter().rev() {
+        fs::create_dir(ancestor)?;
+        created_dirs.push(ancestor.to_path_buf());
+    }
+    Ok(())
+}
+
+/// A sibling path of `target` that does not exist yet.
+fn temp_path_for(target: &Path) -> PathBuf {
+    let file_name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let pid = std::process::id();
+    (0..)
+        .map(|n| target.with_file_name(format!(".{file_name}.apply_patch.{pid}.{n}.tmp")))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| target.with_file_name(format!(".{file_name}.apply_patch.tmp")))
+}
+
+/// Restore already-applied changes, most recent first. Returns the first
+/// error, but keeps restoring the remaining files.
+fn restore(applied: &[PreparedChange]) -> Option<anyhow::Error> {
+    let mut first_error = None;
+    for change in applied.iter().rev() {
+        if let Err(err) = change.undo() {
+            first_error.get_or_insert(err);
+        }
+    }
+    first_error
+}
+
+/// Remove temporary files that were not renamed into place, and directories
+/// created for them (if still empty).
+fn discard(prepared: &[PreparedChange], created_dirs: &[PathBuf]) {
+    for temp in prepared.iter().filter_map(|change| change.temp.as_ref()) {
+        let _ = fs::remove_file(temp);
+    }
+    for dir in created_dirs.iter().rev() {
+        let _ = fs::remove_dir(dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn dir_entries(dir: &Path) -> Vec<String> {
+        let mut entries: Vec<String> = fs::read_dir(dir)
+            .expect("read dir")
+            .map(|entry| {
+                entry
+                    .expect("dir entry")
+                    .file_name()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn staged_changes_are_visible_before_commit() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("new.txt");
+        let mut transaction = FileTransaction::default();
+        assert!(!transaction.is_file(&path));
+        transaction.write(&path, "one\n", None);
+        assert_eq!(transaction.read_to_string(&path).expect("staged"), "one\n");
+        transaction.delete(&path);
+        assert!(!transaction.is_file(&path));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn failed_rename_restores_earlier_files() {
+        let dir = tempdir().expect("tempdir");
+        let first = dir.path().join("a.txt");
+        let deleted = dir.path().join("b.txt");
+        let blocked = dir.path().join("blocked");
+        fs::write(&first, "before\n").expect("write");
+        fs::write(&deleted, "keep me\n").expect("write");
+        fs::create_dir(&blocked).expect("mkdir");
+        fs::write(blocked.join("inner.txt"), "x").expect("write");
+
+        let mut transaction = FileTransaction::default();
+        transaction.write(&first, "after\n", None);
+        transaction.delete(&deleted);
+        transaction.write(&dir.path().join("nested/dir/c.txt"), "new\n", None);
+        // Renaming a file over a non-empty directory fails.
+        transaction.write(&blocked, "oops\n", None);
+        let err = transaction.commit().expect_err("commit should fail");
+
+        let message = err.to_string();
+        assert!(message.starts_with(&format!("Failed to write file {}", blocked.display())));
+        assert!(message.ends_with("Rolled back changes to 3 file(s); no files were modified."));
+        assert_eq!(fs::read_to_string(&first).expect("read"), "before\n");
+        assert_eq!(fs::read_to_string(&deleted).expect("read"), "keep me\n");
+        assert_eq!(
+            dir_entries(dir.path()),
+            vec![
+                "a.txt".to_string(),
+                "b.txt".to_string(),
+                "blocked".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn unified_diff_describes_staged_changes_without_writing() {
+        let dir = tempdir().expect("tempdir");
+        let edited = dir.path().join("a.txt");
+        let removed = dir.path().join("b.txt");
+        let added = dir.path().join("c.txt");
+        fs::write(&edited, "one\ntwo\n").expect("write");
+        fs::write(&removed, "gone\n").expect("write");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&removed, Permissions::from_mode(0o644)).expect("chmod");
+        }
+
+        let mut transaction = FileTransaction::default();
+        transaction.write(&edited, "one\n2\n", None);
+        transaction.delete(&removed);
+        transaction.write(&added, "new\n", None);
+        let diff = transaction.unified_diff();
+
+        let (edited, removed, added) = (edited.display(), removed.display(), added.display());
+        assert_eq!(
+            diff,
+            format!(
+                "diff --git a/{edited} b/{edited}\n\
+                 --- a/{edited}\n\
+                 +++ b/{edited}\n\
+                 @@ -1,2 +1,2 @@\n\
+                 \x20one\n\
+                 -two\n\
+                 +2\n\
+                 diff --git a/{removed} b/{removed}\n\
+                 deleted file mode 100644\n\
+                 --- a/{removed}\n\
+                 +++ /dev/null\n\
+                 @@ -1 +0,0 @@\n\
+                 -gone\n\
+                 diff --git a/{added} b/{added}\n\
+                 new file mode 100644\n\
+                 --- /dev/null\n\
+                 +++ b/{added}\n\
+                 @@ -0,0 +1 @@\n\
+                 +new\n"
+            )
+        );
+        assert_eq!(dir_entries(dir.path()), vec!["a.txt", "b.txt"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rollback_restores_replaced_symlink_and_modes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().expect("tempdir");
+        let script = dir.path().join("run.sh");
+        let link = dir.path().join("latest");
+        fs::write(&script, "echo hi\n").expect("write");
+        std::os::unix::fs::symlink("run.sh", &link).expect("symlink");
+
+        let mut transaction = FileTransaction::default();
+        transaction.write(&script, "echo bye\n", Some(0o755));
+        transaction.symlink(&link, Path::new("elsewhere"));
+        transaction.commit().expect("commit");
+        let mode = fs::metadata(&script)
+            .expect("metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+        assert_eq!(
+            fs::read_link(&link).expect("read link"),
+            PathBuf::from("elsewhere")
+        );
+
+        let blocked = dir.path().join("blocked");
+        fs::create_dir(&blocked).expect("mkdir");
+        fs::write(blocked.join("inner.txt"), "x").expect("write");
+        let mut transaction = FileTransaction::default();
+        transaction.symlink(&link, Path::new("run.sh"));
+        transaction.write(&blocked, "oops\n", None);
+        transaction.commit().expect_err("commit should fail");
+        assert_eq!(
+            fs::read_link(&link).expect("read link"),
+            PathBuf::from("elsewhere")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn move_keeps_permissions_of_the_source() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().expect("tempdir");
+        let source = dir.path().join("run.sh");
+        let dest = dir.path().join("bin").join("run.sh");
+        fs::write(&source, "echo hi\n").expect("write");
+        fs::set_permissions(&source, Permissions::from_mode(0o755)).expect("chmod");
+
+        let mut transaction = FileTransaction::default();
+        transaction.move_file(&source, &dest, "echo bye\n", None);
+        assert!(
+            transaction
+                .unified_diff()
+                .contains("new file mode 100755\n")
+        );
+        transaction.commit().expect("commit");
+
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(&dest).expect("read"), "echo bye\n");
+        let mode = fs::metadata(&dest).expect("metadata").permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rollback_keeps_permissions_of_restored_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().expect("tempdir");
+        let edited = dir.path().join("edit.sh");
+        let deleted = dir.path().join("delete.sh");
+        for script in [&edited, &deleted] {
+            fs::write(script, "echo hi\n").expect("write");
+            fs::set_permissions(script, Permissions::from_mode(0o755)).expect("chmod");
+        }
+        let blocked = dir.path().join("blocked");
+        fs::create_dir(&blocked).expect("mkdir");
+        fs::write(blocked.join("inner.txt"), "x").expect("write");
+
+        let mut transaction = FileTransaction::default();
+        transaction.write(&edited, "echo bye\n", Some(0o644));
+        transaction.delete(&deleted);
+        transaction.write(&blocked, "oops\n", None);
+        transaction.commit().expect_err("commit should fail");
+
+        for script in [&edited, &deleted] {
+            assert_eq!(fs::read_to_string(script).expect("read"), "echo hi\n");
+            let mode = fs::metadata(script).expect("metadata").permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+}
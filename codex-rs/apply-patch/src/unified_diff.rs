@@ -0,0 +1,695 @@
+//! Parses standard unified diffs (as produced by `diff -u`) and git diffs
+//! (`diff --git`, including renames, file modes and symlinks) into the same
+//! [`Hunk`]s as the `*** Begin Patch` format, so both kinds of patches are
+//! approved, tracked and applied identically.
+//!
+//! Hunks are matched against the file by their context and removed lines,
+//! exactly like `*** Update File` chunks; the line numbers in `@@` headers are
+//! only used to find where each hunk body ends. Models often get those counts
+//! wrong, so a body also ends at the next `@@` or `diff --git` line once its
+//! counts are satisfied, and may run longer than the header claims.
+
+use std::path::PathBuf;
+
+use crate::parser::Hunk;
+use crate::parser::ParseError;
+use crate::parser::ParseError::*;
+use crate::parser::UpdateFileChunk;
+
+const GIT_DIFF_MARKER: &str = "diff --git ";
+const OLD_FILE_MARKER: &str = "--- ";
+const NEW_FILE_MARKER: &str = "+++ ";
+const HUNK_HEADER_MARKER: &str = "@@ -";
+const NO_NEWLINE_MARKER: &str = "\\ No newline at end of file";
+const DEV_NULL: &str = "/dev/null";
+/// File type bits of a git mode, and the value for symlinks.
+const GIT_FILE_TYPE_MASK: u32 = 0o170000;
+const GIT_SYMLINK_TYPE: u32 = 0o120000;
+/// Permission bits git uses for ordinary, non-executable files.
+const DEFAULT_FILE_MODE: u32 = 0o644;
+
+/// Whether `lines` (already trimmed of surrounding blank lines) start like a
+/// unified or git diff rather than a `*** Begin Patch` patch.
+pub(crate) fn is_unified_diff(lines: &[&str]) -> bool {
+    match lines {
+        [first, ..] if first.starts_with(GIT_DIFF_MARKER) => true,
+        [first, second, ..] => {
+            first.starts_with(OLD_FILE_MARKER) && second.starts_with(NEW_FILE_MARKER)
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn parse_unified_diff(lines: &[&str]) -> Result<Vec<Hunk>, ParseError> {
+    let mut parser = DiffParser { lines, index: 0 };
+    let mut hunks = Vec::new();
+    while let Some(line) = parser.peek() {
+        if line.trim().is_empty() {
+            parser.index += 1;
+            continue;
+        }
+        let file = if line.starts_with(GIT_DIFF_MARKER) {
+            parser.parse_git_file()?
+        } else if line.starts_with(OLD_FILE_MARKER) {
+            parser.parse_plain_file()?
+        } else {
+            return Err(parser.error(format!(
+                "'{line}' is not a valid diff header; expected 'diff --git' or '--- <path>'"
+            )));
+        };
+        hunks.extend(file.into_hunk()?);
+    }
+    if hunks.is_empty() {
+        return Err(InvalidPatchError(
+            "The diff does not contain any file changes".to_string(),
+        ));
+    }
+    Ok(hunks)
+}
+
+/// The changes to one file, before being turned into a [`Hunk`].
+#[derive(Debug, Default)]
+struct FileDiff {
+    /// 1-based line of the file header, for error messages.
+    line_number: usize,
+    /// `None` for `/dev/null`.
+    old_path: Option<PathBuf>,
+    new_path: Option<PathBuf>,
+    is_new: bool,
+    is_deleted: bool,
+    /// Git modes (file type and permission bits) from the extended headers.
+    old_mode: Option<u32>,
+    new_mode: Option<u32>,
+    chunks: Vec<DiffChunk>,
+}
+
+#[derive(Debug)]
+struct DiffChunk {
+    line_number: usize,
+    old_start: usize,
+    old_lines: Vec<String>,
+    new_lines: Vec<String>,
+    /// A `\ No newline at end of file` marker followed the old side.
+    old_at_eof: bool,
+    /// A `\ No newline at end of file` marker followed the new side.
+    new_missing_newline: bool,
+}
+
+impl FileDiff {
+    fn into_hunk(self) -> Result<Option<Hunk>, ParseError> {
+        let invalid = |message: String| InvalidHunkError {
+            message,
+            line_number: self.line_number,
+        };
+        if self.is_new || self.old_path.is_none() {
+            let path = self
+                .new_path
+                .clone()
+                .ok_or_else(|| invalid("a new file must have a path".to_string()))?;
+            let mut contents = String::new();
+            for chunk in &self.chunks {
+                if !chunk.old_lines.is_empty() {
+                    return Err(InvalidHunkError {
+                        message: format!(
+                            "hunk for new file '{}' must only add lines",
+                            path.display()
+                        ),
+                        line_number: chunk.line_number,
+                    });
+                }
+                for line in &chunk.new_lines {
+                    contents.push_str(line);
+                    contents.push('\n');
+                }
+                if chunk.new_missing_newline {
+                    contents.pop();
+                }
+            }
+            if self.new_mode.is_some_and(is_symlink_mode) {
+                // Git stores a symlink as a file holding the link target.
+                return Ok(Some(Hunk::AddSymlink {
+                    path,
+                    target: PathBuf::from(contents.trim_end_matches('\n')),
+                }));
+            }
+            let mode = self
+                .new_mode
+                .map(permission_bits)
+                .filter(|mode| *mode != DEFAULT_FILE_MODE);
+            return Ok(Some(Hunk::AddFile {
+                path,
+                contents,
+                mode,
+            }));
+        }
+
+        let old_path = self
+            .old_path
+            .clone()
+            .ok_or_else(|| invalid("a changed file must have a path".to_string()))?;
+        if self.is_deleted || self.new_path.is_none() {
+            return Ok(Some(Hunk::DeleteFile { path: old_path }));
+        }
+        let new_path = self.new_path.clone().unwrap_or_else(|| old_path.clone());
+        let move_path = (new_path != old_path).then_some(new_path);
+        if self.old_mode.is_some_and(is_symlink_mode) || self.new_mode.is_some_and(is_symlink_mode)
+        {
+            return Err(invalid(format!(
+                "changing the symlink '{}' is not supported; delete it and add it again",
+                old_path.display()
+            )));
+        }
+        let mode = match (self.old_mode, self.new_mode) {
+            (Some(old_mode), Some(new_mode)) if old_mode != new_mode => {
+                Some(permission_bits(new_mode))
+            }
+            _ => None,
+        };
+        if self.chunks.is_empty() && move_path.is_none() && mode.is_none() {
+            // Nothing at all changed.
+            return Ok(None);
+        }
+
+        let mut chunks = Vec::with_capacity(self.chunks.len());
+        for chunk in self.chunks {
+            if chunk.old_lines.is_empty() && chunk.old_start > 0 {
+                // Without context, the chunk could only be placed by its line
+                // number, which is exactly what the patch is trusted least for.
+                return Err(InvalidHunkError {
+                    message: format!(
+                        "hunk for '{}' only adds lines and has no context lines; \
+                         regenerate the diff with context (for example `git diff -U3`)",
+                        old_path.display()
+                    ),
+                    line_number: chunk.line_number,
+                });
+            }
+            chunks.push(UpdateFileChunk {
+                change_context: None,
+                old_lines: chunk.old_lines,
+                new_lines: chunk.new_lines,
+                is_end_of_file: chunk.old_at_eof,
+            });
+        }
+        Ok(Some(Hunk::UpdateFile {
+            path: old_path,
+            move_path,
+            mode,
+            chunks,
+        }))
+    }
+}
+
+struct DiffParser<'a> {
+    lines: &'a [&'a str],
+    index: usize,
+}
+
+impl<'a> DiffParser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.lines.get(self.index).copied()
+    }
+
+    fn error(&self, message: String) -> ParseError {
+        InvalidHunkError {
+            message,
+            line_number: self.index + 1,
+        }
+    }
+
+    /// Parses a `diff --git` section: extended headers, then optional
+    /// `---`/`+++` lines and hunks.
+    fn parse_git_file(&mut self) -> Result<FileDiff, ParseError> {
+        let line_number = self.index + 1;
+        let header = self.lines[self.index][GIT_DIFF_MARKER.len()..].trim();
+        self.index += 1;
+        let (old_path, new_path) = split_git_header_paths(header).ok_or_else(|| {
+            self.error(format!("could not read the paths in 'diff --git {header}'"))
+        })?;
+        let mut file = FileDiff {
+            line_number,
+            old_path: Some(old_path),
+            new_path: Some(new_path),
+            ..Default::default()
+        };
+
+        while let Some(line) = self.peek() {
+            if line.starts_with(OLD_FILE_MARKER)
+                || line.starts_with(HUNK_HEADER_MARKER)
+                || line.starts_with(GIT_DIFF_MARKER)
+            {
+                break;
+            }
+            if let Some(mode) = line.strip_prefix("new file mode ") {
+                file.is_new = true;
+                file.new_mode = Some(self.parse_mode(mode)?);
+            } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+                file.is_deleted = true;
+                file.old_mode = Some(self.parse_mode(mode)?);
+            } else if let Some(mode) = line.strip_prefix("old mode ") {
+                file.old_mode = Some(self.parse_mode(mode)?);
+            } else if let Some(mode) = line.strip_prefix("new mode ") {
+                file.new_mode = Some(self.parse_mode(mode)?);
+            } else if let Some(path) = line.strip_prefix("rename from ") {
+                file.old_path = Some(PathBuf::from(unquote(path)));
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                file.new_path = Some(PathBuf::from(unquote(path)));
+            } else if line.starts_with("copy from ") || line.starts_with("copy to ") {
+                return Err(self.error("copied files are not supported".to_string()));
+            } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+                return Err(self.error("binary diffs are not supported".to_string()));
+            } else if !(line.trim().is_empty()
+                || line.starts_with("index ")
+                || line.starts_with("similarity index ")
+                || line.starts_with("dissimilarity index "))
+            {
+                return Err(self.error(format!("unexpected line in diff header: '{line}'")));
+            }
+            self.index += 1;
+        }
+
+        if self
+            .peek()
+            .is_some_and(|line| line.starts_with(OLD_FILE_MARKER))
+        {
+            let (old_path, new_path) = self.parse_file_header(true)?;
+            file.old_path = old_path;
+            file.new_path = new_path;
+        }
+        file.chunks = self.parse_chunks()?;
+        Ok(file)
+    }
+
+    /// Parses a git mode such as `100755`, keeping the file type bits.
+    fn parse_mode(&self, mode: &str) -> Result<u32, ParseError> {
+        let mode = mode.trim();
+        u32::from_str_radix(mode, 8).map_err(|_| self.error(format!("invalid file mode '{mode}'")))
+    }
+
+    /// Parses a plain `---`/`+++` section as produced by `diff -u`.
+    fn parse_plain_file(&mut self) -> Result<FileDiff, ParseError> {
+        let line_number = self.index + 1;
+        let (old_path, new_path) = self.parse_file_header(false)?;
+        let chunks = self.parse_chunks()?;
+        Ok(FileDiff {
+            line_number,
+            old_path,
+            new_path,
+            chunks,
+            ..Default::default()
+        })
+    }
+
+    /// Parses the `--- old` and `+++ new` lines. Git diffs always prefix
+    /// paths with `a/` and `b/`; plain diffs only have them stripped when
+    /// both sides use them.
+    fn parse_file_header(
+        &mut self,
+        git: bool,
+    ) -> Result<(Option<PathBuf>, Option<PathBuf>), ParseError> {
+        let old = header_path(&self.lines[self.index][OLD_FILE_MARKER.len()..]);
+        self.index += 1;
+        let Some(new_line) = self
+            .peek()
+            .and_then(|line| line.strip_prefix(NEW_FILE_MARKER))
+        else {
+            return Err(self.error("expected '+++ <path>' after '--- <path>'".to_string()));
+        };
+        let new = header_path(new_line);
+        self.index += 1;
+
+        let strip = git
+            || (old.as_deref().is_none_or(|p| p.starts_with("a/"))
+                && new.as_deref().is_none_or(|p| p.starts_with("b/")));
+        let to_path = |path: Option<String>, prefix: &str| {
+            path.map(|path| match path.strip_prefix(prefix) {
+                Some(stripped) if strip => PathBuf::from(stripped),
+                _ => PathBuf::from(path),
+            })
+        };
+        Ok((to_path(old, "a/"), to_path(new, "b/")))
+    }
+
+    fn parse_chunks(&mut self) -> Result<Vec<DiffChunk>, ParseError> {
+        let mut chunks = Vec::new();
+        while let Some(line) = self.peek() {
+            if line.starts_with(HUNK_HEADER_MARKER) {
+                chunks.push(self.parse_chunk()?);
+            } else if line.trim().is_empty() {
+                self.index += 1;
+            } else {
+                break;
+            }
+        }
+        Ok(chunks)
+    }
+
+    fn parse_chunk(&mut self) -> Result<DiffChunk, ParseError> {
+        let line_number = self.index + 1;
+        let header = self.lines[self.index];
+        let (old_start, mut old_remaining, mut new_remaining) = parse_hunk_header(header)
+            .ok_or_else(|| self.error(format!("invalid hunk header '{header}'")))?;
+        self.index += 1;
+
+        let mut chunk = DiffChunk {
+            line_number,
+            old_start,
+            old_lines: Vec::new(),
+            new_lines: Vec::new(),
+            old_at_eof: false,
+            new_missing_newline: false,
+        };
+        // Which side the previous line belonged to, for `\ No newline`.
+        let mut last_kind = ' ';
+        while let Some(line) = self.peek() {
+            let counts_satisfied = old_remaining == 0 && new_remaining == 0;
+            if counts_satisfied && starts_file_or_chunk(self.lines, self.index) {
+                break;
+            }
+            let kind = match line.chars().next() {
+                // Some tools strip the single space of empty context lines.
+                None if counts_satisfied => break,
+                None => ' ',
+                Some(kind @ (' ' | '-' | '+')) => kind,
+                Some('\\') if line.starts_with(NO_NEWLINE_MARKER) => {
+                    if last_kind != '+' {
+                        chunk.old_at_eof = true;
+                    }
+                    if last_kind != '-' {
+                        chunk.new_missing_newline = true;
+                    }
+                    self.index += 1;
+                    continue;
+                }
+                Some(_) => break,
+            };
+            let text = line.get(1..).unwrap_or_default().to_string();
+            if kind != '+' {
+                chunk.old_lines.push(text.clone());
+                old_remaining = old_remaining.saturating_sub(1);
+            }
+            if kind != '-' {
+                chunk.new_lines.push(text);
+                new_remaining = new_remaining.saturating_sub(1);
+            }
+            last_kind = kind;
+            self.index += 1;
+        }
+        Ok(chunk)
+    }
+}
+
+/// Whether the line at `index` starts a new file section or hunk.
+fn starts_file_or_chunk(lines: &[&str], index: usize) -> bool {
+    let line = lines[index];
+    line.starts_with(HUNK_HEADER_MARKER)
+        || line.starts_with(GIT_DIFF_MARKER)
+        || (line.starts_with(OLD_FILE_MARKER)
+            && lines
+                .get(index + 1)
+                .is_some_and(|next| next.starts_with(NEW_FILE_MARKER)))
+}
+
+/// Parses `@@ -a,b +c,d @@` into `(a, b, d)`. Counts default to 1.
+fn parse_hunk_header(header: &str) -> Option<(usize, usize, usize)> {
+    let ranges = header.strip_prefix("@@ ")?;
+    let end = ranges.find(" @@")?;
+    let mut parts = ranges[..end].split_whitespace();
+    let old = parts.next()?.strip_prefix('-')?;
+    let new = parts.next()?.strip_prefix('+')?;
+    let parse_range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_count) = parse_range(old)?;
+    let (_, new_count) = parse_range(new)?;
+    Some((old_start, old_count, new_count))
+}
+
+/// The path on a `---` or `+++` line, without any trailing timestamp.
+/// Returns `None` for `/dev/null`.
+fn header_path(rest: &str) -> Option<String> {
+    let path = rest.split('\t').next().unwrap_or(rest).trim_end();
+    let path = unquote(path);
+    (path != DEV_NULL).then_some(path)
+}
+
+fn is_symlink_mode(mode: u32) -> bool {
+    mode & GIT_FILE_TYPE_MASK == GIT_SYMLINK_TYPE
+}
+
+fn permission_bits(mode: u32) -> u32 {
+    mode & 0o7777
+}
+
+/// Splits the `a/old b/new` part of a `diff --git` line.
+fn split_git_header_paths(header: &str) -> Option<(PathBuf, PathBuf)> {
+    if let Some(rest) = header.strip_prefix('"') {
+        // Quoted paths: `"a/old" "b/new"`.
+        let end = rest.find("\" ")?;
+        let old = unquote(&header[..end + 2]);
+        let new = unquote(rest[end + 1..].trim());
+        return Some((
+            PathBuf::from(old.strip_prefix("a/")?),
+            PathBuf::from(new.strip_prefix("b/")?),
+        ));
+    }
+    let rest = header.strip_prefix("a/")?;
+    // When the paths are equal (the common case) the split point is exactly in
+    // the middle, which also handles paths containing " b/".
+    let half = rest.len().checked_sub(3)? / 2;
+    if rest.len() % 2 == 1
+        && rest.is_char_boundary(half)
+        && rest[half..].starts_with(" b/")
+        && rest[..half] == rest[half + 3..]
+    {
+        let path = PathBuf::from(&rest[..half]);
+        return Some((path.clone(), path));
+    }
+    let (old, new) = rest.split_once(" b/")?;
+    Some((PathBuf::from(old), PathBuf::from(new)))
+}
+
+/// Removes C-style quotes that git adds around unusual paths.
+fn unquote(path: &str) -> String {
+    let Some(inner) = path
+        .strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+    else {
+        return path.to_string();
+    };
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some(escaped @ ('"' | '\\'))) => {
+                unquoted.push(escaped);
+                chars.next();
+            }
+            ('\\', Some('t')) => {
+                unquoted.push('\t');
+                chars.next();
+            }
+            _ => unquoted.push(c),
+        }
+    }
+    unquoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parse(diff: &str) -> Result<Vec<Hunk>, ParseError> {
+        let lines: Vec<&str> = diff.trim().lines().collect();
+        assert!(is_unified_diff(&lines));
+        parse_unified_diff(&lines)
+    }
+
+    fn chunk(old_lines: &[&str], new_lines: &[&str]) -> UpdateFileChunk {
+        UpdateFileChunk {
+            change_context: None,
+            old_lines: old_lines.iter().map(|s| s.to_string()).collect(),
+            new_lines: new_lines.iter().map(|s| s.to_string()).collect(),
+            is_end_of_file: false,
+        }
+    }
+
+    #[test]
+    fn parses_git_diff_with_update_add_delete_and_rename() {
+        let diff = r#"
+diff --git a/src/lib.rs b/src/lib.rs
+index 83db48f..bf269f4 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@ mod foo;
+ fn main() {
+-    println!("hi");
++    println!("hello");
+ }
+@@ -10,2 +10,3 @@
+ fn other() {}
++fn added() {}
+
+diff --git a/docs/new.md b/docs/new.md
+new file mode 100644
+index 0000000..e69de29
+--- /dev/null
++++ b/docs/new.md
+@@ -0,0 +1,2 @@
++# Title
++body
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+index e69de29..0000000
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/before.rs b/after.rs
+similarity index 100%
+rename from before.rs
+rename to after.rs
+"#;
+        assert_eq!(
+            parse(diff),
+            Ok(vec![
+                Hunk::UpdateFile {
+                    path: PathBuf::from("src/lib.rs"),
+                    move_path: None,
+                    mode: None,
+                    chunks: vec![
+                        chunk(
+                            &["fn main() {", "    println!(\"hi\");", "}"],
+                            &["fn main() {", "    println!(\"hello\");", "}"],
+                        ),
+                        chunk(
+                            &["fn other() {}", ""],
+                            &["fn other() {}", "fn added() {}", ""]
+                        ),
+                    ],
+                },
+                Hunk::AddFile {
+                    path: PathBuf::from("docs/new.md"),
+                    contents: "# Title\nbody\n".to_string(),
+                    mode: None,
+                },
+                Hunk::DeleteFile {
+                    path: PathBuf::from("old.txt"),
+                },
+                Hunk::UpdateFile {
+                    path: PathBuf::from("before.rs"),
+                    move_path: Some(PathBuf::from("after.rs")),
+                    mode: None,
+                    chunks: Vec::new(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_plain_unified_diff_with_wrong_counts_and_timestamps() {
+        let diff = "--- config.toml\t2024-01-01 00:00:00\n\
+                    +++ config.toml\t2024-01-02 00:00:00\n\
+                    @@ -1,2 +1,2 @@\n\
+                    \x20[server]\n\
+                    -port = 80\n\
+                    +port = 8080\n\
+                    \x20host = \"localhost\"\n\
+                    \\ No newline at end of file";
+        assert_eq!(
+            parse(diff),
+            Ok(vec![Hunk::UpdateFile {
+                path: PathBuf::from("config.toml"),
+                move_path: None,
+                mode: None,
+                chunks: vec![UpdateFileChunk {
+                    is_end_of_file: true,
+                    ..chunk(
+                        &["[server]", "port = 80", "host = \"localhost\""],
+                        &["[server]", "port = 8080", "host = \"localhost\""],
+                    )
+                }],
+            }])
+        );
+    }
+
+    #[test]
+    fn rejects_insertions_without_context() {
+        let diff = "--- a/file.txt\n+++ b/file.txt\n@@ -3,0 +4 @@\n+inserted";
+        assert_eq!(
+            parse(diff),
+            Err(InvalidHunkError {
+                message: "hunk for 'file.txt' only adds lines and has no context lines; \
+                          regenerate the diff with context (for example `git diff -U3`)"
+                    .to_string(),
+                line_number: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_git_modes_and_symlinks() {
+        let diff = r#"diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100755
+diff --git a/bin/tool b/bin/tool
+new file mode 100755
+index 0000000..e69de29
+--- /dev/null
++++ b/bin/tool
+@@ -0,0 +1 @@
++#!/bin/sh
+diff --git a/current b/current
+new file mode 120000
+index 0000000..e69de29
+--- /dev/null
++++ b/current
+@@ -0,0 +1 @@
++releases/v2
+\ No newline at end of file
+"#;
+        assert_eq!(
+            parse(diff),
+            Ok(vec![
+                Hunk::UpdateFile {
+                    path: PathBuf::from("run.sh"),
+                    move_path: None,
+                    mode: Some(0o755),
+                    chunks: Vec::new(),
+                },
+                Hunk::AddFile {
+                    path: PathBuf::from("bin/tool"),
+                    contents: "#!/bin/sh\n".to_string(),
+                    mode: Some(0o755),
+                },
+                Hunk::AddSymlink {
+                    path: PathBuf::from("current"),
+                    target: PathBuf::from("releases/v2"),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn splits_git_header_paths() {
+        assert_eq!(
+            split_git_header_paths("a/dir with b/x.rs b/dir with b/x.rs"),
+            Some((
+                PathBuf::from("dir with b/x.rs"),
+                PathBuf::from("dir with b/x.rs")
+            ))
+        );
+        assert_eq!(
+            split_git_header_paths("a/old.rs b/new.rs"),
+            Some((PathBuf::from("old.rs"), PathBuf::from("new.rs")))
+        );
+        assert_eq!(
+            split_git_header_paths(r#""a/tab\there.rs" "b/tab\there.rs""#),
+            Some((PathBuf::from("tab\there.rs"), PathBuf::from("tab\there.rs")))
+        );
+    }
+}
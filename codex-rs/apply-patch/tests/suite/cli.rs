@@ -88,3 +88,72 @@ fn test_apply_patch_cli_stdin_add_and_update() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_apply_patch_cli_check_json_leaves_files_untouched() -> anyhow::Result<()> {
+    let tmp = tempdir()?;
+    let file = "check.txt";
+    let absolute_path = tmp.path().join(file);
+    fs::write(&absolute_path, "one\ntwo\n")?;
+
+    let patch = format!(
+        r#"*** Begin Patch
+*** Update File: {file}
+@@
+ one
+-two
++three
+*** End Patch"#
+    );
+    Command::cargo_bin("apply_patch")
+        .expect("should find apply_patch binary")
+        .args(["--check", "--json"])
+        .arg(patch)
+        .current_dir(tmp.path())
+        .assert()
+        .success()
+        .stdout(format!(
+            "{{\"ok\":true,\"mode\":\"check\",\"files\":[{{\"action\":\"update\",\"path\":\"{file}\",\"chunks\":[{{\"line\":1,\"fuzz\":\"exact\"}}]}}]}}\n"
+        ));
+    assert_eq!(fs::read_to_string(&absolute_path)?, "one\ntwo\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_apply_patch_cli_dry_run_diff_in_cwd() -> anyhow::Result<()> {
+    let tmp = tempdir()?;
+    let file = "dry_run.txt";
+    let absolute_path = tmp.path().join(file);
+    fs::write(&absolute_path, "hello\n")?;
+
+    let patch = format!(
+        r#"*** Begin Patch
+*** Update File: {file}
+@@
+-hello
++world
+*** End Patch"#
+    );
+    let assert = Command::cargo_bin("apply_patch")
+        .expect("should find apply_patch binary")
+        .args(["--dry-run", "--diff", "--cwd"])
+        .arg(tmp.path())
+        .arg(patch)
+        .assert()
+        .success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone())?;
+    assert!(
+        stdout.starts_with(&format!(
+            "Dry run. Would update the following files:\nM {file}\n"
+        )),
+        "unexpected output: {stdout}"
+    );
+    assert!(
+        stdout.contains("-hello\n+world\n"),
+        "missing diff: {stdout}"
+    );
+    assert_eq!(fs::read_to_string(&absolute_path)?, "hello\n");
+
+    Ok(())
+}
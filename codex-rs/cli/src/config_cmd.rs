@@ -0,0 +1,237 @@
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::find_codex_home;
+use codex_core::config::load_config_as_toml;
+use codex_core::config_edit::set_config_value;
+use codex_core::config_inspect::IssueSeverity;
+use codex_core::config_inspect::effective_config_entries;
+use codex_core::config_inspect::validate_config;
+
+/// Inspect, check and edit the configuration.
+///
+/// Subcommands:
+/// - `show`     — print the effective config and where each value comes from
+/// - `validate` — report unknown keys, invalid values, bad paths and providers
+/// - `set`      — write a value to `~/.codex/config.toml`
+#[derive(Debug, clap::Parser)]
+pub struct ConfigCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub cmd: ConfigSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ConfigSubcommand {
+    /// Print the effective config with the source of each value.
+    Show(ShowArgs),
+
+    /// Check the config files for mistakes.
+    Validate(ValidateArgs),
+
+    /// Set a value in `config.toml`.
+    Set(SetArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ShowArgs {
+    /// Resolve the config with this profile active.
+    #[arg(long, short = 'p')]
+    pub profile: Option<String>,
+
+    /// Output the entries as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ValidateArgs {
+    /// Output the issues as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SetArgs {
+    /// Dotted key to set, e.g. `model` or `tui.theme`.
+    pub key: String,
+
+    /// Value in TOML syntax. Anything that does not parse as TOML is written
+    /// as a string, so `o3` and `'"o3"'` are equivalent.
+    pub value: String,
+
+    /// Write the value under `[profiles.<PROFILE>]` instead of the top level.
+    #[arg(long, short = 'p')]
+    pub profile: Option<String>,
+}
+
+impl ConfigCli {
+    pub async fn run(self) -> Result<()> {
+        let ConfigCli {
+            config_overrides,
+            cmd,
+        } = self;
+
+        match cmd {
+            ConfigSubcommand::Show(args) => run_show(&config_overrides, args),
+            ConfigSubcommand::Validate(args) => run_validate(args),
+            ConfigSubcommand::Set(args) => run_set(args).await,
+        }
+    }
+}
+
+fn run_show(config_overrides: &CliConfigOverrides, args: ShowArgs) -> Result<()> {
+    let overrides = config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+    let config = Config::load_with_cli_overrides(
+        overrides.clone(),
+        ConfigOverrides {
+            config_profile: args.profile,
+            ..Default::default()
+        },
+    )
+    .context("failed to load configuration")?;
+    let entries =
+        effective_config_entries(&config, &overrides).context("failed to read configuration")?;
+
+    if args.json {
+        let output = serde_json::to_string_pretty(&entries)?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    let rows: Vec<(String, String)> = entries
+        .iter()
+        .map(|entry| {
+            (
+                format!("{} = {}", entry.key, entry.value),
+                entry.source.to_string(),
+            )
+        })
+        .collect();
+    let width = rows
+        .iter()
+        .map(|(setting, _)| setting.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (setting, source) in rows {
+        println!("{setting:<width$}  # {source}");
+    }
+    Ok(())
+}
+
+fn run_validate(args: ValidateArgs) -> Result<()> {
+    let codex_home = find_codex_home().context("failed to find codex home")?;
+    let cwd = std::env::current_dir().context("failed to resolve current directory")?;
+    let issues = validate_config(&codex_home, &cwd);
+
+    if args.json {
+        let output = serde_json::to_string_pretty(&issues)?;
+        println!("{output}");
+    } else if issues.is_empty() {
+        println!("No problems found.");
+    } else {
+        for issue in &issues {
+            println!("{issue}");
+        }
+    }
+
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == IssueSeverity::Error)
+        .count();
+    if errors > 0 {
+        bail!("found {errors} error(s) in the configuration");
+    }
+    Ok(())
+}
+
+async fn run_set(args: SetArgs) -> Result<()> {
+    let SetArgs {
+        key,
+        value,
+        profile,
+    } = args;
+    let keys = toml_edit::Key::parse(&key).map_err(|e| anyhow!("invalid key `{key}`: {e}"))?;
+    let mut segments: Vec<&str> = Vec::new();
+    if let Some(profile) = profile.as_deref() {
+        segments.extend(["profiles", profile]);
+    }
+    segments.extend(keys.iter().map(toml_edit::Key::get));
+
+    let codex_home = find_codex_home().context("failed to find codex home")?;
+    let cwd = std::env::current_dir().context("failed to resolve current directory")?;
+    let issues = set_config_value(&codex_home, &cwd, &segments, parse_value(&value)).await?;
+    println!("Set {} = {value}.", segments.join("."));
+
+    // Point out when the value has no effect because the active profile
+    // sets the same key.
+    if profile.is_none()
+        && let [top_level] = segments.as_slice()
+        && let Ok(root) = load_config_as_toml(&codex_home)
+        && let Some(active) = root.get("profile").and_then(|p| p.as_str())
+        && root
+            .get("profiles")
+            .and_then(|profiles| profiles.get(active))
+            .and_then(|p| p.get(*top_level))
+            .is_some()
+    {
+        println!(
+            "Note: the active profile `{active}` also sets `{top_level}` and takes precedence."
+        );
+    }
+
+    let dotted = segments.join(".");
+    for issue in issues {
+        if issue.key.as_deref() == Some(dotted.as_str()) {
+            println!("{issue}");
+        }
+    }
+    Ok(())
+}
+
+/// Parse `raw` the way `-c key=value` values are parsed: as a TOML value,
+/// falling back to a plain string.
+fn parse_value(raw: &str) -> toml_edit::Item {
+    let parsed = format!("value = {raw}")
+        .parse::<toml_edit::DocumentMut>()
+        .ok()
+        .and_then(|doc| {
+            doc.get("value")
+                .and_then(toml_edit::Item::as_value)
+                .cloned()
+        });
+    match parsed {
+        Some(mut value) => {
+            // Drop the whitespace around the parsed value so it is formatted
+            // like any other value in the file.
+            value.decor_mut().clear();
+            toml_edit::Item::Value(value)
+        }
+        None => toml_edit::value(raw),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_value_falls_back_to_strings() {
+        assert_eq!(parse_value("true").as_bool(), Some(true));
+        assert_eq!(parse_value("\"o3\"").as_str(), Some("o3"));
+        assert_eq!(parse_value("o3").as_str(), Some("o3"));
+        assert_eq!(
+            parse_value("[\"PATH\", \"HOME\"]")
+                .as_array()
+                .map(toml_edit::Array::len),
+            Some(2)
+        );
+    }
+}
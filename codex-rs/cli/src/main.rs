@@ -17,10 +17,16 @@ use codex_exec::Cli as ExecCli;
 use codex_tui::Cli as TuiCli;
 use std::path::PathBuf;
 
+mod config_cmd;
 mod mcp_cmd;
+mod replay_cmd;
+mod sessions_cmd;
 
+use crate::config_cmd::ConfigCli;
 use crate::mcp_cmd::McpCli;
 use crate::proto::ProtoCli;
+use crate::replay_cmd::ReplayCli;
+use crate::sessions_cmd::SessionsCli;
 
 /// Codex CLI
 ///
@@ -79,6 +85,15 @@ enum Subcommand {
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Search and inspect recorded sessions.
+    Sessions(SessionsCli),
+
+    /// Re-run a recorded session against another model and compare the results.
+    Replay(ReplayCli),
+
+    /// Show, validate and edit the configuration.
+    Config(ConfigCli),
+
     /// Internal: generate TypeScript protocol bindings.
     #[clap(hide = true)]
     GenerateTs(GenerateTsCommand),
@@ -207,6 +222,27 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
         }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            prepend_config_flags(
+                &mut sessions_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            sessions_cli.run().await?;
+        }
+        Some(Subcommand::Replay(mut replay_cli)) => {
+            prepend_config_flags(
+                &mut replay_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            replay_cli.run(codex_linux_sandbox_exe).await?;
+        }
+        Some(Subcommand::Config(mut config_cli)) => {
+            prepend_config_flags(
+                &mut config_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            config_cli.run().await?;
+        }
         Some(Subcommand::Login(mut login_cli)) => {
             prepend_config_flags(
                 &mut login_cli.config_overrides,
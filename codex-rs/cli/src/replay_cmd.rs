@@ -0,0 +1,352 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::ConversationManager;
+use codex_core::NewConversation;
+use codex_core::RolloutSummary;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::find_codex_home;
+use codex_core::load_rollout_items;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::resolve_session_file;
+use codex_core::summarize_rollout;
+use codex_protocol::config_types::SandboxMode;
+
+/// Subdirectory of `$CODEX_HOME` holding replay worktrees and reports.
+const REPLAYS_SUBDIR: &str = "replays";
+
+/// Re-run the user inputs of a recorded session against another model.
+///
+/// The session's repository is checked out at the recorded commit into a
+/// fresh git worktree under `$CODEX_HOME/replays`, the recorded prompts are
+/// sent one turn at a time (without approvals, sandboxed to the worktree),
+/// and a report comparing both runs is written next to the worktree.
+#[derive(Debug, clap::Parser)]
+pub struct ReplayCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Session id (UUID) or path to the rollout file to replay.
+    #[arg(value_name = "SESSION")]
+    pub session: String,
+
+    /// Model to replay the session with.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Model provider to use, as defined under `model_providers` (e.g. `oss`).
+    #[arg(long = "provider", value_name = "PROVIDER")]
+    pub model_provider: Option<String>,
+
+    /// Configuration profile from config.toml to specify default options.
+    #[arg(long = "profile", short = 'p')]
+    pub config_profile: Option<String>,
+
+    /// Repository to create the worktree from. Defaults to the directory the
+    /// session was recorded in.
+    #[arg(long, value_name = "DIR")]
+    pub repo: Option<PathBuf>,
+
+    /// Commit to check out. Defaults to the commit recorded with the session,
+    /// or `HEAD` if none was recorded.
+    #[arg(long)]
+    pub commit: Option<String>,
+
+    /// Remove the worktree once the report has been written.
+    #[arg(long)]
+    pub cleanup: bool,
+
+    /// Print the comparison report as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Outcome of driving the replayed conversation.
+struct ReplayRun {
+    rollout_path: PathBuf,
+    summary: RolloutSummary,
+    /// Unified diff reported by the turn diff tracker at the end of each turn.
+    turn_diffs: Vec<Option<String>>,
+}
+
+impl ReplayCli {
+    pub async fn run(self, codex_linux_sandbox_exe: Option<PathBuf>) -> Result<()> {
+        let codex_home = find_codex_home().context("failed to locate codex home")?;
+        let original_path = resolve_session_file(&codex_home, &self.session)
+            .await
+            .with_context(|| format!("failed to find session `{}`", self.session))?;
+        let original_items = load_rollout_items(&original_path)
+            .await
+            .with_context(|| format!("failed to read {}", original_path.display()))?;
+        let original = summarize_rollout(&original_items);
+        if original.user_inputs.is_empty() {
+            bail!("{} has no user inputs to replay", original_path.display());
+        }
+
+        // Resolve where the original session ran relative to its repository
+        // so the replay starts in the same subdirectory of the worktree.
+        let source_dir = match (&self.repo, &original.cwd) {
+            (Some(repo), _) => repo.clone(),
+            (None, Some(cwd)) => cwd.clone(),
+            (None, None) => bail!("session does not record its directory; pass --repo"),
+        };
+        let repo_root = PathBuf::from(
+            git(&source_dir, &["rev-parse", "--show-toplevel"])
+                .await
+                .with_context(|| format!("{} is not in a git repository", source_dir.display()))?
+                .trim(),
+        );
+        let subdir = original
+            .cwd
+            .as_deref()
+            .filter(|_| self.repo.is_none())
+            .and_then(|cwd| cwd.strip_prefix(&repo_root).ok())
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let commit = self
+            .commit
+            .clone()
+            .or_else(|| original.git_commit.clone())
+            .unwrap_or_else(|| "HEAD".to_string());
+
+        let started = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let replay_dir = codex_home
+            .join(REPLAYS_SUBDIR)
+            .join(format!("replay-{started}"));
+        let worktree = replay_dir.join("worktree");
+        tokio::fs::create_dir_all(&replay_dir).await?;
+        git(
+            &repo_root,
+            &[
+                "worktree",
+                "add",
+                "--detach",
+                &worktree.to_string_lossy(),
+                &commit,
+            ],
+        )
+        .await
+        .with_context(|| format!("failed to create worktree at {commit}"))?;
+        let base_commit = git(&worktree, &["rev-parse", "HEAD"])
+            .await?
+            .trim()
+            .to_string();
+
+        let overrides = ConfigOverrides {
+            model: self.model.clone(),
+            model_provider: self.model_provider.clone(),
+            config_profile: self.config_profile.clone(),
+            cwd: Some(worktree.join(&subdir)),
+            // Replays run unattended; keep the agent inside the worktree.
+            approval_policy: Some(AskForApproval::Never),
+            sandbox_mode: Some(SandboxMode::WorkspaceWrite),
+            codex_linux_sandbox_exe,
+            ..Default::default()
+        };
+        let cli_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(|e| anyhow!(e))?;
+        let config = Config::load_with_cli_overrides(cli_overrides, overrides)
+            .context("failed to load configuration")?;
+
+        let run = drive_replay(config, &codex_home, &original.user_inputs, self.json).await?;
+
+        // Stage everything (including new files) so the diff against the base
+        // commit captures the full effect of the replay.
+        git(&worktree, &["add", "-A"]).await?;
+        let final_diff = git(&worktree, &["diff", "--cached", "--no-color", &base_commit]).await?;
+
+        let report = serde_json::json!({
+            "original": summary_json(&original_path, &original),
+            "replay": summary_json(&run.rollout_path, &run.summary),
+            "base_commit": base_commit,
+            "worktree": worktree,
+            "turn_diffs": run.turn_diffs,
+            "final_diff": final_diff,
+        });
+        let report_path = replay_dir.join("report.json");
+        let diff_path = replay_dir.join("final.diff");
+        tokio::fs::write(&report_path, serde_json::to_string_pretty(&report)?).await?;
+        tokio::fs::write(&diff_path, &final_diff).await?;
+
+        if self.cleanup {
+            git(
+                &repo_root,
+                &["worktree", "remove", "--force", &worktree.to_string_lossy()],
+            )
+            .await
+            .context("failed to remove worktree")?;
+        }
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+
+        println!();
+        print_comparison(&original, &run.summary);
+        println!();
+        let files_changed = final_diff
+            .lines()
+            .filter(|line| line.starts_with("diff --git "))
+            .count();
+        println!(
+            "Final diff: {files_changed} file(s) changed ({})",
+            diff_path.display()
+        );
+        println!("Replay rollout: {}", run.rollout_path.display());
+        if !self.cleanup {
+            println!("Worktree: {}", worktree.display());
+        }
+        println!("Report: {}", report_path.display());
+        Ok(())
+    }
+}
+
+/// Send each recorded input as its own turn, waiting for the turn to finish
+/// before sending the next one, then shut the session down and summarize the
+/// rollout it recorded.
+async fn drive_replay(
+    config: Config,
+    codex_home: &Path,
+    user_inputs: &[String],
+    quiet: bool,
+) -> Result<ReplayRun> {
+    let conversation_manager =
+        ConversationManager::new(AuthManager::shared(codex_home.to_path_buf()));
+    let NewConversation {
+        conversation_id,
+        conversation,
+        session_configured,
+    } = conversation_manager.new_conversation(config).await?;
+    if !quiet {
+        eprintln!(
+            "Replaying {} turn(s) with {}",
+            user_inputs.len(),
+            session_configured.model
+        );
+    }
+
+    let mut turn_diffs = Vec::with_capacity(user_inputs.len());
+    for (index, input) in user_inputs.iter().enumerate() {
+        if !quiet {
+            let first_line = input.lines().next().unwrap_or_default();
+            eprintln!("[{}/{}] {first_line}", index + 1, user_inputs.len());
+        }
+        let items = vec![InputItem::Text {
+            text: input.clone(),
+        }];
+        let turn_id = conversation.submit(Op::UserInput { items }).await?;
+        let mut turn_diff = None;
+        loop {
+            let event = conversation.next_event().await?;
+            if event.id != turn_id {
+                continue;
+            }
+            match event.msg {
+                EventMsg::TurnDiff(ev) => turn_diff = Some(ev.unified_diff),
+                EventMsg::Error(ev) => eprintln!("error: {}", ev.message),
+                EventMsg::TaskComplete(_) | EventMsg::TurnAborted(_) => break,
+                _ => {}
+            }
+        }
+        turn_diffs.push(turn_diff);
+    }
+
+    conversation.submit(Op::Shutdown).await?;
+    while !matches!(
+        conversation.next_event().await?.msg,
+        EventMsg::ShutdownComplete
+    ) {}
+
+    // The rollout may have been renamed on shutdown (e.g. compressed), so look
+    // it up again by id.
+    let rollout_path = resolve_session_file(codex_home, &conversation_id.to_string()).await?;
+    let items = load_rollout_items(&rollout_path).await?;
+    Ok(ReplayRun {
+        rollout_path,
+        summary: summarize_rollout(&items),
+        turn_diffs,
+    })
+}
+
+fn summary_json(rollout_path: &Path, summary: &RolloutSummary) -> serde_json::Value {
+    serde_json::json!({
+        "rollout": rollout_path,
+        "model": summary.model,
+        "turns": summary.turns(),
+        "model_requests": summary.model_requests,
+        "tool_calls": summary.tool_calls,
+        "token_usage": summary.token_usage,
+    })
+}
+
+fn print_comparison(original: &RolloutSummary, replay: &RolloutSummary) {
+    let tokens = |summary: &RolloutSummary| {
+        summary
+            .token_usage
+            .as_ref()
+            .map(|usage| usage.total_tokens.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let rows = [
+        (
+            "model",
+            original.model.clone().unwrap_or_else(|| "-".to_string()),
+            replay.model.clone().unwrap_or_else(|| "-".to_string()),
+        ),
+        (
+            "turns",
+            original.turns().to_string(),
+            replay.turns().to_string(),
+        ),
+        (
+            "model requests",
+            original.model_requests.to_string(),
+            replay.model_requests.to_string(),
+        ),
+        (
+            "tool calls",
+            original.tool_calls.to_string(),
+            replay.tool_calls.to_string(),
+        ),
+        ("total tokens", tokens(original), tokens(replay)),
+    ];
+    println!("{:<16} {:<24} {:<24}", "", "original", "replay");
+    for (label, before, after) in rows {
+        println!("{label:<16} {before:<24} {after:<24}");
+    }
+}
+
+/// Run `git` in `dir` and return its stdout, failing on a non-zero exit.
+async fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .await
+        .context("failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,444 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::PruneCriteria;
+use codex_core::SearchMatchKind;
+use codex_core::SessionSearchQuery;
+use codex_core::archive_session_file;
+use codex_core::archived_sessions_size;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::delete_session_file;
+use codex_core::list_session_files;
+use codex_core::load_rollout_items;
+use codex_core::parse_age;
+use codex_core::parse_date_bound;
+use codex_core::resolve_session_file;
+use codex_core::search_sessions;
+use codex_core::select_prunable;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+
+/// Inspect and manage recorded sessions under `$CODEX_HOME/sessions`.
+///
+/// Subcommands:
+/// - `list`    — list sessions with their size and total disk usage
+/// - `show`    — print a session's metadata and messages
+/// - `search`  — full-text search across past sessions
+/// - `rm`      — permanently delete sessions
+/// - `archive` — move sessions to `$CODEX_HOME/archived_sessions`
+/// - `prune`   — delete sessions by age, keeping the most recent ones
+#[derive(Debug, clap::Parser)]
+pub struct SessionsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub cmd: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionsSubcommand {
+    /// List recorded sessions, newest first, with disk usage.
+    List(ListArgs),
+
+    /// Show a session's metadata and conversation.
+    Show(ShowArgs),
+
+    /// Search message text, commands and touched file paths across all sessions.
+    Search(SearchArgs),
+
+    /// Permanently delete one or more sessions.
+    #[clap(visible_alias = "remove")]
+    Rm(SessionRefArgs),
+
+    /// Move one or more sessions to the archived sessions directory.
+    Archive(SessionRefArgs),
+
+    /// Delete old sessions.
+    Prune(PruneArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// Maximum number of sessions to list.
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Output the sessions as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ShowArgs {
+    /// Session id (UUID) or path to a rollout file.
+    #[arg(value_name = "SESSION")]
+    pub session: String,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SessionRefArgs {
+    /// Session ids (UUIDs) or paths to rollout files.
+    #[arg(value_name = "SESSION", required = true, num_args = 1..)]
+    pub sessions: Vec<String>,
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(group(
+    clap::ArgGroup::new("criteria")
+        .required(true)
+        .multiple(true)
+        .args(["older_than", "keep"])
+))]
+pub struct PruneArgs {
+    /// Delete sessions started longer ago than this (e.g. `30d`, `12h`, `2w`).
+    #[arg(long, value_name = "AGE", value_parser = parse_age_arg)]
+    pub older_than: Option<std::time::Duration>,
+
+    /// Always keep this many of the most recent sessions.
+    #[arg(long, value_name = "N")]
+    pub keep: Option<usize>,
+
+    /// Archive matching sessions instead of deleting them.
+    #[arg(long)]
+    pub archive: bool,
+
+    /// Print what would be removed without touching any files.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SearchArgs {
+    /// Text to search for (case-insensitive).
+    #[arg(required = true, num_args = 1..)]
+    pub query: Vec<String>,
+
+    /// Only include sessions started in this directory or below it.
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Only include sessions started on or after this date (YYYY-MM-DD or RFC 3339).
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<String>,
+
+    /// Only include sessions started before this date (YYYY-MM-DD or RFC 3339).
+    #[arg(long, value_name = "DATE")]
+    pub until: Option<String>,
+
+    /// Only include sessions recorded on this git branch.
+    #[arg(long, value_name = "BRANCH")]
+    pub branch: Option<String>,
+
+    /// Maximum number of sessions to report.
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Output the matches as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl SessionsCli {
+    pub async fn run(self) -> Result<()> {
+        let SessionsCli {
+            config_overrides,
+            cmd,
+        } = self;
+
+        match cmd {
+            SessionsSubcommand::List(args) => run_list(&config_overrides, args).await,
+            SessionsSubcommand::Show(args) => run_show(&config_overrides, args).await,
+            SessionsSubcommand::Search(args) => run_search(&config_overrides, args).await,
+            SessionsSubcommand::Rm(args) => run_remove(&config_overrides, args, false).await,
+            SessionsSubcommand::Archive(args) => run_remove(&config_overrides, args, true).await,
+            SessionsSubcommand::Prune(args) => run_prune(&config_overrides, args).await,
+        }
+    }
+}
+
+fn load_config(config_overrides: &CliConfigOverrides) -> Result<Config> {
+    let overrides = config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+    Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+        .context("failed to load configuration")
+}
+
+fn parse_age_arg(raw: &str) -> Result<std::time::Duration, String> {
+    parse_age(raw).ok_or_else(|| {
+        format!("invalid age '{raw}' (use a number followed by s, m, h, d or w, e.g. 30d)")
+    })
+}
+
+/// Format a byte count using binary units, e.g. `1.5 MiB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{value:.1} {unit}")
+}
+
+async fn run_list(config_overrides: &CliConfigOverrides, args: ListArgs) -> Result<()> {
+    let config = load_config(config_overrides)?;
+    let files = list_session_files(&config.codex_home)
+        .await
+        .context("failed to list sessions")?;
+    let total_bytes: u64 = files.iter().map(|f| f.size_bytes).sum();
+    let archived_bytes = archived_sessions_size(&config.codex_home)
+        .await
+        .context("failed to measure archived sessions")?;
+    let shown = &files[..args.limit.unwrap_or(files.len()).min(files.len())];
+
+    if args.json {
+        let sessions: Vec<_> = shown
+            .iter()
+            .map(|f| {
+                serde_json::json!({
+                    "id": f.conversation_id.to_string(),
+                    "started_at": f.started_at_display(),
+                    "path": f.path,
+                    "size_bytes": f.size_bytes,
+                })
+            })
+            .collect();
+        let output = serde_json::to_string_pretty(&serde_json::json!({
+            "sessions": sessions,
+            "total_sessions": files.len(),
+            "total_bytes": total_bytes,
+            "archived_bytes": archived_bytes,
+        }))?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    if files.is_empty() {
+        println!("No sessions recorded yet.");
+    } else {
+        for f in shown {
+            println!(
+                "{}  {}  {:>10}  {}",
+                f.started_at_display(),
+                f.conversation_id,
+                format_size(f.size_bytes),
+                f.path.display()
+            );
+        }
+        if shown.len() < files.len() {
+            println!("… {} more", files.len() - shown.len());
+        }
+        println!();
+    }
+    println!(
+        "{} sessions using {} ({} archived)",
+        files.len(),
+        format_size(total_bytes),
+        format_size(archived_bytes)
+    );
+    Ok(())
+}
+
+async fn run_show(config_overrides: &CliConfigOverrides, args: ShowArgs) -> Result<()> {
+    let config = load_config(config_overrides)?;
+    let path = resolve_session_file(&config.codex_home, &args.session)
+        .await
+        .with_context(|| format!("failed to find session '{}'", args.session))?;
+    let items = load_rollout_items(&path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+    println!("{}", path.display());
+    println!("  size: {}", format_size(size));
+    for item in items {
+        match item {
+            RolloutItem::SessionMeta(meta_line) => {
+                let meta = meta_line.meta;
+                println!("  id: {}", meta.id);
+                println!("  started: {}", meta.timestamp);
+                println!("  cwd: {}", meta.cwd.display());
+                println!("  cli version: {}", meta.cli_version);
+                if let Some(git) = meta_line.git {
+                    if let Some(branch) = git.branch {
+                        println!("  git branch: {branch}");
+                    }
+                    if let Some(commit) = git.commit_hash {
+                        println!("  git commit: {commit}");
+                    }
+                }
+                println!();
+            }
+            RolloutItem::EventMsg(EventMsg::UserMessage(ev)) => {
+                println!("user:\n{}\n", ev.message.trim_end());
+            }
+            RolloutItem::EventMsg(EventMsg::AgentMessage(ev)) => {
+                println!("codex:\n{}\n", ev.message.trim_end());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+async fn run_remove(
+    config_overrides: &CliConfigOverrides,
+    args: SessionRefArgs,
+    archive: bool,
+) -> Result<()> {
+    let config = load_config(config_overrides)?;
+    let mut failures = 0;
+    for session in args.sessions {
+        let result = async {
+            let path = resolve_session_file(&config.codex_home, &session).await?;
+            if archive {
+                archive_session_file(&config.codex_home, &path).await?;
+            } else {
+                delete_session_file(&config.codex_home, &path).await?;
+            }
+            Ok::<_, std::io::Error>(path)
+        }
+        .await;
+        match result {
+            Ok(path) if archive => println!("Archived {}", path.display()),
+            Ok(path) => println!("Deleted {}", path.display()),
+            Err(e) => {
+                eprintln!("Failed to remove session '{session}': {e}");
+                failures += 1;
+            }
+        }
+    }
+    if failures > 0 {
+        bail!("{failures} session(s) could not be removed");
+    }
+    Ok(())
+}
+
+async fn run_prune(config_overrides: &CliConfigOverrides, args: PruneArgs) -> Result<()> {
+    let config = load_config(config_overrides)?;
+    let files = list_session_files(&config.codex_home)
+        .await
+        .context("failed to list sessions")?;
+    let criteria = PruneCriteria {
+        older_than: args.older_than,
+        keep: args.keep,
+    };
+    let prunable = select_prunable(&files, criteria);
+    if prunable.is_empty() {
+        println!("Nothing to prune.");
+        return Ok(());
+    }
+
+    let verb = match (args.dry_run, args.archive) {
+        (true, true) => "Would archive",
+        (true, false) => "Would delete",
+        (false, true) => "Archived",
+        (false, false) => "Deleted",
+    };
+    let mut freed = 0;
+    let mut count = 0;
+    for file in prunable {
+        if !args.dry_run {
+            let result = if args.archive {
+                archive_session_file(&config.codex_home, &file.path)
+                    .await
+                    .map(|_| ())
+            } else {
+                delete_session_file(&config.codex_home, &file.path).await
+            };
+            if let Err(e) = result {
+                eprintln!("Failed to remove {}: {e}", file.path.display());
+                continue;
+            }
+        }
+        println!(
+            "{verb} {}  {}",
+            file.started_at_display(),
+            file.path.display()
+        );
+        freed += file.size_bytes;
+        count += 1;
+    }
+    println!("{verb} {count} sessions ({}).", format_size(freed));
+    Ok(())
+}
+
+async fn run_search(config_overrides: &CliConfigOverrides, args: SearchArgs) -> Result<()> {
+    let config = load_config(config_overrides)?;
+
+    let parse_date = |flag: &str, raw: &str| {
+        parse_date_bound(raw)
+            .ok_or_else(|| anyhow!("invalid --{flag} value '{raw}' (expected YYYY-MM-DD)"))
+    };
+    let since = args
+        .since
+        .as_deref()
+        .map(|raw| parse_date("since", raw))
+        .transpose()?;
+    let until = args
+        .until
+        .as_deref()
+        .map(|raw| parse_date("until", raw))
+        .transpose()?;
+    let cwd = args.cwd.map(|cwd| std::path::absolute(&cwd).unwrap_or(cwd));
+
+    let query = SessionSearchQuery {
+        text: args.query.join(" "),
+        cwd,
+        since,
+        until,
+        git_branch: args.branch,
+        limit: args.limit,
+    };
+    let hits = search_sessions(&config.codex_home, &query)
+        .await
+        .context("failed to search sessions")?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&hits)?);
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!("No sessions matched '{}'.", query.text);
+        return Ok(());
+    }
+
+    for hit in hits {
+        let mut header = hit.path.display().to_string();
+        if let Some(ts) = &hit.timestamp {
+            header.push_str(&format!("  {ts}"));
+        }
+        if let Some(branch) = &hit.git_branch {
+            header.push_str(&format!("  [{branch}]"));
+        }
+        println!("{header}");
+        if let Some(cwd) = &hit.cwd {
+            println!("  cwd: {}", cwd.display());
+        }
+        for m in hit.matches {
+            let kind = match m.kind {
+                SearchMatchKind::UserMessage => "user",
+                SearchMatchKind::AgentMessage => "agent",
+                SearchMatchKind::Command => "command",
+                SearchMatchKind::FilePath => "file",
+            };
+            println!("  {kind:>7}: {}", m.snippet);
+        }
+        println!();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use anyhow::Result;
+use predicates::str::contains;
+use pretty_assertions::assert_eq;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path, cwd: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home).current_dir(cwd);
+    Ok(cmd)
+}
+
+#[test]
+fn show_reports_the_source_of_each_value() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let cwd = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        r#"
+model = "o3"
+approval_policy = "never"
+profile = "fast"
+
+[profiles.fast]
+model = "gpt-5"
+"#,
+    )?;
+
+    let output = codex_command(codex_home.path(), cwd.path())?
+        .args([
+            "config",
+            "show",
+            "--json",
+            "-c",
+            "approval_policy=on-request",
+        ])
+        .output()?;
+    assert!(output.status.success());
+    let entries: Vec<JsonValue> = serde_json::from_slice(&output.stdout)?;
+    let find = |key: &str| {
+        entries
+            .iter()
+            .find(|entry| entry["key"] == key)
+            .cloned()
+            .unwrap_or(JsonValue::Null)
+    };
+
+    assert_eq!(
+        find("model"),
+        json!({"key": "model", "value": "gpt-5", "source": {"kind": "profile", "name": "fast"}})
+    );
+    assert_eq!(
+        find("approval_policy"),
+        json!({"key": "approval_policy", "value": "on-request", "source": {"kind": "cli_override"}})
+    );
+    assert_eq!(find("profile")["source"]["kind"], "file");
+    assert_eq!(
+        find("sandbox_mode"),
+        json!({"key": "sandbox_mode", "value": "read-only", "source": {"kind": "default"}})
+    );
+
+    Ok(())
+}
+
+#[test]
+fn validate_fails_on_errors_and_set_rejects_invalid_values() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let cwd = TempDir::new()?;
+
+    codex_command(codex_home.path(), cwd.path())?
+        .args(["config", "set", "modle", "o3"])
+        .assert()
+        .success()
+        .stdout(contains("`modle`: unknown key"));
+    codex_command(codex_home.path(), cwd.path())?
+        .args(["config", "set", "model_provider", "openia"])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "`model_provider`: unknown model provider `openia`",
+        ));
+    codex_command(codex_home.path(), cwd.path())?
+        .args(["config", "set", "approval_policy", "sometimes"])
+        .assert()
+        .failure()
+        .stderr(contains("unknown variant `sometimes`"));
+
+    let config_path = codex_home.path().join("config.toml");
+    assert_eq!(std::fs::read_to_string(&config_path)?, "modle = \"o3\"\n");
+
+    // A file edited by hand can still end up broken.
+    std::fs::write(
+        &config_path,
+        "model_provider = \"openia\"\nmodle = \"o3\"\n",
+    )?;
+    codex_command(codex_home.path(), cwd.path())?
+        .args(["config", "validate"])
+        .assert()
+        .failure()
+        .stdout(contains(
+            "`model_provider`: unknown model provider `openia`",
+        ))
+        .stdout(contains("`modle`: unknown key"));
+
+    Ok(())
+}
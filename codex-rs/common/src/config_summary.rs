@@ -27,6 +27,16 @@ pub fn create_config_summary_entries(config: &Config) -> Vec<(&'static str, Stri
             config.model_reasoning_summary.to_string(),
         ));
     }
+    if let Some(path) = &config.project_config_path {
+        let mut value = path.display().to_string();
+        if !config.ignored_project_settings.is_empty() {
+            value.push_str(&format!(
+                " (untrusted, ignoring {})",
+                config.ignored_project_settings.join(", ")
+            ));
+        }
+        entries.push(("project config", value));
+    }
 
     entries
 }
@@ -1,18 +1,33 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::protocol::FileChange;
+use crate::protocol::PatchComment;
+use crate::protocol::RejectedChange;
 use crate::protocol::ReviewDecision;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_patch_safety;
+use base64::Engine;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::Hunk;
+use codex_apply_patch::MaybeApplyPatchVerified;
+use codex_apply_patch::maybe_parse_apply_patch_verified;
+use codex_apply_patch::parse_patch;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
+use similar::ChangeTag;
+use similar::DiffTag;
+use similar::TextDiff;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
 use std::path::PathBuf;
 
 pub const CODEX_APPLY_PATCH_ARG1: &str = "--codex-run-as-apply-patch";
 
+/// Line width used when re-serializing `*** Add Binary File:` contents.
+const BASE64_LINE_WIDTH: usize = 76;
+
 pub(crate) enum InternalApplyPatchInvocation {
     /// The `apply_patch` call was handled programmatically, without any sort
     /// of sandbox, because the user explicitly approved it. This is the
@@ -31,6 +46,16 @@ pub(crate) enum InternalApplyPatchInvocation {
 pub(crate) struct ApplyPatchExec {
     pub(crate) action: ApplyPatchAction,
     pub(crate) user_explicitly_approved_this_action: bool,
+    /// Appended to the exec output when the user approved only part of the
+    /// patch, so the model knows what was left out.
+    pub(crate) rejected_parts_note: Option<String>,
+}
+
+/// What the user sent along with their decision on a patch.
+#[derive(Debug, Default)]
+pub(crate) struct PatchFeedback {
+    pub(crate) comments: Vec<PatchComment>,
+    pub(crate) rejected: Vec<RejectedChange>,
 }
 
 impl From<ResponseInputItem> for InternalApplyPatchInvocation {
@@ -56,6 +81,7 @@ pub(crate) async fn apply_patch(
             InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
                 action,
                 user_explicitly_approved_this_action: false,
+                rejected_parts_note: None,
             })
         }
         SafetyCheck::AskUser => {
@@ -69,18 +95,64 @@ pub(crate) async fn apply_patch(
             let rx_approve = sess
                 .request_patch_approval(sub_id.to_owned(), call_id.to_owned(), &action, None, None)
                 .await;
-            match rx_approve.await.unwrap_or_default() {
+            let decision = rx_approve.await.unwrap_or_default();
+            let feedback = sess.take_patch_feedback(sub_id);
+            match decision {
                 ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
-                    InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
-                        action,
-                        user_explicitly_approved_this_action: true,
+                    if feedback.rejected.is_empty() {
+                        return InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                            action,
+                            user_explicitly_approved_this_action: true,
+                            rejected_parts_note: None,
+                        });
+                    }
+                    // Reads the files being patched, so keep it off the runtime.
+                    let rejected = feedback.rejected;
+                    let partial = tokio::task::spawn_blocking(move || {
+                        without_rejected_parts(&action, &rejected)
                     })
+                    .await
+                    .unwrap_or_else(|err| Err(err.to_string()));
+                    match partial {
+                        Ok(PartialPatch {
+                            action: Some(action),
+                            rejected,
+                        }) => InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                            action,
+                            user_explicitly_approved_this_action: true,
+                            rejected_parts_note: Some(format_rejected_parts(&rejected)),
+                        }),
+                        Ok(PartialPatch {
+                            action: None,
+                            rejected,
+                        }) => ResponseInputItem::FunctionCallOutput {
+                            call_id: call_id.to_owned(),
+                            output: FunctionCallOutputPayload {
+                                content: format!(
+                                    "patch rejected by user\n{}",
+                                    format_rejected_parts(&rejected)
+                                ),
+                                success: Some(false),
+                            },
+                        }
+                        .into(),
+                        Err(err) => ResponseInputItem::FunctionCallOutput {
+                            call_id: call_id.to_owned(),
+                            output: FunctionCallOutputPayload {
+                                content: format!(
+                                    "failed to apply the parts of the patch approved by the user: {err}"
+                                ),
+                                success: Some(false),
+                            },
+                        }
+                        .into(),
+                    }
                 }
                 ReviewDecision::Denied | ReviewDecision::Abort => {
                     ResponseInputItem::FunctionCallOutput {
                         call_id: call_id.to_owned(),
                         output: FunctionCallOutputPayload {
-                            content: "patch rejected by user".to_string(),
+                            content: format_patch_rejection(&feedback.comments),
                             success: Some(false),
                         },
                     }
@@ -99,6 +171,285 @@ pub(crate) async fn apply_patch(
     }
 }
 
+/// Tool output for a rejected patch. Review comments are listed with their
+/// anchors so the model can revise the patch instead of starting over.
+fn format_patch_rejection(comments: &[PatchComment]) -> String {
+    if comments.is_empty() {
+        return "patch rejected by user".to_string();
+    }
+    let mut out = String::from(
+        "patch rejected by user, who requested changes. Address these review comments and propose an updated patch:\n",
+    );
+    for comment in comments {
+        let anchor = match comment.line {
+            Some(line) => format!("{}:{line}", comment.path.display()),
+            None => comment.path.display().to_string(),
+        };
+        out.push_str(&format!("\n- {anchor}\n"));
+        if let Some(snippet) = &comment.snippet {
+            out.push_str(&format!("  > {snippet}\n"));
+        }
+        for line in comment.body.lines() {
+            out.push_str(&format!("  {line}\n"));
+        }
+    }
+    out
+}
+
+/// The accepted part of a patch and what the user left out of it.
+#[derive(Debug)]
+struct PartialPatch {
+    /// `None` when every change was rejected.
+    action: Option<ApplyPatchAction>,
+    /// Human readable descriptions of the rejected parts.
+    rejected: Vec<String>,
+}
+
+/// Narrow `action` to the files and hunks the user did not reject. Hunk
+/// indices refer to the hunks of the `unified_diff` shown in the approval
+/// request.
+fn without_rejected_parts(
+    action: &ApplyPatchAction,
+    rejected: &[RejectedChange],
+) -> Result<PartialPatch, String> {
+    let args = parse_patch(&action.patch).map_err(|err| err.to_string())?;
+    let mut patch = String::from("*** Begin Patch\n");
+    let mut descriptions = Vec::new();
+    let mut kept_any = false;
+    for hunk in &args.hunks {
+        let path = hunk.resolve_path(&action.cwd);
+        let Some(rejection) = rejected.iter().find(|r| r.path == path) else {
+            write_patch_hunk(&mut patch, hunk);
+            kept_any = true;
+            continue;
+        };
+        let (
+            Hunk::UpdateFile {
+                path: relative_path,
+                move_path,
+                mode,
+                ..
+            },
+            Some(ApplyPatchFileChange::Update { new_content, .. }),
+        ) = (hunk, action.changes().get(&path))
+        else {
+            descriptions.push(format!("{}: all changes", path.display()));
+            continue;
+        };
+        if rejection.hunks.is_empty() {
+            descriptions.push(format!("{}: all changes", path.display()));
+            continue;
+        }
+        let original = std::fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        let rejected_hunks: HashSet<usize> = rejection.hunks.iter().copied().collect();
+        let (partial, hunk_headers) = apply_accepted_hunks(&original, new_content, &rejected_hunks);
+        let rejected_headers: Vec<&str> = hunk_headers
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| rejected_hunks.contains(idx))
+            .map(|(_, header)| header.as_str())
+            .collect();
+        if rejected_headers.len() == hunk_headers.len() {
+            descriptions.push(format!("{}: all changes", path.display()));
+            continue;
+        }
+        descriptions.push(format!(
+            "{}: hunks {}",
+            path.display(),
+            rejected_headers.join(", ")
+        ));
+        patch.push_str(&format!("*** Update File: {}\n", relative_path.display()));
+        if let Some(move_path) = move_path {
+            patch.push_str(&format!("*** Move to: {}\n", move_path.display()));
+        }
+        write_set_mode(&mut patch, *mode);
+        write_update_chunks(&mut patch, &original, &partial);
+        kept_any = true;
+    }
+    patch.push_str("*** End Patch");
+
+    if !kept_any {
+        return Ok(PartialPatch {
+            action: None,
+            rejected: descriptions,
+        });
+    }
+    let argv = vec!["apply_patch".to_string(), patch];
+    match maybe_parse_apply_patch_verified(&argv, &action.cwd) {
+        MaybeApplyPatchVerified::Body(action) => Ok(PartialPatch {
+            action: Some(action),
+            rejected: descriptions,
+        }),
+        MaybeApplyPatchVerified::CorrectnessError(err) => Err(err.to_string()),
+        MaybeApplyPatchVerified::ShellParseError(err) => Err(format!("{err:?}")),
+        MaybeApplyPatchVerified::NotApplyPatch => Err("not an apply_patch invocation".to_string()),
+    }
+}
+
+/// Re-serialize a parsed hunk in the `apply_patch` format.
+fn write_patch_hunk(out: &mut String, hunk: &Hunk) {
+    match hunk {
+        Hunk::AddFile {
+            path,
+            contents,
+            mode,
+        } => {
+            out.push_str(&format!("*** Add File: {}\n", path.display()));
+            write_set_mode(out, *mode);
+            for line in contents.lines() {
+                out.push_str(&format!("+{line}\n"));
+            }
+        }
+        Hunk::AddBinaryFile {
+            path,
+            contents,
+            mode,
+        } => {
+            out.push_str(&format!("*** Add Binary File: {}\n", path.display()));
+            write_set_mode(out, *mode);
+            let encoded = base64::engine::general_purpose::STANDARD.encode(contents);
+            for line in encoded.as_bytes().chunks(BASE64_LINE_WIDTH) {
+                out.push('+');
+                out.push_str(&String::from_utf8_lossy(line));
+                out.push('\n');
+            }
+        }
+        Hunk::AddSymlink { path, target } => {
+            out.push_str(&format!(
+                "*** Add Symlink: {} -> {}\n",
+                path.display(),
+                target.display()
+            ));
+        }
+        Hunk::DeleteFile { path } => {
+            out.push_str(&format!("*** Delete File: {}\n", path.display()));
+        }
+        Hunk::UpdateFile {
+            path,
+            move_path,
+            mode,
+            chunks,
+        } => {
+            out.push_str(&format!("*** Update File: {}\n", path.display()));
+            if let Some(move_path) = move_path {
+                out.push_str(&format!("*** Move to: {}\n", move_path.display()));
+            }
+            write_set_mode(out, *mode);
+            for chunk in chunks {
+                match &chunk.change_context {
+                    Some(context) => out.push_str(&format!("@@ {context}\n")),
+                    None => out.push_str("@@\n"),
+                }
+                // Chunks only record which lines go away and which replace
+                // them, so context lines appear on both sides.
+                let common = chunk
+                    .old_lines
+                    .iter()
+                    .zip(&chunk.new_lines)
+                    .take_while(|(old, new)| old == new)
+                    .count();
+                for line in &chunk.old_lines[..common] {
+                    out.push_str(&format!(" {line}\n"));
+                }
+                for line in &chunk.old_lines[common..] {
+                    out.push_str(&format!("-{line}\n"));
+                }
+                for line in &chunk.new_lines[common..] {
+                    out.push_str(&format!("+{line}\n"));
+                }
+                if chunk.is_end_of_file {
+                    out.push_str("*** End of File\n");
+                }
+            }
+        }
+    }
+}
+
+fn write_set_mode(out: &mut String, mode: Option<u32>) {
+    if let Some(mode) = mode {
+        out.push_str(&format!("*** Set Mode: {mode:o}\n"));
+    }
+}
+
+/// Apply the hunks of the `original` → `new_content` diff that are not in
+/// `rejected`. Hunks are numbered like the `unified_diff` of the approval
+/// request. Returns the resulting content and the header of every hunk.
+fn apply_accepted_hunks(
+    original: &str,
+    new_content: &str,
+    rejected: &HashSet<usize>,
+) -> (String, Vec<String>) {
+    let diff = TextDiff::from_lines(original, new_content);
+    let mut rejected_ops = HashSet::new();
+    let mut headers = Vec::new();
+    for (idx, hunk) in diff
+        .unified_diff()
+        .context_radius(1)
+        .iter_hunks()
+        .enumerate()
+    {
+        headers.push(hunk.header().to_string());
+        if rejected.contains(&idx) {
+            rejected_ops.extend(
+                hunk.ops()
+                    .iter()
+                    .map(|op| (op.old_range().start, op.new_range().start)),
+            );
+        }
+    }
+    let mut out = String::new();
+    for op in diff.ops() {
+        let keep_old = op.tag() == DiffTag::Equal
+            || rejected_ops.contains(&(op.old_range().start, op.new_range().start));
+        let lines = if keep_old {
+            &diff.old_slices()[op.old_range()]
+        } else {
+            &diff.new_slices()[op.new_range()]
+        };
+        out.extend(lines.iter().copied());
+    }
+    (out, headers)
+}
+
+/// Write `@@` chunks turning `original` into `updated`, with enough context
+/// for `apply_patch` to locate them. Chunks that reach the end of the file
+/// are anchored there with `*** End of File`, so an unterminated last line
+/// is matched in place and keeps its (missing) line ending.
+fn write_update_chunks(out: &mut String, original: &str, updated: &str) {
+    let diff = TextDiff::from_lines(original, updated);
+    let old_len = diff.old_slices().len();
+    for group in diff.grouped_ops(3) {
+        out.push_str("@@\n");
+        let at_eof = group.last().is_some_and(|op| op.old_range().end == old_len);
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                let sign = match change.tag() {
+                    ChangeTag::Equal => ' ',
+                    ChangeTag::Delete => '-',
+                    ChangeTag::Insert => '+',
+                };
+                let value = change.value();
+                let line = value.strip_suffix('\n').unwrap_or(value);
+                out.push_str(&format!("{sign}{line}\n"));
+            }
+        }
+        if at_eof {
+            out.push_str("*** End of File\n");
+        }
+    }
+}
+
+fn format_rejected_parts(rejected: &[String]) -> String {
+    let mut out = String::from(
+        "The user did not approve these parts of the patch, so they were not applied:\n",
+    );
+    for part in rejected {
+        out.push_str(&format!("- {part}\n"));
+    }
+    out
+}
+
 pub(crate) fn convert_apply_patch_to_protocol(
     action: &ApplyPatchAction,
 ) -> HashMap<PathBuf, FileChange> {
@@ -106,8 +457,16 @@ pub(crate) fn convert_apply_patch_to_protocol(
     let mut result = HashMap::with_capacity(changes.len());
     for (path, change) in changes {
         let protocol_change = match change {
-            ApplyPatchFileChange::Add { content } => FileChange::Add {
+            ApplyPatchFileChange::Add { content, mode } => FileChange::Add {
                 content: content.clone(),
+                mode: *mode,
+            },
+            ApplyPatchFileChange::AddBinary { content, mode } => FileChange::AddBinary {
+                size: content.len() as u64,
+                mode: *mode,
+            },
+            ApplyPatchFileChange::AddSymlink { target } => FileChange::AddSymlink {
+                target: target.clone(),
             },
             ApplyPatchFileChange::Delete { content } => FileChange::Delete {
                 content: content.clone(),
@@ -116,12 +475,151 @@ pub(crate) fn convert_apply_patch_to_protocol(
                 unified_diff,
                 move_path,
                 new_content: _new_content,
+                mode,
             } => FileChange::Update {
                 unified_diff: unified_diff.clone(),
                 move_path: move_path.clone(),
+                mode: *mode,
             },
         };
         result.insert(path.clone(), protocol_change);
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parse_action(patch: &str, cwd: &Path) -> ApplyPatchAction {
+        let argv = vec!["apply_patch".to_string(), patch.to_string()];
+        match maybe_parse_apply_patch_verified(&argv, cwd) {
+            MaybeApplyPatchVerified::Body(action) => action,
+            other => panic!("expected a patch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn partial_approval_drops_rejected_files_and_hunks() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let lib = dir.path().join("lib.txt");
+        std::fs::write(&lib, "a\nb\nc\nd\ne\nf\ng\nh\n").expect("write lib.txt");
+        let action = parse_action(
+            "*** Begin Patch\n\
+             *** Add File: new.txt\n\
+             +hello\n\
+             *** Update File: lib.txt\n\
+             @@\n a\n-b\n+B\n c\n\
+             @@\n f\n-g\n+G\n h\n\
+             *** End Patch",
+            dir.path(),
+        );
+
+        let partial = without_rejected_parts(
+            &action,
+            &[
+                RejectedChange {
+                    path: dir.path().join("new.txt"),
+                    hunks: Vec::new(),
+                },
+                RejectedChange {
+                    path: lib.clone(),
+                    hunks: vec![0],
+                },
+            ],
+        )
+        .expect("partial patch");
+
+        let action = partial.action.expect("some changes remain");
+        assert_eq!(action.changes().len(), 1);
+        match action.changes().get(&lib) {
+            Some(ApplyPatchFileChange::Update { new_content, .. }) => {
+                assert_eq!(new_content, "a\nb\nc\nd\ne\nf\nG\nh\n");
+            }
+            other => panic!("expected an update of lib.txt, got {other:?}"),
+        }
+        assert_eq!(
+            partial.rejected,
+            vec![
+                format!("{}: all changes", dir.path().join("new.txt").display()),
+                format!("{}: hunks @@ -1,3 +1,3 @@", lib.display()),
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_approval_keeps_a_missing_final_newline() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let lib = dir.path().join("lib.txt");
+        std::fs::write(&lib, "a\nb\nc\nd\ne\nf\ng\nh").expect("write lib.txt");
+        let action = parse_action(
+            "*** Begin Patch\n\
+             *** Update File: lib.txt\n\
+             @@\n a\n-b\n+B\n c\n\
+             @@\n f\n-g\n+G\n h\n\
+             *** End Patch",
+            dir.path(),
+        );
+
+        let partial = without_rejected_parts(
+            &action,
+            &[RejectedChange {
+                path: lib.clone(),
+                hunks: vec![0],
+            }],
+        )
+        .expect("partial patch");
+
+        let action = partial.action.expect("some changes remain");
+        match action.changes().get(&lib) {
+            Some(ApplyPatchFileChange::Update { new_content, .. }) => {
+                assert_eq!(new_content, "a\nb\nc\nd\ne\nf\nG\nh");
+            }
+            other => panic!("expected an update of lib.txt, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn partial_approval_without_accepted_parts_applies_nothing() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let action = parse_action(
+            "*** Begin Patch\n*** Add File: new.txt\n+hello\n*** End Patch",
+            dir.path(),
+        );
+        let partial = without_rejected_parts(
+            &action,
+            &[RejectedChange {
+                path: dir.path().join("new.txt"),
+                hunks: Vec::new(),
+            }],
+        )
+        .expect("partial patch");
+        assert!(partial.action.is_none());
+    }
+
+    #[test]
+    fn rejection_lists_anchored_comments() {
+        assert_eq!(format_patch_rejection(&[]), "patch rejected by user");
+        let comments = vec![
+            PatchComment {
+                path: PathBuf::from("src/lib.rs"),
+                line: Some(12),
+                snippet: Some("+    let x = 42;".to_string()),
+                body: "Use a named constant.".to_string(),
+            },
+            PatchComment {
+                path: PathBuf::from("README.md"),
+                line: None,
+                snippet: None,
+                body: "Document the flag.\nAnd the default.".to_string(),
+            },
+        ];
+        assert_eq!(
+            format_patch_rejection(&comments),
+            "patch rejected by user, who requested changes. Address these review comments and propose an updated patch:\n\
+             \n- src/lib.rs:12\n  > +    let x = 42;\n  Use a named constant.\n\
+             \n- README.md\n  Document the flag.\n  And the default.\n"
+        );
+    }
+}
@@ -14,6 +14,7 @@ use crate::event_mapping::map_response_item_to_event_messages;
 use async_channel::Receiver;
 use async_channel::Sender;
 use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
 use codex_apply_patch::MaybeApplyPatchVerified;
 use codex_apply_patch::maybe_parse_apply_patch_verified;
 use codex_protocol::mcp_protocol::ConversationId;
@@ -43,6 +44,7 @@ use crate::apply_patch;
 use crate::apply_patch::ApplyPatchExec;
 use crate::apply_patch::CODEX_APPLY_PATCH_ARG1;
 use crate::apply_patch::InternalApplyPatchInvocation;
+use crate::apply_patch::PatchFeedback;
 use crate::apply_patch::convert_apply_patch_to_protocol;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
@@ -77,6 +79,7 @@ use crate::openai_tools::ToolsConfigParams;
 use crate::openai_tools::get_openai_tools;
 use crate::parse_command::parse_command;
 use crate::plan_tool::handle_update_plan;
+use crate::project_doc::ScopedProjectDocs;
 use crate::project_doc::get_user_instructions;
 use crate::protocol::AgentMessageDeltaEvent;
 use crate::protocol::AgentReasoningDeltaEvent;
@@ -97,6 +100,7 @@ use crate::protocol::ListCustomPromptsResponseEvent;
 use crate::protocol::Op;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
+use crate::protocol::ProjectDocLoadedEvent;
 use crate::protocol::ReviewDecision;
 use crate::protocol::ReviewOutputEvent;
 use crate::protocol::SandboxPolicy;
@@ -108,6 +112,7 @@ use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::protocol::TurnDiffEvent;
 use crate::protocol::WebSearchBeginEvent;
+use crate::redact::Redactor;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
 use crate::safety::SafetyCheck;
@@ -268,8 +273,13 @@ struct State {
     approved_commands: HashSet<Vec<String>>,
     current_task: Option<AgentTask>,
     pending_approvals: HashMap<String, oneshot::Sender<ReviewDecision>>,
+    /// Review comments and rejected parts sent with a patch decision, keyed
+    /// like `pending_approvals` and taken by the patch that asked for approval.
+    patch_feedback: HashMap<String, PatchFeedback>,
     pending_input: Vec<ResponseInputItem>,
     history: ConversationHistory,
+    /// `AGENTS.md` files of subdirectories, loaded as the agent works in them.
+    scoped_project_docs: ScopedProjectDocs,
     token_info: Option<TokenUsageInfo>,
     next_internal_sub_id: u64,
 }
@@ -297,6 +307,8 @@ pub(crate) struct Session {
     codex_linux_sandbox_exe: Option<PathBuf>,
     user_shell: shell::Shell,
     show_raw_agent_reasoning: bool,
+    /// Scrubs secrets from command output before it is surfaced.
+    redactor: Redactor,
 }
 
 /// The context needed for a single turn of the conversation.
@@ -409,15 +421,38 @@ impl Session {
         // - spin up MCP connection manager
         // - perform default shell discovery
         // - load history metadata
+        // - find the project docs on a blocking thread
         let rollout_fut = RolloutRecorder::new(&config, rollout_params);
 
         let mcp_fut = McpConnectionManager::new(config.mcp_servers.clone());
         let default_shell_fut = shell::default_user_shell();
         let history_meta_fut = crate::message_history::history_metadata(&config);
+        let scoped_project_docs_fut = {
+            let config = Arc::clone(&config);
+            async move {
+                tokio::task::spawn_blocking(move || ScopedProjectDocs::new(&config))
+                    .await
+                    .unwrap_or_else(|e| {
+                        error!("failed to look for project docs: {e}");
+                        ScopedProjectDocs::default()
+                    })
+            }
+        };
 
         // Join all independent futures.
-        let (rollout_recorder, mcp_res, default_shell, (history_log_id, history_entry_count)) =
-            tokio::join!(rollout_fut, mcp_fut, default_shell_fut, history_meta_fut);
+        let (
+            rollout_recorder,
+            mcp_res,
+            default_shell,
+            (history_log_id, history_entry_count),
+            scoped_project_docs,
+        ) = tokio::join!(
+            rollout_fut,
+            mcp_fut,
+            default_shell_fut,
+            history_meta_fut,
+            scoped_project_docs_fut
+        );
 
         let rollout_recorder = rollout_recorder.map_err(|e| {
             error!("failed to initialize rollout recorder: {e:#}");
@@ -427,6 +462,7 @@ impl Session {
         // Create the mutable state for the Session.
         let state = State {
             history: ConversationHistory::new(),
+            scoped_project_docs,
             ..Default::default()
         };
 
@@ -499,6 +535,7 @@ impl Session {
             codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            redactor: Redactor::new(&config.redaction),
         });
 
         // Dispatch the SessionConfiguredEvent first and then report any errors.
@@ -670,6 +707,18 @@ impl Session {
         }
     }
 
+    fn set_patch_feedback(&self, sub_id: &str, feedback: PatchFeedback) {
+        let mut state = self.state.lock_unchecked();
+        state.patch_feedback.insert(sub_id.to_string(), feedback);
+    }
+
+    /// Take the review comments and rejected parts sent with the decision on
+    /// `sub_id`'s patch.
+    pub(crate) fn take_patch_feedback(&self, sub_id: &str) -> PatchFeedback {
+        let mut state = self.state.lock_unchecked();
+        state.patch_feedback.remove(sub_id).unwrap_or_default()
+    }
+
     pub fn add_approved_command(&self, cmd: Vec<String>) {
         let mut state = self.state.lock_unchecked();
         state.approved_commands.insert(cmd);
@@ -839,6 +888,7 @@ impl Session {
         call_id: &str,
         output: &ExecToolCallOutput,
         is_apply_patch: bool,
+        redactions: usize,
     ) {
         let ExecToolCallOutput {
             stdout,
@@ -870,6 +920,7 @@ impl Session {
                 exit_code: *exit_code,
                 duration: *duration,
                 formatted_output,
+                redactions,
             })
         };
 
@@ -910,7 +961,7 @@ impl Session {
         self.on_exec_command_begin(turn_diff_tracker, begin_ctx.clone())
             .await;
 
-        let result = process_exec_tool_call(
+        let mut result = process_exec_tool_call(
             exec_args.params,
             exec_args.sandbox_type,
             exec_args.sandbox_policy,
@@ -919,6 +970,16 @@ impl Session {
         )
         .await;
 
+        // Scrub secrets before the output reaches clients, the model or the
+        // rollout.
+        let redactions = match &mut result {
+            Ok(output) => self.redact_exec_output(output),
+            Err(CodexErr::Sandbox(
+                SandboxErr::Timeout { output } | SandboxErr::Denied { output },
+            )) => self.redact_exec_output(output),
+            Err(_) => 0,
+        };
+
         let output_stderr;
         let borrowed: &ExecToolCallOutput = match &result {
             Ok(output) => output,
@@ -941,12 +1002,25 @@ impl Session {
             &call_id,
             borrowed,
             is_apply_patch,
+            redactions,
         )
         .await;
 
         result
     }
 
+    /// Redact secrets from `output` in place. Returns the number of secrets
+    /// removed from the aggregated output, which is what the model sees.
+    fn redact_exec_output(&self, output: &mut ExecToolCallOutput) -> usize {
+        let (stdout, _) = self.redactor.redact(&output.stdout.text);
+        let (stderr, _) = self.redactor.redact(&output.stderr.text);
+        let (aggregated_output, redactions) = self.redactor.redact(&output.aggregated_output.text);
+        output.stdout.text = stdout;
+        output.stderr.text = stderr;
+        output.aggregated_output.text = aggregated_output;
+        redactions
+    }
+
     /// Helper that emits a BackgroundEvent with the given message. This keeps
     /// the call‑sites terse so adding more diagnostics does not clutter the
     /// core agent logic.
@@ -987,6 +1061,42 @@ impl Session {
         }
     }
 
+    /// Queue the `AGENTS.md` files that apply to `paths` and are not in
+    /// context yet, so the model sees them with its next request, and let
+    /// clients know they were loaded. The docs are read on a blocking thread
+    /// from a snapshot, without holding the state lock.
+    async fn load_scoped_project_docs(&self, sub_id: &str, paths: &[PathBuf]) {
+        let snapshot = self.state.lock_unchecked().scoped_project_docs.clone();
+        let paths = paths.to_vec();
+        let found = match tokio::task::spawn_blocking(move || snapshot.find(&paths)).await {
+            Ok(found) => found,
+            Err(e) => {
+                error!("failed to look for project docs: {e}");
+                return;
+            }
+        };
+        let docs = {
+            let mut state = self.state.lock_unchecked();
+            let docs = state.scoped_project_docs.queue(found);
+            for doc in &docs {
+                state
+                    .pending_input
+                    .push(UserInstructions::new(doc.instructions()).into());
+            }
+            docs
+        };
+        for doc in docs {
+            let event = Event {
+                id: sub_id.to_string(),
+                msg: EventMsg::ProjectDocLoaded(ProjectDocLoadedEvent {
+                    path: doc.path,
+                    truncated: doc.truncated,
+                }),
+            };
+            self.send_event(event).await;
+        }
+    }
+
     pub fn get_pending_input(&self) -> Vec<ResponseInputItem> {
         let mut state = self.state.lock_unchecked();
         if state.pending_input.is_empty() {
@@ -1014,7 +1124,9 @@ impl Session {
         info!("interrupt received: abort current task, if any");
         let mut state = self.state.lock_unchecked();
         state.pending_approvals.clear();
+        state.patch_feedback.clear();
         state.pending_input.clear();
+        state.scoped_project_docs.unqueue();
         if let Some(task) = state.current_task.take() {
             task.abort(TurnAbortReason::Interrupted);
         }
@@ -1368,11 +1480,21 @@ async fn submission_loop(
                 }
                 other => sess.notify_approval(&id, other),
             },
-            Op::PatchApproval { id, decision } => match decision {
+            Op::PatchApproval {
+                id,
+                decision,
+                comments,
+                rejected,
+            } => match decision {
                 ReviewDecision::Abort => {
                     sess.interrupt_task();
                 }
-                other => sess.notify_approval(&id, other),
+                other => {
+                    if !comments.is_empty() || !rejected.is_empty() {
+                        sess.set_patch_feedback(&id, PatchFeedback { comments, rejected });
+                    }
+                    sess.notify_approval(&id, other)
+                }
             },
             Op::AddToHistory { text } => {
                 let id = sess.conversation_id;
@@ -1431,15 +1553,63 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
+            Op::ReconfigureMcpServers { mcp_servers } => {
+                let sub_id = sub.id.clone();
+                let sess_clone = sess.clone();
+                // Starting servers can take a while; do not block the
+                // submission loop on it.
+                tokio::spawn(async move {
+                    let outcome = sess_clone
+                        .mcp_connection_manager
+                        .reconfigure(mcp_servers)
+                        .await;
+                    for (server_name, err) in &outcome.errors {
+                        let message =
+                            format!("MCP client for `{server_name}` failed to start: {err:#}");
+                        error!("{message}");
+                        sess_clone
+                            .send_event(Event {
+                                id: sub_id.clone(),
+                                msg: EventMsg::Error(ErrorEvent { message }),
+                            })
+                            .await;
+                    }
+
+                    let mut changes = Vec::new();
+                    for (label, names) in [
+                        ("started", &outcome.started),
+                        ("restarted", &outcome.restarted),
+                        ("stopped", &outcome.stopped),
+                    ] {
+                        if !names.is_empty() {
+                            changes.push(format!("{label} {}", names.join(", ")));
+                        }
+                    }
+                    if !changes.is_empty() {
+                        sess_clone
+                            .notify_background_event(
+                                &sub_id,
+                                format!("MCP servers {}", changes.join("; ")),
+                            )
+                            .await;
+                    }
+                });
+            }
             Op::ListCustomPrompts => {
                 let sub_id = sub.id.clone();
 
+                let trusted = {
+                    let codex_home = config.codex_home.clone();
+                    let cwd = turn_context.cwd.clone();
+                    tokio::task::spawn_blocking(move || {
+                        crate::config::is_project_trusted(&codex_home, &cwd)
+                    })
+                    .await
+                    .unwrap_or(false)
+                };
                 let custom_prompts: Vec<CustomPrompt> =
-                    if let Some(dir) = crate::custom_prompts::default_prompts_dir() {
-                        crate::custom_prompts::discover_prompts_in(&dir).await
-                    } else {
-                        Vec::new()
-                    };
+                    crate::custom_prompts::discover_custom_prompts(&turn_context.cwd, trusted)
+                        .await;
 
                 let event = Event {
                     id: sub_id,
@@ -1697,6 +1867,9 @@ async fn run_task(
             sess.record_conversation_items(&pending_input).await;
             sess.turn_input_with_history(pending_input)
         };
+        // Scoped project docs queued with the pending input are now part of
+        // the conversation.
+        sess.state.lock_unchecked().scoped_project_docs.recorded();
 
         let turn_input_messages: Vec<String> = turn_input
             .iter()
@@ -1828,6 +2001,14 @@ async fn run_task(
                     }
                 }
 
+                // Tool output reaches the model through the history, so redact
+                // it here, whichever tool produced it.
+                let items_to_record_in_conversation_history: Vec<ResponseItem> =
+                    items_to_record_in_conversation_history
+                        .into_iter()
+                        .map(|item| sess.redactor.redact_tool_output(item))
+                        .collect();
+
                 // Only attempt to take the lock if there is something to record.
                 if !items_to_record_in_conversation_history.is_empty() {
                     if is_review_mode {
@@ -2739,10 +2920,26 @@ async fn handle_container_exec_with_params(
         MaybeApplyPatchVerified::NotApplyPatch => None,
     };
 
+    let mut touched_paths = vec![params.cwd.clone()];
+    if let Some(ApplyPatchExec { action, .. }) = &apply_patch_exec {
+        for (path, change) in action.changes() {
+            touched_paths.push(path.clone());
+            if let ApplyPatchFileChange::Update {
+                move_path: Some(dest),
+                ..
+            } = change
+            {
+                touched_paths.push(dest.clone());
+            }
+        }
+    }
+    sess.load_scoped_project_docs(&sub_id, &touched_paths).await;
+
     let (params, safety, command_for_display) = match &apply_patch_exec {
         Some(ApplyPatchExec {
             action: ApplyPatchAction { patch, cwd, .. },
             user_explicitly_approved_this_action,
+            ..
         }) => {
             let path_to_codex = std::env::current_exe()
                 .ok()
@@ -2846,6 +3043,9 @@ async fn handle_container_exec_with_params(
         }
     };
 
+    let rejected_parts_note = apply_patch_exec
+        .as_ref()
+        .and_then(|exec| exec.rejected_parts_note.clone());
     let exec_command_context = ExecCommandContext {
         sub_id: sub_id.clone(),
         call_id: call_id.clone(),
@@ -2855,6 +3055,7 @@ async fn handle_container_exec_with_params(
             |ApplyPatchExec {
                  action,
                  user_explicitly_approved_this_action,
+                 ..
              }| ApplyPatchCommandContext {
                 user_explicitly_approved_this_action,
                 changes: convert_apply_patch_to_protocol(&action),
@@ -2879,6 +3080,7 @@ async fn handle_container_exec_with_params(
                         sub_id: sub_id.clone(),
                         call_id: call_id.clone(),
                         tx_event: sess.tx_event.clone(),
+                        redactor: Some(sess.redactor.clone()),
                     })
                 },
             },
@@ -2886,7 +3088,11 @@ async fn handle_container_exec_with_params(
         .await;
 
     match output_result {
-        Ok(output) => {
+        Ok(mut output) => {
+            if let Some(note) = rejected_parts_note {
+                output.aggregated_output.text.push_str("\n");
+                output.aggregated_output.text.push_str(&note);
+            }
             let ExecToolCallOutput { exit_code, .. } = &output;
 
             let is_success = *exit_code == 0;
@@ -3013,6 +3219,7 @@ async fn handle_sandbox_error(
                                 sub_id: sub_id.clone(),
                                 call_id: call_id.clone(),
                                 tx_event: sess.tx_event.clone(),
+                                redactor: Some(sess.redactor.clone()),
                             })
                         },
                     },
@@ -3568,6 +3775,7 @@ mod tests {
             codex_linux_sandbox_exe: None,
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            redactor: Redactor::new(&config.redaction),
         };
         (session, turn_context)
     }
@@ -181,6 +181,9 @@ async fn run_compact_task_inner(
     {
         let mut state = sess.state.lock_unchecked();
         state.history.replace(new_history);
+        // The summary replaced any scoped AGENTS.md; load them again when
+        // the agent returns to their directories.
+        state.scoped_project_docs.reset();
     }
 
     let rollout_item = RolloutItem::Compacted(CompactedItem {
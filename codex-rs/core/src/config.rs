@@ -3,20 +3,28 @@ use crate::config_types::History;
 use crate::config_types::McpServerConfig;
 use crate::config_types::Notifications;
 use crate::config_types::ReasoningSummaryFormat;
+use crate::config_types::RedactionSettings;
+use crate::config_types::RolloutSettings;
 use crate::config_types::SandboxWorkspaceWrite;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::config_types::ShellEnvironmentPolicyToml;
 use crate::config_types::Tui;
 use crate::config_types::UriBasedFileOpener;
 use crate::git_info::resolve_root_git_project_for_trust;
+use crate::keymap::Keymap;
 use crate::model_family::ModelFamily;
 use crate::model_family::derive_default_model_family;
 use crate::model_family::find_family_for_model;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::built_in_model_providers;
 use crate::openai_model_info::get_model_info;
+use crate::project_config::apply_project_layer;
+use crate::project_config::load_project_config;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::redact::validate_redaction_settings;
+use crate::theme::ThemeColors;
+use crate::theme::ThemeName;
 use anyhow::Context;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
@@ -122,6 +130,23 @@ pub struct Config {
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
 
+    /// Key bindings for the TUI, resolved from `[tui.keymap]`.
+    pub tui_keymap: Keymap,
+
+    /// Color theme for the TUI, from `tui.theme`.
+    pub tui_theme: ThemeName,
+
+    /// Semantic color overrides for the TUI, from `[tui.colors]`.
+    pub tui_colors: ThemeColors,
+
+    /// The repository's `.codex/config.toml`, when one was layered under the
+    /// user's config.
+    pub project_config_path: Option<PathBuf>,
+
+    /// Settings from the project config that were ignored because the
+    /// project is not trusted.
+    pub ignored_project_settings: Vec<String>,
+
     /// The directory that should be treated as the current working directory
     /// for the session. All relative paths inside the business-logic layer are
     /// resolved against this path.
@@ -143,6 +168,12 @@ pub struct Config {
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     pub history: History,
 
+    /// Settings that govern how session rollouts are stored.
+    pub rollout: RolloutSettings,
+
+    /// Settings for redacting secrets from command output and persisted text.
+    pub redaction: RedactionSettings,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: UriBasedFileOpener,
@@ -201,8 +232,9 @@ impl Config {
     /// **in between** the values parsed from `config.toml` and the
     /// strongly-typed overrides specified via [`ConfigOverrides`].
     ///
-    /// The precedence order is therefore: `config.toml` < `-c` overrides <
-    /// `ConfigOverrides`.
+    /// The repository's `.codex/config.toml`, if any, is layered directly
+    /// under `config.toml`, so the precedence order is therefore:
+    /// project config < `config.toml` < `-c` overrides < `ConfigOverrides`.
     pub fn load_with_cli_overrides(
         cli_overrides: Vec<(String, TomlValue)>,
         overrides: ConfigOverrides,
@@ -211,24 +243,47 @@ impl Config {
         // value of $CODEX_HOME) so we can embed it into the resulting
         // `Config` instance.
         let codex_home = find_codex_home()?;
+        Self::load_with_cli_overrides_in(codex_home, cli_overrides, overrides)
+    }
 
+    /// [`Config::load_with_cli_overrides`] with an explicit `codex_home`.
+    pub(crate) fn load_with_cli_overrides_in(
+        codex_home: PathBuf,
+        cli_overrides: Vec<(String, TomlValue)>,
+        overrides: ConfigOverrides,
+    ) -> std::io::Result<Self> {
         // Step 1: parse `config.toml` into a generic JSON value.
         let mut root_value = load_config_as_toml(&codex_home)?;
 
-        // Step 2: apply the `-c` overrides.
+        // Step 2: layer the repository's `.codex/config.toml` underneath,
+        // keeping only what the project's trust level allows. Trust comes from
+        // the user's config alone.
+        let cwd = resolve_cwd(overrides.cwd.clone())?;
+        let trusted = is_cwd_trusted_in(&projects_from_toml(&root_value), &cwd);
+        let project_config = load_project_config(&cwd, &codex_home, trusted)?;
+        if let Some(layer) = &project_config {
+            apply_project_layer(&mut root_value, layer.value.clone());
+        }
+
+        // Step 3: apply the `-c` overrides.
         for (path, value) in cli_overrides.into_iter() {
             apply_toml_override(&mut root_value, &path, value);
         }
 
-        // Step 3: deserialize into `ConfigToml` so that Serde can enforce the
+        // Step 4: deserialize into `ConfigToml` so that Serde can enforce the
         // correct types.
         let cfg: ConfigToml = root_value.try_into().map_err(|e| {
             tracing::error!("Failed to deserialize overridden config: {e}");
             std::io::Error::new(std::io::ErrorKind::InvalidData, e)
         })?;
 
-        // Step 4: merge with the strongly-typed overrides.
-        Self::load_from_base_config_with_overrides(cfg, overrides, codex_home)
+        // Step 5: merge with the strongly-typed overrides.
+        let mut config = Self::load_from_base_config_with_overrides(cfg, overrides, codex_home)?;
+        if let Some(layer) = project_config {
+            config.project_config_path = Some(layer.path);
+            config.ignored_project_settings = layer.ignored;
+        }
+        Ok(config)
     }
 }
 
@@ -558,7 +613,7 @@ pub async fn persist_model_selection(
 }
 
 /// Apply a single dotted-path override onto a TOML value.
-fn apply_toml_override(root: &mut TomlValue, path: &str, value: TomlValue) {
+pub(crate) fn apply_toml_override(root: &mut TomlValue, path: &str, value: TomlValue) {
     use toml::value::Table;
 
     let segments: Vec<&str> = path.split('.').collect();
@@ -661,6 +716,12 @@ pub struct ConfigToml {
     #[serde(default)]
     pub history: Option<History>,
 
+    /// Settings that govern how session rollouts are stored.
+    pub rollout: Option<RolloutSettings>,
+
+    /// Settings for redacting secrets from command output and persisted text.
+    pub redaction: Option<RedactionSettings>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
@@ -781,29 +842,9 @@ impl ConfigToml {
     }
 
     pub fn is_cwd_trusted(&self, resolved_cwd: &Path) -> bool {
-        let projects = self.projects.clone().unwrap_or_default();
-
-        let is_path_trusted = |path: &Path| {
-            let path_str = path.to_string_lossy().to_string();
-            projects
-                .get(&path_str)
-                .map(|p| p.trust_level.as_deref() == Some("trusted"))
-                .unwrap_or(false)
-        };
-
-        // Fast path: exact cwd match
-        if is_path_trusted(resolved_cwd) {
-            return true;
-        }
-
-        // If cwd lives inside a git worktree, check whether the root git project
-        // (the primary repository working directory) is trusted. This lets
-        // worktrees inherit trust from the main project.
-        if let Some(root_project) = resolve_root_git_project_for_trust(resolved_cwd) {
-            return is_path_trusted(&root_project);
-        }
-
-        false
+        self.projects
+            .as_ref()
+            .is_some_and(|projects| is_cwd_trusted_in(projects, resolved_cwd))
     }
 
     pub fn get_config_profile(
@@ -828,6 +869,71 @@ impl ConfigToml {
     }
 }
 
+pub(crate) fn is_cwd_trusted_in(
+    projects: &HashMap<String, ProjectConfig>,
+    resolved_cwd: &Path,
+) -> bool {
+    let is_path_trusted = |path: &Path| {
+        let path_str = path.to_string_lossy().to_string();
+        projects
+            .get(&path_str)
+            .map(|p| p.trust_level.as_deref() == Some("trusted"))
+            .unwrap_or(false)
+    };
+
+    // Fast path: exact cwd match
+    if is_path_trusted(resolved_cwd) {
+        return true;
+    }
+
+    // If cwd lives inside a git worktree, check whether the root git project
+    // (the primary repository working directory) is trusted. This lets
+    // worktrees inherit trust from the main project.
+    if let Some(root_project) = resolve_root_git_project_for_trust(resolved_cwd) {
+        return is_path_trusted(&root_project);
+    }
+
+    false
+}
+
+/// Whether the user's `config.toml` in `codex_home` trusts the project at
+/// `cwd`, which decides what the project's `.codex` directory may configure.
+pub fn is_project_trusted(codex_home: &Path, cwd: &Path) -> bool {
+    load_config_as_toml(codex_home)
+        .is_ok_and(|root_value| is_cwd_trusted_in(&projects_from_toml(&root_value), cwd))
+}
+
+/// The `[projects]` table of a not yet deserialized config. Malformed entries
+/// are left for the full deserialization to report.
+pub(crate) fn projects_from_toml(root_value: &TomlValue) -> HashMap<String, ProjectConfig> {
+    root_value
+        .get("projects")
+        .cloned()
+        .and_then(|projects| projects.try_into().ok())
+        .unwrap_or_default()
+}
+
+/// Resolve the session cwd, defaulting to the process's current directory
+/// and resolving relative paths against it.
+fn resolve_cwd(cwd: Option<PathBuf>) -> std::io::Result<PathBuf> {
+    use std::env;
+
+    match cwd {
+        None => {
+            tracing::info!("cwd not set, using current dir");
+            env::current_dir()
+        }
+        Some(p) if p.is_absolute() => Ok(p),
+        Some(p) => {
+            // Resolve relative path against the current working directory.
+            tracing::info!("cwd is relative, resolving against current dir");
+            let mut current = env::current_dir()?;
+            current.push(p);
+            Ok(current)
+        }
+    }
+}
+
 /// Optional overrides for user configuration (e.g., from CLI flags).
 #[derive(Default, Debug, Clone)]
 pub struct ConfigOverrides {
@@ -917,24 +1023,7 @@ impl Config {
 
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
-        let resolved_cwd = {
-            use std::env;
-
-            match cwd {
-                None => {
-                    tracing::info!("cwd not set, using current dir");
-                    env::current_dir()?
-                }
-                Some(p) if p.is_absolute() => p,
-                Some(p) => {
-                    // Resolve relative path against the current working directory.
-                    tracing::info!("cwd is relative, resolving against current dir");
-                    let mut current = env::current_dir()?;
-                    current.push(p);
-                    current
-                }
-            }
-        };
+        let resolved_cwd = resolve_cwd(cwd)?;
 
         let history = cfg.history.unwrap_or_default();
 
@@ -992,6 +1081,14 @@ impl Config {
             .or(cfg.review_model)
             .unwrap_or_else(default_review_model);
 
+        let redaction = cfg.redaction.unwrap_or_default();
+        validate_redaction_settings(&redaction)?;
+
+        let tui_keymap = match cfg.tui.as_ref() {
+            Some(tui) => Keymap::from_toml(&tui.keymap)?,
+            None => Keymap::default(),
+        };
+
         let config = Self {
             model,
             review_model,
@@ -1016,6 +1113,8 @@ impl Config {
             project_doc_max_bytes: cfg.project_doc_max_bytes.unwrap_or(PROJECT_DOC_MAX_BYTES),
             codex_home,
             history,
+            rollout: cfg.rollout.unwrap_or_default(),
+            redaction,
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
             codex_linux_sandbox_exe,
 
@@ -1053,6 +1152,11 @@ impl Config {
                 .as_ref()
                 .map(|t| t.notifications.clone())
                 .unwrap_or_default(),
+            tui_keymap,
+            tui_theme: cfg.tui.as_ref().map(|t| t.theme).unwrap_or_default(),
+            tui_colors: cfg.tui.as_ref().map(|t| t.colors).unwrap_or_default(),
+            project_config_path: None,
+            ignored_project_settings: Vec::new(),
         };
         Ok(config)
     }
@@ -1163,6 +1267,7 @@ pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
 #[cfg(test)]
 mod tests {
     use crate::config_types::HistoryPersistence;
+    use crate::theme::ThemeColor;
 
     use super::*;
     use pretty_assertions::assert_eq;
@@ -1257,6 +1362,118 @@ exclude_slash_tmp = true
         );
     }
 
+    #[test]
+    fn project_config_is_layered_under_the_user_config() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let repo = TempDir::new()?;
+        let repo_path = repo.path().canonicalize()?;
+        std::fs::create_dir(repo_path.join(".git"))?;
+        std::fs::create_dir(repo_path.join(".codex"))?;
+        let project_key = repo_path.to_string_lossy();
+        std::fs::write(
+            codex_home.path().join(CONFIG_TOML_FILE),
+            format!("model = \"o3\"\n\n[projects.{project_key:?}]\ntrust_level = \"trusted\"\n"),
+        )?;
+        let project_config = repo_path.join(".codex").join(CONFIG_TOML_FILE);
+        std::fs::write(
+            &project_config,
+            "model = \"gpt-5\"\nproject_doc_max_bytes = 1234\nmodel_reasoning_effort = \"high\"\n",
+        )?;
+
+        let config = Config::load_with_cli_overrides_in(
+            codex_home.path().to_path_buf(),
+            vec![(
+                "model_reasoning_effort".to_string(),
+                TomlValue::String("low".to_string()),
+            )],
+            ConfigOverrides {
+                cwd: Some(repo_path),
+                ..Default::default()
+            },
+        )?;
+
+        // config.toml wins over the project, `-c` wins over both.
+        assert_eq!(config.model, "o3");
+        assert_eq!(config.project_doc_max_bytes, 1234);
+        assert_eq!(config.model_reasoning_effort, Some(ReasoningEffort::Low));
+        assert_eq!(config.project_config_path, Some(project_config));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_keymap_is_resolved_and_validated_at_load() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[tui.keymap]
+submit = "ctrl-enter"
+newline = "enter"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.tui_keymap.submit.to_string(), "ctrl-enter");
+        assert_eq!(config.tui_keymap.newline.to_string(), "enter");
+
+        let conflicting = toml::from_str::<ConfigToml>(
+            r#"
+[tui.keymap]
+open_transcript = "tab"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let err = Config::load_from_base_config_with_overrides(
+            conflicting,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("conflicting keymap should be rejected");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        assert!(toml::from_str::<ConfigToml>("[tui.keymap]\nsubmit = \"hyper-x\"\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn tui_theme_and_colors_are_parsed() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+
+        let cfg = toml::from_str::<ConfigToml>(
+            r##"
+[tui]
+theme = "high-contrast"
+
+[tui.colors]
+added = "light-green"
+error = "#ff5f5f"
+"##,
+        )
+        .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.tui_theme, ThemeName::HighContrast);
+        assert_eq!(
+            config.tui_colors,
+            ThemeColors {
+                added: Some(ThemeColor::LightGreen),
+                error: Some(ThemeColor::Rgb(0xff, 0x5f, 0x5f)),
+                ..ThemeColors::default()
+            }
+        );
+
+        assert!(toml::from_str::<ConfigToml>("[tui]\ntheme = \"solarized\"\n").is_err());
+        assert!(toml::from_str::<ConfigToml>("[tui.colors]\nadded = \"lime\"\n").is_err());
+        Ok(())
+    }
+
     #[test]
     fn load_global_mcp_servers_returns_empty_if_missing() -> anyhow::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1599,6 +1816,8 @@ model_verbosity = "high"
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 codex_home: fixture.codex_home(),
                 history: History::default(),
+                rollout: RolloutSettings::default(),
+                redaction: RedactionSettings::default(),
                 file_opener: UriBasedFileOpener::VsCode,
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
@@ -1617,6 +1836,11 @@ model_verbosity = "high"
                 active_profile: Some("o3".to_string()),
                 disable_paste_burst: false,
                 tui_notifications: Default::default(),
+                tui_keymap: Keymap::default(),
+                tui_theme: ThemeName::default(),
+                tui_colors: ThemeColors::default(),
+                project_config_path: None,
+                ignored_project_settings: Vec::new(),
             },
             o3_profile_config
         );
@@ -1657,6 +1881,8 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             codex_home: fixture.codex_home(),
             history: History::default(),
+            rollout: RolloutSettings::default(),
+            redaction: RedactionSettings::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -1675,6 +1901,11 @@ model_verbosity = "high"
             active_profile: Some("gpt3".to_string()),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_keymap: Keymap::default(),
+            tui_theme: ThemeName::default(),
+            tui_colors: ThemeColors::default(),
+            project_config_path: None,
+            ignored_project_settings: Vec::new(),
         };
 
         assert_eq!(expected_gpt3_profile_config, gpt3_profile_config);
@@ -1730,6 +1961,8 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             codex_home: fixture.codex_home(),
             history: History::default(),
+            rollout: RolloutSettings::default(),
+            redaction: RedactionSettings::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -1748,6 +1981,11 @@ model_verbosity = "high"
             active_profile: Some("zdr".to_string()),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_keymap: Keymap::default(),
+            tui_theme: ThemeName::default(),
+            tui_colors: ThemeColors::default(),
+            project_config_path: None,
+            ignored_project_settings: Vec::new(),
         };
 
         assert_eq!(expected_zdr_profile_config, zdr_profile_config);
@@ -1789,6 +2027,8 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             codex_home: fixture.codex_home(),
             history: History::default(),
+            rollout: RolloutSettings::default(),
+            redaction: RedactionSettings::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -1807,6 +2047,11 @@ model_verbosity = "high"
             active_profile: Some("gpt5".to_string()),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_keymap: Keymap::default(),
+            tui_theme: ThemeName::default(),
+            tui_colors: ThemeColors::default(),
+            project_config_path: None,
+            ignored_project_settings: Vec::new(),
         };
 
         assert_eq!(expected_gpt5_profile_config, gpt5_profile_config);
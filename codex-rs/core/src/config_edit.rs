@@ -1,5 +1,10 @@
 use crate::config::CONFIG_TOML_FILE;
+use crate::config_inspect::ConfigIssue;
+use crate::config_inspect::IssueSeverity;
+use crate::config_inspect::validate_config;
+use crate::config_inspect::validate_config_contents;
 use anyhow::Result;
+use std::collections::HashSet;
 use std::path::Path;
 use tempfile::NamedTempFile;
 use toml_edit::DocumentMut;
@@ -49,6 +54,62 @@ pub async fn persist_overrides_and_clear_if_none(
     persist_overrides_with_behavior(codex_home, profile, overrides, NoneBehavior::Remove).await
 }
 
+/// Set the value at `segments` in `config.toml`, as given and without
+/// redirecting it into the active profile, preserving existing formatting and
+/// comments. The edited file is validated as `codex config validate` would
+/// from `cwd` and left untouched if the edit introduces errors. Returns what
+/// validation still reports about `config.toml`.
+pub async fn set_config_value(
+    codex_home: &Path,
+    cwd: &Path,
+    segments: &[&str],
+    value: toml_edit::Item,
+) -> Result<Vec<ConfigIssue>> {
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    let mut doc = match tokio::fs::read_to_string(&config_path).await {
+        Ok(contents) => contents.parse::<DocumentMut>()?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tokio::fs::create_dir_all(codex_home).await?;
+            DocumentMut::new()
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    apply_toml_edit_override_segments(&mut doc, segments, value);
+    let contents = doc.to_string();
+    // Errors the file already had are reported, but do not block the edit,
+    // which may well be the fix for them.
+    let existing_errors: HashSet<String> = validate_config(codex_home, cwd)
+        .iter()
+        .filter(|issue| issue.severity == IssueSeverity::Error)
+        .map(ToString::to_string)
+        .collect();
+    let issues: Vec<ConfigIssue> = validate_config_contents(codex_home, cwd, &contents)
+        .into_iter()
+        .filter(|issue| issue.file == config_path)
+        .collect();
+    let errors: Vec<String> = issues
+        .iter()
+        .filter(|issue| issue.severity == IssueSeverity::Error)
+        .map(ToString::to_string)
+        .filter(|error| !existing_errors.contains(error))
+        .collect();
+    if !errors.is_empty() {
+        anyhow::bail!(
+            "`{}` would make {} invalid; not writing it:\n{}",
+            segments.join("."),
+            config_path.display(),
+            errors.join("\n")
+        );
+    }
+
+    let tmp_file = NamedTempFile::new_in(codex_home)?;
+    tokio::fs::write(tmp_file.path(), contents).await?;
+    tmp_file.persist(config_path)?;
+
+    Ok(issues)
+}
+
 /// Apply a single override onto a `toml_edit` document while preserving
 /// existing formatting/comments.
 /// The key is expressed as explicit segments to correctly handle keys that
@@ -740,6 +801,66 @@ model_reasoning_effort = "high"
         assert!(!codex_home.join(CONFIG_TOML_FILE).exists());
     }
 
+    #[tokio::test]
+    async fn set_config_value_writes_typed_values_and_rejects_invalid_ones() {
+        let tmpdir = tempdir().expect("tmp");
+        let codex_home = tmpdir.path();
+        let seed = "# keep me\nprofile = \"team\"\n";
+        tokio::fs::write(codex_home.join(CONFIG_TOML_FILE), seed)
+            .await
+            .expect("seed write");
+
+        // The undefined profile is reported, but does not block other edits.
+        let issues = set_config_value(
+            codex_home,
+            codex_home,
+            &["sandbox_workspace_write", "network_access"],
+            toml_edit::value(true),
+        )
+        .await
+        .expect("set");
+        assert_eq!(
+            issues
+                .iter()
+                .map(|issue| issue.key.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("profile")]
+        );
+        let err = set_config_value(
+            codex_home,
+            codex_home,
+            &["approval_policy"],
+            toml_edit::value("sometimes"),
+        )
+        .await
+        .expect_err("invalid value");
+        assert!(
+            err.to_string().contains("unknown variant `sometimes`"),
+            "{err}"
+        );
+        // Values that parse but do not resolve are rejected too.
+        let err = set_config_value(
+            codex_home,
+            codex_home,
+            &["model_provider"],
+            toml_edit::value("openia"),
+        )
+        .await
+        .expect_err("unknown provider");
+        assert!(
+            err.to_string().contains("unknown model provider `openia`"),
+            "{err}"
+        );
+        let contents = read_config(codex_home).await;
+        let expected = r#"# keep me
+profile = "team"
+
+[sandbox_workspace_write]
+network_access = true
+"#;
+        assert_eq!(contents, expected);
+    }
+
     // Test helper moved to bottom per review guidance.
     async fn read_config(codex_home: &Path) -> String {
         let p = codex_home.join(CONFIG_TOML_FILE);
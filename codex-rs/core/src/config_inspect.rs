@@ -0,0 +1,700 @@
+//! Inspection of the configuration for `codex config show` and
+//! `codex config validate`.
+//!
+//! [`effective_config_entries`] replays the layering done by
+//! [`Config::load_with_cli_overrides`] value by value to record where each
+//! effective setting comes from. [`validate_config`] checks the user's
+//! `config.toml` and the project's `.codex/config.toml` for mistakes that
+//! loading otherwise ignores or only reports one at a time.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use toml::Value as TomlValue;
+
+use crate::config::CONFIG_TOML_FILE;
+use crate::config::Config;
+use crate::config::ConfigOverrides;
+use crate::config::ConfigToml;
+use crate::config::apply_toml_override;
+use crate::config::is_cwd_trusted_in;
+use crate::config::load_config_as_toml;
+use crate::config::projects_from_toml;
+use crate::model_provider_info::built_in_model_providers;
+use crate::project_config::PROJECT_CONFIG_KEYS;
+use crate::project_config::apply_project_layer;
+use crate::project_config::filter_project_config;
+use crate::project_config::find_project_config;
+use crate::project_config::load_project_config;
+use crate::protocol::SandboxPolicy;
+
+/// Keys of a `[profiles.<name>]` table. The active profile's values take
+/// precedence over the same keys at the top level, `-c` overrides included.
+const PROFILE_KEYS: &[&str] = &[
+    "model",
+    "model_provider",
+    "approval_policy",
+    "model_reasoning_effort",
+    "model_reasoning_summary",
+    "model_verbosity",
+    "chatgpt_base_url",
+    "experimental_instructions_file",
+];
+
+/// Where the effective value of a setting comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConfigSource {
+    /// Built-in default; not set anywhere.
+    Default,
+    /// `$CODEX_HOME/config.toml` or a project's `.codex/config.toml`.
+    File { path: PathBuf },
+    /// The active `[profiles.<name>]` table.
+    Profile { name: String },
+    /// A `-c key=value` command-line override.
+    CliOverride,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::File { path } => write!(f, "{}", path.display()),
+            ConfigSource::Profile { name } => write!(f, "profile `{name}`"),
+            ConfigSource::CliOverride => write!(f, "-c override"),
+        }
+    }
+}
+
+/// One effective setting, keyed by its dotted TOML path.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: TomlValue,
+    pub source: ConfigSource,
+}
+
+/// Leaf values by key path, with the layer that set them.
+type Entries = BTreeMap<Vec<String>, (TomlValue, ConfigSource)>;
+
+/// The effective configuration behind `config`, one entry per leaf value,
+/// sorted by key. `cli_overrides` must be the `-c` overrides `config` was
+/// loaded with. Profiles other than the active one are left out, and the
+/// active profile's values are reported under the keys they override.
+pub fn effective_config_entries(
+    config: &Config,
+    cli_overrides: &[(String, TomlValue)],
+) -> std::io::Result<Vec<ConfigEntry>> {
+    let mut entries = Entries::new();
+
+    let user = load_config_as_toml(&config.codex_home)?;
+    let user_source = ConfigSource::File {
+        path: config.codex_home.join(CONFIG_TOML_FILE),
+    };
+    let trusted = is_cwd_trusted_in(&projects_from_toml(&user), &config.cwd);
+    let mut merged = user.clone();
+    if let Some(layer) = load_project_config(&config.cwd, &config.codex_home, trusted)? {
+        let source = ConfigSource::File { path: layer.path };
+        set_leaves(&mut entries, Vec::new(), &layer.value, &source);
+        apply_project_layer(&mut merged, layer.value);
+    }
+    set_leaves(&mut entries, Vec::new(), &user, &user_source);
+
+    for (path, value) in cli_overrides {
+        // Like `apply_toml_override`, an override replaces the whole value
+        // at its path rather than merging into it.
+        let key: Vec<String> = path.split('.').map(str::to_string).collect();
+        entries.retain(|existing, _| !existing.starts_with(&key));
+        set_leaves(&mut entries, key, value, &ConfigSource::CliOverride);
+        apply_toml_override(&mut merged, path, value.clone());
+    }
+
+    if let Some(name) = &config.active_profile
+        && let Some(TomlValue::Table(profile)) = merged
+            .get("profiles")
+            .and_then(|profiles| profiles.get(name))
+    {
+        let source = ConfigSource::Profile { name: name.clone() };
+        for (key, value) in profile {
+            if PROFILE_KEYS.contains(&key.as_str()) {
+                set_leaves(&mut entries, vec![key.clone()], value, &source);
+            }
+        }
+    }
+    entries.retain(|key, _| key.first().map(String::as_str) != Some("profiles"));
+
+    for (key, value) in default_values(config) {
+        entries
+            .entry(vec![key.to_string()])
+            .or_insert((value, ConfigSource::Default));
+    }
+
+    Ok(entries
+        .into_iter()
+        .map(|(key, (value, source))| ConfigEntry {
+            key: display_key(&key),
+            value,
+            source,
+        })
+        .collect())
+}
+
+/// Record the leaves of `value` at `key`, replacing whatever they shadow the
+/// way `merge_toml` does: a table merges into a table, anything else replaces
+/// the value at its key and everything below it.
+fn set_leaves(entries: &mut Entries, key: Vec<String>, value: &TomlValue, source: &ConfigSource) {
+    match value {
+        TomlValue::Table(table) if key.is_empty() || !table.is_empty() => {
+            entries.remove(&key);
+            for (child, value) in table {
+                let mut child_key = key.clone();
+                child_key.push(child.clone());
+                set_leaves(entries, child_key, value, source);
+            }
+        }
+        _ => {
+            for len in 1..key.len() {
+                entries.remove(&key[..len]);
+            }
+            entries.retain(|existing, _| !existing.starts_with(&key));
+            entries.insert(key, (value.clone(), source.clone()));
+        }
+    }
+}
+
+/// Resolved values of the main settings, reported as defaults when no layer
+/// sets them.
+fn default_values(config: &Config) -> Vec<(&'static str, TomlValue)> {
+    let sandbox_mode = match config.sandbox_policy {
+        SandboxPolicy::DangerFullAccess => "danger-full-access",
+        SandboxPolicy::ReadOnly => "read-only",
+        SandboxPolicy::WorkspaceWrite { .. } => "workspace-write",
+    };
+    let mut values = vec![
+        ("model", TomlValue::String(config.model.clone())),
+        (
+            "review_model",
+            TomlValue::String(config.review_model.clone()),
+        ),
+        (
+            "model_provider",
+            TomlValue::String(config.model_provider_id.clone()),
+        ),
+        (
+            "approval_policy",
+            TomlValue::String(config.approval_policy.to_string()),
+        ),
+        ("sandbox_mode", TomlValue::String(sandbox_mode.to_string())),
+        (
+            "model_reasoning_summary",
+            TomlValue::String(config.model_reasoning_summary.to_string()),
+        ),
+        (
+            "project_doc_max_bytes",
+            TomlValue::Integer(config.project_doc_max_bytes as i64),
+        ),
+        (
+            "hide_agent_reasoning",
+            TomlValue::Boolean(config.hide_agent_reasoning),
+        ),
+        (
+            "show_raw_agent_reasoning",
+            TomlValue::Boolean(config.show_raw_agent_reasoning),
+        ),
+    ];
+    if let Some(effort) = config.model_reasoning_effort {
+        values.push((
+            "model_reasoning_effort",
+            TomlValue::String(effort.to_string()),
+        ));
+    }
+    values
+}
+
+/// Join key segments into a dotted TOML key, quoting segments that are not
+/// bare keys.
+fn display_key(segments: &[String]) -> String {
+    segments
+        .iter()
+        .map(|segment| {
+            let bare = !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if bare {
+                segment.clone()
+            } else {
+                TomlValue::String(segment.clone()).to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+/// A problem found by [`validate_config`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigIssue {
+    pub severity: IssueSeverity,
+    pub file: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            IssueSeverity::Error => "error",
+            IssueSeverity::Warning => "warning",
+        };
+        write!(f, "{severity}: {}", self.file.display())?;
+        if let Some(key) = &self.key {
+            write!(f, ": `{key}`")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Check `$CODEX_HOME/config.toml` and the `.codex/config.toml` of the
+/// repository containing `cwd` for syntax errors, unknown keys, invalid
+/// values, missing files and commands, and unknown profiles and providers.
+pub fn validate_config(codex_home: &Path, cwd: &Path) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let user = check_file(&codex_home.join(CONFIG_TOML_FILE), &mut issues);
+    validate_with_user_config(codex_home, cwd, user, issues)
+}
+
+/// Like [`validate_config`], with `contents` in place of what is on disk in
+/// `$CODEX_HOME/config.toml`. Used to check an edit before writing it.
+pub fn validate_config_contents(codex_home: &Path, cwd: &Path, contents: &str) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let user = check_contents(&codex_home.join(CONFIG_TOML_FILE), contents, &mut issues);
+    validate_with_user_config(codex_home, cwd, user, issues)
+}
+
+/// The rest of [`validate_config`] once the user's config was parsed.
+fn validate_with_user_config(
+    codex_home: &Path,
+    cwd: &Path,
+    user: Option<(TomlValue, ConfigToml)>,
+    mut issues: Vec<ConfigIssue>,
+) -> Vec<ConfigIssue> {
+    let user_path = codex_home.join(CONFIG_TOML_FILE);
+    let mut provider_ids: HashSet<String> = built_in_model_providers().into_keys().collect();
+    if let Some((_, cfg)) = &user {
+        provider_ids.extend(cfg.model_providers.keys().cloned());
+        check_values(&user_path, cfg, cwd, &provider_ids, &mut issues);
+    }
+
+    if let Some(project_path) = find_project_config(cwd, codex_home)
+        && let Some((value, cfg)) = check_file(&project_path, &mut issues)
+    {
+        let table = match value {
+            TomlValue::Table(table) => table,
+            _ => toml::Table::new(),
+        };
+        for key in table.keys() {
+            if !PROJECT_CONFIG_KEYS.contains(&key.as_str()) {
+                issues.push(ConfigIssue {
+                    severity: IssueSeverity::Warning,
+                    file: project_path.clone(),
+                    key: Some(key.clone()),
+                    message: "cannot be set in a project config and is ignored".to_string(),
+                });
+            }
+        }
+        let trusted = user
+            .as_ref()
+            .is_some_and(|(_, user_cfg)| user_cfg.is_cwd_trusted(cwd));
+        let (_, ignored) = filter_project_config(table, trusted);
+        for key in ignored {
+            issues.push(ConfigIssue {
+                severity: IssueSeverity::Warning,
+                file: project_path.clone(),
+                key: Some(key),
+                message: "is ignored until the project is trusted".to_string(),
+            });
+        }
+        check_values(&project_path, &cfg, cwd, &provider_ids, &mut issues);
+    }
+
+    // Anything the checks above do not cover (key bindings, redaction
+    // patterns, ...) still surfaces when building the effective config.
+    if !issues
+        .iter()
+        .any(|issue| issue.severity == IssueSeverity::Error)
+        && let Some((_, cfg)) = user
+    {
+        let overrides = ConfigOverrides {
+            cwd: Some(cwd.to_path_buf()),
+            ..Default::default()
+        };
+        if let Err(e) =
+            Config::load_from_base_config_with_overrides(cfg, overrides, codex_home.to_path_buf())
+        {
+            issues.push(error(&user_path, None, e.to_string()));
+        }
+    }
+
+    issues
+}
+
+fn error(file: &Path, key: Option<&str>, message: String) -> ConfigIssue {
+    ConfigIssue {
+        severity: IssueSeverity::Error,
+        file: file.to_path_buf(),
+        key: key.map(str::to_string),
+        message,
+    }
+}
+
+fn warning(file: &Path, key: Option<&str>, message: String) -> ConfigIssue {
+    ConfigIssue {
+        severity: IssueSeverity::Warning,
+        ..error(file, key, message)
+    }
+}
+
+/// Parse `path`, reporting syntax errors, unknown keys and values of the
+/// wrong type. Returns `None` when the file does not exist or cannot be
+/// deserialized.
+fn check_file(path: &Path, issues: &mut Vec<ConfigIssue>) -> Option<(TomlValue, ConfigToml)> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            issues.push(error(path, None, format!("failed to read: {e}")));
+            return None;
+        }
+    };
+    check_contents(path, &contents, issues)
+}
+
+/// Like [`check_file`], for the `contents` of the file at `path`.
+fn check_contents(
+    path: &Path,
+    contents: &str,
+    issues: &mut Vec<ConfigIssue>,
+) -> Option<(TomlValue, ConfigToml)> {
+    let value: TomlValue = match toml::from_str(contents) {
+        Ok(value) => value,
+        Err(e) => {
+            issues.push(error(path, None, e.to_string().trim_end().to_string()));
+            return None;
+        }
+    };
+
+    let mut unknown = Vec::new();
+    let _: Result<ConfigToml, _> = serde_ignored::deserialize(value.clone(), |path| {
+        let mut segments = Vec::new();
+        ignored_key_segments(&path, &mut segments);
+        unknown.push(display_key(&segments));
+    });
+    unknown.sort();
+    for key in unknown {
+        issues.push(warning(path, Some(&key), "unknown key".to_string()));
+    }
+
+    // Deserialize from the text again so errors point at a line.
+    match toml::from_str::<ConfigToml>(contents) {
+        Ok(cfg) => Some((value, cfg)),
+        Err(e) => {
+            issues.push(error(path, None, e.to_string().trim_end().to_string()));
+            None
+        }
+    }
+}
+
+/// The key path of an ignored value, leaving out the `Option` and newtype
+/// wrappers `serde_ignored` records along the way.
+fn ignored_key_segments(path: &serde_ignored::Path<'_>, segments: &mut Vec<String>) {
+    match path {
+        serde_ignored::Path::Root => {}
+        serde_ignored::Path::Seq { parent, index } => {
+            ignored_key_segments(parent, segments);
+            segments.push(index.to_string());
+        }
+        serde_ignored::Path::Map { parent, key } => {
+            ignored_key_segments(parent, segments);
+            segments.push(key.clone());
+        }
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => {
+            ignored_key_segments(parent, segments);
+        }
+    }
+}
+
+/// Check references to profiles, providers, files and commands.
+fn check_values(
+    path: &Path,
+    cfg: &ConfigToml,
+    cwd: &Path,
+    provider_ids: &HashSet<String>,
+    issues: &mut Vec<ConfigIssue>,
+) {
+    if let Some(profile) = &cfg.profile
+        && !cfg.profiles.contains_key(profile)
+    {
+        issues.push(error(
+            path,
+            Some("profile"),
+            format!("profile `{profile}` is not defined under [profiles]"),
+        ));
+    }
+
+    let mut model_providers = vec![("model_provider".to_string(), &cfg.model_provider)];
+    let mut instructions_files = vec![(
+        "experimental_instructions_file".to_string(),
+        &cfg.experimental_instructions_file,
+    )];
+    for (name, profile) in &cfg.profiles {
+        let prefix = display_key(&["profiles".to_string(), name.clone()]);
+        model_providers.push((format!("{prefix}.model_provider"), &profile.model_provider));
+        instructions_files.push((
+            format!("{prefix}.experimental_instructions_file"),
+            &profile.experimental_instructions_file,
+        ));
+    }
+    for (key, provider) in model_providers {
+        if let Some(provider) = provider
+            && !provider_ids.contains(provider)
+        {
+            let mut known: Vec<&str> = provider_ids.iter().map(String::as_str).collect();
+            known.sort_unstable();
+            issues.push(error(
+                path,
+                Some(&key),
+                format!(
+                    "unknown model provider `{provider}` (known providers: {})",
+                    known.join(", ")
+                ),
+            ));
+        }
+    }
+    for (key, file) in instructions_files {
+        if let Some(file) = file
+            && !cwd.join(file).is_file()
+        {
+            issues.push(error(
+                path,
+                Some(&key),
+                format!("file `{}` does not exist", file.display()),
+            ));
+        }
+    }
+
+    if let Some(sandbox) = &cfg.sandbox_workspace_write {
+        for root in &sandbox.writable_roots {
+            if !root.is_absolute() {
+                issues.push(error(
+                    path,
+                    Some("sandbox_workspace_write.writable_roots"),
+                    format!("`{}` is not an absolute path", root.display()),
+                ));
+            } else if !root.exists() {
+                issues.push(warning(
+                    path,
+                    Some("sandbox_workspace_write.writable_roots"),
+                    format!("`{}` does not exist", root.display()),
+                ));
+            }
+        }
+    }
+
+    let mut commands: Vec<(String, &str)> = cfg
+        .mcp_servers
+        .iter()
+        .map(|(name, server)| {
+            let key = display_key(&[
+                "mcp_servers".to_string(),
+                name.clone(),
+                "command".to_string(),
+            ]);
+            (key, server.command.as_str())
+        })
+        .collect();
+    if let Some(program) = cfg.notify.as_ref().and_then(|notify| notify.first()) {
+        commands.push(("notify".to_string(), program.as_str()));
+    }
+    commands.sort();
+    for (key, command) in commands {
+        if which::which(command).is_err() && !cwd.join(command).is_file() {
+            issues.push(warning(
+                path,
+                Some(&key),
+                format!("command `{command}` was not found"),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn messages(issues: &[ConfigIssue]) -> Vec<(IssueSeverity, Option<&str>, &str)> {
+        issues
+            .iter()
+            .map(|issue| (issue.severity, issue.key.as_deref(), issue.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn validate_reports_unknown_keys_providers_profiles_and_paths() {
+        let codex_home = TempDir::new().unwrap();
+        let cwd = TempDir::new().unwrap();
+        std::fs::write(
+            codex_home.path().join(CONFIG_TOML_FILE),
+            r#"
+modle = "o3"
+profile = "fast"
+model_provider = "openia"
+
+[sandbox_workspace_write]
+writable_roots = ["relative/dir"]
+
+[profiles.slow]
+model = "o3"
+experimental_instructions_file = "missing.md"
+
+[mcp_servers.docs]
+command = "definitely-not-a-real-command-for-codex-tests"
+comand = "typo"
+"#,
+        )
+        .unwrap();
+
+        let issues = validate_config(codex_home.path(), cwd.path());
+
+        let known_providers = {
+            let mut ids: Vec<String> = built_in_model_providers().into_keys().collect();
+            ids.sort_unstable();
+            ids.join(", ")
+        };
+        let provider_message =
+            format!("unknown model provider `openia` (known providers: {known_providers})");
+        assert_eq!(
+            messages(&issues),
+            vec![
+                (
+                    IssueSeverity::Warning,
+                    Some("mcp_servers.docs.comand"),
+                    "unknown key"
+                ),
+                (IssueSeverity::Warning, Some("modle"), "unknown key"),
+                (
+                    IssueSeverity::Error,
+                    Some("profile"),
+                    "profile `fast` is not defined under [profiles]"
+                ),
+                (
+                    IssueSeverity::Error,
+                    Some("model_provider"),
+                    provider_message.as_str()
+                ),
+                (
+                    IssueSeverity::Error,
+                    Some("profiles.slow.experimental_instructions_file"),
+                    "file `missing.md` does not exist"
+                ),
+                (
+                    IssueSeverity::Error,
+                    Some("sandbox_workspace_write.writable_roots"),
+                    "`relative/dir` is not an absolute path"
+                ),
+                (
+                    IssueSeverity::Warning,
+                    Some("mcp_servers.docs.command"),
+                    "command `definitely-not-a-real-command-for-codex-tests` was not found"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_reports_invalid_enum_values_with_their_line() {
+        let codex_home = TempDir::new().unwrap();
+        let cwd = TempDir::new().unwrap();
+        std::fs::write(
+            codex_home.path().join(CONFIG_TOML_FILE),
+            "model = \"o3\"\napproval_policy = \"sometimes\"\n",
+        )
+        .unwrap();
+
+        let issues = validate_config(codex_home.path(), cwd.path());
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+        assert!(
+            issues[0].message.contains("line 2"),
+            "{}",
+            issues[0].message
+        );
+        assert!(
+            issues[0].message.contains("unknown variant `sometimes`"),
+            "{}",
+            issues[0].message
+        );
+    }
+
+    #[test]
+    fn set_leaves_follows_merge_and_override_precedence() {
+        let file = ConfigSource::File {
+            path: PathBuf::from("config.toml"),
+        };
+        let profile = ConfigSource::Profile {
+            name: "fast".to_string(),
+        };
+        let mut entries = Entries::new();
+        let base: TomlValue = toml::from_str(
+            "model = \"o3\"\n[shell_environment_policy]\ninherit = \"core\"\nexclude = [\"AWS_*\"]\n",
+        )
+        .unwrap();
+        set_leaves(&mut entries, Vec::new(), &base, &file);
+        let key = vec!["shell_environment_policy".to_string()];
+        entries.retain(|existing, _| !existing.starts_with(&key));
+        set_leaves(
+            &mut entries,
+            key,
+            &toml::from_str::<TomlValue>("inherit = \"all\"").unwrap(),
+            &ConfigSource::CliOverride,
+        );
+        set_leaves(
+            &mut entries,
+            vec!["model".to_string()],
+            &TomlValue::String("gpt-5".to_string()),
+            &profile,
+        );
+
+        let entries: Vec<(String, String, ConfigSource)> = entries
+            .into_iter()
+            .map(|(key, (value, source))| (display_key(&key), value.to_string(), source))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("model".to_string(), "\"gpt-5\"".to_string(), profile),
+                (
+                    "shell_environment_policy.inherit".to_string(),
+                    "\"all\"".to_string(),
+                    ConfigSource::CliOverride
+                ),
+            ]
+        );
+    }
+}
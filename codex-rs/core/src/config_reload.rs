@@ -0,0 +1,558 @@
+//! Picking up `config.toml` edits while a session is running.
+//!
+//! Frontends watch the config files with [`ConfigReloader::watch`], reload
+//! the [`Config`] when they change and use the returned [`ConfigChanges`] to
+//! update running conversations. Only some settings can change mid-session:
+//!
+//! * the model and reasoning settings are applied through
+//!   [`Op::OverrideTurnContext`];
+//! * the approval policy, sandbox and MCP servers are only applied once the
+//!   user confirms them, since an agent allowed to write to the workspace
+//!   could otherwise loosen its own permissions by editing a config file.
+//!   MCP servers are restarted through [`Op::ReconfigureMcpServers`], which
+//!   only touches the servers whose configuration changed;
+//! * settings that only the frontend reads (notifications, theme, ...) are
+//!   left to the frontend;
+//! * everything else (providers, `notify`, tools, ...) is captured when the
+//!   session starts and needs a restart.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::unbounded_channel;
+use tokio::time::MissedTickBehavior;
+use toml::Value as TomlValue;
+
+use crate::config::CONFIG_TOML_FILE;
+use crate::config::Config;
+use crate::config::ConfigOverrides;
+use crate::config::find_codex_home;
+use crate::project_config::project_config_location;
+use crate::protocol::Op;
+
+/// How often the config files are checked for changes.
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How a new [`Config`] differs from the previous one. Settings are named by
+/// their `config.toml` key.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConfigChanges {
+    /// Ops that apply the changes in `session` to a running conversation.
+    pub ops: Vec<Op>,
+    /// Settings a running conversation picks up through `ops`.
+    pub session: Vec<&'static str>,
+    /// Ops that apply the changes in `permissions`. Frontends must not
+    /// submit these without the user's confirmation.
+    pub permission_ops: Vec<Op>,
+    /// Approval, sandbox and MCP server settings, applied through
+    /// `permission_ops`.
+    pub permissions: Vec<&'static str>,
+    /// Settings only a frontend reads. Each frontend decides which of these
+    /// it can apply live.
+    pub frontend: Vec<&'static str>,
+    /// Settings that only take effect in a new session.
+    pub requires_restart: Vec<&'static str>,
+}
+
+impl ConfigChanges {
+    pub fn is_empty(&self) -> bool {
+        self.session.is_empty()
+            && self.permissions.is_empty()
+            && self.frontend.is_empty()
+            && self.requires_restart.is_empty()
+    }
+}
+
+/// Compare two configs loaded for the same session and classify every
+/// setting that differs.
+pub fn diff_config(old: &Config, new: &Config) -> ConfigChanges {
+    // Destructure so that adding a field to `Config` forces a decision here.
+    let Config {
+        model,
+        review_model,
+        model_family,
+        model_context_window,
+        model_max_output_tokens,
+        model_auto_compact_token_limit,
+        model_provider_id,
+        model_provider,
+        approval_policy,
+        sandbox_policy,
+        shell_environment_policy,
+        hide_agent_reasoning,
+        show_raw_agent_reasoning,
+        user_instructions,
+        base_instructions,
+        notify,
+        tui_notifications,
+        tui_keymap,
+        tui_theme,
+        tui_colors,
+        project_config_path: _,
+        ignored_project_settings: _,
+        cwd: _,
+        mcp_servers,
+        model_providers,
+        project_doc_max_bytes,
+        codex_home: _,
+        history,
+        rollout,
+        redaction,
+        file_opener,
+        codex_linux_sandbox_exe: _,
+        model_reasoning_effort,
+        model_reasoning_summary,
+        model_verbosity,
+        chatgpt_base_url,
+        include_plan_tool,
+        include_apply_patch_tool,
+        tools_web_search_request,
+        use_experimental_streamable_shell_tool,
+        use_experimental_unified_exec_tool,
+        include_view_image_tool,
+        active_profile: _,
+        disable_paste_burst,
+    } = new;
+
+    let mut changes = ConfigChanges::default();
+
+    let model_changed = *model != old.model;
+    let effort_changed = *model_reasoning_effort != old.model_reasoning_effort;
+    let summary_changed = *model_reasoning_summary != old.model_reasoning_summary;
+    let approval_changed = *approval_policy != old.approval_policy;
+    let sandbox_changed = *sandbox_policy != old.sandbox_policy;
+    note(&mut changes.session, model_changed, "model");
+    note(
+        &mut changes.session,
+        effort_changed,
+        "model_reasoning_effort",
+    );
+    note(
+        &mut changes.session,
+        summary_changed,
+        "model_reasoning_summary",
+    );
+    if !changes.session.is_empty() {
+        changes.ops.push(Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy: None,
+            sandbox_policy: None,
+            model: model_changed.then(|| model.clone()),
+            effort: effort_changed.then_some(*model_reasoning_effort),
+            summary: summary_changed.then_some(*model_reasoning_summary),
+        });
+    }
+
+    note(
+        &mut changes.permissions,
+        approval_changed,
+        "approval_policy",
+    );
+    note(&mut changes.permissions, sandbox_changed, "sandbox");
+    if approval_changed || sandbox_changed {
+        changes.permission_ops.push(Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy: approval_changed.then_some(*approval_policy),
+            sandbox_policy: sandbox_changed.then(|| sandbox_policy.clone()),
+            model: None,
+            effort: None,
+            summary: None,
+        });
+    }
+    if *mcp_servers != old.mcp_servers {
+        changes.permissions.push("mcp_servers");
+        changes.permission_ops.push(Op::ReconfigureMcpServers {
+            mcp_servers: mcp_servers.clone(),
+        });
+    }
+
+    let frontend = &mut changes.frontend;
+    note(
+        frontend,
+        *tui_notifications != old.tui_notifications,
+        "tui.notifications",
+    );
+    note(frontend, *tui_keymap != old.tui_keymap, "tui.keymap");
+    note(frontend, *tui_theme != old.tui_theme, "tui.theme");
+    note(frontend, *tui_colors != old.tui_colors, "tui.colors");
+    note(frontend, *file_opener != old.file_opener, "file_opener");
+    note(
+        frontend,
+        *hide_agent_reasoning != old.hide_agent_reasoning,
+        "hide_agent_reasoning",
+    );
+    note(
+        frontend,
+        *disable_paste_burst != old.disable_paste_burst,
+        "disable_paste_burst",
+    );
+
+    let restart = &mut changes.requires_restart;
+    note(restart, *review_model != old.review_model, "review_model");
+    // The context window and output limits follow the model, so they only
+    // need a restart when they were changed on their own.
+    if !model_changed {
+        note(restart, *model_family != old.model_family, "model_family");
+        note(
+            restart,
+            *model_context_window != old.model_context_window,
+            "model_context_window",
+        );
+        note(
+            restart,
+            *model_max_output_tokens != old.model_max_output_tokens,
+            "model_max_output_tokens",
+        );
+        note(
+            restart,
+            *model_auto_compact_token_limit != old.model_auto_compact_token_limit,
+            "model_auto_compact_token_limit",
+        );
+    }
+    note(
+        restart,
+        *model_provider_id != old.model_provider_id || *model_provider != old.model_provider,
+        "model_provider",
+    );
+    note(
+        restart,
+        *model_providers != old.model_providers,
+        "model_providers",
+    );
+    note(
+        restart,
+        *model_verbosity != old.model_verbosity,
+        "model_verbosity",
+    );
+    note(
+        restart,
+        *shell_environment_policy != old.shell_environment_policy,
+        "shell_environment_policy",
+    );
+    note(
+        restart,
+        *user_instructions != old.user_instructions,
+        "instructions",
+    );
+    note(
+        restart,
+        *base_instructions != old.base_instructions,
+        "experimental_instructions_file",
+    );
+    note(
+        restart,
+        *show_raw_agent_reasoning != old.show_raw_agent_reasoning,
+        "show_raw_agent_reasoning",
+    );
+    note(restart, *notify != old.notify, "notify");
+    note(
+        restart,
+        *project_doc_max_bytes != old.project_doc_max_bytes,
+        "project_doc_max_bytes",
+    );
+    note(restart, *history != old.history, "history");
+    note(restart, *rollout != old.rollout, "rollout");
+    note(restart, *redaction != old.redaction, "redaction");
+    note(
+        restart,
+        *chatgpt_base_url != old.chatgpt_base_url,
+        "chatgpt_base_url",
+    );
+    note(
+        restart,
+        *include_plan_tool != old.include_plan_tool,
+        "include_plan_tool",
+    );
+    note(
+        restart,
+        *include_apply_patch_tool != old.include_apply_patch_tool,
+        "include_apply_patch_tool",
+    );
+    note(
+        restart,
+        *tools_web_search_request != old.tools_web_search_request,
+        "tools.web_search",
+    );
+    note(
+        restart,
+        *use_experimental_streamable_shell_tool != old.use_experimental_streamable_shell_tool,
+        "experimental_use_exec_command_tool",
+    );
+    note(
+        restart,
+        *use_experimental_unified_exec_tool != old.use_experimental_unified_exec_tool,
+        "experimental_use_unified_exec_tool",
+    );
+    note(
+        restart,
+        *include_view_image_tool != old.include_view_image_tool,
+        "tools.view_image",
+    );
+
+    changes
+}
+
+fn note(list: &mut Vec<&'static str>, changed: bool, key: &'static str) {
+    if changed {
+        list.push(key);
+    }
+}
+
+/// Reloads the config with the overrides it was first loaded with, and
+/// reports how it changed since the last load.
+pub struct ConfigReloader {
+    codex_home: PathBuf,
+    cli_overrides: Vec<(String, TomlValue)>,
+    overrides: ConfigOverrides,
+    current: Config,
+}
+
+impl ConfigReloader {
+    /// Load the config the same way the session did. Frontends adjust their
+    /// config at runtime (e.g. after the user trusts a project), so changes
+    /// are measured against a fresh load rather than the live config.
+    pub fn new(
+        cli_overrides: Vec<(String, TomlValue)>,
+        overrides: ConfigOverrides,
+    ) -> std::io::Result<Self> {
+        Self::new_in(find_codex_home()?, cli_overrides, overrides)
+    }
+
+    fn new_in(
+        codex_home: PathBuf,
+        cli_overrides: Vec<(String, TomlValue)>,
+        overrides: ConfigOverrides,
+    ) -> std::io::Result<Self> {
+        let current = Config::load_with_cli_overrides_in(
+            codex_home.clone(),
+            cli_overrides.clone(),
+            overrides.clone(),
+        )?;
+        Ok(Self {
+            codex_home,
+            cli_overrides,
+            overrides,
+            current,
+        })
+    }
+
+    /// The config as of the last successful load.
+    pub fn config(&self) -> &Config {
+        &self.current
+    }
+
+    /// Load the config again. On error the previous config is kept.
+    pub fn reload(&mut self) -> std::io::Result<ConfigChanges> {
+        let new = Config::load_with_cli_overrides_in(
+            self.codex_home.clone(),
+            self.cli_overrides.clone(),
+            self.overrides.clone(),
+        )?;
+        let changes = diff_config(&self.current, &new);
+        self.current = new;
+        Ok(changes)
+    }
+
+    /// Watch the user's `config.toml` and the project config for changes.
+    /// Must be called from within a Tokio runtime.
+    pub fn watch(&self) -> UnboundedReceiver<()> {
+        let mut paths = vec![self.current.codex_home.join(CONFIG_TOML_FILE)];
+        paths.extend(project_config_location(&self.current.cwd));
+        watch_files(paths)
+    }
+}
+
+/// Poll `paths` and send a message whenever one of them is created, removed
+/// or modified. Polling stops once the receiver is dropped.
+fn watch_files(paths: Vec<PathBuf>) -> UnboundedReceiver<()> {
+    let (tx, rx) = unbounded_channel();
+    tokio::spawn(async move {
+        let mut last = file_stamps(&paths);
+        let mut interval = tokio::time::interval(CONFIG_POLL_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if tx.is_closed() {
+                break;
+            }
+            let stamps = file_stamps(&paths);
+            if stamps != last {
+                last = stamps;
+                if tx.send(()).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    rx
+}
+
+fn file_stamps(paths: &[PathBuf]) -> Vec<Option<(Option<SystemTime>, u64)>> {
+    paths.iter().map(|path| file_stamp(path)).collect()
+}
+
+fn file_stamp(path: &Path) -> Option<(Option<SystemTime>, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok(), metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigToml;
+    use crate::config_types::McpServerConfig;
+    use crate::protocol::AskForApproval;
+    use crate::protocol::SandboxPolicy;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn load(codex_home: &TempDir, toml: &str) -> Config {
+        let cfg: ConfigToml = toml::from_str(toml).expect("valid config");
+        Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect("load config")
+    }
+
+    #[test]
+    fn diff_config_classifies_changes() {
+        let codex_home = TempDir::new().unwrap();
+        let old = load(
+            &codex_home,
+            r#"
+model = "o3"
+approval_policy = "untrusted"
+"#,
+        );
+        let new = load(
+            &codex_home,
+            r#"
+model = "gpt-5"
+approval_policy = "on-request"
+notify = ["notify-send"]
+
+[mcp_servers.docs]
+command = "docs-server"
+
+[tui]
+notifications = true
+"#,
+        );
+
+        let changes = diff_config(&old, &new);
+
+        let expected = ConfigChanges {
+            ops: vec![Op::OverrideTurnContext {
+                cwd: None,
+                approval_policy: None,
+                sandbox_policy: None,
+                model: Some("gpt-5".to_string()),
+                effort: None,
+                summary: None,
+            }],
+            session: vec!["model"],
+            permission_ops: vec![
+                Op::OverrideTurnContext {
+                    cwd: None,
+                    approval_policy: Some(AskForApproval::OnRequest),
+                    sandbox_policy: None,
+                    model: None,
+                    effort: None,
+                    summary: None,
+                },
+                Op::ReconfigureMcpServers {
+                    mcp_servers: HashMap::from([(
+                        "docs".to_string(),
+                        McpServerConfig {
+                            command: "docs-server".to_string(),
+                            args: Vec::new(),
+                            env: None,
+                            startup_timeout_ms: None,
+                        },
+                    )]),
+                },
+            ],
+            permissions: vec!["approval_policy", "mcp_servers"],
+            frontend: vec!["tui.notifications"],
+            requires_restart: vec!["notify"],
+        };
+        assert_eq!(changes, expected);
+        assert!(diff_config(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn project_config_edits_cannot_loosen_the_sandbox_unconfirmed() {
+        let codex_home = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        let repo_path = repo.path().canonicalize().unwrap();
+        std::fs::create_dir(repo_path.join(".git")).unwrap();
+        std::fs::create_dir(repo_path.join(".codex")).unwrap();
+        let project_key = repo_path.to_string_lossy();
+        std::fs::write(
+            codex_home.path().join(CONFIG_TOML_FILE),
+            format!("[projects.{project_key:?}]\ntrust_level = \"trusted\"\n"),
+        )
+        .unwrap();
+        let project_config = repo_path.join(".codex").join(CONFIG_TOML_FILE);
+        std::fs::write(
+            &project_config,
+            "model = \"o3\"\nsandbox_mode = \"workspace-write\"\n",
+        )
+        .unwrap();
+
+        let overrides = ConfigOverrides {
+            cwd: Some(repo_path.clone()),
+            ..Default::default()
+        };
+        let mut reloader =
+            ConfigReloader::new_in(codex_home.path().to_path_buf(), Vec::new(), overrides)
+                .expect("load config");
+        let initial_sandbox = reloader.config().sandbox_policy.clone();
+        assert!(matches!(
+            initial_sandbox,
+            SandboxPolicy::WorkspaceWrite { .. }
+        ));
+
+        // What an agent in workspace-write mode could do if `.codex` were
+        // writable.
+        std::fs::write(
+            &project_config,
+            "model = \"gpt-5\"\nsandbox_mode = \"danger-full-access\"\n",
+        )
+        .unwrap();
+        let changes = reloader.reload().expect("reload config");
+
+        assert_eq!(changes.session, vec!["model"]);
+        assert_eq!(changes.permissions, vec!["sandbox"]);
+        for op in &changes.ops {
+            assert!(
+                matches!(
+                    op,
+                    Op::OverrideTurnContext {
+                        approval_policy: None,
+                        sandbox_policy: None,
+                        ..
+                    }
+                ),
+                "{op:?} would change permissions without confirmation"
+            );
+        }
+        assert_eq!(
+            changes.permission_ops,
+            vec![Op::OverrideTurnContext {
+                cwd: None,
+                approval_policy: None,
+                sandbox_policy: Some(SandboxPolicy::DangerFullAccess),
+                model: None,
+                effort: None,
+                summary: None,
+            }]
+        );
+    }
+}
@@ -9,20 +9,11 @@ use wildmatch::WildMatchPattern;
 
 use serde::Deserialize;
 
-#[derive(Deserialize, Debug, Clone, PartialEq)]
-pub struct McpServerConfig {
-    pub command: String,
-
-    #[serde(default)]
-    pub args: Vec<String>,
-
-    #[serde(default)]
-    pub env: Option<HashMap<String, String>>,
+use crate::keymap::TuiKeymapToml;
+use crate::theme::ThemeColors;
+use crate::theme::ThemeName;
 
-    /// Startup timeout in milliseconds for initializing MCP server & initially listing tools.
-    #[serde(default)]
-    pub startup_timeout_ms: Option<u64>,
-}
+pub use codex_protocol::config_types::McpServerConfig;
 
 #[derive(Deserialize, Debug, Copy, Clone, PartialEq)]
 pub enum UriBasedFileOpener {
@@ -76,6 +67,63 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// Settings that govern how session rollouts are stored under `~/.codex/sessions`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct RolloutSettings {
+    /// Compression applied to a rollout once its session shuts down.
+    #[serde(default)]
+    pub compression: RolloutCompression,
+
+    /// If set, the maximum number of bytes of a single tool or command output
+    /// stored in the rollout. Longer outputs are truncated in the middle. This
+    /// only affects what is persisted, not what the model sees.
+    pub max_output_bytes: Option<usize>,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum RolloutCompression {
+    /// Keep rollouts as plain `.jsonl` files.
+    #[default]
+    None,
+    /// Compress rollouts to `.jsonl.zst` on shutdown.
+    Zstd,
+}
+
+/// Settings for scrubbing secrets from command output before it is sent to the
+/// model and from text persisted to rollouts and `history.jsonl`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct RedactionSettings {
+    /// Master switch for redaction. Defaults to `true`.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Also redact long tokens that look random (high Shannon entropy) even
+    /// when no specific detector matches. Defaults to `false`: the heuristic
+    /// also hits base64 data and hashes, and output the model sees must
+    /// match the files it patches.
+    #[serde(default)]
+    pub high_entropy: bool,
+
+    /// Additional regular expressions whose matches are redacted.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            high_entropy: false,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(untagged)]
 pub enum Notifications {
@@ -94,7 +142,20 @@ impl Default for Notifications {
 pub struct Tui {
     /// Enable desktop notifications from the TUI when the terminal is unfocused.
     /// Defaults to `false`.
+    #[serde(default)]
     pub notifications: Notifications,
+
+    /// Key bindings for TUI actions. Unset actions keep their defaults.
+    #[serde(default)]
+    pub keymap: TuiKeymapToml,
+
+    /// Color theme: `auto` (the default), `dark`, `light` or `high-contrast`.
+    #[serde(default)]
+    pub theme: ThemeName,
+
+    /// Overrides for individual semantic colors of the theme.
+    #[serde(default)]
+    pub colors: ThemeColors,
 }
 
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
@@ -109,6 +109,15 @@ impl ConversationManager {
             .ok_or_else(|| CodexErr::ConversationNotFound(conversation_id))
     }
 
+    /// Every conversation the manager is currently tracking.
+    pub async fn list_conversations(&self) -> Vec<(ConversationId, Arc<CodexConversation>)> {
+        let conversations = self.conversations.read().await;
+        conversations
+            .iter()
+            .map(|(id, conversation)| (*id, conversation.clone()))
+            .collect()
+    }
+
     pub async fn resume_conversation_from_rollout(
         &self,
         config: Config,
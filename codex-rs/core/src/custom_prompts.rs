@@ -1,4 +1,28 @@
+//! User-defined slash commands backed by Markdown files.
+//!
+//! Prompts live in `$CODEX_HOME/prompts/*.md` and, for trusted projects, in
+//! the project's `.codex/prompts/*.md`; project prompts win when both define
+//! the same name.
+//! A prompt file may start with front matter:
+//!
+//! ```text
+//! ---
+//! description: Review a file for bugs
+//! argument-hint: <file> [focus]
+//! model: gpt-5
+//! reasoning-effort: high
+//! ---
+//! ```
+//!
+//! The body may use `$1` through `$9` for positional arguments, `$ARGUMENTS`
+//! for all of them, `$FILE` for the first `@path` argument and `$$` for a
+//! literal `$`. Without placeholders, arguments are appended to the body.
+
+use crate::git_info::get_git_repo_root;
+use crate::util::split_front_matter;
+use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::custom_prompts::CustomPrompt;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
@@ -12,6 +36,39 @@ pub fn default_prompts_dir() -> Option<PathBuf> {
         .map(|home| home.join("prompts"))
 }
 
+/// Return the project prompts directory: `.codex/prompts` at the Git root
+/// containing `cwd`, or under `cwd` outside a repository.
+pub fn project_prompts_dir(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd)
+        .unwrap_or_else(|| cwd.to_path_buf())
+        .join(".codex")
+        .join("prompts")
+}
+
+/// Discover the global prompts and, when the project at `cwd` is `trusted`,
+/// its prompts, sorted by name. Project prompts replace global prompts with
+/// the same name, so an untrusted checkout cannot redefine them.
+pub async fn discover_custom_prompts(cwd: &Path, trusted: bool) -> Vec<CustomPrompt> {
+    let global = match default_prompts_dir() {
+        Some(dir) => discover_prompts_in(&dir).await,
+        None => Vec::new(),
+    };
+    if !trusted {
+        return global;
+    }
+    let project = discover_prompts_in(&project_prompts_dir(cwd)).await;
+    merge_prompts(global, project)
+}
+
+/// Merge two prompt lists sorted by name; `overrides` win on name clashes.
+pub fn merge_prompts(base: Vec<CustomPrompt>, overrides: Vec<CustomPrompt>) -> Vec<CustomPrompt> {
+    let mut by_name: BTreeMap<String, CustomPrompt> = BTreeMap::new();
+    for prompt in base.into_iter().chain(overrides) {
+        by_name.insert(prompt.name.clone(), prompt);
+    }
+    by_name.into_values().collect()
+}
+
 /// Discover prompt files in the given directory, returning entries sorted by name.
 /// Non-files are ignored. If the directory does not exist or cannot be read, returns empty.
 pub async fn discover_prompts_in(dir: &Path) -> Vec<CustomPrompt> {
@@ -63,16 +120,117 @@ pub async fn discover_prompts_in_excluding(
             Ok(s) => s,
             Err(_) => continue,
         };
-        out.push(CustomPrompt {
-            name,
-            path,
-            content,
-        });
+        out.push(parse_prompt(name, path, &content));
     }
     out.sort_by(|a, b| a.name.cmp(&b.name));
     out
 }
 
+/// Build a prompt from the contents of its file, reading the front matter if
+/// there is any. Unknown front matter keys are ignored.
+pub fn parse_prompt(name: String, path: PathBuf, text: &str) -> CustomPrompt {
+    let mut prompt = CustomPrompt {
+        name,
+        path,
+        ..Default::default()
+    };
+    let Some((front_matter, body)) = split_front_matter(text) else {
+        prompt.content = text.to_string();
+        return prompt;
+    };
+    prompt.content = body.to_string();
+    for line in front_matter.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').trim_matches('\'');
+        if value.is_empty() {
+            continue;
+        }
+        match key.trim().replace('_', "-").as_str() {
+            "description" => prompt.description = Some(value.to_string()),
+            "argument-hint" => prompt.argument_hint = Some(value.to_string()),
+            "model" => prompt.model = Some(value.to_string()),
+            "reasoning-effort" => prompt.effort = parse_effort(value),
+            _ => {}
+        }
+    }
+    prompt
+}
+
+fn parse_effort(value: &str) -> Option<ReasoningEffort> {
+    match value.to_ascii_lowercase().as_str() {
+        "minimal" => Some(ReasoningEffort::Minimal),
+        "low" => Some(ReasoningEffort::Low),
+        "medium" => Some(ReasoningEffort::Medium),
+        "high" => Some(ReasoningEffort::High),
+        _ => None,
+    }
+}
+
+/// Expand the placeholders in `prompt` with `args`, the text typed after
+/// `/name`. Arguments are split like a shell would, so quoted arguments may
+/// contain spaces.
+pub fn expand_custom_prompt(prompt: &CustomPrompt, args: &str) -> String {
+    let args = args.trim();
+    let positional: Vec<String> =
+        shlex::split(args).unwrap_or_else(|| args.split_whitespace().map(str::to_string).collect());
+    let file = positional
+        .iter()
+        .find_map(|arg| arg.strip_prefix('@'))
+        .unwrap_or_default();
+
+    let content = prompt.content.as_str();
+    let mut out = String::with_capacity(content.len() + args.len());
+    let mut used_placeholder = false;
+    let mut rest = content;
+    while let Some(idx) = rest.find('$') {
+        out.push_str(&rest[..idx]);
+        let after = &rest[idx + 1..];
+        if let Some(after_dollar) = after.strip_prefix('$') {
+            out.push('$');
+            rest = after_dollar;
+        } else if let Some(digit) = after.chars().next().and_then(|c| c.to_digit(10))
+            && digit > 0
+        {
+            let arg = positional.get(digit as usize - 1);
+            out.push_str(arg.map(String::as_str).unwrap_or_default());
+            used_placeholder = true;
+            rest = &after[1..];
+        } else if let Some(after_name) = strip_placeholder(after, "ARGUMENTS") {
+            out.push_str(args);
+            used_placeholder = true;
+            rest = after_name;
+        } else if let Some(after_name) = strip_placeholder(after, "FILE") {
+            out.push_str(file);
+            used_placeholder = true;
+            rest = after_name;
+        } else {
+            out.push('$');
+            rest = after;
+        }
+    }
+    out.push_str(rest);
+
+    if !used_placeholder && !args.is_empty() {
+        let body_len = out.trim_end().len();
+        out.truncate(body_len);
+        out.push_str("\n\n");
+        out.push_str(args);
+    }
+    out
+}
+
+/// Strip `name` from the start of `text` unless it continues as a longer
+/// identifier, so `$FILENAME` is not taken for `$FILE`.
+fn strip_placeholder<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    let rest = text.strip_prefix(name)?;
+    match rest.chars().next() {
+        Some(c) if c.is_ascii_alphanumeric() || c == '_' => None,
+        _ => Some(rest),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,4 +282,90 @@ mod tests {
         let names: Vec<String> = found.into_iter().map(|e| e.name).collect();
         assert_eq!(names, vec!["good"]);
     }
+
+    #[tokio::test]
+    async fn reads_front_matter() {
+        let tmp = tempdir().expect("create TempDir");
+        let dir = tmp.path();
+        fs::write(
+            dir.join("review.md"),
+            "---\ndescription: Review a file\nargument-hint: <file> [focus]\nmodel: gpt-5\nreasoning_effort: high\n---\nReview $FILE.\n",
+        )
+        .unwrap();
+        let found = discover_prompts_in(dir).await;
+        let prompt = &found[0];
+        assert_eq!(prompt.content, "Review $FILE.\n");
+        assert_eq!(prompt.description.as_deref(), Some("Review a file"));
+        assert_eq!(prompt.argument_hint.as_deref(), Some("<file> [focus]"));
+        assert_eq!(prompt.model.as_deref(), Some("gpt-5"));
+        assert_eq!(prompt.effort, Some(ReasoningEffort::High));
+    }
+
+    #[test]
+    fn expands_placeholders() {
+        let prompt = parse_prompt(
+            "review".to_string(),
+            PathBuf::from("review.md"),
+            "Review $FILE ($1) for \"$2\". All: $ARGUMENTS. Cost: $$5, missing: [$3]",
+        );
+        assert_eq!(
+            expand_custom_prompt(&prompt, " @src/lib.rs \"error handling\" "),
+            "Review src/lib.rs (@src/lib.rs) for \"error handling\". All: @src/lib.rs \"error handling\". Cost: $5, missing: []"
+        );
+    }
+
+    #[test]
+    fn appends_arguments_without_placeholders() {
+        let prompt = parse_prompt(
+            "explain".to_string(),
+            PathBuf::from("explain.md"),
+            "Explain this code.\n",
+        );
+        assert_eq!(
+            expand_custom_prompt(&prompt, "the parser"),
+            "Explain this code.\n\nthe parser"
+        );
+        assert_eq!(expand_custom_prompt(&prompt, ""), "Explain this code.\n");
+    }
+
+    #[tokio::test]
+    async fn project_prompts_need_a_trusted_project() {
+        let tmp = tempdir().expect("create TempDir");
+        let prompts = tmp.path().join(".codex").join("prompts");
+        fs::create_dir_all(&prompts).unwrap();
+        fs::write(prompts.join("project-only-prompt.md"), b"project").unwrap();
+        let has_project_prompt = |found: Vec<CustomPrompt>| {
+            found
+                .iter()
+                .any(|prompt| prompt.name == "project-only-prompt")
+        };
+
+        assert!(!has_project_prompt(
+            discover_custom_prompts(tmp.path(), false).await
+        ));
+        assert!(has_project_prompt(
+            discover_custom_prompts(tmp.path(), true).await
+        ));
+    }
+
+    #[test]
+    fn project_prompts_override_global_ones() {
+        let prompt = |name: &str, content: &str| CustomPrompt {
+            name: name.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        };
+        let merged = merge_prompts(
+            vec![prompt("b", "global b"), prompt("c", "global c")],
+            vec![prompt("a", "project a"), prompt("b", "project b")],
+        );
+        let merged: Vec<(&str, &str)> = merged
+            .iter()
+            .map(|p| (p.name.as_str(), p.content.as_str()))
+            .collect();
+        assert_eq!(
+            merged,
+            vec![("a", "project a"), ("b", "project b"), ("c", "global c")]
+        );
+    }
 }
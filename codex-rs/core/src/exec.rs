@@ -23,6 +23,7 @@ use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandOutputDeltaEvent;
 use crate::protocol::ExecOutputStream;
 use crate::protocol::SandboxPolicy;
+use crate::redact::Redactor;
 use crate::seatbelt::spawn_command_under_seatbelt;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
@@ -76,6 +77,9 @@ pub struct StdoutStream {
     pub sub_id: String,
     pub call_id: String,
     pub tx_event: Sender<Event>,
+    /// Redacts secrets from `ExecCommandOutputDelta` chunks before they are
+    /// sent.
+    pub redactor: Option<Redactor>,
 }
 
 pub async fn process_exec_tool_call(
@@ -381,7 +385,10 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
         if let Some(stream) = &stream
             && emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL
         {
-            let chunk = tmp[..n].to_vec();
+            let chunk = match &stream.redactor {
+                Some(redactor) => redactor.redact_chunk(&tmp[..n]),
+                None => tmp[..n].to_vec(),
+            };
             let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
                 call_id: stream.call_id.clone(),
                 stream: if is_stderr {
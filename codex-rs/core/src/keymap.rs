@@ -0,0 +1,391 @@
+//! Key bindings for the TUI, configured under `[tui.keymap]`.
+//!
+//! Each named action maps to a single key chord written as a string such as
+//! `"enter"`, `"ctrl-t"` or `"alt-shift-up"`. Unset actions keep their
+//! defaults. Chords are parsed when `config.toml` is deserialized and the
+//! resolved [`Keymap`] is checked for conflicts when the config is loaded, so
+//! a broken keymap is reported before the TUI starts.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+/// A key without modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// A printable character, stored lower-cased for letters.
+    Char(char),
+    Enter,
+    Esc,
+    Tab,
+    Backspace,
+    Delete,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    F(u8),
+}
+
+/// A key together with the modifiers that must be held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub key: Key,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+impl KeyChord {
+    pub const fn plain(key: Key) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    pub const fn ctrl(key: Key) -> Self {
+        Self {
+            key,
+            ctrl: true,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    pub const fn shift(key: Key) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            alt: false,
+            shift: true,
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chord = KeyChord::plain(Key::Enter);
+        let mut rest = s.trim();
+        // Strip modifier prefixes one at a time; whatever remains names the
+        // key. This keeps chords like `ctrl--` (Ctrl and minus) unambiguous.
+        loop {
+            let Some((modifier, tail)) = rest.split_once(['-', '+']) else {
+                break;
+            };
+            if tail.is_empty() {
+                break;
+            }
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => chord.ctrl = true,
+                "alt" | "meta" | "option" => chord.alt = true,
+                "shift" => chord.shift = true,
+                _ => break,
+            }
+            rest = tail;
+        }
+
+        chord.key = match rest.to_ascii_lowercase().as_str() {
+            "" => return Err(format!("invalid key chord `{s}`: missing key")),
+            "enter" | "return" => Key::Enter,
+            "esc" | "escape" => Key::Esc,
+            "tab" => Key::Tab,
+            "backspace" => Key::Backspace,
+            "delete" | "del" => Key::Delete,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" | "pgup" => Key::PageUp,
+            "pagedown" | "pgdn" => Key::PageDown,
+            "space" => Key::Char(' '),
+            name => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    // Letters are case-insensitive; Shift has to be spelled out.
+                    (Some(c), None) => Key::Char(c.to_ascii_lowercase()),
+                    _ => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                        Some(n @ 1..=24) => Key::F(n),
+                        _ => return Err(format!("invalid key chord `{s}`: unknown key `{rest}`")),
+                    },
+                }
+            }
+        };
+        Ok(chord)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "ctrl-")?;
+        }
+        if self.alt {
+            write!(f, "alt-")?;
+        }
+        if self.shift {
+            write!(f, "shift-")?;
+        }
+        match self.key {
+            Key::Char(' ') => write!(f, "space"),
+            Key::Char(c) => write!(f, "{c}"),
+            Key::Enter => write!(f, "enter"),
+            Key::Esc => write!(f, "esc"),
+            Key::Tab => write!(f, "tab"),
+            Key::Backspace => write!(f, "backspace"),
+            Key::Delete => write!(f, "delete"),
+            Key::Up => write!(f, "up"),
+            Key::Down => write!(f, "down"),
+            Key::Left => write!(f, "left"),
+            Key::Right => write!(f, "right"),
+            Key::Home => write!(f, "home"),
+            Key::End => write!(f, "end"),
+            Key::PageUp => write!(f, "pageup"),
+            Key::PageDown => write!(f, "pagedown"),
+            Key::F(n) => write!(f, "f{n}"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyChord {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Actions that can be rebound under `[tui.keymap]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeymapAction {
+    Submit,
+    Newline,
+    Interrupt,
+    OpenTranscript,
+    Backtrack,
+    HistoryPrev,
+    HistoryNext,
+    AcceptPopup,
+    ExternalEditor,
+}
+
+impl KeymapAction {
+    pub const ALL: [KeymapAction; 9] = [
+        KeymapAction::Submit,
+        KeymapAction::Newline,
+        KeymapAction::Interrupt,
+        KeymapAction::OpenTranscript,
+        KeymapAction::Backtrack,
+        KeymapAction::HistoryPrev,
+        KeymapAction::HistoryNext,
+        KeymapAction::AcceptPopup,
+        KeymapAction::ExternalEditor,
+    ];
+
+    /// Name of the action as written in `config.toml`.
+    pub fn name(self) -> &'static str {
+        match self {
+            KeymapAction::Submit => "submit",
+            KeymapAction::Newline => "newline",
+            KeymapAction::Interrupt => "interrupt",
+            KeymapAction::OpenTranscript => "open_transcript",
+            KeymapAction::Backtrack => "backtrack",
+            KeymapAction::HistoryPrev => "history_prev",
+            KeymapAction::HistoryNext => "history_next",
+            KeymapAction::AcceptPopup => "accept_popup",
+            KeymapAction::ExternalEditor => "external_editor",
+        }
+    }
+
+    /// Whether both actions may share a chord. Interrupt only applies while a
+    /// turn is running and backtrack only while idle, which is why both
+    /// default to Esc.
+    fn may_share_chord(self, other: KeymapAction) -> bool {
+        matches!(
+            (self, other),
+            (KeymapAction::Interrupt, KeymapAction::Backtrack)
+                | (KeymapAction::Backtrack, KeymapAction::Interrupt)
+        )
+    }
+}
+
+/// The `[tui.keymap]` table as written in `config.toml`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TuiKeymapToml {
+    pub submit: Option<KeyChord>,
+    pub newline: Option<KeyChord>,
+    pub interrupt: Option<KeyChord>,
+    pub open_transcript: Option<KeyChord>,
+    pub backtrack: Option<KeyChord>,
+    pub history_prev: Option<KeyChord>,
+    pub history_next: Option<KeyChord>,
+    pub accept_popup: Option<KeyChord>,
+    pub external_editor: Option<KeyChord>,
+}
+
+/// Resolved key bindings, with defaults filled in for unset actions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    /// Send the composer contents. Default: `enter`.
+    pub submit: KeyChord,
+    /// Insert a newline in the composer. Default: `shift-enter`; `ctrl-j`
+    /// always works as well for terminals that cannot report Shift+Enter.
+    pub newline: KeyChord,
+    /// Interrupt the running turn. Default: `esc`.
+    pub interrupt: KeyChord,
+    /// Open (and close) the transcript overlay. Default: `ctrl-t`.
+    pub open_transcript: KeyChord,
+    /// Prime backtracking and step to older messages. Default: `esc`.
+    pub backtrack: KeyChord,
+    /// Recall the previous history entry. Default: `up`.
+    pub history_prev: KeyChord,
+    /// Recall the next history entry. Default: `down`.
+    pub history_next: KeyChord,
+    /// Complete the selected entry of the slash-command or file popup.
+    /// Default: `tab`.
+    pub accept_popup: KeyChord,
+    /// Edit the composer contents in `$VISUAL` / `$EDITOR`. Default: `ctrl-g`.
+    pub external_editor: KeyChord,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            submit: KeyChord::plain(Key::Enter),
+            newline: KeyChord::shift(Key::Enter),
+            interrupt: KeyChord::plain(Key::Esc),
+            open_transcript: KeyChord::ctrl(Key::Char('t')),
+            backtrack: KeyChord::plain(Key::Esc),
+            history_prev: KeyChord::plain(Key::Up),
+            history_next: KeyChord::plain(Key::Down),
+            accept_popup: KeyChord::plain(Key::Tab),
+            external_editor: KeyChord::ctrl(Key::Char('g')),
+        }
+    }
+}
+
+impl Keymap {
+    pub fn get(&self, action: KeymapAction) -> KeyChord {
+        match action {
+            KeymapAction::Submit => self.submit,
+            KeymapAction::Newline => self.newline,
+            KeymapAction::Interrupt => self.interrupt,
+            KeymapAction::OpenTranscript => self.open_transcript,
+            KeymapAction::Backtrack => self.backtrack,
+            KeymapAction::HistoryPrev => self.history_prev,
+            KeymapAction::HistoryNext => self.history_next,
+            KeymapAction::AcceptPopup => self.accept_popup,
+            KeymapAction::ExternalEditor => self.external_editor,
+        }
+    }
+
+    /// Apply the bindings from `toml` on top of the defaults and reject
+    /// keymaps where one chord would trigger two actions.
+    pub fn from_toml(toml: &TuiKeymapToml) -> std::io::Result<Self> {
+        let defaults = Self::default();
+        let keymap = Self {
+            submit: toml.submit.unwrap_or(defaults.submit),
+            newline: toml.newline.unwrap_or(defaults.newline),
+            interrupt: toml.interrupt.unwrap_or(defaults.interrupt),
+            open_transcript: toml.open_transcript.unwrap_or(defaults.open_transcript),
+            backtrack: toml.backtrack.unwrap_or(defaults.backtrack),
+            history_prev: toml.history_prev.unwrap_or(defaults.history_prev),
+            history_next: toml.history_next.unwrap_or(defaults.history_next),
+            accept_popup: toml.accept_popup.unwrap_or(defaults.accept_popup),
+            external_editor: toml.external_editor.unwrap_or(defaults.external_editor),
+        };
+        keymap.validate()?;
+        Ok(keymap)
+    }
+
+    fn validate(&self) -> std::io::Result<()> {
+        for (i, &a) in KeymapAction::ALL.iter().enumerate() {
+            for &b in &KeymapAction::ALL[i + 1..] {
+                if self.get(a) == self.get(b) && !a.may_share_chord(b) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!(
+                            "tui.keymap: `{}` is bound to both `{}` and `{}`",
+                            self.get(a),
+                            a.name(),
+                            b.name()
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn chord(s: &str) -> KeyChord {
+        s.parse().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    #[test]
+    fn parses_and_displays_chords() {
+        assert_eq!(chord("enter"), KeyChord::plain(Key::Enter));
+        assert_eq!(chord("Ctrl+T"), KeyChord::ctrl(Key::Char('t')));
+        assert_eq!(chord("shift-Return"), KeyChord::shift(Key::Enter));
+        assert_eq!(chord("ctrl--"), KeyChord::ctrl(Key::Char('-')));
+        assert_eq!(chord("alt-shift-up").to_string(), "alt-shift-up");
+        assert_eq!(chord("P"), chord("p"));
+        assert_eq!(chord("f5"), KeyChord::plain(Key::F(5)));
+        assert!("ctrl-".parse::<KeyChord>().is_err());
+        assert!("hyper-x".parse::<KeyChord>().is_err());
+        assert!("ctrl-bogus".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn unset_actions_keep_defaults() {
+        let toml = TuiKeymapToml {
+            submit: Some(chord("ctrl-enter")),
+            newline: Some(chord("enter")),
+            ..Default::default()
+        };
+        let keymap = Keymap::from_toml(&toml).expect("valid keymap");
+        assert_eq!(keymap.submit, chord("ctrl-enter"));
+        assert_eq!(keymap.newline, chord("enter"));
+        assert_eq!(keymap.open_transcript, Keymap::default().open_transcript);
+    }
+
+    #[test]
+    fn conflicting_bindings_are_rejected() {
+        let toml = TuiKeymapToml {
+            open_transcript: Some(chord("up")),
+            ..Default::default()
+        };
+        let err = Keymap::from_toml(&toml).expect_err("conflict");
+        assert_eq!(
+            err.to_string(),
+            "tui.keymap: `up` is bound to both `open_transcript` and `history_prev`"
+        );
+
+        // Interrupt and backtrack never apply at the same time.
+        let toml = TuiKeymapToml {
+            interrupt: Some(chord("ctrl-x")),
+            backtrack: Some(chord("ctrl-x")),
+            ..Default::default()
+        };
+        assert!(Keymap::from_toml(&toml).is_ok());
+    }
+}
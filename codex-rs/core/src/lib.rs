@@ -20,7 +20,9 @@ pub mod token_data;
 pub use codex_conversation::CodexConversation;
 pub mod config;
 pub mod config_edit;
+pub mod config_inspect;
 pub mod config_profile;
+pub mod config_reload;
 pub mod config_types;
 mod conversation_history;
 pub mod custom_prompts;
@@ -31,6 +33,7 @@ mod exec_command;
 pub mod exec_env;
 mod flags;
 pub mod git_info;
+pub mod keymap;
 pub mod internal_storage;
 mod is_safe_command;
 pub mod landlock;
@@ -60,23 +63,45 @@ pub mod model_family;
 mod openai_model_info;
 mod openai_tools;
 pub mod plan_tool;
+pub mod project_config;
 pub mod project_doc;
+mod redact;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
 pub mod shell;
 pub mod spawn;
 pub mod terminal;
+pub mod theme;
 mod tool_apply_patch;
 pub mod turn_diff_tracker;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
 pub use rollout::RolloutRecorder;
+pub use rollout::SESSION_INDEX_SUBDIR;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
 pub use rollout::find_conversation_path_by_id_str;
 pub use rollout::list::ConversationItem;
 pub use rollout::list::ConversationsPage;
 pub use rollout::list::Cursor;
+pub use rollout::manage::PruneCriteria;
+pub use rollout::manage::SessionFile;
+pub use rollout::manage::archive_session_file;
+pub use rollout::manage::archived_sessions_size;
+pub use rollout::manage::delete_session_file;
+pub use rollout::manage::list_session_files;
+pub use rollout::manage::load_rollout_items;
+pub use rollout::manage::parse_age;
+pub use rollout::manage::resolve_session_file;
+pub use rollout::manage::select_prunable;
+pub use rollout::search::SearchMatch;
+pub use rollout::search::SearchMatchKind;
+pub use rollout::search::SessionSearchHit;
+pub use rollout::search::SessionSearchQuery;
+pub use rollout::search::parse_date_bound;
+pub use rollout::search::search_sessions;
+pub use rollout::summary::RolloutSummary;
+pub use rollout::summary::summarize_rollout;
 mod user_notification;
 pub mod util;
 
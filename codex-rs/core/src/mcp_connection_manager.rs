@@ -5,11 +5,18 @@
 //! helpers to query the available tools across *all* servers and returns them
 //! in a single aggregated map using the fully-qualified tool name
 //! `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the key.
+//!
+//! The set of servers can be changed while a session is running with
+//! [`McpConnectionManager::reconfigure`], which only restarts the servers
+//! whose configuration changed.
 
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ffi::OsString;
 use std::sync::Arc;
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+use std::sync::RwLockWriteGuard;
 use std::time::Duration;
 
 use anyhow::Context;
@@ -23,6 +30,7 @@ use mcp_types::Tool;
 use serde_json::json;
 use sha1::Digest;
 use sha1::Sha1;
+use tokio::sync::Mutex;
 use tokio::task::JoinSet;
 use tracing::info;
 use tracing::warn;
@@ -76,20 +84,24 @@ fn qualify_tools(tools: Vec<ToolInfo>) -> HashMap<String, ToolInfo> {
     qualified_tools
 }
 
+#[derive(Clone)]
 struct ToolInfo {
     server_name: String,
     tool_name: String,
     tool: Tool,
 }
 
+#[derive(Clone)]
 struct ManagedClient {
     client: Arc<McpClient>,
     startup_timeout: Duration,
+    /// The configuration the client was started with, used to decide whether
+    /// it must be restarted on [`McpConnectionManager::reconfigure`].
+    config: McpServerConfig,
 }
 
-/// A thin wrapper around a set of running [`McpClient`] instances.
 #[derive(Default)]
-pub(crate) struct McpConnectionManager {
+struct McpState {
     /// Server-name -> client instance.
     ///
     /// The server name originates from the keys of the `mcp_servers` map in
@@ -100,6 +112,29 @@ pub(crate) struct McpConnectionManager {
     tools: HashMap<String, ToolInfo>,
 }
 
+/// Outcome of [`McpConnectionManager::reconfigure`]. Server names are sorted.
+#[derive(Debug, Default)]
+pub(crate) struct McpReconfiguration {
+    /// Servers that were not running before.
+    pub started: Vec<String>,
+    /// Running servers whose configuration changed.
+    pub restarted: Vec<String>,
+    /// Servers that were removed from the configuration.
+    pub stopped: Vec<String>,
+    /// Servers that failed to (re)start.
+    pub errors: ClientStartErrors,
+}
+
+/// A thin wrapper around a set of running [`McpClient`] instances.
+#[derive(Default)]
+pub(crate) struct McpConnectionManager {
+    state: RwLock<McpState>,
+
+    /// Serializes calls to [`McpConnectionManager::reconfigure`] so that
+    /// concurrent reloads cannot interleave.
+    reconfigure_lock: Mutex<()>,
+}
+
 impl McpConnectionManager {
     /// Spawn a [`McpClient`] for each configured server.
     ///
@@ -117,121 +152,80 @@ impl McpConnectionManager {
             return Ok((Self::default(), ClientStartErrors::default()));
         }
 
-        // Launch all configured servers concurrently.
-        let mut join_set = JoinSet::new();
-        let mut errors = ClientStartErrors::new();
-
-        for (server_name, cfg) in mcp_servers {
-            // Validate server name before spawning
-            if !is_valid_mcp_server_name(&server_name) {
-                let error = anyhow::anyhow!(
-                    "invalid server name '{}': must match pattern ^[a-zA-Z0-9_-]+$",
-                    server_name
-                );
-                errors.insert(server_name, error);
-                continue;
-            }
+        let (clients, errors) = start_clients(mcp_servers).await;
+        let tools = qualify_tools(list_tools_or_warn(&clients).await);
 
-            let startup_timeout = cfg
-                .startup_timeout_ms
-                .map(Duration::from_millis)
-                .unwrap_or(DEFAULT_STARTUP_TIMEOUT);
-
-            join_set.spawn(async move {
-                let McpServerConfig {
-                    command, args, env, ..
-                } = cfg;
-                let client_res = McpClient::new_stdio_client(
-                    command.into(),
-                    args.into_iter().map(OsString::from).collect(),
-                    env,
-                )
-                .await;
-                match client_res {
-                    Ok(client) => {
-                        // Initialize the client.
-                        let params = mcp_types::InitializeRequestParams {
-                            capabilities: ClientCapabilities {
-                                experimental: None,
-                                roots: None,
-                                sampling: None,
-                                // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
-                                // indicates this should be an empty object.
-                                elicitation: Some(json!({})),
-                            },
-                            client_info: Implementation {
-                                name: "codex-mcp-client".to_owned(),
-                                version: env!("CARGO_PKG_VERSION").to_owned(),
-                                title: Some("Codex".into()),
-                                // This field is used by Codex when it is an MCP
-                                // server: it should not be used when Codex is
-                                // an MCP client.
-                                user_agent: None,
-                            },
-                            protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
-                        };
-                        let initialize_notification_params = None;
-                        match client
-                            .initialize(
-                                params,
-                                initialize_notification_params,
-                                Some(startup_timeout),
-                            )
-                            .await
-                        {
-                            Ok(_response) => (server_name, Ok((client, startup_timeout))),
-                            Err(e) => (server_name, Err(e)),
-                        }
-                    }
-                    Err(e) => (server_name, Err(e.into())),
-                }
-            });
-        }
+        let manager = Self {
+            state: RwLock::new(McpState { clients, tools }),
+            reconfigure_lock: Mutex::new(()),
+        };
+        Ok((manager, errors))
+    }
 
-        let mut clients: HashMap<String, ManagedClient> = HashMap::with_capacity(join_set.len());
+    /// Replace the configured servers with `mcp_servers`. Servers whose
+    /// configuration is unchanged keep running, changed servers are restarted,
+    /// new ones are started and removed ones are shut down once no tool call
+    /// is using them anymore.
+    pub async fn reconfigure(
+        &self,
+        mcp_servers: HashMap<String, McpServerConfig>,
+    ) -> McpReconfiguration {
+        let _guard = self.reconfigure_lock.lock().await;
+        let current = self.read_state().clients.clone();
 
-        while let Some(res) = join_set.join_next().await {
-            let (server_name, client_res) = match res {
-                Ok((server_name, client_res)) => (server_name, client_res),
-                Err(e) => {
-                    warn!("Task panic when starting MCP server: {e:#}");
-                    continue;
+        let mut outcome = McpReconfiguration::default();
+        let mut clients = HashMap::with_capacity(mcp_servers.len());
+        let mut to_start = HashMap::new();
+        for (server_name, cfg) in mcp_servers {
+            match current.get(&server_name) {
+                Some(existing) if existing.config == cfg => {
+                    clients.insert(server_name, existing.clone());
                 }
-            };
-
-            match client_res {
-                Ok((client, startup_timeout)) => {
-                    clients.insert(
-                        server_name,
-                        ManagedClient {
-                            client: Arc::new(client),
-                            startup_timeout,
-                        },
-                    );
+                Some(_) => {
+                    outcome.restarted.push(server_name.clone());
+                    to_start.insert(server_name, cfg);
                 }
-                Err(e) => {
-                    errors.insert(server_name, e);
+                None => {
+                    outcome.started.push(server_name.clone());
+                    to_start.insert(server_name, cfg);
                 }
             }
         }
+        outcome.stopped = current
+            .keys()
+            .filter(|name| !clients.contains_key(*name) && !to_start.contains_key(*name))
+            .cloned()
+            .collect();
+
+        let (started, errors) = start_clients(to_start).await;
+        clients.extend(started);
+        let tools = qualify_tools(list_tools_or_warn(&clients).await);
+        *self.write_state() = McpState { clients, tools };
+
+        outcome.started.retain(|name| !errors.contains_key(name));
+        outcome.restarted.retain(|name| !errors.contains_key(name));
+        outcome.started.sort();
+        outcome.restarted.sort();
+        outcome.stopped.sort();
+        outcome.errors = errors;
+        outcome
+    }
 
-        let all_tools = match list_all_tools(&clients).await {
-            Ok(tools) => tools,
-            Err(e) => {
-                warn!("Failed to list tools from some MCP servers: {e:#}");
-                Vec::new()
-            }
-        };
-
-        let tools = qualify_tools(all_tools);
+    fn read_state(&self) -> RwLockReadGuard<'_, McpState> {
+        #[expect(clippy::expect_used)]
+        self.state.read().expect("poisoned lock")
+    }
 
-        Ok((Self { clients, tools }, errors))
+    fn write_state(&self) -> RwLockWriteGuard<'_, McpState> {
+        #[expect(clippy::expect_used)]
+        self.state.write().expect("poisoned lock")
     }
 
     /// Returns a single map that contains **all** tools. Each key is the
     /// fully-qualified name for the tool.
     pub fn list_all_tools(&self) -> HashMap<String, Tool> {
-        self.tools
+        self.read_state()
+            .tools
             .iter()
             .map(|(name, tool)| (name.clone(), tool.tool.clone()))
             .collect()
@@ -246,11 +240,11 @@ impl McpConnectionManager {
         timeout: Option<Duration>,
     ) -> Result<mcp_types::CallToolResult> {
         let client = self
+            .read_state()
             .clients
             .get(server)
-            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?
-            .client
-            .clone();
+            .map(|managed| managed.client.clone())
+            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
 
         client
             .call_tool(tool.to_string(), arguments, timeout)
@@ -259,12 +253,130 @@ impl McpConnectionManager {
     }
 
     pub fn parse_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
-        self.tools
+        self.read_state()
+            .tools
             .get(tool_name)
             .map(|tool| (tool.server_name.clone(), tool.tool_name.clone()))
     }
 }
 
+/// Spawn and initialize a [`McpClient`] for each server in `mcp_servers`.
+async fn start_clients(
+    mcp_servers: HashMap<String, McpServerConfig>,
+) -> (HashMap<String, ManagedClient>, ClientStartErrors) {
+    // Launch all configured servers concurrently.
+    let mut join_set = JoinSet::new();
+    let mut errors = ClientStartErrors::new();
+
+    for (server_name, cfg) in mcp_servers {
+        // Validate server name before spawning
+        if !is_valid_mcp_server_name(&server_name) {
+            let error = anyhow::anyhow!(
+                "invalid server name '{}': must match pattern ^[a-zA-Z0-9_-]+$",
+                server_name
+            );
+            errors.insert(server_name, error);
+            continue;
+        }
+
+        let config = cfg.clone();
+        let startup_timeout = cfg
+            .startup_timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_STARTUP_TIMEOUT);
+
+        join_set.spawn(async move {
+            let McpServerConfig {
+                command, args, env, ..
+            } = cfg;
+            let client_res = McpClient::new_stdio_client(
+                command.into(),
+                args.into_iter().map(OsString::from).collect(),
+                env,
+            )
+            .await;
+            match client_res {
+                Ok(client) => {
+                    // Initialize the client.
+                    let params = mcp_types::InitializeRequestParams {
+                        capabilities: ClientCapabilities {
+                            experimental: None,
+                            roots: None,
+                            sampling: None,
+                            // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
+                            // indicates this should be an empty object.
+                            elicitation: Some(json!({})),
+                        },
+                        client_info: Implementation {
+                            name: "codex-mcp-client".to_owned(),
+                            version: env!("CARGO_PKG_VERSION").to_owned(),
+                            title: Some("Codex".into()),
+                            // This field is used by Codex when it is an MCP
+                            // server: it should not be used when Codex is
+                            // an MCP client.
+                            user_agent: None,
+                        },
+                        protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
+                    };
+                    let initialize_notification_params = None;
+                    match client
+                        .initialize(
+                            params,
+                            initialize_notification_params,
+                            Some(startup_timeout),
+                        )
+                        .await
+                    {
+                        Ok(_response) => (server_name, Ok((client, startup_timeout, config))),
+                        Err(e) => (server_name, Err(e)),
+                    }
+                }
+                Err(e) => (server_name, Err(e.into())),
+            }
+        });
+    }
+
+    let mut clients: HashMap<String, ManagedClient> = HashMap::with_capacity(join_set.len());
+
+    while let Some(res) = join_set.join_next().await {
+        let (server_name, client_res) = match res {
+            Ok((server_name, client_res)) => (server_name, client_res),
+            Err(e) => {
+                warn!("Task panic when starting MCP server: {e:#}");
+                continue;
+            }
+        };
+
+        match client_res {
+            Ok((client, startup_timeout, config)) => {
+                clients.insert(
+                    server_name,
+                    ManagedClient {
+                        client: Arc::new(client),
+                        startup_timeout,
+                        config,
+                    },
+                );
+            }
+            Err(e) => {
+                errors.insert(server_name, e);
+            }
+        }
+    }
+
+    (clients, errors)
+}
+
+async fn list_tools_or_warn(clients: &HashMap<String, ManagedClient>) -> Vec<ToolInfo> {
+    match list_all_tools(clients).await {
+        Ok(tools) => tools,
+        Err(e) => {
+            warn!("Failed to list tools from some MCP servers: {e:#}");
+            Vec::new()
+        }
+    }
+}
+
 /// Query every server for its available tools and return a single map that
 /// contains **all** tools. Each key is the fully-qualified name for the tool.
 async fn list_all_tools(clients: &HashMap<String, ManagedClient>) -> Result<Vec<ToolInfo>> {
@@ -29,6 +29,7 @@ use tokio::io::AsyncReadExt;
 
 use crate::config::Config;
 use crate::config_types::HistoryPersistence;
+use crate::redact::Redactor;
 
 use codex_protocol::mcp_protocol::ConversationId;
 #[cfg(unix)]
@@ -73,7 +74,8 @@ pub(crate) async fn append_entry(
         }
     }
 
-    // TODO: check `text` for sensitive patterns
+    // Scrub secrets that were pasted into the prompt.
+    let (text, _) = Redactor::new(&config.redaction).redact(text);
 
     // Resolve `~/.codex/history.jsonl` and ensure the parent directory exists.
     let path = history_filepath(config);
@@ -91,7 +93,7 @@ pub(crate) async fn append_entry(
     let entry = HistoryEntry {
         session_id: conversation_id.to_string(),
         ts,
-        text: text.to_string(),
+        text,
     };
     let mut line = serde_json::to_string(&entry)
         .map_err(|e| std::io::Error::other(format!("failed to serialise history entry: {e}")))?;
@@ -0,0 +1,267 @@
+//! Repo-local configuration.
+//!
+//! A repository can commit `.codex/config.toml` at its root to share settings
+//! with everyone working in it. The file is layered under
+//! `$CODEX_HOME/config.toml`, so it only fills in what the user leaves unset,
+//! and under `-c` overrides and command-line flags. Only the keys in
+//! [`PROJECT_CONFIG_KEYS`] are honored.
+//!
+//! The file is only applied once the user trusts the project
+//! (`projects.<path>.trust_level = "trusted"` in the user's config, or the
+//! trust prompt at startup). Until then its settings are held back and
+//! reported, so a repository cannot change the model, sandbox, MCP servers or
+//! environment of commands without the user's consent.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use toml::Value as TomlValue;
+use toml::value::Table;
+
+use crate::config::CONFIG_TOML_FILE;
+use crate::git_info::get_git_repo_root;
+
+/// Location of the project config relative to the repository root.
+const PROJECT_CONFIG_DIR: &str = ".codex";
+
+/// Keys a project config may set. Anything else (providers, `notify`,
+/// `projects`, ...) stays under the user's control.
+pub const PROJECT_CONFIG_KEYS: &[&str] = &[
+    "model",
+    "review_model",
+    "model_reasoning_effort",
+    "model_reasoning_summary",
+    "model_verbosity",
+    "sandbox_mode",
+    "sandbox_workspace_write",
+    "mcp_servers",
+    "shell_environment_policy",
+    "project_doc_max_bytes",
+];
+
+/// A project config after filtering, ready to be merged into the user's.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ProjectConfigLayer {
+    pub(crate) path: PathBuf,
+    pub(crate) value: TomlValue,
+    /// Settings that were held back because the project is not trusted.
+    pub(crate) ignored: Vec<String>,
+}
+
+/// `.codex/config.toml` at the root of the Git repository containing `cwd`,
+/// if there is one.
+pub fn project_config_path(cwd: &Path) -> Option<PathBuf> {
+    let path = project_config_location(cwd)?;
+    path.is_file().then_some(path)
+}
+
+/// Where the project config for `cwd` lives, whether or not it exists.
+/// `None` outside a Git repository.
+pub fn project_config_location(cwd: &Path) -> Option<PathBuf> {
+    Some(
+        get_git_repo_root(cwd)?
+            .join(PROJECT_CONFIG_DIR)
+            .join(CONFIG_TOML_FILE),
+    )
+}
+
+/// The project config to layer under the user's config for `cwd`. Returns
+/// `None` when the repository has none, or when it is the user's own config
+/// (a repository rooted at the home directory).
+pub(crate) fn find_project_config(cwd: &Path, codex_home: &Path) -> Option<PathBuf> {
+    let path = project_config_path(cwd)?;
+    if let (Ok(dir), Ok(home)) = (
+        path.parent().unwrap_or(&path).canonicalize(),
+        codex_home.canonicalize(),
+    ) && dir == home
+    {
+        return None;
+    }
+    Some(path)
+}
+
+/// Load and filter the project config for `cwd`, if there is one.
+pub(crate) fn load_project_config(
+    cwd: &Path,
+    codex_home: &Path,
+    trusted: bool,
+) -> std::io::Result<Option<ProjectConfigLayer>> {
+    let Some(path) = find_project_config(cwd, codex_home) else {
+        return Ok(None);
+    };
+
+    let contents = std::fs::read_to_string(&path)?;
+    let table: Table = toml::from_str(&contents).map_err(|e| {
+        tracing::error!("Failed to parse {}: {e}", path.display());
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    })?;
+    let (table, ignored) = filter_project_config(table, trusted);
+    if !ignored.is_empty() {
+        tracing::warn!(
+            "ignoring settings from untrusted project config {}: {}",
+            path.display(),
+            ignored.join(", ")
+        );
+    }
+    Ok(Some(ProjectConfigLayer {
+        path,
+        value: TomlValue::Table(table),
+        ignored,
+    }))
+}
+
+/// Drop keys a project may not set, and hold back every setting of an
+/// untrusted project. Returns the kept settings and the names of those held
+/// back for lack of trust.
+pub(crate) fn filter_project_config(table: Table, trusted: bool) -> (Table, Vec<String>) {
+    let mut kept = Table::new();
+    let mut ignored = Vec::new();
+    for (key, value) in table {
+        if !PROJECT_CONFIG_KEYS.contains(&key.as_str()) {
+            tracing::warn!("`{key}` cannot be set in a project config; ignoring it");
+            continue;
+        }
+        if trusted {
+            kept.insert(key, value);
+            continue;
+        }
+        match (key.as_str(), &value) {
+            ("mcp_servers", TomlValue::Table(servers)) => {
+                ignored.extend(servers.keys().map(|name| format!("mcp_servers.{name}")))
+            }
+            _ => ignored.push(key),
+        }
+    }
+    ignored.sort();
+    (kept, ignored)
+}
+
+/// Layer `user`'s config over the project config `project` in place, so the
+/// user's own settings win and the project fills in the rest.
+pub(crate) fn apply_project_layer(user: &mut TomlValue, project: TomlValue) {
+    let user_value = std::mem::replace(user, project);
+    merge_toml(user, user_value);
+}
+
+/// Merge `layer` into `base`. Tables are merged key by key; any other value
+/// in `layer` replaces the one in `base`.
+pub(crate) fn merge_toml(base: &mut TomlValue, layer: TomlValue) {
+    match (base, layer) {
+        (TomlValue::Table(base), TomlValue::Table(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn table(text: &str) -> Table {
+        toml::from_str(text).expect("valid toml")
+    }
+
+    const PROJECT_CONFIG: &str = r#"
+model = "gpt-5-codex"
+sandbox_mode = "workspace-write"
+notify = ["say", "done"]
+
+[sandbox_workspace_write]
+writable_roots = ["/var/cache/build"]
+
+[mcp_servers.docs]
+command = "docs-server"
+
+[shell_environment_policy]
+inherit = "core"
+"#;
+
+    #[test]
+    fn untrusted_projects_apply_nothing() {
+        let (kept, ignored) = filter_project_config(table(PROJECT_CONFIG), false);
+
+        assert_eq!(kept, Table::new());
+        assert_eq!(
+            ignored,
+            vec![
+                "mcp_servers.docs".to_string(),
+                "model".to_string(),
+                "sandbox_mode".to_string(),
+                "sandbox_workspace_write".to_string(),
+                "shell_environment_policy".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn untrusted_projects_cannot_set_the_shell_environment() {
+        let (kept, ignored) = filter_project_config(
+            table(
+                "[shell_environment_policy]\ninherit = \"all\"\nignore_default_excludes = true\nset = { PATH = \"/tmp/evil\", LD_PRELOAD = \"/tmp/evil.so\" }\n",
+            ),
+            false,
+        );
+
+        assert_eq!(kept, Table::new());
+        assert_eq!(ignored, vec!["shell_environment_policy".to_string()]);
+    }
+
+    #[test]
+    fn trusted_projects_keep_every_supported_setting() {
+        let (kept, ignored) = filter_project_config(table(PROJECT_CONFIG), true);
+
+        let mut expected = table(PROJECT_CONFIG);
+        expected.remove("notify");
+        assert_eq!(kept, expected);
+        assert!(ignored.is_empty());
+    }
+
+    #[test]
+    fn merge_toml_merges_tables_and_replaces_values() {
+        let mut base = TomlValue::Table(table(
+            "model = \"o3\"\n[mcp_servers.user]\ncommand = \"user-server\"\n[shell_environment_policy]\nexclude = [\"AWS_*\"]\n",
+        ));
+        merge_toml(
+            &mut base,
+            TomlValue::Table(table(
+                "model = \"gpt-5\"\n[mcp_servers.docs]\ncommand = \"docs-server\"\n[shell_environment_policy]\nexclude = [\"GH_*\"]\n",
+            )),
+        );
+
+        assert_eq!(
+            base,
+            TomlValue::Table(table(
+                "model = \"gpt-5\"\n[mcp_servers.user]\ncommand = \"user-server\"\n[mcp_servers.docs]\ncommand = \"docs-server\"\n[shell_environment_policy]\nexclude = [\"GH_*\"]\n",
+            ))
+        );
+    }
+
+    #[test]
+    fn user_settings_win_over_the_project_layer() {
+        let mut user = TomlValue::Table(table(
+            "model = \"o3\"\n[mcp_servers.docs]\ncommand = \"my-docs-server\"\n",
+        ));
+        apply_project_layer(
+            &mut user,
+            TomlValue::Table(table(
+                "model = \"gpt-5\"\nsandbox_mode = \"workspace-write\"\n[mcp_servers.docs]\ncommand = \"docs-server\"\nargs = [\"--verbose\"]\n",
+            )),
+        );
+
+        assert_eq!(
+            user,
+            TomlValue::Table(table(
+                "model = \"o3\"\nsandbox_mode = \"workspace-write\"\n[mcp_servers.docs]\ncommand = \"my-docs-server\"\nargs = [\"--verbose\"]\n",
+            ))
+        );
+    }
+}
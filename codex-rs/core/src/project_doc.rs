@@ -11,10 +11,23 @@
 //!     current working directory (inclusive) and concatenate their contents in
 //!     that order.
 //! 3.  We do **not** walk past the Git root.
+//!
+//! Each doc may pull in other files with `@include` and restrict parts of
+//! itself to certain model families or sandbox modes; see `directives`.
+//!
+//! Docs in other directories below the Git root are not part of the initial
+//! instructions. `ScopedProjectDocs` loads them once the agent runs a command
+//! or edits a file under their directory, within what is left of
+//! `project_doc_max_bytes`.
+
+mod directives;
 
 use crate::config::Config;
+use directives::DocContext;
+use directives::expand_project_doc;
+use std::collections::HashSet;
+use std::path::Path;
 use std::path::PathBuf;
-use tokio::io::AsyncReadExt;
 use tracing::error;
 
 /// Currently, we only match the filename `AGENTS.md` exactly.
@@ -55,50 +68,87 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
         return Ok(None);
     }
 
-    let paths = discover_project_doc_paths(config)?;
-    if paths.is_empty() {
-        return Ok(None);
+    // Discovery and expansion use blocking file I/O.
+    let config = config.clone();
+    let (docs, _) = tokio::task::spawn_blocking(move || {
+        let dirs = project_doc_search_dirs(&config)?;
+        let Some(project_root) = dirs.first() else {
+            return Ok((Vec::new(), 0));
+        };
+        let context = DocContext::from_config(&config, project_root);
+        load_docs(&find_docs(&dirs)?, &context, max_total as u64)
+    })
+    .await
+    .map_err(std::io::Error::other)??;
+    if docs.is_empty() {
+        Ok(None)
+    } else {
+        let parts: Vec<String> = docs.into_iter().map(|doc| doc.contents).collect();
+        Ok(Some(parts.join("\n\n")))
     }
+}
 
-    let mut remaining: u64 = max_total as u64;
-    let mut parts: Vec<String> = Vec::new();
-
+/// Load the docs at `paths` in order until `budget` bytes are used up,
+/// truncating the last one that does not fit. Returns the loaded docs and the
+/// bytes left over.
+fn load_docs(
+    paths: &[PathBuf],
+    context: &DocContext,
+    budget: u64,
+) -> std::io::Result<(Vec<LoadedProjectDoc>, u64)> {
+    let mut remaining = budget;
+    let mut docs: Vec<LoadedProjectDoc> = Vec::new();
     for p in paths {
         if remaining == 0 {
             break;
         }
-
-        let file = match tokio::fs::File::open(&p).await {
-            Ok(f) => f,
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
-            Err(e) => return Err(e),
+        let Some(doc) = load_doc(p, context, remaining)? else {
+            continue;
         };
-
-        let size = file.metadata().await?.len();
-        let mut reader = tokio::io::BufReader::new(file).take(remaining);
-        let mut data: Vec<u8> = Vec::new();
-        reader.read_to_end(&mut data).await?;
-
-        if size > remaining {
+        if doc.truncated {
             tracing::warn!(
                 "Project doc `{}` exceeds remaining budget ({} bytes) - truncating.",
                 p.display(),
                 remaining,
             );
         }
+        remaining = remaining.saturating_sub(doc.contents.len() as u64);
+        docs.push(doc);
+    }
+    Ok((docs, remaining))
+}
 
-        let text = String::from_utf8_lossy(&data).to_string();
-        if !text.trim().is_empty() {
-            parts.push(text);
-            remaining = remaining.saturating_sub(data.len() as u64);
-        }
+/// Load the doc at `path` with its `@include` and conditional directives
+/// applied, truncated to `budget` bytes. Returns `None` when the file is
+/// missing, excluded by its front matter or blank.
+fn load_doc(
+    path: &Path,
+    context: &DocContext,
+    budget: u64,
+) -> std::io::Result<Option<LoadedProjectDoc>> {
+    let mut contents = match expand_project_doc(path, context, budget) {
+        Ok(Some(contents)) => contents,
+        Ok(None) => return Ok(None),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if contents.trim().is_empty() {
+        return Ok(None);
     }
 
-    if parts.is_empty() {
-        Ok(None)
-    } else {
-        Ok(Some(parts.join("\n\n")))
+    let truncated = contents.len() as u64 > budget;
+    if truncated {
+        let mut end = usize::try_from(budget).unwrap_or(usize::MAX);
+        while !contents.is_char_boundary(end) {
+            end -= 1;
+        }
+        contents.truncate(end);
     }
+    Ok(Some(LoadedProjectDoc {
+        path: path.to_path_buf(),
+        contents,
+        truncated,
+    }))
 }
 
 /// Discover the list of AGENTS.md files using the same search rules as
@@ -107,70 +157,278 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
 /// directory (inclusive). Symlinks are allowed. When `project_doc_max_bytes`
 /// is zero, returns an empty list.
 pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBuf>> {
+    find_docs(&project_doc_search_dirs(config)?)
+}
+
+/// The project docs in `dirs`, in the same order.
+fn find_docs(dirs: &[PathBuf]) -> std::io::Result<Vec<PathBuf>> {
+    let mut found: Vec<PathBuf> = Vec::new();
+    for d in dirs {
+        if let Some(doc) = find_doc_in(d)? {
+            found.push(doc);
+        }
+    }
+
+    Ok(found)
+}
+
+/// Directories searched for the initial project docs, ordered from the
+/// repository root down to the current working directory. Outside a Git
+/// repository only the working directory is searched.
+fn project_doc_search_dirs(config: &Config) -> std::io::Result<Vec<PathBuf>> {
     let mut dir = config.cwd.clone();
     if let Ok(canon) = dir.canonicalize() {
         dir = canon;
     }
 
-    // Build chain from cwd upwards and detect git root.
-    let mut chain: Vec<PathBuf> = vec![dir.clone()];
-    let mut git_root: Option<PathBuf> = None;
-    let mut cursor = dir;
-    while let Some(parent) = cursor.parent() {
-        let git_marker = cursor.join(".git");
-        let git_exists = match std::fs::metadata(&git_marker) {
-            Ok(_) => true,
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+    let Some(root) = find_git_root(&dir)? else {
+        return Ok(vec![config.cwd.clone()]);
+    };
+    let mut dirs: Vec<PathBuf> = dir
+        .ancestors()
+        .take_while(|p| p.starts_with(&root))
+        .map(Path::to_path_buf)
+        .collect();
+    dirs.reverse();
+    Ok(dirs)
+}
+
+/// Walk upwards from `dir` until a `.git` directory or file is found.
+fn find_git_root(dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    for cursor in dir.ancestors() {
+        match std::fs::metadata(cursor.join(".git")) {
+            Ok(_) => return Ok(Some(cursor.to_path_buf())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
             Err(e) => return Err(e),
-        };
+        }
+    }
+    Ok(None)
+}
 
-        if git_exists {
-            git_root = Some(cursor.clone());
-            break;
+/// Return the project doc in `dir`, if there is one.
+fn find_doc_in(dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    for name in CANDIDATE_FILENAMES {
+        let candidate = dir.join(name);
+        match std::fs::symlink_metadata(&candidate) {
+            Ok(md) => {
+                let ft = md.file_type();
+                // Allow regular files and symlinks; opening will later fail for dangling links.
+                if ft.is_file() || ft.is_symlink() {
+                    return Ok(Some(candidate));
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
         }
+    }
+    Ok(None)
+}
+
+/// A project doc with its directives applied.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LoadedProjectDoc {
+    pub(crate) path: PathBuf,
+    pub(crate) contents: String,
+    /// The doc did not fit in what was left of `project_doc_max_bytes`.
+    pub(crate) truncated: bool,
+}
 
-        chain.push(parent.to_path_buf());
-        cursor = parent.to_path_buf();
+impl LoadedProjectDoc {
+    /// Text handed to the model for a doc loaded after the session started,
+    /// which spells out the directory the instructions apply to.
+    pub(crate) fn instructions(&self) -> String {
+        let dir = self.path.parent().unwrap_or(&self.path);
+        format!(
+            "Instructions from {}. They apply to files under {}:\n\n{}",
+            self.path.display(),
+            dir.display(),
+            self.contents
+        )
     }
+}
+
+/// Tracks which directories have already had their `AGENTS.md` added to the
+/// model's context, so docs in subdirectories the agent later works in are
+/// loaded exactly once and share the `project_doc_max_bytes` budget with the
+/// initial docs.
+///
+/// Looking for docs reads from disk, so [`ScopedProjectDocs::find`] runs on
+/// a snapshot off the async runtime and [`ScopedProjectDocs::queue`] records
+/// the result. Queued docs count as loaded once they are
+/// [`recorded`](ScopedProjectDocs::recorded) in the conversation, or are
+/// looked for again after [`unqueue`](ScopedProjectDocs::unqueue).
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ScopedProjectDocs {
+    /// Nested docs are only picked up below this directory: the Git root, or
+    /// the working directory outside a repository. `None` disables loading.
+    root: Option<PathBuf>,
+    /// Directories whose docs are part of the initial user instructions.
+    initial_dirs: HashSet<PathBuf>,
+    /// Directories searched so far, including those of queued docs.
+    loaded_dirs: HashSet<PathBuf>,
+    /// Docs waiting to be recorded in the conversation.
+    queued: Vec<LoadedProjectDoc>,
+    /// Bytes of `project_doc_max_bytes` left over by the initial docs.
+    initial_budget: u64,
+    remaining: u64,
+    context: Option<DocContext>,
+}
+
+/// Result of [`ScopedProjectDocs::find`].
+#[derive(Debug, Default)]
+pub(crate) struct FoundProjectDocs {
+    /// Directories that were searched, whether or not they had a doc.
+    dirs: Vec<PathBuf>,
+    docs: Vec<LoadedProjectDoc>,
+}
 
-    let search_dirs: Vec<PathBuf> = if let Some(root) = git_root {
-        let mut dirs: Vec<PathBuf> = Vec::new();
-        let mut saw_root = false;
-        for p in chain.iter().rev() {
-            if !saw_root {
-                if p == &root {
-                    saw_root = true;
-                } else {
-                    continue;
+impl ScopedProjectDocs {
+    pub(crate) fn new(config: &Config) -> Self {
+        if config.project_doc_max_bytes == 0 {
+            return Self::default();
+        }
+        let dirs = match project_doc_search_dirs(config) {
+            Ok(dirs) => dirs,
+            Err(e) => {
+                error!("error trying to find project doc directories: {e:#}");
+                return Self::default();
+            }
+        };
+        let dirs: Vec<PathBuf> = dirs
+            .into_iter()
+            .map(|d| d.canonicalize().unwrap_or(d))
+            .collect();
+
+        let Some(project_root) = dirs.first() else {
+            return Self::default();
+        };
+        let context = DocContext::from_config(config, project_root);
+        let initial_docs: Vec<PathBuf> = dirs
+            .iter()
+            .filter_map(|d| find_doc_in(d).ok().flatten())
+            .collect();
+        let initial_budget =
+            match load_docs(&initial_docs, &context, config.project_doc_max_bytes as u64) {
+                Ok((_, remaining)) => remaining,
+                Err(e) => {
+                    error!("error trying to read project docs: {e:#}");
+                    0
                 }
+            };
+        let initial_dirs: HashSet<PathBuf> = dirs.iter().cloned().collect();
+        Self {
+            root: dirs.into_iter().next(),
+            loaded_dirs: initial_dirs.clone(),
+            queued: Vec::new(),
+            initial_dirs,
+            initial_budget,
+            remaining: initial_budget,
+            context: Some(context),
+        }
+    }
+
+    /// Read the docs that apply to `paths` (files or directories the agent is
+    /// working on) and are neither in context nor queued, ordered from the
+    /// outermost directory inwards. Does not change `self`; pass the result
+    /// to [`ScopedProjectDocs::queue`].
+    pub(crate) fn find(&self, paths: &[PathBuf]) -> FoundProjectDocs {
+        let mut snapshot = self.clone();
+        let mut found = FoundProjectDocs::default();
+        for path in paths {
+            snapshot.find_for_path(path, &mut found);
+        }
+        found
+    }
+
+    /// Queue the docs in `found` that are still new, and return them.
+    pub(crate) fn queue(&mut self, found: FoundProjectDocs) -> Vec<LoadedProjectDoc> {
+        let new_dirs: HashSet<PathBuf> = found
+            .dirs
+            .into_iter()
+            .filter(|dir| self.loaded_dirs.insert(dir.clone()))
+            .collect();
+        let docs: Vec<LoadedProjectDoc> = found
+            .docs
+            .into_iter()
+            .filter(|doc| doc.path.parent().is_some_and(|dir| new_dirs.contains(dir)))
+            .collect();
+        for doc in &docs {
+            self.remaining = self.remaining.saturating_sub(doc.contents.len() as u64);
+        }
+        self.queued.extend(docs.iter().cloned());
+        docs
+    }
+
+    /// The queued docs are now part of the conversation.
+    pub(crate) fn recorded(&mut self) {
+        self.queued.clear();
+    }
+
+    /// The queued docs were dropped before reaching the conversation, e.g.
+    /// because the turn was interrupted; look for them again next time.
+    pub(crate) fn unqueue(&mut self) {
+        for doc in self.queued.drain(..) {
+            if let Some(dir) = doc.path.parent() {
+                self.loaded_dirs.remove(dir);
             }
-            dirs.push(p.clone());
+            self.remaining = (self.remaining + doc.contents.len() as u64).min(self.initial_budget);
         }
-        dirs
-    } else {
-        vec![config.cwd.clone()]
-    };
+    }
 
-    let mut found: Vec<PathBuf> = Vec::new();
-    for d in search_dirs {
-        for name in CANDIDATE_FILENAMES {
-            let candidate = d.join(name);
-            match std::fs::symlink_metadata(&candidate) {
-                Ok(md) => {
-                    let ft = md.file_type();
-                    // Allow regular files and symlinks; opening will later fail for dangling links.
-                    if ft.is_file() || ft.is_symlink() {
-                        found.push(candidate);
-                        break;
-                    }
+    fn find_for_path(&mut self, path: &Path, found: &mut FoundProjectDocs) {
+        let (Some(root), Some(context)) = (self.root.clone(), self.context.clone()) else {
+            return;
+        };
+        let Some(dir) = nearest_existing_dir(path) else {
+            return;
+        };
+        let mut new_dirs: Vec<PathBuf> = dir
+            .ancestors()
+            .take_while(|d| d.starts_with(&root))
+            .filter(|d| !self.loaded_dirs.contains(*d))
+            .map(Path::to_path_buf)
+            .collect();
+        new_dirs.reverse();
+
+        for dir in new_dirs {
+            self.loaded_dirs.insert(dir.clone());
+            found.dirs.push(dir.clone());
+            if self.remaining == 0 {
+                continue;
+            }
+            let doc = find_doc_in(&dir).and_then(|path| match path {
+                Some(path) => load_doc(&path, &context, self.remaining),
+                None => Ok(None),
+            });
+            match doc {
+                Ok(Some(doc)) => {
+                    self.remaining = self.remaining.saturating_sub(doc.contents.len() as u64);
+                    found.docs.push(doc);
                 }
-                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
-                Err(e) => return Err(e),
+                Ok(None) => {}
+                Err(e) => error!("error reading project doc in {}: {e:#}", dir.display()),
             }
         }
     }
 
-    Ok(found)
+    /// Forget the docs loaded so far, e.g. after compaction dropped them from
+    /// the conversation history.
+    pub(crate) fn reset(&mut self) {
+        self.loaded_dirs = self.initial_dirs.clone();
+        self.queued.clear();
+        self.remaining = self.initial_budget;
+    }
+}
+
+/// The directory containing `path`, resolved through its closest existing
+/// ancestor so paths of files that are about to be created still count.
+fn nearest_existing_dir(path: &Path) -> Option<PathBuf> {
+    let existing = path.ancestors().find_map(|p| p.canonicalize().ok())?;
+    if existing.is_dir() {
+        Some(existing)
+    } else {
+        existing.parent().map(Path::to_path_buf)
+    }
 }
 
 #[cfg(test)]
@@ -202,6 +460,14 @@ mod tests {
         config
     }
 
+    /// Find, queue and record the scoped docs for `path`.
+    fn load_for_path(docs: &mut ScopedProjectDocs, path: &Path) -> Vec<LoadedProjectDoc> {
+        let found = docs.find(&[path.to_path_buf()]);
+        let loaded = docs.queue(found);
+        docs.recorded();
+        loaded
+    }
+
     /// AGENTS.md missing – should yield `None`.
     #[tokio::test]
     async fn no_doc_file_returns_none() {
@@ -347,4 +613,79 @@ mod tests {
         let res = get_user_instructions(&cfg).await.expect("doc expected");
         assert_eq!(res, "root doc\n\ncrate doc");
     }
+
+    /// Docs below the repository root that are not on the path to the cwd
+    /// are loaded once the agent works under them, at most once each.
+    #[test]
+    fn scoped_docs_are_loaded_once_per_directory() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        std::fs::write(
+            repo.path().join(".git"),
+            "gitdir: /path/to/actual/git/dir\n",
+        )
+        .unwrap();
+        fs::write(repo.path().join("AGENTS.md"), "root doc").unwrap();
+        let pkg = repo.path().join("packages/pkg");
+        std::fs::create_dir_all(pkg.join("src")).unwrap();
+        fs::write(repo.path().join("packages/AGENTS.md"), "packages doc").unwrap();
+        fs::write(pkg.join("AGENTS.md"), "pkg doc").unwrap();
+
+        let mut docs = ScopedProjectDocs::new(&make_config(&repo, 4096, None));
+
+        let loaded = load_for_path(&mut docs, &pkg.join("src/new_file.rs"));
+        let contents: Vec<&str> = loaded.iter().map(|doc| doc.contents.as_str()).collect();
+        assert_eq!(contents, vec!["packages doc", "pkg doc"]);
+        assert!(loaded.iter().all(|doc| !doc.truncated));
+
+        assert_eq!(load_for_path(&mut docs, &pkg), Vec::new());
+        assert_eq!(load_for_path(&mut docs, repo.path()), Vec::new());
+
+        docs.reset();
+        assert_eq!(load_for_path(&mut docs, &pkg).len(), 2);
+    }
+
+    /// Scoped docs share the `project_doc_max_bytes` budget with the docs
+    /// loaded at startup.
+    #[test]
+    fn scoped_docs_respect_remaining_budget() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        fs::write(tmp.path().join("AGENTS.md"), "12345").unwrap();
+        let nested = tmp.path().join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("AGENTS.md"), "abcdefgh").unwrap();
+
+        let mut docs = ScopedProjectDocs::new(&make_config(&tmp, 8, None));
+        let loaded = load_for_path(&mut docs, &nested);
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].contents, "abc");
+        assert!(loaded[0].truncated);
+
+        let mut disabled = ScopedProjectDocs::new(&make_config(&tmp, 0, None));
+        assert_eq!(load_for_path(&mut disabled, &nested), Vec::new());
+    }
+
+    /// Docs dropped before they were recorded, e.g. by an interrupt, are
+    /// found again; docs that are only queued are not found twice.
+    #[test]
+    fn unqueued_docs_are_found_again() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let nested = tmp.path().join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("AGENTS.md"), "nested doc").unwrap();
+        let paths = [nested.clone()];
+
+        let mut docs = ScopedProjectDocs::new(&make_config(&tmp, 4096, None));
+        let found = docs.find(&paths);
+        assert_eq!(docs.queue(found).len(), 1);
+        let found = docs.find(&paths);
+        assert_eq!(docs.queue(found), Vec::new());
+
+        docs.unqueue();
+        let found = docs.find(&paths);
+        let queued = docs.queue(found);
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].contents, "nested doc");
+        assert!(!queued[0].truncated);
+    }
 }
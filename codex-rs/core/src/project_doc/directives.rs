@@ -0,0 +1,506 @@
+//! Directives understood inside project docs.
+//!
+//! - `@include PATH` on a line of its own is replaced by the contents of
+//!   `PATH`, resolved relative to the including file. Only files inside the
+//!   project root or `$CODEX_HOME` can be included; absolute paths, `..`,
+//!   and `~/` or `$CODEX_HOME/` prefixes are fine as long as they end up
+//!   there. Included files may include others; cycles, overly deep nesting
+//!   and files outside those roots are skipped with a warning.
+//! - `@if model=gpt-oss,gpt-4.1 sandbox=read-only` ... `@endif` keeps the
+//!   enclosed lines only when every condition holds. Sections may nest.
+//! - Front matter delimited by `---` lines at the very top of a file takes
+//!   the same `model:` and `sandbox:` keys and drops the whole file when they
+//!   do not match. Other front matter keys are ignored.
+//!
+//! `model` matches when the model family or slug starts with one of the
+//! values, `sandbox` when the sandbox mode (`read-only`, `workspace-write`,
+//! `danger-full-access`) is one of the values. Directives inside fenced code
+//! blocks are left alone.
+//!
+//! Files are read no further than the bytes left of `project_doc_max_bytes`,
+//! so a large (or endless) include cannot stall loading the instructions.
+
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::config_types::SandboxMode;
+use dirs::home_dir;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::protocol::SandboxPolicy;
+use crate::util::split_front_matter;
+
+const INCLUDE_DIRECTIVE: &str = "@include";
+const IF_DIRECTIVE: &str = "@if";
+const ENDIF_DIRECTIVE: &str = "@endif";
+const CODE_FENCE: &str = "```";
+
+/// Includes nested deeper than this are skipped.
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// What conditional sections and front matter are evaluated against.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DocConditions {
+    pub(crate) model_slug: String,
+    pub(crate) model_family: String,
+    pub(crate) sandbox_mode: SandboxMode,
+}
+
+impl DocConditions {
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self {
+            model_slug: config.model_family.slug.clone(),
+            model_family: config.model_family.family.clone(),
+            sandbox_mode: match config.sandbox_policy {
+                SandboxPolicy::DangerFullAccess => SandboxMode::DangerFullAccess,
+                SandboxPolicy::ReadOnly => SandboxMode::ReadOnly,
+                SandboxPolicy::WorkspaceWrite { .. } => SandboxMode::WorkspaceWrite,
+            },
+        }
+    }
+
+    /// Whether `key` holds for any of `values`. Unknown keys never hold.
+    fn matches(&self, key: &str, values: &[&str]) -> bool {
+        match key {
+            "model" => values.iter().any(|value| {
+                self.model_family.starts_with(value) || self.model_slug.starts_with(value)
+            }),
+            "sandbox" => {
+                let mode = self.sandbox_mode.to_string();
+                values.iter().any(|value| *value == mode)
+            }
+            _ => {
+                warn!("unknown project doc condition `{key}`");
+                false
+            }
+        }
+    }
+}
+
+/// How project docs are expanded.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DocContext {
+    pub(crate) conditions: DocConditions,
+    /// Canonical directories that `@include` may read from.
+    pub(crate) include_roots: Vec<PathBuf>,
+    /// What a `~/` prefix of an include expands to.
+    pub(crate) home: Option<PathBuf>,
+    /// What a `$CODEX_HOME/` prefix of an include expands to.
+    pub(crate) codex_home: PathBuf,
+}
+
+impl DocContext {
+    /// Includes may read from `project_root` (the Git root, or the working
+    /// directory outside a repository) and `$CODEX_HOME`.
+    pub(crate) fn from_config(config: &Config, project_root: &Path) -> Self {
+        let include_roots = [project_root, config.codex_home.as_path()]
+            .into_iter()
+            .map(|root| root.canonicalize().unwrap_or_else(|_| root.to_path_buf()))
+            .collect();
+        Self {
+            conditions: DocConditions::from_config(config),
+            include_roots,
+            home: home_dir(),
+            codex_home: config.codex_home.clone(),
+        }
+    }
+}
+
+/// Read the project doc at `path` with its directives applied. Returns
+/// `Ok(None)` when front matter excludes the file. Each file is read up to
+/// one byte past what is left of `budget`, so the caller can tell the doc was
+/// cut off and has to truncate the result itself.
+pub(crate) fn expand_project_doc(
+    path: &Path,
+    context: &DocContext,
+    budget: u64,
+) -> std::io::Result<Option<String>> {
+    Expander {
+        context,
+        stack: Vec::new(),
+        remaining: budget,
+    }
+    .expand_file(path)
+}
+
+struct Expander<'a> {
+    context: &'a DocContext,
+    /// Canonical paths of the files currently being expanded, outermost
+    /// first, used to detect include cycles.
+    stack: Vec<PathBuf>,
+    /// Bytes of output left before the budget is used up.
+    remaining: u64,
+}
+
+impl Expander<'_> {
+    fn expand_file(&mut self, path: &Path) -> std::io::Result<Option<String>> {
+        let canonical = path.canonicalize()?;
+        if self.stack.contains(&canonical) {
+            warn!("skipping cyclic include of `{}`", path.display());
+            return Ok(None);
+        }
+        if self.stack.len() >= MAX_INCLUDE_DEPTH {
+            warn!(
+                "skipping include of `{}`: nested more than {MAX_INCLUDE_DEPTH} levels deep",
+                path.display()
+            );
+            return Ok(None);
+        }
+
+        // One byte past the budget lets the caller tell the doc was cut off.
+        let mut data = Vec::new();
+        std::fs::File::open(path)?
+            .take(self.remaining.saturating_add(1))
+            .read_to_end(&mut data)?;
+        let text = String::from_utf8_lossy(&data);
+        let Some(body) = self.apply_front_matter(&text) else {
+            return Ok(None);
+        };
+
+        let dir = canonical
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        self.stack.push(canonical);
+        let expanded = self.expand_body(body, &dir);
+        self.stack.pop();
+        Ok(Some(expanded))
+    }
+
+    /// Strip front matter from `text`, or return `None` if its conditions do
+    /// not hold.
+    fn apply_front_matter<'t>(&self, text: &'t str) -> Option<&'t str> {
+        let Some((front_matter, body)) = split_front_matter(text) else {
+            return Some(text);
+        };
+        for line in front_matter.lines() {
+            let Some((key, values)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim();
+            if key != "model" && key != "sandbox" {
+                continue;
+            }
+            let values = values.trim().trim_start_matches('[').trim_end_matches(']');
+            let values: Vec<&str> = values.split(',').map(str::trim).collect();
+            if !self.context.conditions.matches(key, &values) {
+                return None;
+            }
+        }
+        Some(body)
+    }
+
+    fn expand_body(&mut self, body: &str, dir: &Path) -> String {
+        let mut out = String::new();
+        let mut in_fence = false;
+        // One entry per open `@if`; lines are kept only while all hold.
+        let mut sections: Vec<bool> = Vec::new();
+        for line in body.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if trimmed.starts_with(CODE_FENCE) {
+                in_fence = !in_fence;
+            } else if !in_fence {
+                if let Some(conditions) = directive_argument(trimmed, IF_DIRECTIVE) {
+                    sections.push(self.section_applies(conditions));
+                    continue;
+                }
+                if trimmed == ENDIF_DIRECTIVE {
+                    if sections.pop().is_none() {
+                        warn!("ignoring unmatched {ENDIF_DIRECTIVE}");
+                    }
+                    continue;
+                }
+            }
+            if sections.contains(&false) {
+                continue;
+            }
+            if !in_fence
+                && let Some(target) = directive_argument(trimmed, INCLUDE_DIRECTIVE)
+                && !target.is_empty()
+            {
+                let Some(path) = self.resolve_include(target, dir) else {
+                    continue;
+                };
+                match self.expand_file(&path) {
+                    Ok(Some(included)) => {
+                        // The included lines were counted as they were added.
+                        out.push_str(&included);
+                        if !included.is_empty() && !included.ends_with('\n') {
+                            self.push(&mut out, "\n");
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => warn!("failed to include `{}`: {e}", path.display()),
+                }
+                continue;
+            }
+            self.push(&mut out, line);
+        }
+        out
+    }
+
+    fn push(&mut self, out: &mut String, text: &str) {
+        out.push_str(text);
+        self.remaining = self.remaining.saturating_sub(text.len() as u64);
+    }
+
+    /// The file an `@include` of `target` in `dir` refers to, or `None` (with
+    /// a warning) when it is not allowed.
+    fn resolve_include(&self, target: &str, dir: &Path) -> Option<PathBuf> {
+        let path = if let Some(rest) = strip_dir_prefix(target, "~") {
+            let Some(home) = &self.context.home else {
+                warn!("skipping include of `{target}`: no home directory");
+                return None;
+            };
+            home.join(rest)
+        } else if let Some(rest) = strip_dir_prefix(target, "$CODEX_HOME") {
+            self.context.codex_home.join(rest)
+        } else {
+            dir.join(target)
+        };
+        let canonical = match path.canonicalize() {
+            Ok(canonical) => canonical,
+            Err(e) => {
+                warn!("failed to include `{}`: {e}", path.display());
+                return None;
+            }
+        };
+        if !self
+            .context
+            .include_roots
+            .iter()
+            .any(|root| canonical.starts_with(root))
+        {
+            warn!(
+                "skipping include of `{}`: outside the project and CODEX_HOME",
+                path.display()
+            );
+            return None;
+        }
+        Some(canonical)
+    }
+
+    /// Evaluate the `key=value,value` pairs of an `@if` line.
+    fn section_applies(&self, conditions: &str) -> bool {
+        conditions.split_whitespace().all(|condition| {
+            let Some((key, values)) = condition.split_once('=') else {
+                warn!("malformed project doc condition `{condition}`");
+                return false;
+            };
+            let values: Vec<&str> = values.split(',').collect();
+            self.context.conditions.matches(key, &values)
+        })
+    }
+}
+
+/// The rest of `target` when it starts with the directory `prefix`, e.g.
+/// `notes.md` for `~/notes.md` and `""` for `~`.
+fn strip_dir_prefix<'t>(target: &'t str, prefix: &str) -> Option<&'t str> {
+    let rest = target.strip_prefix(prefix)?;
+    if rest.is_empty() {
+        Some(rest)
+    } else {
+        rest.strip_prefix('/')
+    }
+}
+
+/// The argument of `directive` when `line` consists of it, e.g. the path of
+/// an `@include path` line.
+fn directive_argument<'l>(line: &'l str, directive: &str) -> Option<&'l str> {
+    let rest = line.strip_prefix(directive)?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    /// Budget large enough to never cut a test doc short.
+    const NO_LIMIT: u64 = 1 << 20;
+
+    fn context(root: &Path, family: &str, sandbox_mode: SandboxMode) -> DocContext {
+        DocContext {
+            conditions: DocConditions {
+                model_slug: format!("{family}-latest"),
+                model_family: family.to_string(),
+                sandbox_mode,
+            },
+            include_roots: vec![root.canonicalize().unwrap()],
+            home: None,
+            codex_home: root.to_path_buf(),
+        }
+    }
+
+    #[test]
+    fn includes_files_and_skips_cycles() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let shared = tmp.path().join("shared");
+        fs::create_dir_all(&shared).unwrap();
+        fs::write(
+            tmp.path().join("AGENTS.md"),
+            "top\n@include shared/conventions.md\nbottom\n",
+        )
+        .unwrap();
+        fs::write(
+            shared.join("conventions.md"),
+            "conventions\n@include ../AGENTS.md\n@include missing.md\n```\n@include not-a-directive.md\n```",
+        )
+        .unwrap();
+
+        let expanded = expand_project_doc(
+            &tmp.path().join("AGENTS.md"),
+            &context(tmp.path(), "gpt-5", SandboxMode::WorkspaceWrite),
+            NO_LIMIT,
+        )
+        .unwrap();
+
+        assert_eq!(
+            expanded.as_deref(),
+            Some("top\nconventions\n```\n@include not-a-directive.md\n```\nbottom\n")
+        );
+    }
+
+    #[test]
+    fn keeps_sections_for_matching_model_and_sandbox() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let path = tmp.path().join("AGENTS.md");
+        fs::write(
+            &path,
+            "always\n@if model=gpt-oss\noss\n@if sandbox=read-only\noss read-only\n@endif\n@endif\n@if model=gpt-5 sandbox=workspace-write\ngpt-5\n@endif\nend\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            expand_project_doc(
+                &path,
+                &context(tmp.path(), "gpt-oss", SandboxMode::ReadOnly),
+                NO_LIMIT
+            )
+            .unwrap()
+            .as_deref(),
+            Some("always\noss\noss read-only\nend\n")
+        );
+        assert_eq!(
+            expand_project_doc(
+                &path,
+                &context(tmp.path(), "gpt-5", SandboxMode::WorkspaceWrite),
+                NO_LIMIT
+            )
+            .unwrap()
+            .as_deref(),
+            Some("always\ngpt-5\nend\n")
+        );
+    }
+
+    #[test]
+    fn front_matter_selects_whole_files() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let path = tmp.path().join("AGENTS.md");
+        fs::write(
+            &path,
+            "---\ntitle: local models\nmodel: [gpt-oss]\n---\nuse the function apply_patch tool\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            expand_project_doc(
+                &path,
+                &context(tmp.path(), "gpt-oss", SandboxMode::ReadOnly),
+                NO_LIMIT
+            )
+            .unwrap()
+            .as_deref(),
+            Some("use the function apply_patch tool\n")
+        );
+        assert_eq!(
+            expand_project_doc(
+                &path,
+                &context(tmp.path(), "gpt-5", SandboxMode::ReadOnly),
+                NO_LIMIT
+            )
+            .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn includes_stay_inside_the_include_roots() {
+        let outside = tempfile::tempdir().expect("tempdir");
+        fs::write(outside.path().join("secret.md"), "secret\n").unwrap();
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let path = tmp.path().join("AGENTS.md");
+        fs::write(
+            &path,
+            format!(
+                "top\n@include {}\n@include ../{}/secret.md\n@include ~/.ssh/id_rsa\nbottom\n",
+                outside.path().join("secret.md").display(),
+                outside.path().file_name().unwrap().to_string_lossy(),
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(
+            expand_project_doc(
+                &path,
+                &context(tmp.path(), "gpt-5", SandboxMode::ReadOnly),
+                NO_LIMIT
+            )
+            .unwrap()
+            .as_deref(),
+            Some("top\nbottom\n")
+        );
+    }
+
+    #[test]
+    fn home_prefixes_expand_before_the_root_check() {
+        let home = tempfile::tempdir().expect("tempdir");
+        let codex_home = home.path().join(".codex");
+        fs::create_dir_all(&codex_home).unwrap();
+        fs::write(codex_home.join("shared.md"), "shared\n").unwrap();
+        fs::write(home.path().join("secret.md"), "secret\n").unwrap();
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let path = tmp.path().join("AGENTS.md");
+        fs::write(
+            &path,
+            "top\n@include $CODEX_HOME/shared.md\n@include ~/.codex/shared.md\n@include ~/secret.md\n@include $CODEX_HOME/../secret.md\nbottom\n",
+        )
+        .unwrap();
+        let mut context = context(tmp.path(), "gpt-5", SandboxMode::ReadOnly);
+        context
+            .include_roots
+            .push(codex_home.canonicalize().unwrap());
+        context.home = Some(home.path().to_path_buf());
+        context.codex_home = codex_home;
+
+        assert_eq!(
+            expand_project_doc(&path, &context, NO_LIMIT)
+                .unwrap()
+                .as_deref(),
+            Some("top\nshared\nshared\nbottom\n")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reads_stop_at_the_budget() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let path = tmp.path().join("AGENTS.md");
+        std::os::unix::fs::symlink("/dev/zero", &path).unwrap();
+
+        let expanded = expand_project_doc(
+            &path,
+            &context(tmp.path(), "gpt-5", SandboxMode::ReadOnly),
+            16,
+        )
+        .unwrap();
+        assert_eq!(expanded, Some("\0".repeat(17)));
+    }
+}
@@ -0,0 +1,132 @@
+//! Reading and writing zstd-compressed rollout files.
+//!
+//! Rollouts are always recorded as plain JSONL. When `rollout.compression` is
+//! set to `zstd`, the recorder compresses the file to `*.jsonl.zst` once the
+//! session shuts down. Readers go through [`open_rollout_lines`] so listing,
+//! search and resume work the same for both forms; resuming a compressed
+//! rollout first decompresses it so new items can be appended.
+
+use std::io;
+use std::io::BufRead;
+use std::path::Path;
+use std::path::PathBuf;
+
+use tokio::io::AsyncBufReadExt;
+
+/// File name suffix of a plain rollout.
+pub(crate) const ROLLOUT_SUFFIX: &str = ".jsonl";
+
+/// File name suffix of a compressed rollout.
+pub(crate) const COMPRESSED_ROLLOUT_SUFFIX: &str = ".jsonl.zst";
+
+/// zstd level used for rollouts; favours speed since compression happens on
+/// session shutdown.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Whether `path` names a compressed rollout.
+pub(crate) fn is_compressed_rollout(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(COMPRESSED_ROLLOUT_SUFFIX))
+}
+
+/// Line reader over a rollout that hides whether it is compressed.
+pub(crate) enum RolloutLines {
+    Plain(tokio::io::Lines<tokio::io::BufReader<tokio::fs::File>>),
+    Decompressed(std::vec::IntoIter<String>),
+}
+
+impl RolloutLines {
+    pub(crate) async fn next_line(&mut self) -> io::Result<Option<String>> {
+        match self {
+            RolloutLines::Plain(lines) => lines.next_line().await,
+            RolloutLines::Decompressed(lines) => Ok(lines.next()),
+        }
+    }
+}
+
+/// Open the rollout at `path` for line-by-line reading.
+pub(crate) async fn open_rollout_lines(path: &Path) -> io::Result<RolloutLines> {
+    if is_compressed_rollout(path) {
+        let path = path.to_path_buf();
+        let lines = tokio::task::spawn_blocking(move || -> io::Result<Vec<String>> {
+            let decoder = zstd::stream::read::Decoder::new(std::fs::File::open(&path)?)?;
+            io::BufReader::new(decoder).lines().collect()
+        })
+        .await
+        .map_err(io::Error::other)??;
+        Ok(RolloutLines::Decompressed(lines.into_iter()))
+    } else {
+        let file = tokio::fs::File::open(path).await?;
+        Ok(RolloutLines::Plain(tokio::io::BufReader::new(file).lines()))
+    }
+}
+
+/// Read the full text of the rollout at `path`, decompressing if needed.
+pub(crate) async fn read_rollout_text(path: &Path) -> io::Result<String> {
+    if is_compressed_rollout(path) {
+        let path = path.to_path_buf();
+        let bytes = tokio::task::spawn_blocking(move || {
+            zstd::stream::decode_all(std::fs::File::open(&path)?)
+        })
+        .await
+        .map_err(io::Error::other)??;
+        String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    } else {
+        tokio::fs::read_to_string(path).await
+    }
+}
+
+/// Compress the plain rollout at `path` into a sibling `*.jsonl.zst` file and
+/// remove the original. Returns the path of the compressed file.
+pub(crate) async fn compress_rollout_file(path: &Path) -> io::Result<PathBuf> {
+    let source = path.to_path_buf();
+    let destination = swap_suffix(path, ROLLOUT_SUFFIX, COMPRESSED_ROLLOUT_SUFFIX)?;
+    let dest = destination.clone();
+    tokio::task::spawn_blocking(move || -> io::Result<()> {
+        // Write to a temp file first so a crash never leaves a truncated
+        // `.zst` next to (or instead of) the original rollout.
+        let dir = dest.parent().unwrap_or_else(|| Path::new("."));
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+        zstd::stream::copy_encode(std::fs::File::open(&source)?, &mut tmp, ZSTD_LEVEL)?;
+        tmp.as_file().sync_all()?;
+        tmp.persist(&dest).map_err(|e| e.error)?;
+        std::fs::remove_file(&source)
+    })
+    .await
+    .map_err(io::Error::other)??;
+    Ok(destination)
+}
+
+/// Inverse of [`compress_rollout_file`]: restore a plain `*.jsonl` rollout so
+/// it can be appended to again. Returns the path of the plain file.
+pub(crate) async fn decompress_rollout_file(path: &Path) -> io::Result<PathBuf> {
+    let source = path.to_path_buf();
+    let destination = swap_suffix(path, COMPRESSED_ROLLOUT_SUFFIX, ROLLOUT_SUFFIX)?;
+    let dest = destination.clone();
+    tokio::task::spawn_blocking(move || -> io::Result<()> {
+        let dir = dest.parent().unwrap_or_else(|| Path::new("."));
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+        zstd::stream::copy_decode(std::fs::File::open(&source)?, &mut tmp)?;
+        tmp.as_file().sync_all()?;
+        tmp.persist(&dest).map_err(|e| e.error)?;
+        std::fs::remove_file(&source)
+    })
+    .await
+    .map_err(io::Error::other)??;
+    Ok(destination)
+}
+
+fn swap_suffix(path: &Path, from: &str, to: &str) -> io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(from))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("`{}` is not a `*{from}` rollout", path.display()),
+            )
+        })?;
+    Ok(path.with_file_name(format!("{name}{to}")))
+}
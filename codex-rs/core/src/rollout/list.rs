@@ -14,6 +14,9 @@ use time::macros::format_description;
 use uuid::Uuid;
 
 use super::SESSIONS_SUBDIR;
+use super::compression::COMPRESSED_ROLLOUT_SUFFIX;
+use super::compression::ROLLOUT_SUFFIX;
+use super::compression::open_rollout_lines;
 use crate::protocol::EventMsg;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::RolloutLine;
@@ -149,7 +152,10 @@ async fn traverse_directories_for_paths(
                     break 'outer;
                 }
                 let mut day_files = collect_files(day_path, |name_str, path| {
-                    if !name_str.starts_with("rollout-") || !name_str.ends_with(".jsonl") {
+                    if !name_str.starts_with("rollout-")
+                        || !(name_str.ends_with(ROLLOUT_SUFFIX)
+                            || name_str.ends_with(COMPRESSED_ROLLOUT_SUFFIX))
+                    {
                         return None;
                     }
 
@@ -198,6 +204,33 @@ async fn traverse_directories_for_paths(
     })
 }
 
+/// Collect the paths of every rollout file under `codex_home`, newest first.
+/// Unlike [`get_conversations`] this is not bounded by a scan cap and does not
+/// open the files, so it is suited to whole-history operations such as search.
+pub(crate) async fn collect_rollout_paths(codex_home: &Path) -> io::Result<Vec<PathBuf>> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = Vec::new();
+    for (_year, year_path) in collect_dirs_desc(&root, |s| s.parse::<u16>().ok()).await? {
+        for (_month, month_path) in collect_dirs_desc(&year_path, |s| s.parse::<u8>().ok()).await? {
+            for (_day, day_path) in collect_dirs_desc(&month_path, |s| s.parse::<u8>().ok()).await?
+            {
+                let mut day_files = collect_files(&day_path, |name_str, path| {
+                    parse_timestamp_uuid_from_filename(name_str)
+                        .map(|(ts, id)| (ts, id, path.to_path_buf()))
+                })
+                .await?;
+                day_files.sort_by_key(|(ts, sid, _path)| (Reverse(*ts), Reverse(*sid)));
+                paths.extend(day_files.into_iter().map(|(_ts, _sid, path)| path));
+            }
+        }
+    }
+    Ok(paths)
+}
+
 /// Pagination cursor token format: "<file_ts>|<uuid>" where `file_ts` matches the
 /// filename timestamp portion (YYYY-MM-DDThh-mm-ss) used in rollout filenames.
 /// The cursor orders files by timestamp desc, then UUID desc.
@@ -269,9 +302,12 @@ where
     Ok(collected)
 }
 
-fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
-    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl
-    let core = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
+pub(crate) fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
+    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl, optionally followed by `.zst`
+    let name = name.strip_prefix("rollout-")?;
+    let core = name
+        .strip_suffix(ROLLOUT_SUFFIX)
+        .or_else(|| name.strip_suffix(COMPRESSED_ROLLOUT_SUFFIX))?;
 
     // Scan from the right for a '-' such that the suffix parses as a UUID.
     let (sep_idx, uuid) = core
@@ -290,11 +326,7 @@ async fn read_head_and_flags(
     path: &Path,
     max_records: usize,
 ) -> io::Result<(Vec<serde_json::Value>, bool, bool)> {
-    use tokio::io::AsyncBufReadExt;
-
-    let file = tokio::fs::File::open(path).await?;
-    let reader = tokio::io::BufReader::new(file);
-    let mut lines = reader.lines();
+    let mut lines = open_rollout_lines(path).await?;
     let mut head: Vec<serde_json::Value> = Vec::new();
     let mut saw_session_meta = false;
     let mut saw_user_event = false;
@@ -0,0 +1,238 @@
+//! Housekeeping for recorded sessions: listing with disk usage, deleting,
+//! archiving and pruning rollout files under `$CODEX_HOME/sessions`.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use time::OffsetDateTime;
+use time::UtcOffset;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::ARCHIVED_SESSIONS_SUBDIR;
+use super::SESSIONS_SUBDIR;
+use super::compression::open_rollout_lines;
+use super::list::collect_rollout_paths;
+use super::list::find_conversation_path_by_id_str;
+use super::list::parse_timestamp_uuid_from_filename;
+use super::search::index_path_for_rollout;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+
+/// A rollout file on disk together with the metadata encoded in its name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionFile {
+    pub path: PathBuf,
+    pub conversation_id: Uuid,
+    /// Session start time taken from the file name.
+    pub started_at: OffsetDateTime,
+    pub size_bytes: u64,
+}
+
+impl SessionFile {
+    /// Start time formatted as `YYYY-MM-DD hh:mm:ss` for display.
+    pub fn started_at_display(&self) -> String {
+        let format: &[FormatItem] =
+            format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+        self.started_at
+            .format(format)
+            .unwrap_or_else(|_| self.started_at.to_string())
+    }
+}
+
+/// Which sessions [`select_prunable`] should remove.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruneCriteria {
+    /// Only prune sessions that started longer ago than this.
+    pub older_than: Option<Duration>,
+    /// Always keep this many of the most recent sessions.
+    pub keep: Option<usize>,
+}
+
+/// List every recorded session under `codex_home`, newest first.
+pub async fn list_session_files(codex_home: &Path) -> io::Result<Vec<SessionFile>> {
+    let mut files = Vec::new();
+    for path in collect_rollout_paths(codex_home).await? {
+        let Some((started_at, conversation_id)) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(parse_timestamp_uuid_from_filename)
+        else {
+            continue;
+        };
+        let size_bytes = match tokio::fs::metadata(&path).await {
+            Ok(metadata) => metadata.len(),
+            Err(e) => {
+                warn!("failed to stat rollout {path:?}: {e}");
+                continue;
+            }
+        };
+        files.push(SessionFile {
+            path,
+            conversation_id,
+            started_at,
+            size_bytes,
+        });
+    }
+    Ok(files)
+}
+
+/// Total size in bytes of the archived sessions under `codex_home`.
+pub async fn archived_sessions_size(codex_home: &Path) -> io::Result<u64> {
+    let dir = codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    let mut entries = match tokio::fs::read_dir(&dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let mut total = 0;
+    while let Some(entry) = entries.next_entry().await? {
+        let metadata = entry.metadata().await?;
+        if metadata.is_file() {
+            total += metadata.len();
+        }
+    }
+    Ok(total)
+}
+
+/// Resolve a session given either its conversation id or a path to its
+/// rollout file. Paths must point inside the sessions directory.
+pub async fn resolve_session_file(codex_home: &Path, id_or_path: &str) -> io::Result<PathBuf> {
+    if Uuid::parse_str(id_or_path).is_ok() {
+        return find_conversation_path_by_id_str(codex_home, id_or_path)
+            .await?
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no session with id {id_or_path}"),
+                )
+            });
+    }
+    ensure_in_sessions_dir(codex_home, Path::new(id_or_path)).await
+}
+
+/// Read every rollout line recorded at `path`, skipping lines that fail to
+/// parse.
+pub async fn load_rollout_items(path: &Path) -> io::Result<Vec<RolloutItem>> {
+    let mut lines = open_rollout_lines(path).await?;
+    let mut items = Vec::new();
+    while let Some(line) = lines.next_line().await? {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<RolloutLine>(trimmed) {
+            Ok(rollout_line) => items.push(rollout_line.item),
+            Err(e) => warn!("failed to parse rollout line in {path:?}: {e}"),
+        }
+    }
+    Ok(items)
+}
+
+/// Permanently delete a session rollout and its search index.
+pub async fn delete_session_file(codex_home: &Path, path: &Path) -> io::Result<()> {
+    let path = ensure_in_sessions_dir(codex_home, path).await?;
+    tokio::fs::remove_file(&path).await?;
+    remove_search_index(codex_home, &path).await;
+    Ok(())
+}
+
+/// Move a session rollout into `$CODEX_HOME/archived_sessions`, returning
+/// its new location. Archived sessions are no longer listed or searched.
+pub async fn archive_session_file(codex_home: &Path, path: &Path) -> io::Result<PathBuf> {
+    let path = ensure_in_sessions_dir(codex_home, path).await?;
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("rollout path `{}` missing file name", path.display()),
+        )
+    })?;
+    let archive_folder = codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    tokio::fs::create_dir_all(&archive_folder).await?;
+    let destination = archive_folder.join(file_name);
+    tokio::fs::rename(&path, &destination).await?;
+    remove_search_index(codex_home, &path).await;
+    Ok(destination)
+}
+
+/// Select the sessions in `files` (ordered newest first, as returned by
+/// [`list_session_files`]) that match `criteria`.
+pub fn select_prunable(files: &[SessionFile], criteria: PruneCriteria) -> Vec<&SessionFile> {
+    // Rollout file names record local wall-clock time, which `started_at`
+    // carries with a UTC offset; compare against "now" expressed the same way.
+    let now = OffsetDateTime::now_local()
+        .unwrap_or_else(|_| OffsetDateTime::now_utc())
+        .replace_offset(UtcOffset::UTC);
+    select_prunable_at(files, criteria, now)
+}
+
+pub(crate) fn select_prunable_at(
+    files: &[SessionFile],
+    criteria: PruneCriteria,
+    now: OffsetDateTime,
+) -> Vec<&SessionFile> {
+    if criteria.older_than.is_none() && criteria.keep.is_none() {
+        return Vec::new();
+    }
+    let keep = criteria.keep.unwrap_or(0);
+    files
+        .iter()
+        .skip(keep)
+        .filter(|file| match criteria.older_than {
+            Some(age) => now - file.started_at > age,
+            None => true,
+        })
+        .collect()
+}
+
+/// Parse a human-friendly age such as `30d`, `12h`, `2w` or `90m`.
+pub fn parse_age(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount.parse().ok()?;
+    let seconds_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        "w" => 60 * 60 * 24 * 7,
+        _ => return None,
+    };
+    amount
+        .checked_mul(seconds_per_unit)
+        .map(Duration::from_secs)
+}
+
+/// Canonicalize `path` and verify it lives in the sessions directory so a
+/// stray argument cannot delete or move arbitrary files.
+async fn ensure_in_sessions_dir(codex_home: &Path, path: &Path) -> io::Result<PathBuf> {
+    let sessions_root = tokio::fs::canonicalize(codex_home.join(SESSIONS_SUBDIR)).await?;
+    let canonical = tokio::fs::canonicalize(path).await?;
+    if canonical.starts_with(&sessions_root) {
+        Ok(canonical)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "rollout path `{}` must be in sessions directory",
+                path.display()
+            ),
+        ))
+    }
+}
+
+async fn remove_search_index(codex_home: &Path, rollout_path: &Path) {
+    let Some(index_path) = index_path_for_rollout(codex_home, rollout_path) else {
+        return;
+    };
+    if let Err(e) = tokio::fs::remove_file(&index_path).await
+        && e.kind() != io::ErrorKind::NotFound
+    {
+        warn!("failed to remove session index {index_path:?}: {e}");
+    }
+}
@@ -2,10 +2,15 @@
 
 pub const SESSIONS_SUBDIR: &str = "sessions";
 pub const ARCHIVED_SESSIONS_SUBDIR: &str = "archived_sessions";
+pub const SESSION_INDEX_SUBDIR: &str = "session_index";
 
+mod compression;
 pub mod list;
+pub mod manage;
 pub(crate) mod policy;
 pub mod recorder;
+pub mod search;
+pub mod summary;
 
 pub use codex_protocol::protocol::SessionMeta;
 pub use list::find_conversation_path_by_id_str;
@@ -21,13 +21,24 @@ use tracing::info;
 use tracing::warn;
 
 use super::SESSIONS_SUBDIR;
+use super::compression::compress_rollout_file;
+use super::compression::decompress_rollout_file;
+use super::compression::is_compressed_rollout;
+use super::compression::read_rollout_text;
 use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
 use super::policy::is_persisted_response_item;
+use super::search::append_index_entries;
+use super::search::index_entries_for_item;
+use super::search::index_path_for_rollout;
+use super::search::load_or_build_index;
 use crate::config::Config;
+use crate::config_types::RolloutCompression;
 use crate::default_client::ORIGINATOR;
 use crate::git_info::collect_git_info;
+use crate::redact::Redactor;
+use crate::truncate::truncate_middle;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::InitialHistory;
 use codex_protocol::protocol::ResumedHistory;
@@ -62,6 +73,10 @@ pub struct SavedSession {
 pub struct RolloutRecorder {
     tx: Sender<RolloutCmd>,
     pub(crate) rollout_path: PathBuf,
+    /// Cap on the size of a single tool output stored in the rollout.
+    max_output_bytes: Option<usize>,
+    /// Scrubs secrets from tool output before it is stored.
+    redactor: Redactor,
 }
 
 #[derive(Clone)]
@@ -146,15 +161,30 @@ impl RolloutRecorder {
                     }),
                 )
             }
-            RolloutRecorderParams::Resume { path } => (
-                tokio::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&path)
-                    .await?,
-                path,
-                None,
-            ),
+            RolloutRecorderParams::Resume { path } => {
+                // Compressed rollouts are restored to plain JSONL so the
+                // resumed session can keep appending to them.
+                let path = if is_compressed_rollout(&path) {
+                    decompress_rollout_file(&path).await?
+                } else {
+                    path
+                };
+                // Make sure rollouts recorded before the search index existed
+                // are indexed in full before new items get appended.
+                if let Err(e) = load_or_build_index(&config.codex_home, &path).await {
+                    warn!("failed to index resumed rollout {path:?}: {e}");
+                }
+                (
+                    tokio::fs::OpenOptions::new()
+                        .append(true)
+                        .open(&path)
+                        .await?,
+                    path,
+                    None,
+                )
+            }
         };
+        let index_path = index_path_for_rollout(&config.codex_home, &rollout_path);
 
         // Clone the cwd for the spawned task to collect git info asynchronously
         let cwd = config.cwd.clone();
@@ -167,9 +197,22 @@ impl RolloutRecorder {
         // Spawn a Tokio task that owns the file handle and performs async
         // writes. Using `tokio::fs::File` keeps everything on the async I/O
         // driver instead of blocking the runtime.
-        tokio::task::spawn(rollout_writer(file, rx, meta, cwd));
-
-        Ok(Self { tx, rollout_path })
+        tokio::task::spawn(rollout_writer(
+            file,
+            rx,
+            meta,
+            cwd,
+            index_path,
+            rollout_path.clone(),
+            config.rollout.compression,
+        ));
+
+        Ok(Self {
+            tx,
+            rollout_path,
+            max_output_bytes: config.rollout.max_output_bytes,
+            redactor: Redactor::new(&config.redaction),
+        })
     }
 
     pub(crate) async fn record_items(&self, items: &[RolloutItem]) -> std::io::Result<()> {
@@ -179,7 +222,7 @@ impl RolloutRecorder {
             // "fully qualified MCP tool calls," so we could consider
             // reformatting them in that case.
             if is_persisted_response_item(item) {
-                filtered.push(item.clone());
+                filtered.push(self.prepare_output_for_storage(item));
             }
         }
         if filtered.is_empty() {
@@ -191,6 +234,44 @@ impl RolloutRecorder {
             .map_err(|e| IoError::other(format!("failed to queue rollout items: {e}")))
     }
 
+    /// Redact secrets from the messages, reasoning and tool output in `item`,
+    /// and truncate tool output to `rollout.max_output_bytes` so a single huge
+    /// command output does not dominate the rollout size. The session index
+    /// is built from the stored item, so it never sees the secrets either.
+    fn prepare_output_for_storage(&self, item: &RolloutItem) -> RolloutItem {
+        let truncate = |text: String| match self.max_output_bytes {
+            Some(max_bytes) => truncate_middle(&text, max_bytes).0,
+            None => text,
+        };
+        match item {
+            RolloutItem::ResponseItem(item) => {
+                match self.redactor.redact_response_item(item.clone()) {
+                    ResponseItem::FunctionCallOutput {
+                        call_id,
+                        mut output,
+                    } => {
+                        output.content = truncate(output.content);
+                        RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                            call_id,
+                            output,
+                        })
+                    }
+                    ResponseItem::CustomToolCallOutput { call_id, output } => {
+                        RolloutItem::ResponseItem(ResponseItem::CustomToolCallOutput {
+                            call_id,
+                            output: truncate(output),
+                        })
+                    }
+                    item => RolloutItem::ResponseItem(item),
+                }
+            }
+            RolloutItem::EventMsg(msg) => {
+                RolloutItem::EventMsg(self.redactor.redact_event_msg(msg.clone()))
+            }
+            _ => item.clone(),
+        }
+    }
+
     /// Flush all queued writes and wait until they are committed by the writer task.
     pub async fn flush(&self) -> std::io::Result<()> {
         let (tx, rx) = oneshot::channel();
@@ -204,7 +285,7 @@ impl RolloutRecorder {
 
     pub(crate) async fn get_rollout_history(path: &Path) -> std::io::Result<InitialHistory> {
         info!("Resuming rollout from {path:?}");
-        let text = tokio::fs::read_to_string(path).await?;
+        let text = read_rollout_text(path).await?;
         if text.trim().is_empty() {
             return Err(IoError::other("empty session file"));
         }
@@ -350,8 +431,11 @@ async fn rollout_writer(
     mut rx: mpsc::Receiver<RolloutCmd>,
     mut meta: Option<SessionMeta>,
     cwd: std::path::PathBuf,
+    index_path: Option<PathBuf>,
+    rollout_path: PathBuf,
+    compression: RolloutCompression,
 ) -> std::io::Result<()> {
-    let mut writer = JsonlWriter { file };
+    let mut writer = JsonlWriter { file, index_path };
 
     // If we have a meta, collect git info asynchronously and write meta first
     if let Some(session_meta) = meta.take() {
@@ -386,6 +470,19 @@ async fn rollout_writer(
                 let _ = ack.send(());
             }
             RolloutCmd::Shutdown { ack } => {
+                if compression == RolloutCompression::Zstd {
+                    let flushed = writer.file.flush().await;
+                    drop(writer);
+                    let result = match flushed {
+                        Ok(()) => compress_rollout_file(&rollout_path).await.map(|_| ()),
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = &result {
+                        warn!("failed to compress rollout {rollout_path:?}: {e}");
+                    }
+                    let _ = ack.send(());
+                    return result;
+                }
                 let _ = ack.send(());
             }
         }
@@ -396,6 +493,8 @@ async fn rollout_writer(
 
 struct JsonlWriter {
     file: tokio::fs::File,
+    /// Sidecar search index kept in sync with the rollout, if any.
+    index_path: Option<PathBuf>,
 }
 
 impl JsonlWriter {
@@ -407,6 +506,14 @@ impl JsonlWriter {
             .format(timestamp_format)
             .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))?;
 
+        if let Some(index_path) = &self.index_path {
+            // The index only speeds up search; never fail the rollout over it.
+            let entries = index_entries_for_item(&rollout_item);
+            if let Err(e) = append_index_entries(index_path, &entries).await {
+                warn!("failed to update session index {index_path:?}: {e}");
+            }
+        }
+
         let line = RolloutLine {
             timestamp,
             item: rollout_item,
@@ -0,0 +1,429 @@
+//! Full-text search across recorded sessions.
+//!
+//! Every rollout has a small sidecar index under `$CODEX_HOME/session_index`
+//! named after its conversation id. The index keeps only the searchable parts
+//! of a session (user and agent messages, commands and touched file paths) so
+//! that searching does not have to re-parse large command outputs. The rollout
+//! writer appends to the index as items are recorded; rollouts written before
+//! the index existed are indexed lazily the first time they are searched.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use time::Date;
+use time::OffsetDateTime;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use super::SESSION_INDEX_SUBDIR;
+use super::compression::open_rollout_lines;
+use super::list::collect_rollout_paths;
+use super::list::parse_timestamp_uuid_from_filename;
+use crate::protocol::EventMsg;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+
+/// Number of characters of surrounding text kept on each side of a match.
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+
+/// Maximum number of matches reported for a single session.
+const MAX_MATCHES_PER_SESSION: usize = 5;
+
+/// Kind of content an index entry (and thus a search match) came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMatchKind {
+    UserMessage,
+    AgentMessage,
+    Command,
+    FilePath,
+}
+
+/// A single line of a session's sidecar search index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum IndexEntry {
+    Meta {
+        timestamp: String,
+        cwd: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        git_branch: Option<String>,
+    },
+    Text {
+        kind: SearchMatchKind,
+        text: String,
+    },
+}
+
+/// Query and filters for [`search_sessions`].
+#[derive(Debug, Clone, Default)]
+pub struct SessionSearchQuery {
+    /// Text to look for; matched case-insensitively.
+    pub text: String,
+    /// Only include sessions whose recorded cwd is this path or lives below it.
+    pub cwd: Option<PathBuf>,
+    /// Only include sessions started at or after this instant.
+    pub since: Option<OffsetDateTime>,
+    /// Only include sessions started before this instant.
+    pub until: Option<OffsetDateTime>,
+    /// Only include sessions recorded on this git branch.
+    pub git_branch: Option<String>,
+    /// Maximum number of sessions to return.
+    pub limit: usize,
+}
+
+/// A session that matched a [`SessionSearchQuery`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSearchHit {
+    /// Absolute path to the rollout file.
+    pub path: PathBuf,
+    /// Session start timestamp as recorded in the session meta.
+    pub timestamp: Option<String>,
+    pub cwd: Option<PathBuf>,
+    pub git_branch: Option<String>,
+    /// Matching snippets in the order they appear in the session.
+    pub matches: Vec<SearchMatch>,
+}
+
+/// A single match inside a session, with surrounding context.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchMatch {
+    pub kind: SearchMatchKind,
+    /// Excerpt of the matching text with up to [`SNIPPET_CONTEXT_CHARS`] of
+    /// context on each side, collapsed to a single line.
+    pub snippet: String,
+}
+
+/// Parse a date bound used by session filters. Accepts either a calendar date
+/// (`YYYY-MM-DD`, interpreted as midnight UTC) or an RFC 3339 timestamp.
+pub fn parse_date_bound(value: &str) -> Option<OffsetDateTime> {
+    let value = value.trim();
+    let date_format: &[FormatItem] = format_description!("[year]-[month]-[day]");
+    if let Ok(date) = Date::parse(value, date_format) {
+        return Some(date.midnight().assume_utc());
+    }
+    OffsetDateTime::parse(value, &time::format_description::well_known::Rfc3339).ok()
+}
+
+/// Search every recorded session under `codex_home` for `query.text`.
+/// Sessions are visited newest first and the search stops once
+/// `query.limit` sessions have matched.
+pub async fn search_sessions(
+    codex_home: &Path,
+    query: &SessionSearchQuery,
+) -> io::Result<Vec<SessionSearchHit>> {
+    let needle = query.text.trim().to_lowercase();
+    if needle.is_empty() || query.limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut hits = Vec::new();
+    for path in collect_rollout_paths(codex_home).await? {
+        let entries = match load_or_build_index(codex_home, &path).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("failed to index rollout {path:?}: {e}");
+                continue;
+            }
+        };
+        if let Some(hit) = match_session(&path, &entries, &needle, query) {
+            hits.push(hit);
+            if hits.len() >= query.limit {
+                break;
+            }
+        }
+    }
+    Ok(hits)
+}
+
+/// Path of the sidecar index for the rollout at `rollout_path`, or `None` if
+/// the file name does not follow the rollout naming scheme.
+pub(crate) fn index_path_for_rollout(codex_home: &Path, rollout_path: &Path) -> Option<PathBuf> {
+    let file_name = rollout_path.file_name()?.to_str()?;
+    let (_ts, id) = parse_timestamp_uuid_from_filename(file_name)?;
+    Some(
+        codex_home
+            .join(SESSION_INDEX_SUBDIR)
+            .join(format!("{id}.jsonl")),
+    )
+}
+
+/// Extract the searchable entries recorded for a single rollout item.
+pub(crate) fn index_entries_for_item(item: &RolloutItem) -> Vec<IndexEntry> {
+    match item {
+        RolloutItem::SessionMeta(meta_line) => vec![IndexEntry::Meta {
+            timestamp: meta_line.meta.timestamp.clone(),
+            cwd: meta_line.meta.cwd.clone(),
+            git_branch: meta_line.git.as_ref().and_then(|git| git.branch.clone()),
+        }],
+        RolloutItem::EventMsg(EventMsg::UserMessage(ev)) => {
+            text_entry(SearchMatchKind::UserMessage, &ev.message)
+        }
+        RolloutItem::EventMsg(EventMsg::AgentMessage(ev)) => {
+            text_entry(SearchMatchKind::AgentMessage, &ev.message)
+        }
+        RolloutItem::ResponseItem(item) => index_entries_for_response_item(item),
+        RolloutItem::EventMsg(_) | RolloutItem::Compacted(_) | RolloutItem::TurnContext(_) => {
+            Vec::new()
+        }
+    }
+}
+
+fn index_entries_for_response_item(item: &ResponseItem) -> Vec<IndexEntry> {
+    match item {
+        ResponseItem::LocalShellCall {
+            action: LocalShellAction::Exec(exec),
+            ..
+        } => command_entries(&exec.command),
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } => match name.as_str() {
+            "shell" => serde_json::from_str::<ShellToolCallParams>(arguments)
+                .map(|params| command_entries(&params.command))
+                .unwrap_or_default(),
+            "apply_patch" => serde_json::from_str::<serde_json::Value>(arguments)
+                .ok()
+                .and_then(|args| args.get("input")?.as_str().map(patch_path_entries))
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        },
+        ResponseItem::CustomToolCall { name, input, .. } if name == "apply_patch" => {
+            patch_path_entries(input)
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn text_entry(kind: SearchMatchKind, text: &str) -> Vec<IndexEntry> {
+    if text.trim().is_empty() {
+        return Vec::new();
+    }
+    vec![IndexEntry::Text {
+        kind,
+        text: text.to_string(),
+    }]
+}
+
+fn command_entries(command: &[String]) -> Vec<IndexEntry> {
+    let joined =
+        shlex::try_join(command.iter().map(String::as_str)).unwrap_or_else(|_| command.join(" "));
+    text_entry(SearchMatchKind::Command, &joined)
+}
+
+/// File paths named by the `*** Add/Update/Delete File:` and `*** Move to:`
+/// headers of an apply_patch body.
+fn patch_path_entries(patch: &str) -> Vec<IndexEntry> {
+    const HEADERS: [&str; 4] = [
+        "*** Add File: ",
+        "*** Update File: ",
+        "*** Delete File: ",
+        "*** Move to: ",
+    ];
+    patch
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            HEADERS.iter().find_map(|header| line.strip_prefix(header))
+        })
+        .flat_map(|path| text_entry(SearchMatchKind::FilePath, path.trim()))
+        .collect()
+}
+
+/// Append `entries` to the sidecar index at `index_path`.
+pub(crate) async fn append_index_entries(
+    index_path: &Path,
+    entries: &[IndexEntry],
+) -> io::Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    if let Some(parent) = index_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let buf = serialize_entries(entries)?;
+    let mut file = tokio::fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(index_path)
+        .await?;
+    file.write_all(buf.as_bytes()).await?;
+    file.flush().await
+}
+
+/// Load the sidecar index for `rollout_path`, building it from the rollout
+/// when it does not exist yet.
+pub(crate) async fn load_or_build_index(
+    codex_home: &Path,
+    rollout_path: &Path,
+) -> io::Result<Vec<IndexEntry>> {
+    let Some(index_path) = index_path_for_rollout(codex_home, rollout_path) else {
+        return Ok(Vec::new());
+    };
+
+    match tokio::fs::File::open(&index_path).await {
+        Ok(file) => {
+            let mut lines = tokio::io::BufReader::new(file).lines();
+            let mut entries = Vec::new();
+            while let Some(line) = lines.next_line().await? {
+                if let Ok(entry) = serde_json::from_str::<IndexEntry>(&line) {
+                    entries.push(entry);
+                }
+            }
+            Ok(entries)
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let entries = build_index_from_rollout(rollout_path).await?;
+            // Persist the index even when empty so the next search can skip
+            // re-parsing; a write failure only costs performance.
+            if let Err(e) = write_index(&index_path, &entries).await {
+                warn!("failed to write session index {index_path:?}: {e}");
+            }
+            Ok(entries)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+async fn build_index_from_rollout(rollout_path: &Path) -> io::Result<Vec<IndexEntry>> {
+    let mut lines = open_rollout_lines(rollout_path).await?;
+    let mut entries = Vec::new();
+    while let Some(line) = lines.next_line().await? {
+        let Ok(rollout_line) = serde_json::from_str::<RolloutLine>(line.trim()) else {
+            continue;
+        };
+        entries.extend(index_entries_for_item(&rollout_line.item));
+    }
+    Ok(entries)
+}
+
+async fn write_index(index_path: &Path, entries: &[IndexEntry]) -> io::Result<()> {
+    if let Some(parent) = index_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(index_path, serialize_entries(entries)?).await
+}
+
+fn serialize_entries(entries: &[IndexEntry]) -> io::Result<String> {
+    let mut buf = String::new();
+    for entry in entries {
+        buf.push_str(&serde_json::to_string(entry)?);
+        buf.push('\n');
+    }
+    Ok(buf)
+}
+
+fn match_session(
+    path: &Path,
+    entries: &[IndexEntry],
+    needle: &str,
+    query: &SessionSearchQuery,
+) -> Option<SessionSearchHit> {
+    let (timestamp, cwd, git_branch) = entries
+        .iter()
+        .find_map(|entry| match entry {
+            IndexEntry::Meta {
+                timestamp,
+                cwd,
+                git_branch,
+            } => Some((
+                Some(timestamp.clone()),
+                Some(cwd.clone()),
+                git_branch.clone(),
+            )),
+            IndexEntry::Text { .. } => None,
+        })
+        .unwrap_or((None, None, None));
+
+    if let Some(filter_cwd) = &query.cwd
+        && !cwd
+            .as_deref()
+            .is_some_and(|cwd| cwd.starts_with(filter_cwd))
+    {
+        return None;
+    }
+    if let Some(branch) = &query.git_branch
+        && git_branch.as_deref() != Some(branch.as_str())
+    {
+        return None;
+    }
+    if query.since.is_some() || query.until.is_some() {
+        let started = session_start(path, timestamp.as_deref())?;
+        if query.since.is_some_and(|since| started < since)
+            || query.until.is_some_and(|until| started >= until)
+        {
+            return None;
+        }
+    }
+
+    let matches: Vec<SearchMatch> = entries
+        .iter()
+        .filter_map(|entry| match entry {
+            IndexEntry::Text { kind, text } => {
+                snippet_for(text, needle).map(|snippet| SearchMatch {
+                    kind: *kind,
+                    snippet,
+                })
+            }
+            IndexEntry::Meta { .. } => None,
+        })
+        .take(MAX_MATCHES_PER_SESSION)
+        .collect();
+    if matches.is_empty() {
+        return None;
+    }
+
+    Some(SessionSearchHit {
+        path: path.to_path_buf(),
+        timestamp,
+        cwd,
+        git_branch,
+        matches,
+    })
+}
+
+/// Start time of a session, preferring the recorded meta timestamp and
+/// falling back to the timestamp embedded in the rollout file name.
+fn session_start(path: &Path, meta_timestamp: Option<&str>) -> Option<OffsetDateTime> {
+    if let Some(ts) = meta_timestamp.and_then(parse_date_bound) {
+        return Some(ts);
+    }
+    let file_name = path.file_name()?.to_str()?;
+    parse_timestamp_uuid_from_filename(file_name).map(|(ts, _)| ts)
+}
+
+/// Case-insensitive match of `needle` (already lowercased) in `text`,
+/// returning the surrounding context on a single line.
+fn snippet_for(text: &str, needle: &str) -> Option<String> {
+    let haystack: Vec<char> = text.chars().collect();
+    let lowered: Vec<char> = haystack
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let needle: Vec<char> = needle.chars().collect();
+    let start = lowered
+        .windows(needle.len())
+        .position(|window| window == needle.as_slice())?;
+    let end = start + needle.len();
+
+    let from = start.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let to = (end + SNIPPET_CONTEXT_CHARS).min(haystack.len());
+    let mut snippet = String::new();
+    if from > 0 {
+        snippet.push('…');
+    }
+    let excerpt: String = haystack[from..to].iter().collect();
+    snippet.push_str(&excerpt.split_whitespace().collect::<Vec<_>>().join(" "));
+    if to < haystack.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
@@ -0,0 +1,76 @@
+//! Aggregate statistics over the items of a recorded rollout.
+//!
+//! Used by `codex replay` to re-run the user inputs of a session and to
+//! compare the original run with the replayed one.
+
+use std::path::PathBuf;
+
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::InputMessageKind;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::TokenUsage;
+
+/// What happened in a recorded session, as far as the rollout tells.
+#[derive(Debug, Clone, Default)]
+pub struct RolloutSummary {
+    /// Working directory the session started in.
+    pub cwd: Option<PathBuf>,
+    /// Commit checked out when the session started, if it ran in a git repo.
+    pub git_commit: Option<String>,
+    /// Model used for the last request of the session.
+    pub model: Option<String>,
+    /// Text the user typed, in order. Each one started a turn.
+    pub user_inputs: Vec<String>,
+    /// Number of requests sent to the model, including automatic compaction.
+    pub model_requests: usize,
+    /// Number of tool calls (shell, apply_patch, MCP, web search) the model made.
+    pub tool_calls: usize,
+    /// Token usage accumulated over the whole session.
+    pub token_usage: Option<TokenUsage>,
+}
+
+impl RolloutSummary {
+    /// Number of user turns in the session.
+    pub fn turns(&self) -> usize {
+        self.user_inputs.len()
+    }
+}
+
+/// Summarize the `items` read from a rollout file.
+pub fn summarize_rollout(items: &[RolloutItem]) -> RolloutSummary {
+    let mut summary = RolloutSummary::default();
+    for item in items {
+        match item {
+            RolloutItem::SessionMeta(meta_line) => {
+                summary.cwd = Some(meta_line.meta.cwd.clone());
+                summary.git_commit = meta_line
+                    .git
+                    .as_ref()
+                    .and_then(|git| git.commit_hash.clone());
+            }
+            RolloutItem::TurnContext(turn_context) => {
+                summary.model_requests += 1;
+                summary.model = Some(turn_context.model.clone());
+            }
+            RolloutItem::ResponseItem(
+                ResponseItem::FunctionCall { .. }
+                | ResponseItem::LocalShellCall { .. }
+                | ResponseItem::CustomToolCall { .. }
+                | ResponseItem::WebSearchCall { .. },
+            ) => summary.tool_calls += 1,
+            RolloutItem::EventMsg(EventMsg::UserMessage(user_message)) => {
+                if matches!(user_message.kind, None | Some(InputMessageKind::Plain)) {
+                    summary.user_inputs.push(user_message.message.clone());
+                }
+            }
+            RolloutItem::EventMsg(EventMsg::TokenCount(token_count)) => {
+                if let Some(info) = &token_count.info {
+                    summary.token_usage = Some(info.total_token_usage.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    summary
+}
@@ -12,11 +12,26 @@ use time::format_description::FormatItem;
 use time::macros::format_description;
 use uuid::Uuid;
 
+use crate::rollout::compression::compress_rollout_file;
+use crate::rollout::compression::decompress_rollout_file;
 use crate::rollout::list::ConversationItem;
 use crate::rollout::list::ConversationsPage;
 use crate::rollout::list::Cursor;
 use crate::rollout::list::get_conversation;
 use crate::rollout::list::get_conversations;
+use crate::rollout::manage::PruneCriteria;
+use crate::rollout::manage::archive_session_file;
+use crate::rollout::manage::delete_session_file;
+use crate::rollout::manage::list_session_files;
+use crate::rollout::manage::load_rollout_items;
+use crate::rollout::manage::parse_age;
+use crate::rollout::manage::resolve_session_file;
+use crate::rollout::manage::select_prunable_at;
+use crate::rollout::search::SearchMatchKind;
+use crate::rollout::search::SessionSearchQuery;
+use crate::rollout::search::parse_date_bound;
+use crate::rollout::search::search_sessions;
+use crate::rollout::summary::summarize_rollout;
 
 fn write_session_file(
     root: &Path,
@@ -443,3 +458,350 @@ async fn test_stable_ordering_same_second_pagination() {
     };
     assert_eq!(page2, expected_page2);
 }
+
+fn write_searchable_session(
+    root: &Path,
+    ts_str: &str,
+    uuid: Uuid,
+    cwd: &str,
+    branch: &str,
+) -> std::io::Result<std::path::PathBuf> {
+    let dir = root
+        .join("sessions")
+        .join(&ts_str[0..4])
+        .join(&ts_str[5..7])
+        .join(&ts_str[8..10]);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("rollout-{ts_str}-{uuid}.jsonl"));
+
+    let lines = [
+        serde_json::json!({
+            "timestamp": ts_str,
+            "type": "session_meta",
+            "payload": {
+                "id": uuid,
+                "timestamp": ts_str,
+                "instructions": null,
+                "cwd": cwd,
+                "originator": "test_originator",
+                "cli_version": "test_version",
+                "git": { "branch": branch }
+            }
+        }),
+        serde_json::json!({
+            "timestamp": ts_str,
+            "type": "event_msg",
+            "payload": {
+                "type": "user_message",
+                "message": "Please fix the flaky websocket reconnect test",
+                "kind": "plain"
+            }
+        }),
+        serde_json::json!({
+            "timestamp": ts_str,
+            "type": "response_item",
+            "payload": {
+                "type": "function_call",
+                "name": "shell",
+                "arguments": "{\"command\":[\"cargo\",\"test\",\"-p\",\"reconnect\"]}",
+                "call_id": "call-1"
+            }
+        }),
+    ];
+    let mut file = File::create(&path)?;
+    for line in lines {
+        writeln!(file, "{line}")?;
+    }
+    Ok(path)
+}
+
+#[tokio::test]
+async fn test_search_sessions_matches_messages_and_commands() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let u1 = Uuid::from_u128(101);
+    let u2 = Uuid::from_u128(102);
+    let p1 = write_searchable_session(home, "2025-05-01T10-00-00", u1, "/work/a", "main").unwrap();
+    write_session_file(home, "2025-05-02T10-00-00", u2, 0).unwrap();
+
+    let query = SessionSearchQuery {
+        text: "RECONNECT".to_string(),
+        limit: 10,
+        ..Default::default()
+    };
+    let hits = search_sessions(home, &query).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].path, p1);
+    assert_eq!(hits[0].git_branch.as_deref(), Some("main"));
+    let kinds: Vec<SearchMatchKind> = hits[0].matches.iter().map(|m| m.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![SearchMatchKind::UserMessage, SearchMatchKind::Command]
+    );
+    assert_eq!(hits[0].matches[1].snippet, "cargo test -p reconnect");
+
+    // The lazily built sidecar index is persisted for subsequent searches.
+    assert!(
+        home.join("session_index")
+            .join(format!("{u1}.jsonl"))
+            .exists()
+    );
+}
+
+#[tokio::test]
+async fn test_search_sessions_applies_filters() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let u1 = Uuid::from_u128(201);
+    let u2 = Uuid::from_u128(202);
+    write_searchable_session(home, "2025-06-01T10-00-00", u1, "/work/a", "main").unwrap();
+    let p2 =
+        write_searchable_session(home, "2025-06-03T10-00-00", u2, "/work/b", "feature").unwrap();
+
+    let base = SessionSearchQuery {
+        text: "websocket".to_string(),
+        limit: 10,
+        ..Default::default()
+    };
+
+    let by_cwd = SessionSearchQuery {
+        cwd: Some("/work/b".into()),
+        ..base.clone()
+    };
+    let hits = search_sessions(home, &by_cwd).await.unwrap();
+    assert_eq!(hits.iter().map(|h| &h.path).collect::<Vec<_>>(), vec![&p2]);
+
+    let by_branch = SessionSearchQuery {
+        git_branch: Some("feature".to_string()),
+        ..base.clone()
+    };
+    let hits = search_sessions(home, &by_branch).await.unwrap();
+    assert_eq!(hits.iter().map(|h| &h.path).collect::<Vec<_>>(), vec![&p2]);
+
+    let by_date = SessionSearchQuery {
+        since: parse_date_bound("2025-06-02"),
+        ..base.clone()
+    };
+    let hits = search_sessions(home, &by_date).await.unwrap();
+    assert_eq!(hits.iter().map(|h| &h.path).collect::<Vec<_>>(), vec![&p2]);
+
+    let none = SessionSearchQuery {
+        until: parse_date_bound("2025-05-31"),
+        ..base
+    };
+    assert!(search_sessions(home, &none).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_prune_selection_respects_age_and_keep() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    write_session_file(home, "2025-01-01T00-00-00", Uuid::from_u128(301), 0).unwrap();
+    write_session_file(home, "2025-01-20T00-00-00", Uuid::from_u128(302), 0).unwrap();
+    write_session_file(home, "2025-02-01T00-00-00", Uuid::from_u128(303), 0).unwrap();
+
+    let files = list_session_files(home).await.unwrap();
+    let ids: Vec<Uuid> = files.iter().map(|f| f.conversation_id).collect();
+    assert_eq!(
+        ids,
+        vec![
+            Uuid::from_u128(303),
+            Uuid::from_u128(302),
+            Uuid::from_u128(301)
+        ]
+    );
+
+    let now = parse_date_bound("2025-02-05").unwrap();
+    let selected_ids = |criteria: PruneCriteria| -> Vec<Uuid> {
+        select_prunable_at(&files, criteria, now)
+            .into_iter()
+            .map(|f| f.conversation_id)
+            .collect()
+    };
+
+    let older_than_10d = PruneCriteria {
+        older_than: parse_age("10d"),
+        keep: None,
+    };
+    assert_eq!(
+        selected_ids(older_than_10d),
+        vec![Uuid::from_u128(302), Uuid::from_u128(301)]
+    );
+
+    let keep_two = PruneCriteria {
+        older_than: None,
+        keep: Some(2),
+    };
+    assert_eq!(selected_ids(keep_two), vec![Uuid::from_u128(301)]);
+
+    let older_than_1d_keep_all = PruneCriteria {
+        older_than: parse_age("1d"),
+        keep: Some(3),
+    };
+    assert!(selected_ids(older_than_1d_keep_all).is_empty());
+    assert!(selected_ids(PruneCriteria::default()).is_empty());
+}
+
+#[test]
+fn test_parse_age_units() {
+    use std::time::Duration;
+
+    assert_eq!(
+        parse_age("30d"),
+        Some(Duration::from_secs(30 * 24 * 60 * 60))
+    );
+    assert_eq!(
+        parse_age("2w"),
+        Some(Duration::from_secs(14 * 24 * 60 * 60))
+    );
+    assert_eq!(parse_age("90m"), Some(Duration::from_secs(90 * 60)));
+    assert_eq!(parse_age("30"), None);
+    assert_eq!(parse_age("d"), None);
+    assert_eq!(parse_age("5y"), None);
+}
+
+#[tokio::test]
+async fn test_archive_and_delete_session_files() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let u1 = Uuid::from_u128(401);
+    let u2 = Uuid::from_u128(402);
+    write_session_file(home, "2025-04-01T00-00-00", u1, 0).unwrap();
+    write_session_file(home, "2025-04-02T00-00-00", u2, 0).unwrap();
+
+    let p1 = resolve_session_file(home, &u1.to_string()).await.unwrap();
+    let archived = archive_session_file(home, &p1).await.unwrap();
+    assert!(archived.starts_with(home.join("archived_sessions")));
+    assert!(archived.exists());
+    assert!(!p1.exists());
+
+    let p2 = resolve_session_file(home, &u2.to_string()).await.unwrap();
+    delete_session_file(home, &p2).await.unwrap();
+    assert!(!p2.exists());
+    assert!(list_session_files(home).await.unwrap().is_empty());
+
+    // Paths outside the sessions directory are rejected.
+    let outside = home.join("outside.jsonl");
+    File::create(&outside).unwrap();
+    assert!(delete_session_file(home, &outside).await.is_err());
+    assert!(outside.exists());
+}
+
+#[tokio::test]
+async fn test_compressed_rollouts_are_listed_and_readable() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let u1 = Uuid::from_u128(501);
+    write_session_file(home, "2025-08-01T00-00-00", u1, 2).unwrap();
+    let plain = resolve_session_file(home, &u1.to_string()).await.unwrap();
+
+    let compressed = compress_rollout_file(&plain).await.unwrap();
+    assert!(!plain.exists());
+    assert_eq!(
+        compressed.file_name().unwrap().to_str().unwrap(),
+        format!("rollout-2025-08-01T00-00-00-{u1}.jsonl.zst")
+    );
+
+    let page = get_conversations(home, 10, None).await.unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].path, compressed);
+    assert_eq!(page.items[0].head.len(), 1);
+
+    let items = load_rollout_items(&compressed).await.unwrap();
+    assert_eq!(items.len(), 2);
+
+    let restored = decompress_rollout_file(&compressed).await.unwrap();
+    assert_eq!(restored, plain);
+    assert!(plain.exists());
+    assert!(!compressed.exists());
+}
+
+#[tokio::test]
+async fn test_summarize_rollout_counts_turns_tools_and_tokens() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let ts = "2025-09-01T10-00-00";
+    let uuid = Uuid::from_u128(601);
+    let path = write_searchable_session(home, ts, uuid, "/work/app", "main").unwrap();
+
+    let usage = serde_json::json!({
+        "input_tokens": 1200,
+        "cached_input_tokens": 200,
+        "output_tokens": 300,
+        "reasoning_output_tokens": 100,
+        "total_tokens": 1500
+    });
+    let extra = [
+        serde_json::json!({
+            "timestamp": ts,
+            "type": "event_msg",
+            "payload": {
+                "type": "user_message",
+                "message": "<environment_context>cwd</environment_context>",
+                "kind": "environment_context"
+            }
+        }),
+        serde_json::json!({
+            "timestamp": ts,
+            "type": "turn_context",
+            "payload": {
+                "cwd": "/work/app",
+                "approval_policy": "never",
+                "sandbox_policy": { "mode": "read-only" },
+                "model": "gpt-5",
+                "summary": "auto"
+            }
+        }),
+        serde_json::json!({
+            "timestamp": ts,
+            "type": "event_msg",
+            "payload": {
+                "type": "token_count",
+                "info": {
+                    "total_token_usage": usage,
+                    "last_token_usage": usage,
+                    "model_context_window": null
+                }
+            }
+        }),
+        serde_json::json!({
+            "timestamp": ts,
+            "type": "event_msg",
+            "payload": {
+                "type": "user_message",
+                "message": "Now add a regression test",
+                "kind": "plain"
+            }
+        }),
+    ];
+    let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    for line in extra {
+        writeln!(file, "{line}").unwrap();
+    }
+
+    let items = load_rollout_items(&path).await.unwrap();
+    let summary = summarize_rollout(&items);
+    assert_eq!(summary.cwd, Some(std::path::PathBuf::from("/work/app")));
+    assert_eq!(summary.git_commit, None);
+    assert_eq!(summary.model.as_deref(), Some("gpt-5"));
+    assert_eq!(
+        summary.user_inputs,
+        vec![
+            "Please fix the flaky websocket reconnect test".to_string(),
+            "Now add a regression test".to_string(),
+        ]
+    );
+    assert_eq!(summary.turns(), 2);
+    assert_eq!(summary.model_requests, 1);
+    assert_eq!(summary.tool_calls, 1);
+    assert_eq!(
+        summary.token_usage.map(|usage| usage.total_tokens),
+        Some(1500)
+    );
+}
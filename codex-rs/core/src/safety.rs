@@ -229,15 +229,34 @@ fn is_write_patch_constrained_to_writable_paths(
 
     for (path, change) in action.changes() {
         match change {
-            ApplyPatchFileChange::Add { .. } | ApplyPatchFileChange::Delete { .. } => {
-                if !is_path_writable(path) {
+            ApplyPatchFileChange::Add { mode, .. }
+            | ApplyPatchFileChange::AddBinary { mode, .. } => {
+                if !is_path_writable(path) || mode.is_some_and(has_special_mode_bits) {
+                    return false;
+                }
+            }
+            ApplyPatchFileChange::AddSymlink { target } => {
+                // A link pointing outside the writable roots would let later
+                // writes through it escape them.
+                let target = match path.parent() {
+                    Some(parent) => parent.join(target),
+                    None => target.clone(),
+                };
+                if !is_path_writable(path) || !is_path_writable(&target) {
                     return false;
                 }
             }
-            ApplyPatchFileChange::Update { move_path, .. } => {
+            ApplyPatchFileChange::Delete { .. } => {
                 if !is_path_writable(path) {
                     return false;
                 }
+            }
+            ApplyPatchFileChange::Update {
+                move_path, mode, ..
+            } => {
+                if !is_path_writable(path) || mode.is_some_and(has_special_mode_bits) {
+                    return false;
+                }
                 if let Some(dest) = move_path
                     && !is_path_writable(dest)
                 {
@@ -250,6 +269,12 @@ fn is_write_patch_constrained_to_writable_paths(
     true
 }
 
+/// Setuid, setgid and sticky bits are never needed for ordinary edits, so
+/// patches setting them always go to the user.
+fn has_special_mode_bits(mode: u32) -> bool {
+    mode & 0o7000 != 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -305,6 +330,47 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_symlinks_and_special_modes_need_approval() {
+        let tmp = TempDir::new().unwrap();
+        let cwd = tmp.path().to_path_buf();
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+        let parse = |body: &str| {
+            let argv = vec![
+                "apply_patch".to_string(),
+                format!("*** Begin Patch\n{body}\n*** End Patch"),
+            ];
+            match codex_apply_patch::maybe_parse_apply_patch_verified(&argv, &cwd) {
+                codex_apply_patch::MaybeApplyPatchVerified::Body(action) => action,
+                other => panic!("expected a patch, got {other:?}"),
+            }
+        };
+
+        let inside = parse("*** Add Symlink: link -> dir/file.txt");
+        assert!(is_write_patch_constrained_to_writable_paths(
+            &inside, &policy, &cwd
+        ));
+        let outside = parse("*** Add Symlink: link -> ../../etc/passwd");
+        assert!(!is_write_patch_constrained_to_writable_paths(
+            &outside, &policy, &cwd
+        ));
+        let executable = parse("*** Add File: run.sh\n*** Set Mode: 755\n+echo hi");
+        assert!(is_write_patch_constrained_to_writable_paths(
+            &executable,
+            &policy,
+            &cwd
+        ));
+        let setuid = parse("*** Add File: run.sh\n*** Set Mode: 4755\n+echo hi");
+        assert!(!is_write_patch_constrained_to_writable_paths(
+            &setuid, &policy, &cwd
+        ));
+    }
+
     #[test]
     fn test_request_escalated_privileges() {
         // Should not be a trusted command
@@ -0,0 +1,183 @@
+//! Color theme for the TUI, configured under `[tui]`.
+//!
+//! `theme` picks one of the built-in palettes (or `auto`, which asks the
+//! terminal for its background color) and `[tui.colors]` overrides individual
+//! semantic colors on top of it. Colors are parsed when `config.toml` is
+//! deserialized, so a typo is reported before the TUI starts.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+/// Built-in palette to use for the TUI.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    /// Pick `dark` or `light` from the terminal's background color, falling
+    /// back to `dark` when the terminal does not report it.
+    #[default]
+    Auto,
+    Dark,
+    Light,
+    HighContrast,
+}
+
+/// A color as written in `config.toml`: an ANSI color name such as `green`
+/// or `light-red`, `default` for the terminal's own foreground, or a
+/// `#rrggbb` hex triple.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeColor {
+    Default,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+    DarkGray,
+    LightRed,
+    LightGreen,
+    LightYellow,
+    LightBlue,
+    LightMagenta,
+    LightCyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+
+const NAMED_COLORS: &[(&str, ThemeColor)] = &[
+    ("default", ThemeColor::Default),
+    ("black", ThemeColor::Black),
+    ("red", ThemeColor::Red),
+    ("green", ThemeColor::Green),
+    ("yellow", ThemeColor::Yellow),
+    ("blue", ThemeColor::Blue),
+    ("magenta", ThemeColor::Magenta),
+    ("cyan", ThemeColor::Cyan),
+    ("gray", ThemeColor::Gray),
+    ("dark-gray", ThemeColor::DarkGray),
+    ("light-red", ThemeColor::LightRed),
+    ("light-green", ThemeColor::LightGreen),
+    ("light-yellow", ThemeColor::LightYellow),
+    ("light-blue", ThemeColor::LightBlue),
+    ("light-magenta", ThemeColor::LightMagenta),
+    ("light-cyan", ThemeColor::LightCyan),
+    ("white", ThemeColor::White),
+];
+
+impl FromStr for ThemeColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        if let Some(hex) = trimmed.strip_prefix('#') {
+            let channel = |i: usize| {
+                hex.get(i..i + 2)
+                    .and_then(|c| u8::from_str_radix(c, 16).ok())
+            };
+            let valid = hex.len() == 6 && hex.bytes().all(|b| b.is_ascii_hexdigit());
+            return match (valid, channel(0), channel(2), channel(4)) {
+                (true, Some(r), Some(g), Some(b)) => Ok(ThemeColor::Rgb(r, g, b)),
+                _ => Err(format!("invalid color `{s}`: expected `#rrggbb`")),
+            };
+        }
+        let name = trimmed.to_ascii_lowercase().replace('_', "-");
+        let name = match name.as_str() {
+            "grey" => "gray",
+            "dark-grey" => "dark-gray",
+            other => other,
+        };
+        NAMED_COLORS
+            .iter()
+            .find(|(candidate, _)| *candidate == name)
+            .map(|(_, color)| *color)
+            .ok_or_else(|| format!("invalid color `{s}`: unknown color name"))
+    }
+}
+
+impl fmt::Display for ThemeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let ThemeColor::Rgb(r, g, b) = self {
+            return write!(f, "#{r:02x}{g:02x}{b:02x}");
+        }
+        let name = NAMED_COLORS
+            .iter()
+            .find(|(_, color)| color == self)
+            .map(|(name, _)| *name)
+            .unwrap_or_default();
+        write!(f, "{name}")
+    }
+}
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Overrides for the semantic colors of the selected theme, as written under
+/// `[tui.colors]`. Unset entries keep the theme's color.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeColors {
+    /// Added lines and line counts in diffs.
+    pub added: Option<ThemeColor>,
+    /// Removed lines and line counts in diffs.
+    pub removed: Option<ThemeColor>,
+    /// Commands and tool calls run by the agent.
+    pub command: Option<ThemeColor>,
+    /// Reasoning ("thinking") headers and summaries.
+    pub reasoning: Option<ThemeColor>,
+    /// Errors and failed commands.
+    pub error: Option<ThemeColor>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_names_and_hex_colors() {
+        assert_eq!("green".parse(), Ok(ThemeColor::Green));
+        assert_eq!("Light_Red".parse(), Ok(ThemeColor::LightRed));
+        assert_eq!("dark-grey".parse(), Ok(ThemeColor::DarkGray));
+        assert_eq!("#1e90FF".parse(), Ok(ThemeColor::Rgb(0x1e, 0x90, 0xff)));
+        assert!("#12345".parse::<ThemeColor>().is_err());
+        assert!("#12345g".parse::<ThemeColor>().is_err());
+        assert!("chartreuse".parse::<ThemeColor>().is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for color in [
+            ThemeColor::Default,
+            ThemeColor::LightMagenta,
+            ThemeColor::Rgb(1, 2, 255),
+        ] {
+            assert_eq!(color.to_string().parse(), Ok(color));
+        }
+    }
+
+    #[test]
+    fn colors_table_rejects_unknown_roles() {
+        let colors = toml::from_str::<ThemeColors>("added = \"#00ff00\"\nerror = \"red\"\n");
+        assert_eq!(
+            colors.ok(),
+            Some(ThemeColors {
+                added: Some(ThemeColor::Rgb(0, 255, 0)),
+                error: Some(ThemeColor::Red),
+                ..ThemeColors::default()
+            })
+        );
+        let err = toml::from_str::<ThemeColors>("warning = \"red\"\n");
+        assert!(err.is_err());
+    }
+}
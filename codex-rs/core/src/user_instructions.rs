@@ -2,6 +2,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::USER_INSTRUCTIONS_CLOSE_TAG;
 use codex_protocol::protocol::USER_INSTRUCTIONS_OPEN_TAG;
@@ -40,3 +41,14 @@ impl From<UserInstructions> for ResponseItem {
         }
     }
 }
+
+impl From<UserInstructions> for ResponseInputItem {
+    fn from(ui: UserInstructions) -> Self {
+        ResponseInputItem::Message {
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: ui.serialize_to_xml(),
+            }],
+        }
+    }
+}
@@ -40,6 +40,7 @@ async fn test_exec_stdout_stream_events_echo() {
         sub_id: "test-sub".to_string(),
         call_id: "call-1".to_string(),
         tx_event: tx,
+        redactor: None,
     };
 
     let cmd = vec![
@@ -90,6 +91,7 @@ async fn test_exec_stderr_stream_events_echo() {
         sub_id: "test-sub".to_string(),
         call_id: "call-2".to_string(),
         tx_event: tx,
+        redactor: None,
     };
 
     let cmd = vec![
@@ -19,6 +19,7 @@ use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::ProjectDocLoadedEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
@@ -181,6 +182,15 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::StreamError(StreamErrorEvent { message }) => {
                 ts_println!(self, "{}", message.style(self.dimmed));
             }
+            EventMsg::ProjectDocLoaded(ProjectDocLoadedEvent { path, truncated }) => {
+                let suffix = if truncated { " (truncated)" } else { "" };
+                ts_println!(
+                    self,
+                    "{}",
+                    format!("loaded instructions from {}{suffix}", path.display())
+                        .style(self.dimmed)
+                );
+            }
             EventMsg::TaskStarted(_) => {
                 // Ignore.
             }
@@ -299,6 +309,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 aggregated_output,
                 duration,
                 exit_code,
+                redactions,
                 ..
             }) => {
                 let exec_command = self.call_id_to_command.remove(&call_id);
@@ -328,6 +339,10 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     }
                 }
                 println!("{}", truncated_output.style(self.dimmed));
+                if redactions > 0 {
+                    let note = format!("{redactions} secret(s) redacted from output");
+                    println!("{}", note.style(self.dimmed));
+                }
             }
             EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
                 call_id: _,
@@ -400,17 +415,36 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 // it's easy to scan in the terminal output.
                 for (path, change) in changes.iter() {
                     match change {
-                        FileChange::Add { content } => {
+                        FileChange::Add { content, mode } => {
                             let header = format!(
-                                "{} {}",
+                                "{} {}{}",
                                 format_file_change(change),
-                                path.to_string_lossy()
+                                path.to_string_lossy(),
+                                format_mode(*mode)
                             );
                             println!("{}", header.style(self.magenta));
                             for line in content.lines() {
                                 println!("{}", line.style(self.green));
                             }
                         }
+                        FileChange::AddBinary { size, mode } => {
+                            let header = format!(
+                                "{} {} (binary, {size} bytes){}",
+                                format_file_change(change),
+                                path.to_string_lossy(),
+                                format_mode(*mode)
+                            );
+                            println!("{}", header.style(self.magenta));
+                        }
+                        FileChange::AddSymlink { target } => {
+                            let header = format!(
+                                "{} {} -> {}",
+                                format_file_change(change),
+                                path.to_string_lossy(),
+                                target.to_string_lossy()
+                            );
+                            println!("{}", header.style(self.magenta));
+                        }
                         FileChange::Delete { content } => {
                             let header = format!(
                                 "{} {}",
@@ -425,16 +459,23 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                         FileChange::Update {
                             unified_diff,
                             move_path,
+                            mode,
                         } => {
                             let header = if let Some(dest) = move_path {
                                 format!(
-                                    "{} {} -> {}",
+                                    "{} {} -> {}{}",
                                     format_file_change(change),
                                     path.to_string_lossy(),
-                                    dest.to_string_lossy()
+                                    dest.to_string_lossy(),
+                                    format_mode(*mode)
                                 )
                             } else {
-                                format!("{} {}", format_file_change(change), path.to_string_lossy())
+                                format!(
+                                    "{} {}{}",
+                                    format_file_change(change),
+                                    path.to_string_lossy(),
+                                    format_mode(*mode)
+                                )
                             };
                             println!("{}", header.style(self.magenta));
 
@@ -575,7 +616,9 @@ fn escape_command(command: &[String]) -> String {
 
 fn format_file_change(change: &FileChange) -> &'static str {
     match change {
-        FileChange::Add { .. } => "A",
+        FileChange::Add { .. } | FileChange::AddBinary { .. } | FileChange::AddSymlink { .. } => {
+            "A"
+        }
         FileChange::Delete { .. } => "D",
         FileChange::Update {
             move_path: Some(_), ..
@@ -586,6 +629,13 @@ fn format_file_change(change: &FileChange) -> &'static str {
     }
 }
 
+fn format_mode(mode: Option<u32>) -> String {
+    match mode {
+        Some(mode) => format!(" (mode {mode:o})"),
+        None => String::new(),
+    }
+}
+
 fn format_mcp_invocation(invocation: &McpInvocation) -> String {
     // Build fully-qualified tool name: server.tool
     let fq_tool_name = format!("{}.{}", invocation.server, invocation.tool);
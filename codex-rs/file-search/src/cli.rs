@@ -4,7 +4,10 @@ use std::path::PathBuf;
 use clap::ArgAction;
 use clap::Parser;
 
-/// Fuzzy matches filenames under a directory.
+use crate::ContentMatchMode;
+
+/// Fuzzy matches filenames under a directory, or searches file contents with
+/// `--content`.
 #[derive(Parser)]
 #[command(version)]
 pub struct Cli {
@@ -37,6 +40,14 @@ pub struct Cli {
     #[arg(short, long, action = ArgAction::Append)]
     pub exclude: Vec<String>,
 
+    /// Search file contents instead of file names.
+    #[arg(long, default_value = "false")]
+    pub content: bool,
+
+    /// How `--content` matches the pattern against each line.
+    #[arg(long, value_enum, default_value_t = ContentMatchMode::Fuzzy, requires = "content")]
+    pub mode: ContentMatchMode,
+
     /// Search pattern.
     pub pattern: Option<String>,
 }
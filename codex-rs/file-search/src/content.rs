@@ -0,0 +1,389 @@
+//! Content search: finds lines inside files (rather than file paths) that
+//! match a regex or fuzzy pattern.
+//!
+//! The traversal reuses the same parallel walker and ignore rules as the path
+//! search in the crate root. Each worker keeps its own bounded list of the best
+//! matches, which are merged once the walk is complete.
+
+use nucleo_matcher::Matcher;
+use nucleo_matcher::Utf32Str;
+use nucleo_matcher::pattern::Pattern;
+use regex_lite::Regex;
+use serde::Serialize;
+use std::cell::UnsafeCell;
+use std::cmp::Ordering as CmpOrdering;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::num::NonZero;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use crate::WorkerCount;
+use crate::build_walker;
+use crate::create_pattern;
+use crate::create_worker_count;
+
+/// Files larger than this are not searched.
+const MAX_CONTENT_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Lines longer than this (typically minified or generated code) are skipped.
+const MAX_LINE_BYTES: usize = 1024;
+
+/// How the search pattern is matched against each line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ContentMatchMode {
+    /// Fuzzy match each line, ranked by `nucleo_matcher` score.
+    #[default]
+    Fuzzy,
+    /// Match each line against a regular expression, ordered by path and line.
+    Regex,
+}
+
+/// A single line that matched a content search.
+///
+/// * `score` – Relevance score for fuzzy matches; always `0` for regex matches.
+/// * `path` – Path to the file (relative to the search directory).
+/// * `line_number` – 1-based line number within the file.
+/// * `line` – The matching line, without its trailing newline.
+/// * `indices` – Optional sorted, deduplicated character indices within
+///   `line` that matched the pattern. Only filled when `compute_indices` is
+///   `true`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContentMatch {
+    pub score: u32,
+    pub path: String,
+    pub line_number: u64,
+    pub line: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indices: Option<Vec<u32>>,
+}
+
+pub struct ContentSearchResults {
+    pub matches: Vec<ContentMatch>,
+    pub total_match_count: usize,
+}
+
+/// The worker threads will periodically check `cancel_flag` to see if they
+/// should stop processing files.
+#[allow(clippy::too_many_arguments)]
+pub fn run_content(
+    pattern_text: &str,
+    mode: ContentMatchMode,
+    limit: NonZero<usize>,
+    search_directory: &Path,
+    exclude: Vec<String>,
+    threads: NonZero<usize>,
+    cancel_flag: Arc<AtomicBool>,
+    compute_indices: bool,
+) -> anyhow::Result<ContentSearchResults> {
+    let line_matcher = LineMatcher::new(pattern_text, mode)?;
+    let WorkerCount {
+        num_walk_builder_threads,
+        num_best_matches_lists,
+    } = create_worker_count(threads);
+    let best_lines_per_worker: Vec<UnsafeCell<BestLinesList>> = (0..num_best_matches_lists)
+        .map(|_| UnsafeCell::new(BestLinesList::new(limit.get(), line_matcher.clone())))
+        .collect();
+
+    let walker = build_walker(search_directory, exclude, num_walk_builder_threads)?;
+
+    let index_counter = AtomicUsize::new(0);
+    walker.run(|| {
+        let index = index_counter.fetch_add(1, Ordering::Relaxed);
+        let best_list_ptr = best_lines_per_worker[index].get();
+        let best_list = unsafe { &mut *best_list_ptr };
+
+        // Reading files is much more expensive than matching paths, so check
+        // the flag after every file.
+        let cancel = cancel_flag.clone();
+
+        Box::new(move |entry| {
+            let Ok(entry) = entry else {
+                return ignore::WalkState::Continue;
+            };
+            let is_searchable = entry.file_type().is_some_and(|ft| ft.is_file())
+                && entry
+                    .metadata()
+                    .is_ok_and(|meta| meta.len() <= MAX_CONTENT_FILE_BYTES);
+            if is_searchable
+                && let Ok(rel_path) = entry.path().strip_prefix(search_directory)
+                && let Some(rel_path) = rel_path.to_str()
+                && let Ok(bytes) = std::fs::read(entry.path())
+            {
+                best_list.search_file(rel_path, &bytes);
+            }
+
+            if cancel.load(Ordering::Relaxed) {
+                ignore::WalkState::Quit
+            } else {
+                ignore::WalkState::Continue
+            }
+        })
+    });
+
+    if cancel_flag.load(Ordering::Relaxed) {
+        return Ok(ContentSearchResults {
+            matches: Vec::new(),
+            total_match_count: 0,
+        });
+    }
+
+    // Merge results across best_lines_per_worker.
+    let mut global_heap: BinaryHeap<Reverse<RankedLine>> = BinaryHeap::new();
+    let mut total_match_count = 0;
+    for best_list_cell in best_lines_per_worker.into_iter() {
+        let best_list = best_list_cell.into_inner();
+        total_match_count += best_list.num_matches;
+        for Reverse(ranked) in best_list.binary_heap.into_vec() {
+            push_bounded(&mut global_heap, limit.get(), ranked);
+        }
+    }
+
+    let mut ranked: Vec<RankedLine> = global_heap.into_iter().map(|r| r.0).collect();
+    // Best first.
+    ranked.sort_by(|a, b| b.cmp(a));
+
+    let mut matcher = Matcher::new(nucleo_matcher::Config::DEFAULT);
+    let matches = ranked
+        .into_iter()
+        .map(|ranked| {
+            let indices = compute_indices.then(|| line_matcher.indices(&ranked.line, &mut matcher));
+            ContentMatch {
+                score: ranked.score,
+                path: ranked.path,
+                line_number: ranked.line_number,
+                line: ranked.line,
+                indices,
+            }
+        })
+        .collect();
+
+    Ok(ContentSearchResults {
+        matches,
+        total_match_count,
+    })
+}
+
+#[derive(Clone)]
+enum LineMatcher {
+    Fuzzy(Pattern),
+    Regex(Regex),
+}
+
+impl LineMatcher {
+    fn new(pattern_text: &str, mode: ContentMatchMode) -> anyhow::Result<Self> {
+        Ok(match mode {
+            ContentMatchMode::Fuzzy => Self::Fuzzy(create_pattern(pattern_text)),
+            ContentMatchMode::Regex => Self::Regex(Regex::new(pattern_text)?),
+        })
+    }
+
+    fn score(&self, line: &str, matcher: &mut Matcher, utf32buf: &mut Vec<char>) -> Option<u32> {
+        match self {
+            Self::Fuzzy(pattern) => pattern.score(Utf32Str::new(line, utf32buf), matcher),
+            Self::Regex(regex) => regex.is_match(line).then_some(0),
+        }
+    }
+
+    /// Sorted, deduplicated character indices of `line` that matched.
+    fn indices(&self, line: &str, matcher: &mut Matcher) -> Vec<u32> {
+        let mut indices: Vec<u32> = match self {
+            Self::Fuzzy(pattern) => {
+                let mut buf = Vec::<char>::new();
+                let mut indices = Vec::new();
+                pattern.indices(Utf32Str::new(line, &mut buf), matcher, &mut indices);
+                indices
+            }
+            Self::Regex(regex) => {
+                let ranges: Vec<std::ops::Range<usize>> =
+                    regex.find_iter(line).map(|m| m.range()).collect();
+                line.char_indices()
+                    .enumerate()
+                    .filter(|(_, (byte, _))| ranges.iter().any(|r| r.contains(byte)))
+                    .map(|(i, _)| i as u32)
+                    .collect()
+            }
+        };
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+}
+
+/// A candidate match, ordered so that "greater" means "better": higher score,
+/// then earlier path, then earlier line.
+#[derive(Debug, PartialEq, Eq)]
+struct RankedLine {
+    score: u32,
+    path: String,
+    line_number: u64,
+    line: String,
+}
+
+impl Ord for RankedLine {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.score
+            .cmp(&other.score)
+            .then_with(|| other.path.cmp(&self.path))
+            .then_with(|| other.line_number.cmp(&self.line_number))
+    }
+}
+
+impl PartialOrd for RankedLine {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn push_bounded(heap: &mut BinaryHeap<Reverse<RankedLine>>, max_count: usize, ranked: RankedLine) {
+    if heap.len() < max_count {
+        heap.push(Reverse(ranked));
+    } else if let Some(min_element) = heap.peek()
+        && ranked > min_element.0
+    {
+        heap.pop();
+        heap.push(Reverse(ranked));
+    }
+}
+
+/// Maintains the `max_count` best matching lines seen by one worker.
+struct BestLinesList {
+    max_count: usize,
+    num_matches: usize,
+    line_matcher: LineMatcher,
+    matcher: Matcher,
+    binary_heap: BinaryHeap<Reverse<RankedLine>>,
+
+    /// Internal buffer for converting strings to UTF-32.
+    utf32buf: Vec<char>,
+}
+
+impl BestLinesList {
+    fn new(max_count: usize, line_matcher: LineMatcher) -> Self {
+        Self {
+            max_count,
+            num_matches: 0,
+            line_matcher,
+            matcher: Matcher::new(nucleo_matcher::Config::DEFAULT),
+            binary_heap: BinaryHeap::new(),
+            utf32buf: Vec::<char>::new(),
+        }
+    }
+
+    fn search_file(&mut self, path: &str, bytes: &[u8]) {
+        // Skip binary files, using the same heuristic as git.
+        if bytes.iter().take(8000).any(|b| *b == 0) {
+            return;
+        }
+        let Ok(text) = std::str::from_utf8(bytes) else {
+            return;
+        };
+        for (index, line) in text.lines().enumerate() {
+            if line.len() > MAX_LINE_BYTES || line.trim().is_empty() {
+                continue;
+            }
+            let Some(score) = self
+                .line_matcher
+                .score(line, &mut self.matcher, &mut self.utf32buf)
+            else {
+                continue;
+            };
+            self.num_matches += 1;
+            let ranked = RankedLine {
+                score,
+                path: path.to_string(),
+                line_number: index as u64 + 1,
+                line: line.to_string(),
+            };
+            push_bounded(&mut self.binary_heap, self.max_count, ranked);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(dir: &Path, pattern: &str, mode: ContentMatchMode) -> ContentSearchResults {
+        run_content(
+            pattern,
+            mode,
+            NonZero::new(10).expect("non-zero"),
+            dir,
+            Vec::new(),
+            NonZero::new(2).expect("non-zero"),
+            Arc::new(AtomicBool::new(false)),
+            true,
+        )
+        .expect("search succeeds")
+    }
+
+    #[test]
+    fn regex_matches_are_ordered_by_path_and_line() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("b.rs"), "fn beta() {}\nfn alpha() {}\n").expect("write");
+        std::fs::write(dir.path().join("a.rs"), "// nothing\nfn gamma() {}\n").expect("write");
+        std::fs::write(dir.path().join("blob.bin"), b"fn \0binary").expect("write");
+
+        let results = search(dir.path(), r"fn \w+\(", ContentMatchMode::Regex);
+        let found: Vec<(String, u64)> = results
+            .matches
+            .iter()
+            .map(|m| (m.path.clone(), m.line_number))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("a.rs".to_string(), 2),
+                ("b.rs".to_string(), 1),
+                ("b.rs".to_string(), 2),
+            ]
+        );
+        assert_eq!(results.total_match_count, 3);
+        assert_eq!(
+            results.matches[0].indices,
+            Some(vec![0, 1, 2, 3, 4, 5, 6, 7, 8])
+        );
+    }
+
+    #[test]
+    fn fuzzy_matches_rank_best_line_first() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(
+            dir.path().join("lib.rs"),
+            "let file_search = 1;\nfn fresh() {}\nlet unrelated = 2;\n",
+        )
+        .expect("write");
+
+        let results = search(dir.path(), "file_search", ContentMatchMode::Fuzzy);
+        assert_eq!(
+            results.matches.first().map(|m| m.line.as_str()),
+            Some("let file_search = 1;")
+        );
+        assert!(
+            results
+                .matches
+                .iter()
+                .all(|m| m.line != "let unrelated = 2;")
+        );
+    }
+
+    #[test]
+    fn invalid_regex_is_an_error() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let result = run_content(
+            "(",
+            ContentMatchMode::Regex,
+            NonZero::new(10).expect("non-zero"),
+            dir.path(),
+            Vec::new(),
+            NonZero::new(1).expect("non-zero"),
+            Arc::new(AtomicBool::new(false)),
+            false,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use ignore::WalkBuilder;
+use ignore::WalkParallel;
 use ignore::overrides::OverrideBuilder;
 use nucleo_matcher::Matcher;
 use nucleo_matcher::Utf32Str;
@@ -19,8 +20,13 @@ use std::sync::atomic::Ordering;
 use tokio::process::Command;
 
 mod cli;
+mod content;
 
 pub use cli::Cli;
+pub use content::ContentMatch;
+pub use content::ContentMatchMode;
+pub use content::ContentSearchResults;
+pub use content::run_content;
 
 /// A single match result returned from the search.
 ///
@@ -47,6 +53,7 @@ pub struct FileSearchResults {
 
 pub trait Reporter {
     fn report_match(&self, file_match: &FileMatch);
+    fn report_content_match(&self, content_match: &ContentMatch);
     fn warn_matches_truncated(&self, total_match_count: usize, shown_match_count: usize);
     fn warn_no_search_pattern(&self, search_directory: &Path);
 }
@@ -60,6 +67,8 @@ pub async fn run_main<T: Reporter>(
         json: _,
         exclude,
         threads,
+        content,
+        mode,
     }: Cli,
     reporter: T,
 ) -> anyhow::Result<()> {
@@ -94,6 +103,30 @@ pub async fn run_main<T: Reporter>(
     };
 
     let cancel_flag = Arc::new(AtomicBool::new(false));
+    if content {
+        let ContentSearchResults {
+            total_match_count,
+            matches,
+        } = run_content(
+            &pattern_text,
+            mode,
+            limit,
+            &search_directory,
+            exclude,
+            threads,
+            cancel_flag,
+            compute_indices,
+        )?;
+        let match_count = matches.len();
+        for content_match in matches {
+            reporter.report_content_match(&content_match);
+        }
+        if total_match_count > match_count {
+            reporter.warn_matches_truncated(total_match_count, match_count);
+        }
+        return Ok(());
+    }
+
     let FileSearchResults {
         total_match_count,
         matches,
@@ -148,27 +181,7 @@ pub fn run(
         })
         .collect();
 
-    // Use the same tree-walker library that ripgrep uses. We use it directly so
-    // that we can leverage the parallelism it provides.
-    let mut walk_builder = WalkBuilder::new(search_directory);
-    walk_builder
-        .threads(num_walk_builder_threads)
-        // Allow hidden entries.
-        .hidden(false)
-        // Don't require git to be present to apply to apply git-related ignore rules.
-        .require_git(false);
-
-    if !exclude.is_empty() {
-        let mut override_builder = OverrideBuilder::new(search_directory);
-        for exclude in exclude {
-            // The `!` prefix is used to indicate an exclude pattern.
-            let exclude_pattern = format!("!{exclude}");
-            override_builder.add(&exclude_pattern)?;
-        }
-        let override_matcher = override_builder.build()?;
-        walk_builder.overrides(override_matcher);
-    }
-    let walker = walk_builder.build_parallel();
+    let walker = build_walker(search_directory, exclude, num_walk_builder_threads)?;
 
     // Each worker created by `WalkParallel::run()` will have its own
     // `BestMatchesList` to update.
@@ -285,6 +298,35 @@ pub fn run(
     })
 }
 
+/// Builds the parallel walker shared by path and content search.
+fn build_walker(
+    search_directory: &Path,
+    exclude: Vec<String>,
+    num_walk_builder_threads: usize,
+) -> anyhow::Result<WalkParallel> {
+    // Use the same tree-walker library that ripgrep uses. We use it directly so
+    // that we can leverage the parallelism it provides.
+    let mut walk_builder = WalkBuilder::new(search_directory);
+    walk_builder
+        .threads(num_walk_builder_threads)
+        // Allow hidden entries.
+        .hidden(false)
+        // Don't require git to be present to apply to apply git-related ignore rules.
+        .require_git(false);
+
+    if !exclude.is_empty() {
+        let mut override_builder = OverrideBuilder::new(search_directory);
+        for exclude in exclude {
+            // The `!` prefix is used to indicate an exclude pattern.
+            let exclude_pattern = format!("!{exclude}");
+            override_builder.add(&exclude_pattern)?;
+        }
+        let override_matcher = override_builder.build()?;
+        walk_builder.overrides(override_matcher);
+    }
+    Ok(walk_builder.build_parallel())
+}
+
 /// Sort matches in-place by descending score, then ascending path.
 fn sort_matches(matches: &mut [(u32, String)]) {
     matches.sort_by(|a, b| match b.0.cmp(&a.0) {
@@ -3,6 +3,7 @@ use std::path::Path;
 
 use clap::Parser;
 use codex_file_search::Cli;
+use codex_file_search::ContentMatch;
 use codex_file_search::FileMatch;
 use codex_file_search::Reporter;
 use codex_file_search::run_main;
@@ -33,28 +34,25 @@ impl Reporter for StdioReporter {
                 .indices
                 .as_ref()
                 .expect("--compute-indices was specified");
-            // `indices` is guaranteed to be sorted in ascending order. Instead
-            // of calling `contains` for every character (which would be O(N^2)
-            // in the worst-case), walk through the `indices` vector once while
-            // iterating over the characters.
-            let mut indices_iter = indices.iter().peekable();
+            print_highlighted(&file_match.path, indices);
+            println!();
+        } else {
+            println!("{}", file_match.path);
+        }
+    }
 
-            for (i, c) in file_match.path.chars().enumerate() {
-                match indices_iter.peek() {
-                    Some(next) if **next == i as u32 => {
-                        // ANSI escape code for bold: \x1b[1m ... \x1b[0m
-                        print!("\x1b[1m{c}\x1b[0m");
-                        // advance the iterator since we've consumed this index
-                        indices_iter.next();
-                    }
-                    _ => {
-                        print!("{c}");
-                    }
+    fn report_content_match(&self, content_match: &ContentMatch) {
+        if self.write_output_as_json {
+            println!("{}", serde_json::to_string(&content_match).unwrap());
+        } else {
+            print!("{}:{}:", content_match.path, content_match.line_number);
+            match &content_match.indices {
+                Some(indices) if self.show_indices => {
+                    print_highlighted(&content_match.line, indices)
                 }
+                _ => print!("{}", content_match.line),
             }
             println!();
-        } else {
-            println!("{}", file_match.path);
         }
     }
 
@@ -76,3 +74,26 @@ impl Reporter for StdioReporter {
         );
     }
 }
+
+/// Prints `text` with the characters at `indices` in bold.
+fn print_highlighted(text: &str, indices: &[u32]) {
+    // `indices` is guaranteed to be sorted in ascending order. Instead
+    // of calling `contains` for every character (which would be O(N^2)
+    // in the worst-case), walk through the `indices` vector once while
+    // iterating over the characters.
+    let mut indices_iter = indices.iter().peekable();
+
+    for (i, c) in text.chars().enumerate() {
+        match indices_iter.peek() {
+            Some(next) if **next == i as u32 => {
+                // ANSI escape code for bold: \x1b[1m ... \x1b[0m
+                print!("\x1b[1m{c}\x1b[0m");
+                // advance the iterator since we've consumed this index
+                indices_iter.next();
+            }
+            _ => {
+                print!("{c}");
+            }
+        }
+    }
+}
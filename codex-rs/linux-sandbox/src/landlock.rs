@@ -6,6 +6,7 @@ use codex_core::error::CodexErr;
 use codex_core::error::Result;
 use codex_core::error::SandboxErr;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::WritableRoot;
 
 use landlock::ABI;
 use landlock::Access;
@@ -36,12 +37,12 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
     }
 
     if !sandbox_policy.has_full_disk_write_access() {
-        let writable_roots = sandbox_policy
+        let writable_paths = sandbox_policy
             .get_writable_roots_with_cwd(cwd)
-            .into_iter()
-            .map(|writable_root| writable_root.root)
+            .iter()
+            .flat_map(writable_paths)
             .collect();
-        install_filesystem_landlock_rules_on_current_thread(writable_roots)?;
+        install_filesystem_landlock_rules_on_current_thread(writable_paths)?;
     }
 
     // TODO(ragona): Add appropriate restrictions if
@@ -50,6 +51,43 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
     Ok(())
 }
 
+/// The paths to grant write access to for `writable_root`. Landlock rules
+/// can only add access, so a root with read-only subpaths is granted entry by
+/// entry, leaving the read-only subpaths out. New entries cannot be created
+/// directly in such a root (nor a missing read-only subpath). Symlinks are
+/// skipped so that a rule never reaches outside the root.
+fn writable_paths(writable_root: &WritableRoot) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    collect_writable_paths(
+        &writable_root.root,
+        &writable_root.read_only_subpaths,
+        &mut paths,
+    );
+    paths
+}
+
+fn collect_writable_paths(path: &Path, read_only_subpaths: &[PathBuf], out: &mut Vec<PathBuf>) {
+    if read_only_subpaths.iter().any(|ro| path.starts_with(ro)) {
+        return;
+    }
+    if !read_only_subpaths.iter().any(|ro| ro.starts_with(path)) {
+        out.push(path.to_path_buf());
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry
+            .file_type()
+            .is_ok_and(|file_type| file_type.is_symlink())
+        {
+            continue;
+        }
+        collect_writable_paths(&entry.path(), read_only_subpaths, out);
+    }
+}
+
 /// Installs Landlock file-system rules on the current thread allowing read
 /// access to the entire file-system while restricting write access to
 /// `/dev/null` and the provided list of `writable_roots`.
@@ -120,6 +120,46 @@ async fn test_writable_root() {
     .await;
 }
 
+#[tokio::test]
+async fn test_writable_repo_subdirectory() {
+    let repo = tempfile::tempdir().unwrap();
+    std::fs::create_dir(repo.path().join(".git")).unwrap();
+    std::fs::create_dir(repo.path().join("src")).unwrap();
+    let file_path = repo.path().join("src").join("main.rs");
+    run_cmd(
+        &[
+            "bash",
+            "-lc",
+            &format!("echo blah > {}", file_path.to_string_lossy()),
+        ],
+        &[repo.path().to_path_buf()],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_project_config_is_read_only() {
+    let repo = tempfile::tempdir().unwrap();
+    std::fs::create_dir(repo.path().join(".git")).unwrap();
+    std::fs::create_dir(repo.path().join(".codex")).unwrap();
+    let config_path = repo.path().join(".codex").join("config.toml");
+    run_cmd(
+        &[
+            "bash",
+            "-lc",
+            &format!(
+                "echo 'sandbox_mode = \"danger-full-access\"' > {}",
+                config_path.to_string_lossy()
+            ),
+        ],
+        &[repo.path().to_path_buf()],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
 #[tokio::test]
 #[should_panic(expected = "Sandbox(Timeout")]
 async fn test_timeout() {
@@ -895,19 +895,12 @@ impl CodexMessageProcessor {
         }
 
         // Move the .jsonl file to the archived sessions subdir.
-        let result: std::io::Result<()> = async {
-            let archive_folder = self
-                .config
-                .codex_home
-                .join(codex_core::ARCHIVED_SESSIONS_SUBDIR);
-            tokio::fs::create_dir_all(&archive_folder).await?;
-            tokio::fs::rename(&canonical_rollout_path, &archive_folder.join(&file_name)).await?;
-            Ok(())
-        }
-        .await;
+        let result =
+            codex_core::archive_session_file(&self.config.codex_home, &canonical_rollout_path)
+                .await;
 
         match result {
-            Ok(()) => {
+            Ok(_) => {
                 let response = ArchiveConversationResponse {};
                 self.outgoing.send_response(request_id, response).await;
             }
@@ -1295,6 +1288,8 @@ async fn on_patch_approval_response(
                 .submit(Op::PatchApproval {
                     id: event_id.clone(),
                     decision: ReviewDecision::Denied,
+                    comments: Vec::new(),
+                    rejected: Vec::new(),
                 })
                 .await
             {
@@ -1316,6 +1311,8 @@ async fn on_patch_approval_response(
         .submit(Op::PatchApproval {
             id: event_id,
             decision: response.decision,
+            comments: Vec::new(),
+            rejected: Vec::new(),
         })
         .await
     {
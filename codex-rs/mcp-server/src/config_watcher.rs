@@ -0,0 +1,58 @@
+//! Applies `config.toml` edits to the conversations this server is running.
+//!
+//! New conversations load the config from disk when they start, so they pick
+//! up every change. Running conversations get the model and reasoning
+//! changes. There is no one to confirm approval, sandbox or MCP server
+//! changes, so those, like the rest, only apply to new conversations.
+
+use std::sync::Arc;
+
+use codex_core::ConversationManager;
+use codex_core::config_reload::ConfigReloader;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+pub(crate) async fn run_config_watcher(
+    mut reloader: ConfigReloader,
+    conversation_manager: Arc<ConversationManager>,
+) {
+    let mut changed = reloader.watch();
+    while changed.recv().await.is_some() {
+        let changes = match reloader.reload() {
+            Ok(changes) => changes,
+            Err(err) => {
+                error!("Failed to reload config.toml, keeping the current settings: {err}");
+                continue;
+            }
+        };
+
+        if !changes.ops.is_empty() {
+            let conversations = conversation_manager.list_conversations().await;
+            for (conversation_id, conversation) in &conversations {
+                for op in &changes.ops {
+                    if let Err(err) = conversation.submit(op.clone()).await {
+                        warn!("Failed to apply config.toml changes to {conversation_id}: {err}");
+                    }
+                }
+            }
+            info!(
+                "Applied config.toml changes to {} running conversation(s): {}",
+                conversations.len(),
+                changes.session.join(", ")
+            );
+        }
+        if !changes.permissions.is_empty() {
+            warn!(
+                "config.toml changes to {} are not applied to running conversations; they apply to new conversations only",
+                changes.permissions.join(", ")
+            );
+        }
+        if !changes.requires_restart.is_empty() {
+            info!(
+                "config.toml changes to {} apply to new conversations only",
+                changes.requires_restart.join(", ")
+            );
+        }
+    }
+}
@@ -6,8 +6,8 @@ use std::io::Result as IoResult;
 use std::path::PathBuf;
 
 use codex_common::CliConfigOverrides;
-use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
+use codex_core::config_reload::ConfigReloader;
 
 use mcp_types::JSONRPCMessage;
 use tokio::io::AsyncBufReadExt;
@@ -23,6 +23,7 @@ use tracing_subscriber::EnvFilter;
 mod codex_message_processor;
 mod codex_tool_config;
 mod codex_tool_runner;
+mod config_watcher;
 mod error_code;
 mod exec_approval;
 mod json_to_toml;
@@ -92,10 +93,11 @@ pub async fn run_main(
             format!("error parsing -c overrides: {e}"),
         )
     })?;
-    let config = Config::load_with_cli_overrides(cli_kv_overrides, ConfigOverrides::default())
+    let config_reloader = ConfigReloader::new(cli_kv_overrides, ConfigOverrides::default())
         .map_err(|e| {
             std::io::Error::new(ErrorKind::InvalidData, format!("error loading config: {e}"))
         })?;
+    let config = config_reloader.config().clone();
 
     // Task: process incoming messages.
     let processor_handle = tokio::spawn({
@@ -105,6 +107,11 @@ pub async fn run_main(
             codex_linux_sandbox_exe,
             std::sync::Arc::new(config),
         );
+        // Task: apply config.toml edits to running conversations.
+        tokio::spawn(config_watcher::run_config_watcher(
+            config_reloader,
+            processor.conversation_manager(),
+        ));
         async move {
             while let Some(msg) = incoming_rx.recv().await {
                 match msg {
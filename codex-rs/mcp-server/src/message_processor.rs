@@ -75,6 +75,10 @@ impl MessageProcessor {
         }
     }
 
+    pub(crate) fn conversation_manager(&self) -> Arc<ConversationManager> {
+        self.conversation_manager.clone()
+    }
+
     pub(crate) async fn process_request(&mut self, request: JSONRPCRequest) {
         if let Ok(request_json) = serde_json::to_value(request.clone())
             && let Ok(codex_request) = serde_json::from_value::<ClientRequest>(request_json)
@@ -122,6 +122,8 @@ pub(crate) async fn on_patch_approval_response(
                 .submit(Op::PatchApproval {
                     id: event_id.clone(),
                     decision: ReviewDecision::Denied,
+                    comments: Vec::new(),
+                    rejected: Vec::new(),
                 })
                 .await
             {
@@ -142,6 +144,8 @@ pub(crate) async fn on_patch_approval_response(
         .submit(Op::PatchApproval {
             id: event_id,
             decision: response.decision,
+            comments: Vec::new(),
+            rejected: Vec::new(),
         })
         .await
     {
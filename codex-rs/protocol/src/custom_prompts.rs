@@ -3,9 +3,24 @@ use serde::Serialize;
 use std::path::PathBuf;
 use ts_rs::TS;
 
-#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+use crate::config_types::ReasoningEffort;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, TS)]
 pub struct CustomPrompt {
     pub name: String,
     pub path: PathBuf,
+    /// Body of the prompt file, without its front matter.
     pub content: String,
+    /// Shown next to the command in the slash popup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Describes the arguments the prompt expects, e.g. `<file> [focus]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument_hint: Option<String>,
+    /// Model to use for the turn the prompt starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Reasoning effort to use for the turn the prompt starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effort: Option<ReasoningEffort>,
 }
@@ -214,6 +214,24 @@ impl From<Vec<InputItem>> for ResponseInputItem {
                 .filter_map(|c| match c {
                     InputItem::Text { text } => Some(ContentItem::InputText { text }),
                     InputItem::Image { image_url } => Some(ContentItem::InputImage { image_url }),
+                    InputItem::Attachment {
+                        mention,
+                        path,
+                        start_line,
+                        end_line,
+                        contents,
+                    } => {
+                        let lines = match (start_line, end_line) {
+                            (Some(start), Some(end)) => format!(" lines=\"{start}-{end}\""),
+                            _ => String::new(),
+                        };
+                        Some(ContentItem::InputText {
+                            text: format!(
+                                "<attachment mention=\"{mention}\" path=\"{}\"{lines}>\n{contents}\n</attachment>",
+                                path.display()
+                            ),
+                        })
+                    }
                     InputItem::LocalImage { path } => match std::fs::read(&path) {
                         Ok(bytes) => {
                             let mime = mime_guess::from_path(&path)
@@ -10,6 +10,7 @@ use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 
+use crate::config_types::McpServerConfig;
 use crate::config_types::ReasoningEffort as ReasoningEffortConfig;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use crate::custom_prompts::CustomPrompt;
@@ -138,6 +139,14 @@ pub enum Op {
         id: String,
         /// The user's decision in response to the request.
         decision: ReviewDecision,
+        /// Review comments anchored to the proposed patch. When the patch is
+        /// denied they are returned to the model so it can revise the patch.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        comments: Vec<PatchComment>,
+        /// Parts of the patch the user declined while approving the rest.
+        /// Only the accepted parts are applied.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        rejected: Vec<RejectedChange>,
     },
 
     /// Append an entry to the persistent cross-session message history.
@@ -163,6 +172,15 @@ pub enum Op {
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
+    /// Replace the set of MCP servers used by this session. Servers whose
+    /// configuration is unchanged keep running; new and changed servers are
+    /// started and removed ones are shut down. The outcome is reported via
+    /// `EventMsg::BackgroundEvent`, and servers that fail to start via
+    /// `EventMsg::Error`.
+    ReconfigureMcpServers {
+        mcp_servers: HashMap<String, McpServerConfig>,
+    },
+
     /// Request the agent to summarize the current conversation context.
     /// The agent will use its existing context (either conversation history or previous response id)
     /// to generate a summary which will be returned as an AgentMessage event.
@@ -245,8 +263,8 @@ pub enum SandboxPolicy {
 
 /// A writable root path accompanied by a list of subpaths that should remain
 /// read‑only even when the root is writable. This is primarily used to ensure
-/// top‑level VCS metadata directories (e.g. `.git`) under a writable root are
-/// not modified by the agent.
+/// top‑level VCS metadata directories (e.g. `.git`) and the project's Codex
+/// config (`.codex`) under a writable root are not modified by the agent.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WritableRoot {
     /// Absolute path, by construction.
@@ -364,15 +382,35 @@ impl SandboxPolicy {
                     roots.push(PathBuf::from(tmpdir));
                 }
 
+                // The project config can loosen the sandbox once the project
+                // is trusted, so the agent must not be able to write it (or
+                // create it) either. It lives in `.codex` at the Git root
+                // containing `cwd`, which may be above `cwd` and below a
+                // configured writable root.
+                let project_codex_dir = cwd
+                    .ancestors()
+                    .find(|dir| dir.join(".git").exists())
+                    .map(|repo_root| repo_root.join(".codex"));
+
                 // For each root, compute subpaths that should remain read-only.
                 roots
                     .into_iter()
                     .map(|writable_root| {
                         let mut subpaths = Vec::new();
                         let top_level_git = writable_root.join(".git");
+                        let is_repo_root = top_level_git.exists();
                         if top_level_git.is_dir() {
                             subpaths.push(top_level_git);
                         }
+                        if is_repo_root {
+                            subpaths.push(writable_root.join(".codex"));
+                        }
+                        if let Some(dir) = &project_codex_dir
+                            && dir.starts_with(&writable_root)
+                            && !subpaths.contains(dir)
+                        {
+                            subpaths.push(dir.clone());
+                        }
                         WritableRoot {
                             root: writable_root,
                             read_only_subpaths: subpaths,
@@ -402,6 +440,21 @@ pub enum InputItem {
     LocalImage {
         path: std::path::PathBuf,
     },
+
+    /// File contents, a line range of a file or a directory listing that the
+    /// user attached with an `@` mention. Sent to the model as text tagged
+    /// with its source.
+    Attachment {
+        /// The mention as typed, e.g. `@src/lib.rs#L10-80`.
+        mention: String,
+        path: std::path::PathBuf,
+        /// 1-based, inclusive line range when only part of a file is attached.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        start_line: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        end_line: Option<u64>,
+        contents: String,
+    },
 }
 
 /// Event Queue Entry - events from agent
@@ -480,6 +533,10 @@ pub enum EventMsg {
 
     BackgroundEvent(BackgroundEventEvent),
 
+    /// An `AGENTS.md` from a subdirectory was added to the model's context
+    /// because the agent started working under that directory.
+    ProjectDocLoaded(ProjectDocLoadedEvent),
+
     /// Notification that a model stream experienced an error or disconnect
     /// and the system is handling it (e.g., retrying with backoff).
     StreamError(StreamErrorEvent),
@@ -1052,6 +1109,9 @@ pub struct ExecCommandEndEvent {
     pub duration: Duration,
     /// Formatted output from the command, as seen by the model.
     pub formatted_output: String,
+    /// Number of secrets redacted from the output before it was surfaced.
+    #[serde(default)]
+    pub redactions: usize,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
@@ -1105,6 +1165,14 @@ pub struct BackgroundEventEvent {
     pub message: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct ProjectDocLoadedEvent {
+    pub path: PathBuf,
+    /// Only the start of the file fit in what was left of
+    /// `project_doc_max_bytes`.
+    pub truncated: bool,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct StreamErrorEvent {
     pub message: String,
@@ -1207,11 +1275,53 @@ pub enum ReviewDecision {
     Abort,
 }
 
+/// A review comment on a proposed patch.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, TS)]
+pub struct PatchComment {
+    /// File the comment refers to, as keyed in the request's `changes`.
+    pub path: PathBuf,
+    /// 1-based line number the comment is anchored to: in the new version
+    /// of the file, or in the old version for removed lines. `None` for a
+    /// comment on the whole file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u64>,
+    /// The commented line as shown in the patch, including its `+`, `-` or
+    /// ` ` prefix.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    pub body: String,
+}
+
+/// A file, or some hunks of a file, left out of an approved patch.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, TS)]
+pub struct RejectedChange {
+    /// File as keyed in the request's `changes`.
+    pub path: PathBuf,
+    /// 0-based indices of the rejected hunks in the file's `unified_diff`.
+    /// Empty when the whole file is rejected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hunks: Vec<usize>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum FileChange {
     Add {
         content: String,
+        /// Unix permission bits for the new file, e.g. `0o755`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
+    },
+    /// A new binary file. Only its size is sent; the contents are in the patch.
+    AddBinary {
+        size: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
+    },
+    AddSymlink {
+        /// Where the link points, relative to the directory holding it unless
+        /// absolute.
+        target: PathBuf,
     },
     Delete {
         content: String,
@@ -1219,6 +1329,9 @@ pub enum FileChange {
     Update {
         unified_diff: String,
         move_path: Option<PathBuf>,
+        /// New Unix permission bits, if the patch changes them.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
     },
 }
 
@@ -1282,6 +1395,39 @@ mod tests {
         assert_eq!(expected, serde_json::to_value(&event).unwrap());
     }
 
+    #[test]
+    fn project_codex_dir_is_read_only_under_any_writable_root() {
+        let workspace = tempfile::tempdir().unwrap();
+        let repo = workspace.path().join("repo");
+        let cwd = repo.join("crates").join("app");
+        std::fs::create_dir_all(&cwd).unwrap();
+        std::fs::create_dir(repo.join(".git")).unwrap();
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![workspace.path().to_path_buf()],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+
+        let roots = policy.get_writable_roots_with_cwd(&cwd);
+
+        assert_eq!(
+            roots,
+            vec![
+                WritableRoot {
+                    root: workspace.path().to_path_buf(),
+                    read_only_subpaths: vec![repo.join(".codex")],
+                },
+                WritableRoot {
+                    root: cwd.clone(),
+                    read_only_subpaths: Vec::new(),
+                },
+            ]
+        );
+        assert!(!roots[0].is_path_writable(&repo.join(".codex").join("config.toml")));
+        assert!(roots[0].is_path_writable(&repo.join("README.md")));
+    }
+
     #[test]
     fn vec_u8_as_base64_serialization_and_deserialization() {
         let event = ExecCommandOutputDeltaEvent {
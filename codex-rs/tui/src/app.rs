@@ -2,16 +2,23 @@ use crate::app_backtrack::BacktrackState;
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::chatwidget::ChatWidget;
+use crate::external_editor;
 use crate::file_search::FileSearchManager;
+use crate::file_search::SearchKind;
+use crate::get_git_diff::HunkAction;
+use crate::get_git_diff::apply_hunk;
+use crate::get_git_diff::get_git_diff;
+use crate::keymap;
 use crate::pager_overlay::Overlay;
+use crate::pager_overlay::TranscriptCell;
 use crate::resume_picker::ResumeSelection;
 use crate::tui;
 use crate::tui::TuiEvent;
-use codex_ansi_escape::ansi_escape_line;
 use codex_core::AuthManager;
 use codex_core::ConversationManager;
 use codex_core::config::Config;
 use codex_core::config::persist_model_selection;
+use codex_core::config_reload::ConfigReloader;
 use codex_core::model_family::find_family_for_model;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
@@ -44,10 +51,13 @@ pub(crate) struct App {
     pub(crate) active_profile: Option<String>,
 
     pub(crate) file_search: FileSearchManager,
+    pub(crate) content_search: FileSearchManager,
 
     pub(crate) transcript_lines: Vec<Line<'static>>,
+    /// Where each history cell starts in `transcript_lines`.
+    pub(crate) transcript_cells: Vec<TranscriptCell>,
 
-    // Pager overlay state (Transcript or Static like Diff)
+    // Pager overlay state (Transcript or Diff)
     pub(crate) overlay: Option<Overlay>,
     pub(crate) deferred_history_lines: Vec<Line<'static>>,
     has_emitted_history_lines: bool,
@@ -59,6 +69,10 @@ pub(crate) struct App {
 
     // Esc-backtracking state grouped
     pub(crate) backtrack: crate::app_backtrack::BacktrackState,
+
+    /// Reloads `config.toml` when it changes on disk. `None` when the config
+    /// could not be loaded a second time at startup.
+    config_reloader: Option<ConfigReloader>,
 }
 
 impl App {
@@ -66,6 +80,7 @@ impl App {
         tui: &mut tui::Tui,
         auth_manager: Arc<AuthManager>,
         config: Config,
+        config_reloader: Option<ConfigReloader>,
         active_profile: Option<String>,
         initial_prompt: Option<String>,
         initial_images: Vec<PathBuf>,
@@ -120,7 +135,23 @@ impl App {
             }
         };
 
-        let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
+        let file_search =
+            FileSearchManager::new(config.cwd.clone(), SearchKind::Files, app_event_tx.clone());
+        let content_search = FileSearchManager::new(
+            config.cwd.clone(),
+            SearchKind::Content,
+            app_event_tx.clone(),
+        );
+
+        if let Some(reloader) = &config_reloader {
+            let mut changed = reloader.watch();
+            let app_event_tx = app_event_tx.clone();
+            tokio::spawn(async move {
+                while changed.recv().await.is_some() {
+                    app_event_tx.send(AppEvent::ConfigFileChanged);
+                }
+            });
+        }
 
         let mut app = Self {
             server: conversation_manager,
@@ -130,13 +161,16 @@ impl App {
             config,
             active_profile,
             file_search,
+            content_search,
             enhanced_keys_supported,
             transcript_lines: Vec::new(),
+            transcript_cells: Vec::new(),
             overlay: None,
             deferred_history_lines: Vec::new(),
             has_emitted_history_lines: false,
             commit_anim_running: Arc::new(AtomicBool::new(false)),
             backtrack: BacktrackState::default(),
+            config_reloader,
         };
 
         let tui_events = tui.event_stream();
@@ -216,14 +250,24 @@ impl App {
             }
             AppEvent::InsertHistoryCell(cell) => {
                 let mut cell_transcript = cell.transcript_lines();
-                if !cell.is_stream_continuation() && !self.transcript_lines.is_empty() {
-                    cell_transcript.insert(0, Line::from(""));
+                let mut cell_start = None;
+                if !cell.is_stream_continuation() {
+                    let separated = !self.transcript_lines.is_empty();
+                    if separated {
+                        cell_transcript.insert(0, Line::from(""));
+                    }
+                    // Start past the separator so jumps land on the cell itself.
+                    cell_start = Some(TranscriptCell {
+                        start: self.transcript_lines.len() + usize::from(separated),
+                        kind: cell.kind(),
+                    });
                 }
                 if let Some(Overlay::Transcript(t)) = &mut self.overlay {
-                    t.insert_lines(cell_transcript.clone());
+                    t.insert_cell(cell_transcript.clone(), cell_start);
                     tui.frame_requester().schedule_frame();
                 }
                 self.transcript_lines.extend(cell_transcript.clone());
+                self.transcript_cells.extend(cell_start);
                 let mut display = cell.display_lines(tui.terminal.last_known_screen_size.width);
                 if !display.is_empty() {
                     // Only insert a separating blank line for new cells that are not
@@ -278,19 +322,53 @@ impl App {
             AppEvent::DiffResult(text) => {
                 // Clear the in-progress state in the bottom pane
                 self.chat_widget.on_diff_complete();
-                // Enter alternate screen using TUI helper and build pager lines
-                let _ = tui.enter_alt_screen();
-                let pager_lines: Vec<ratatui::text::Line<'static>> = if text.trim().is_empty() {
-                    vec!["No changes detected.".italic().into()]
+                if let Some(Overlay::Diff(diff)) = &mut self.overlay {
+                    // Refresh after a hunk was staged or reverted.
+                    diff.set_working_tree_diff(text);
                 } else {
-                    text.lines().map(ansi_escape_line).collect()
-                };
-                self.overlay = Some(Overlay::new_static_with_title(
-                    pager_lines,
-                    "D I F F".to_string(),
-                ));
+                    // Enter alternate screen using TUI helper and open the viewer
+                    let _ = tui.enter_alt_screen();
+                    self.overlay = Some(Overlay::new_diff(
+                        text,
+                        self.chat_widget.last_turn_diff(),
+                        self.config.cwd.clone(),
+                        self.app_event_tx.clone(),
+                    ));
+                }
+                tui.frame_requester().schedule_frame();
+            }
+            AppEvent::ApplyDiffHunk { patch, action } => {
+                let tx = self.app_event_tx.clone();
+                tokio::spawn(async move {
+                    let error = apply_hunk(&patch, action).await.err();
+                    let applied = error.is_none();
+                    tx.send(AppEvent::DiffHunkApplied {
+                        action,
+                        error: error.map(|e| e.to_string()),
+                    });
+                    if applied {
+                        let text = match get_git_diff().await {
+                            Ok((_, diff_text)) => diff_text,
+                            Err(e) => format!("Failed to compute diff: {e}"),
+                        };
+                        tx.send(AppEvent::DiffResult(text));
+                    }
+                });
+            }
+            AppEvent::DiffHunkApplied { action, error } => {
+                if let Some(Overlay::Diff(diff)) = &mut self.overlay {
+                    let status = match (action, error) {
+                        (_, Some(error)) => error.red().into(),
+                        (HunkAction::Stage, None) => "Hunk staged.".green().into(),
+                        (HunkAction::Revert, None) => "Hunk reverted.".green().into(),
+                    };
+                    diff.set_status(status);
+                }
                 tui.frame_requester().schedule_frame();
             }
+            AppEvent::SubmitUserMessage(text) => {
+                self.chat_widget.submit_text_message(text);
+            }
             AppEvent::StartFileSearch(query) => {
                 if !query.is_empty() {
                     self.file_search.on_user_query(query);
@@ -299,6 +377,17 @@ impl App {
             AppEvent::FileSearchResult { query, matches } => {
                 self.chat_widget.apply_file_search_result(query, matches);
             }
+            AppEvent::StartContentSearch(query) => {
+                if !query.is_empty() {
+                    self.content_search.on_user_query(query);
+                }
+            }
+            AppEvent::ContentSearchResult { query, matches } => {
+                self.chat_widget.apply_content_search_result(query, matches);
+            }
+            AppEvent::MentionEstimate { token, tokens } => {
+                self.chat_widget.apply_mention_estimate(token, tokens);
+            }
             AppEvent::UpdateReasoningEffort(effort) => {
                 self.on_update_reasoning_effort(effort);
             }
@@ -347,6 +436,9 @@ impl App {
             AppEvent::UpdateSandboxPolicy(policy) => {
                 self.chat_widget.set_sandbox_policy(policy);
             }
+            AppEvent::ConfigFileChanged => {
+                self.on_config_file_changed();
+            }
         }
         Ok(true)
     }
@@ -360,27 +452,138 @@ impl App {
         self.config.model_reasoning_effort = effort;
     }
 
+    /// Reload `config.toml`, apply what the running session and the TUI can
+    /// pick up, and tell the user which changes need a restart. Approval,
+    /// sandbox and MCP server changes are only applied once the user confirms
+    /// them.
+    fn on_config_file_changed(&mut self) {
+        let Some(reloader) = self.config_reloader.as_mut() else {
+            return;
+        };
+        let changes = match reloader.reload() {
+            Ok(changes) => changes,
+            Err(err) => {
+                self.chat_widget.add_error_message(format!(
+                    "Failed to reload config.toml, keeping the current settings: {err}"
+                ));
+                return;
+            }
+        };
+        if changes.is_empty() {
+            return;
+        }
+        let reloaded = reloader.config().clone();
+
+        for op in changes.ops {
+            self.chat_widget.submit_op(op);
+        }
+        // The model and effort can also be changed from the TUI, so only
+        // overwrite them when the file changed them.
+        for key in &changes.session {
+            match *key {
+                "model" => {
+                    self.chat_widget.set_model(&reloaded.model);
+                    self.config.model = reloaded.model.clone();
+                    self.config.model_family = reloaded.model_family.clone();
+                }
+                "model_reasoning_effort" => {
+                    self.on_update_reasoning_effort(reloaded.model_reasoning_effort);
+                }
+                _ => {}
+            }
+        }
+        if !changes.permissions.is_empty() {
+            let approval = changes
+                .permissions
+                .contains(&"approval_policy")
+                .then_some(reloaded.approval_policy);
+            let sandbox = changes
+                .permissions
+                .contains(&"sandbox")
+                .then(|| reloaded.sandbox_policy.clone());
+            self.chat_widget.open_config_permissions_popup(
+                &changes.permissions,
+                changes.permission_ops,
+                approval,
+                sandbox,
+            );
+        }
+        apply_file_only_settings(&mut self.config, &reloaded);
+        self.chat_widget
+            .update_config(|config| apply_file_only_settings(config, &reloaded));
+        if changes
+            .frontend
+            .iter()
+            .any(|key| matches!(*key, "tui.theme" | "tui.colors"))
+        {
+            crate::theme::reload(&self.config);
+        }
+
+        let applied: Vec<&str> = changes
+            .session
+            .iter()
+            .chain(&changes.frontend)
+            .copied()
+            .collect();
+        let message = if applied.is_empty() {
+            "config.toml changed".to_string()
+        } else {
+            format!("Applied config.toml changes: {}", applied.join(", "))
+        };
+        let hint = (!changes.requires_restart.is_empty()).then(|| {
+            format!(
+                "Restart Codex to apply {}",
+                changes.requires_restart.join(", ")
+            )
+        });
+        self.chat_widget.add_info_message(message, hint);
+    }
+
+    /// Open the composer contents in `$VISUAL` / `$EDITOR` and load the
+    /// result back into the composer.
+    async fn edit_composer_in_external_editor(&mut self, tui: &mut tui::Tui) {
+        let Some(command) = external_editor::editor_command() else {
+            self.chat_widget.add_error_message(
+                "Set $VISUAL or $EDITOR to compose prompts in an external editor.".to_string(),
+            );
+            return;
+        };
+        let text = self.chat_widget.composer_text();
+        match tui
+            .with_released_terminal(move || external_editor::edit_text(&command, &text))
+            .await
+        {
+            Ok(Ok(edited)) => self.chat_widget.set_composer_text_from_editor(&edited),
+            Ok(Err(err)) | Err(err) => self
+                .chat_widget
+                .add_error_message(format!("Failed to edit prompt in external editor: {err}")),
+        }
+    }
+
     async fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) {
         match key_event {
             KeyEvent {
-                code: KeyCode::Char('t'),
-                modifiers: crossterm::event::KeyModifiers::CONTROL,
                 kind: KeyEventKind::Press,
                 ..
-            } => {
-                // Enter alternate screen and set viewport to full size.
-                let _ = tui.enter_alt_screen();
-                self.overlay = Some(Overlay::new_transcript(self.transcript_lines.clone()));
-                tui.frame_requester().schedule_frame();
+            } if keymap::matches(self.config.tui_keymap.open_transcript, &key_event) => {
+                self.open_transcript_overlay(tui);
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                ..
+            } if keymap::matches(self.config.tui_keymap.external_editor, &key_event)
+                && self.chat_widget.composer_is_visible() =>
+            {
+                self.edit_composer_in_external_editor(tui).await;
             }
-            // Esc primes/advances backtracking only in normal (not working) mode
-            // with an empty composer. In any other state, forward Esc so the
-            // active UI (e.g. status indicator, modals, popups) handles it.
+            // The backtrack key primes/advances backtracking only in normal
+            // (not working) mode with an empty composer. In any other state,
+            // forward it so the active UI (e.g. status indicator, modals,
+            // popups) handles it.
             KeyEvent {
-                code: KeyCode::Esc,
                 kind: KeyEventKind::Press | KeyEventKind::Repeat,
                 ..
-            } => {
+            } if keymap::matches(self.config.tui_keymap.backtrack, &key_event) => {
                 if self.chat_widget.is_normal_backtrack_mode()
                     && self.chat_widget.composer_is_empty()
                 {
@@ -405,10 +608,10 @@ impl App {
                 kind: KeyEventKind::Press | KeyEventKind::Repeat,
                 ..
             } => {
-                // Any non-Esc key press should cancel a primed backtrack.
+                // Any other key press should cancel a primed backtrack.
                 // This avoids stale "Esc-primed" state after the user starts typing
                 // (even if they later backspace to empty).
-                if key_event.code != KeyCode::Esc && self.backtrack.primed {
+                if self.backtrack.primed {
                     self.reset_backtrack_state();
                 }
                 self.chat_widget.handle_key_event(key_event);
@@ -420,12 +623,26 @@ impl App {
     }
 }
 
+/// Copy the settings that can only change through `config.toml` from a
+/// reloaded config.
+fn apply_file_only_settings(config: &mut Config, reloaded: &Config) {
+    config.model_reasoning_summary = reloaded.model_reasoning_summary;
+    config.tui_notifications = reloaded.tui_notifications.clone();
+    config.tui_keymap = reloaded.tui_keymap.clone();
+    config.tui_theme = reloaded.tui_theme;
+    config.tui_colors = reloaded.tui_colors;
+    config.file_opener = reloaded.file_opener;
+    config.hide_agent_reasoning = reloaded.hide_agent_reasoning;
+    config.disable_paste_burst = reloaded.disable_paste_burst;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::app_backtrack::BacktrackState;
     use crate::chatwidget::tests::make_chatwidget_manual_with_sender;
     use crate::file_search::FileSearchManager;
+    use crate::file_search::SearchKind;
     use codex_core::AuthManager;
     use codex_core::CodexAuth;
     use codex_core::ConversationManager;
@@ -442,7 +659,13 @@ mod tests {
         )));
         let auth_manager =
             AuthManager::from_auth_for_testing(CodexAuth::from_api_key("Test API Key"));
-        let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
+        let file_search =
+            FileSearchManager::new(config.cwd.clone(), SearchKind::Files, app_event_tx.clone());
+        let content_search = FileSearchManager::new(
+            config.cwd.clone(),
+            SearchKind::Content,
+            app_event_tx.clone(),
+        );
 
         App {
             server,
@@ -452,13 +675,16 @@ mod tests {
             config,
             active_profile: None,
             file_search,
+            content_search,
             transcript_lines: Vec::<Line<'static>>::new(),
+            transcript_cells: Vec::new(),
             overlay: None,
             deferred_history_lines: Vec::new(),
             has_emitted_history_lines: false,
             enhanced_keys_supported: false,
             commit_anim_running: Arc::new(AtomicBool::new(false)),
             backtrack: BacktrackState::default(),
+            config_reloader: None,
         }
     }
 
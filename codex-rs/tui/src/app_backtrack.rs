@@ -2,6 +2,7 @@ use std::path::PathBuf;
 
 use crate::app::App;
 use crate::backtrack_helpers;
+use crate::keymap;
 use crate::pager_overlay::Overlay;
 use crate::tui;
 use crate::tui::TuiEvent;
@@ -29,21 +30,44 @@ pub(crate) struct BacktrackState {
 
 impl App {
     /// Route overlay events when transcript overlay is active.
-    /// - If backtrack preview is active: Esc steps selection; Enter confirms.
-    /// - Otherwise: Esc begins preview; all other events forward to overlay.
+    /// - While a search query is being typed, every event goes to the overlay.
+    /// - If backtrack preview is active: the backtrack key (Esc by default)
+    ///   steps selection; Enter confirms.
+    /// - Otherwise: the backtrack key begins preview, Enter begins it at the
+    ///   cell reached by search or a cell jump; all other events forward to
+    ///   overlay.
     ///   interactions (Esc to step target, Enter to confirm) and overlay lifecycle.
     pub(crate) async fn handle_backtrack_overlay_event(
         &mut self,
         tui: &mut tui::Tui,
         event: TuiEvent,
     ) -> Result<bool> {
-        if self.backtrack.overlay_preview_active {
-            match event {
-                TuiEvent::Key(KeyEvent {
-                    code: KeyCode::Esc,
+        let backtrack_key = self.config.tui_keymap.backtrack;
+        let is_backtrack_key = |event: &TuiEvent| {
+            matches!(
+                event,
+                TuiEvent::Key(key_event @ KeyEvent {
                     kind: KeyEventKind::Press | KeyEventKind::Repeat,
                     ..
-                }) => {
+                }) if keymap::matches(backtrack_key, key_event)
+            )
+        };
+        // The diff viewer and an in-progress search own every key.
+        let editing_search = match &self.overlay {
+            Some(Overlay::Transcript(t)) => t.is_editing_search(),
+            Some(Overlay::Diff(_)) => true,
+            _ => false,
+        };
+        let selected_line = match &self.overlay {
+            Some(Overlay::Transcript(t)) => t.selected_line(),
+            _ => None,
+        };
+        if editing_search {
+            self.overlay_forward_event(tui, event)?;
+            Ok(true)
+        } else if self.backtrack.overlay_preview_active {
+            match event {
+                _ if is_backtrack_key(&event) => {
                     self.overlay_step_backtrack(tui, event)?;
                     Ok(true)
                 }
@@ -61,14 +85,27 @@ impl App {
                     Ok(true)
                 }
             }
-        } else if let TuiEvent::Key(KeyEvent {
-            code: KeyCode::Esc,
-            kind: KeyEventKind::Press | KeyEventKind::Repeat,
-            ..
-        }) = event
+        } else if is_backtrack_key(&event) {
+            // First backtrack key press in transcript overlay: begin backtrack
+            // preview at latest user message.
+            self.begin_overlay_backtrack_preview(tui, 1);
+            Ok(true)
+        } else if let (
+            TuiEvent::Key(KeyEvent {
+                code: KeyCode::Enter,
+                kind: KeyEventKind::Press,
+                ..
+            }),
+            Some(line),
+        ) = (&event, selected_line)
         {
-            // First Esc in transcript overlay: begin backtrack preview at latest user message.
-            self.begin_overlay_backtrack_preview(tui);
+            // Enter on a found cell: preview the user message that started
+            // its turn so it can be edited and the conversation forked there.
+            if let Some(nth) =
+                backtrack_helpers::nth_last_user_at_or_before(&self.transcript_lines, line)
+            {
+                self.begin_overlay_backtrack_preview(tui, nth);
+            }
             Ok(true)
         } else {
             // Not in backtrack mode: forward events to the overlay widget.
@@ -107,7 +144,11 @@ impl App {
     /// Open transcript overlay (enters alternate screen and shows full transcript).
     pub(crate) fn open_transcript_overlay(&mut self, tui: &mut tui::Tui) {
         let _ = tui.enter_alt_screen();
-        self.overlay = Some(Overlay::new_transcript(self.transcript_lines.clone()));
+        self.overlay = Some(Overlay::new_transcript(
+            self.transcript_lines.clone(),
+            self.transcript_cells.clone(),
+            self.config.tui_keymap.clone(),
+        ));
         tui.frame_requester().schedule_frame();
     }
 
@@ -152,12 +193,17 @@ impl App {
         self.step_backtrack_and_highlight(tui);
     }
 
-    /// When overlay is already open, begin preview mode and select latest user message.
-    fn begin_overlay_backtrack_preview(&mut self, tui: &mut tui::Tui) {
+    /// When overlay is already open, begin preview mode and select the Nth
+    /// last user message.
+    fn begin_overlay_backtrack_preview(&mut self, tui: &mut tui::Tui, nth: usize) {
         self.backtrack.primed = true;
         self.backtrack.base_id = self.chat_widget.conversation_id();
         self.backtrack.overlay_preview_active = true;
-        let sel = self.compute_backtrack_selection(tui, 1);
+        // The preview scrolls by position in the full transcript.
+        if let Some(Overlay::Transcript(t)) = &mut self.overlay {
+            t.clear_filter();
+        }
+        let sel = self.compute_backtrack_selection(tui, nth);
         self.apply_backtrack_selection(sel);
         tui.frame_requester().schedule_frame();
     }
@@ -347,8 +393,10 @@ impl App {
             backtrack_helpers::find_nth_last_user_header_index(&self.transcript_lines, drop_count)
         {
             self.transcript_lines.truncate(cut_idx);
+            self.transcript_cells.retain(|cell| cell.start < cut_idx);
         } else {
             self.transcript_lines.clear();
+            self.transcript_cells.clear();
         }
     }
 }
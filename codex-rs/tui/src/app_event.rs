@@ -1,7 +1,9 @@
 use codex_core::protocol::ConversationPathResponseEvent;
 use codex_core::protocol::Event;
+use codex_file_search::ContentMatch;
 use codex_file_search::FileMatch;
 
+use crate::get_git_diff::HunkAction;
 use crate::history_cell::HistoryCell;
 
 use codex_core::protocol::AskForApproval;
@@ -36,9 +38,42 @@ pub(crate) enum AppEvent {
         matches: Vec<FileMatch>,
     },
 
+    /// Kick off an asynchronous search of file contents for the given query
+    /// (text after the `#`). Debounced and cancelled like `StartFileSearch`.
+    StartContentSearch(String),
+
+    /// Result of a completed asynchronous content search.
+    ContentSearchResult {
+        query: String,
+        matches: Vec<ContentMatch>,
+    },
+
+    /// Estimated tokens for an `@` mention in the composer, or `None` when
+    /// the mention does not resolve.
+    MentionEstimate {
+        token: String,
+        tokens: Option<usize>,
+    },
+
     /// Result of computing a `/diff` command.
     DiffResult(String),
 
+    /// Stage or revert one hunk selected in the diff viewer, then refresh
+    /// the diff.
+    ApplyDiffHunk {
+        patch: String,
+        action: HunkAction,
+    },
+
+    /// Outcome of an `ApplyDiffHunk`; `error` is `None` on success.
+    DiffHunkApplied {
+        action: HunkAction,
+        error: Option<String>,
+    },
+
+    /// Submit `text` as a user message, e.g. a comment on a diff hunk.
+    SubmitUserMessage(String),
+
     InsertHistoryCell(Box<dyn HistoryCell>),
 
     StartCommitAnimation,
@@ -65,4 +100,7 @@ pub(crate) enum AppEvent {
 
     /// Forwarded conversation history snapshot from the current conversation.
     ConversationHistory(ConversationPathResponseEvent),
+
+    /// `config.toml` or the project config changed on disk.
+    ConfigFileChanged,
 }
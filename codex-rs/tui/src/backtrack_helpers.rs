@@ -23,13 +23,7 @@ pub(crate) fn find_nth_last_user_header_index(lines: &[Line<'_>], n: usize) -> O
     }
     let mut found = 0usize;
     for (idx, line) in lines.iter().enumerate().rev() {
-        let content: String = line
-            .spans
-            .iter()
-            .map(|s| s.content.as_ref())
-            .collect::<Vec<_>>()
-            .join("");
-        if content.trim() == "user" {
+        if is_user_header(line) {
             found += 1;
             if found == n {
                 return Some(idx);
@@ -39,6 +33,31 @@ pub(crate) fn find_nth_last_user_header_index(lines: &[Line<'_>], n: usize) -> O
     None
 }
 
+/// Return `n` such that the Nth last user message is the last one starting at
+/// or before `line_idx`, i.e. the message that began the turn containing it.
+pub(crate) fn nth_last_user_at_or_before(lines: &[Line<'_>], line_idx: usize) -> Option<usize> {
+    let mut found = 0usize;
+    for (idx, line) in lines.iter().enumerate().rev() {
+        if is_user_header(line) {
+            found += 1;
+            if idx <= line_idx {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+fn is_user_header(line: &Line<'_>) -> bool {
+    let content: String = line
+        .spans
+        .iter()
+        .map(|s| s.content.as_ref())
+        .collect::<Vec<_>>()
+        .join("");
+    content.trim() == "user"
+}
+
 /// Normalize a requested backtrack step `n` against the available user messages.
 /// - Returns `0` if there are no user messages.
 /// - Returns `n` if the Nth last user message exists.
@@ -150,4 +169,18 @@ mod tests {
         let lines = transcript_with_users(3);
         assert_eq!(normalize_backtrack_n(&lines, 2), 2);
     }
+
+    #[test]
+    fn nth_last_user_for_line_finds_enclosing_turn() {
+        // Lines 0..3, 3..6 and 6..9 belong to the three user messages.
+        let lines = transcript_with_users(3);
+        assert_eq!(nth_last_user_at_or_before(&lines, 8), Some(1));
+        assert_eq!(nth_last_user_at_or_before(&lines, 6), Some(1));
+        assert_eq!(nth_last_user_at_or_before(&lines, 4), Some(2));
+        assert_eq!(nth_last_user_at_or_before(&lines, 0), Some(3));
+        assert_eq!(
+            nth_last_user_at_or_before(&transcript_with_users(0), 0),
+            None
+        );
+    }
 }
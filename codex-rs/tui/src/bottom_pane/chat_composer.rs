@@ -1,3 +1,4 @@
+use codex_core::keymap::Keymap;
 use codex_core::protocol::TokenUsageInfo;
 use codex_protocol::num_format::format_si_suffix;
 use crossterm::event::KeyCode;
@@ -24,11 +25,14 @@ use ratatui::widgets::WidgetRef;
 use super::chat_composer_history::ChatComposerHistory;
 use super::command_popup::CommandItem;
 use super::command_popup::CommandPopup;
+use super::content_search_popup::ContentSearchPopup;
 use super::file_search_popup::FileSearchPopup;
 use super::paste_burst::CharDecision;
 use super::paste_burst::PasteBurst;
 use crate::bottom_pane::paste_burst::FlushResult;
 use crate::slash_command::SlashCommand;
+use codex_core::custom_prompts::expand_custom_prompt;
+use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::custom_prompts::CustomPrompt;
 
 use crate::app_event::AppEvent;
@@ -38,7 +42,12 @@ use crate::bottom_pane::textarea::TextAreaState;
 use crate::clipboard_paste::normalize_pasted_path;
 use crate::clipboard_paste::pasted_image_format;
 use crate::key_hint;
+use crate::keymap;
+use crate::mentions::Attachment;
+use crate::mentions::MentionResolver;
+use crate::mentions::find_mentions;
 use crate::ui_consts::LIVE_PREFIX_COLS;
+use codex_file_search::ContentMatch;
 use codex_file_search::FileMatch;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -51,10 +60,26 @@ use std::time::Instant;
 /// placeholder in the UI.
 const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
 
+/// The text typed after the `/name` token of a slash command.
+fn prompt_arguments(text: &str) -> &str {
+    text.trim_start()
+        .strip_prefix('/')
+        .and_then(|command| command.split_once(char::is_whitespace))
+        .map(|(_, args)| args)
+        .unwrap_or_default()
+}
+
 /// Result returned when the user interacts with the text area.
 #[derive(Debug, PartialEq)]
 pub enum InputResult {
     Submitted(String),
+    /// A custom prompt whose front matter picks the model or reasoning effort
+    /// for the turn it starts.
+    SubmittedWithOverrides {
+        text: String,
+        model: Option<String>,
+        effort: Option<ReasoningEffortConfig>,
+    },
     Command(SlashCommand),
     None,
 }
@@ -75,6 +100,7 @@ pub(crate) struct ChatComposer {
     esc_backtrack_hint: bool,
     use_shift_enter_hint: bool,
     dismissed_file_popup_token: Option<String>,
+    dismissed_content_popup_token: Option<String>,
     current_file_query: Option<String>,
     pending_pastes: Vec<(String, String)>,
     token_usage_info: Option<TokenUsageInfo>,
@@ -87,6 +113,22 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    keymap: Keymap,
+    /// Resolves `@` mentions to attachments; unset when there is no
+    /// workspace to resolve them against.
+    mentions: Option<MentionResolver>,
+    /// Estimated tokens per mention in the composer, shown in the footer.
+    /// Mentions are resolved on a blocking thread; see
+    /// [`ChatComposer::request_mention_estimates`].
+    mention_estimates: HashMap<String, MentionEstimate>,
+}
+
+/// Footer estimate for one `@` mention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MentionEstimate {
+    Pending,
+    Tokens(usize),
+    Unresolved,
 }
 
 /// Popup state – at most one can be visible at any time.
@@ -94,8 +136,13 @@ enum ActivePopup {
     None,
     Command(CommandPopup),
     File(FileSearchPopup),
+    Content(ContentSearchPopup),
 }
 
+/// Lines of context around a `#` content-search match included in the
+/// inserted `@path#L` mention.
+const CONTENT_MENTION_CONTEXT_LINES: u64 = 10;
+
 const FOOTER_HINT_HEIGHT: u16 = 1;
 const FOOTER_SPACING_HEIGHT: u16 = 1;
 const FOOTER_HEIGHT_WITH_HINT: u16 = FOOTER_HINT_HEIGHT + FOOTER_SPACING_HEIGHT;
@@ -120,6 +167,7 @@ impl ChatComposer {
             esc_backtrack_hint: false,
             use_shift_enter_hint,
             dismissed_file_popup_token: None,
+            dismissed_content_popup_token: None,
             current_file_query: None,
             pending_pastes: Vec::new(),
             token_usage_info: None,
@@ -130,6 +178,9 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            keymap: Keymap::default(),
+            mentions: None,
+            mention_estimates: HashMap::new(),
         };
         // Apply configuration via the setter to keep side-effects centralized.
         this.set_disable_paste_burst(disable_paste_burst);
@@ -144,6 +195,7 @@ impl ChatComposer {
                 ActivePopup::None => FOOTER_HEIGHT_WITH_HINT,
                 ActivePopup::Command(c) => c.calculate_required_height(width),
                 ActivePopup::File(c) => c.calculate_required_height(),
+                ActivePopup::Content(c) => c.calculate_required_height(),
             }
     }
 
@@ -153,6 +205,7 @@ impl ChatComposer {
                 Constraint::Max(popup.calculate_required_height(area.width))
             }
             ActivePopup::File(popup) => Constraint::Max(popup.calculate_required_height()),
+            ActivePopup::Content(popup) => Constraint::Max(popup.calculate_required_height()),
             ActivePopup::None => Constraint::Max(FOOTER_HEIGHT_WITH_HINT),
         };
         let [textarea_rect, _] =
@@ -216,6 +269,7 @@ impl ChatComposer {
         // Keep popup sync consistent with key handling: prefer slash popup; only
         // sync file popup when slash popup is NOT active.
         self.sync_command_popup();
+        self.request_mention_estimates();
         if matches!(self.active_popup, ActivePopup::Command(_)) {
             self.dismissed_file_popup_token = None;
         } else {
@@ -251,6 +305,81 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    /// Resolve `@` mentions against `root`.
+    pub(crate) fn set_mention_root(&mut self, root: PathBuf) {
+        self.mentions = Some(MentionResolver::new(root));
+        self.mention_estimates.clear();
+        self.request_mention_estimates();
+    }
+
+    /// Attachments for the mentions in `text`, plus a message for each
+    /// mention that could not be attached.
+    pub(crate) fn resolve_mentions(&mut self, text: &str) -> (Vec<Attachment>, Vec<String>) {
+        self.mention_estimates.clear();
+        match &self.mentions {
+            Some(resolver) => resolver.resolve_all(text),
+            None => (Vec::new(), Vec::new()),
+        }
+    }
+
+    /// Start estimating the mentions in the composer that have no estimate
+    /// yet, and forget the ones that were removed. Resolving reads files and
+    /// may build the symbol index, so it runs on a blocking thread and the
+    /// result comes back as [`AppEvent::MentionEstimate`].
+    fn request_mention_estimates(&mut self) {
+        let Some(resolver) = &self.mentions else {
+            return;
+        };
+        let mentions = find_mentions(self.textarea.text());
+        self.mention_estimates
+            .retain(|token, _| mentions.iter().any(|(t, _)| t == token));
+        for (token, mention) in mentions {
+            if self.mention_estimates.contains_key(&token) {
+                continue;
+            }
+            self.mention_estimates
+                .insert(token.clone(), MentionEstimate::Pending);
+            let resolver = resolver.clone();
+            let tx = self.app_event_tx.clone();
+            tokio::task::spawn_blocking(move || {
+                let tokens = resolver
+                    .resolve(&token, &mention)
+                    .ok()
+                    .map(|attachments| attachments.iter().map(Attachment::estimated_tokens).sum());
+                tx.send(AppEvent::MentionEstimate { token, tokens });
+            });
+        }
+    }
+
+    /// Record the estimate for `token` if it is still in the composer.
+    pub(crate) fn on_mention_estimate(&mut self, token: String, tokens: Option<usize>) {
+        if let Some(estimate) = self.mention_estimates.get_mut(&token) {
+            *estimate = match tokens {
+                Some(tokens) => MentionEstimate::Tokens(tokens),
+                None => MentionEstimate::Unresolved,
+            };
+        }
+    }
+
+    /// Number of resolved mentions in the composer and their estimated
+    /// token count. Only reads the estimates computed so far.
+    fn mention_summary(&self) -> Option<(usize, usize)> {
+        self.mentions.as_ref()?;
+        let mut count = 0;
+        let mut tokens = 0;
+        for (token, _) in find_mentions(self.textarea.text()) {
+            if let Some(MentionEstimate::Tokens(estimate)) = self.mention_estimates.get(&token) {
+                count += 1;
+                tokens += estimate;
+            }
+        }
+        (count > 0).then_some((count, tokens))
+    }
+
     /// Replace the entire composer content with `text` and reset cursor.
     pub(crate) fn set_text_content(&mut self, text: String) {
         // Clear any existing content, placeholders, and attachments first.
@@ -260,15 +389,54 @@ impl ChatComposer {
         self.textarea.set_text(&text);
         self.textarea.set_cursor(0);
         self.sync_command_popup();
+        self.request_mention_estimates();
         self.sync_file_search_popup();
     }
 
     /// Get the current composer text.
-    #[cfg(test)]
     pub(crate) fn current_text(&self) -> String {
         self.textarea.text().to_string()
     }
 
+    /// Replace the composer text with `text` as edited outside the composer
+    /// (e.g. in `$EDITOR`). Paste and image placeholders that survived the
+    /// edit become atomic elements again and keep their content; the others
+    /// are dropped.
+    pub(crate) fn set_text_preserving_elements(&mut self, text: &str) {
+        let placeholders: Vec<String> = self
+            .pending_pastes
+            .iter()
+            .map(|(placeholder, _)| placeholder.clone())
+            .chain(
+                self.attached_images
+                    .iter()
+                    .map(|img| img.placeholder.clone()),
+            )
+            .collect();
+
+        self.textarea.set_text("");
+        let mut rest = text;
+        while let Some((idx, placeholder)) = placeholders
+            .iter()
+            .filter_map(|placeholder| {
+                rest.find(placeholder.as_str())
+                    .map(|idx| (idx, placeholder))
+            })
+            .min_by_key(|(idx, _)| *idx)
+        {
+            self.textarea.insert_str(&rest[..idx]);
+            self.textarea.insert_element(placeholder);
+            rest = &rest[idx + placeholder.len()..];
+        }
+        self.textarea.insert_str(rest);
+        self.textarea.set_cursor(self.textarea.text().len());
+
+        self.prune_removed_elements();
+        self.sync_command_popup();
+        self.request_mention_estimates();
+        self.sync_file_search_popup();
+    }
+
     /// Attempt to start a burst by retro-capturing recent chars before the cursor.
     pub fn attach_image(&mut self, path: PathBuf, width: u32, height: u32, format_label: &str) {
         let placeholder = format!("[image {width}x{height} {format_label}]");
@@ -313,6 +481,20 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn on_content_search_result(&mut self, query: String, matches: Vec<ContentMatch>) {
+        // Only apply if user is still editing a token starting with `query`.
+        let Some(current_token) = Self::current_hash_token(&self.textarea) else {
+            return;
+        };
+        if !current_token.starts_with(&query) {
+            return;
+        }
+
+        if let ActivePopup::Content(popup) = &mut self.active_popup {
+            popup.set_matches(&query, matches);
+        }
+    }
+
     pub fn set_ctrl_c_quit_hint(&mut self, show: bool, has_focus: bool) {
         self.ctrl_c_quit_hint = show;
         self.set_has_focus(has_focus);
@@ -321,6 +503,7 @@ impl ChatComposer {
     pub(crate) fn insert_str(&mut self, text: &str) {
         self.textarea.insert_str(text);
         self.sync_command_popup();
+        self.request_mention_estimates();
         self.sync_file_search_popup();
     }
 
@@ -329,11 +512,13 @@ impl ChatComposer {
         let result = match &mut self.active_popup {
             ActivePopup::Command(_) => self.handle_key_event_with_slash_popup(key_event),
             ActivePopup::File(_) => self.handle_key_event_with_file_popup(key_event),
+            ActivePopup::Content(_) => self.handle_key_event_with_content_popup(key_event),
             ActivePopup::None => self.handle_key_event_without_popup(key_event),
         };
 
         // Update (or hide/show) popup after processing the key.
         self.sync_command_popup();
+        self.request_mention_estimates();
         if matches!(self.active_popup, ActivePopup::Command(_)) {
             self.dismissed_file_popup_token = None;
         } else {
@@ -375,9 +560,7 @@ impl ChatComposer {
                 self.active_popup = ActivePopup::None;
                 (InputResult::None, true)
             }
-            KeyEvent {
-                code: KeyCode::Tab, ..
-            } => {
+            key_event if keymap::matches(self.keymap.accept_popup, &key_event) => {
                 // Ensure popup filtering/selection reflects the latest composer text
                 // before applying completion.
                 let first_line = self.textarea.text().lines().next().unwrap_or("");
@@ -410,19 +593,20 @@ impl ChatComposer {
                 }
                 (InputResult::None, true)
             }
-            KeyEvent {
-                code: KeyCode::Enter,
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => {
+            key_event if keymap::matches(self.keymap.submit, &key_event) => {
                 if let Some(sel) = popup.selected_item() {
+                    let mut text = self.textarea.text().to_string();
+                    for (placeholder, actual) in &self.pending_pastes {
+                        if text.contains(placeholder) {
+                            text = text.replace(placeholder, actual);
+                        }
+                    }
                     // Clear textarea so no residual text remains.
                     self.textarea.set_text("");
+                    self.pending_pastes.clear();
                     // Capture any needed data from popup before clearing it.
-                    let prompt_content = match sel {
-                        CommandItem::UserPrompt(idx) => {
-                            popup.prompt_content(idx).map(|s| s.to_string())
-                        }
+                    let prompt = match sel {
+                        CommandItem::UserPrompt(idx) => popup.prompt(idx).cloned(),
                         _ => None,
                     };
                     // Hide popup since an action has been dispatched.
@@ -433,10 +617,21 @@ impl ChatComposer {
                             return (InputResult::Command(cmd), true);
                         }
                         CommandItem::UserPrompt(_) => {
-                            if let Some(contents) = prompt_content {
+                            let Some(prompt) = prompt else {
+                                return (InputResult::None, true);
+                            };
+                            let contents = expand_custom_prompt(&prompt, prompt_arguments(&text));
+                            if prompt.model.is_none() && prompt.effort.is_none() {
                                 return (InputResult::Submitted(contents), true);
                             }
-                            return (InputResult::None, true);
+                            return (
+                                InputResult::SubmittedWithOverrides {
+                                    text: contents,
+                                    model: prompt.model,
+                                    effort: prompt.effort,
+                                },
+                                true,
+                            );
                         }
                     }
                 }
@@ -502,14 +697,10 @@ impl ChatComposer {
                 self.active_popup = ActivePopup::None;
                 (InputResult::None, true)
             }
-            KeyEvent {
-                code: KeyCode::Tab, ..
-            }
-            | KeyEvent {
-                code: KeyCode::Enter,
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => {
+            key_event
+                if keymap::matches(self.keymap.accept_popup, &key_event)
+                    || keymap::matches(self.keymap.submit, &key_event) =>
+            {
                 let Some(sel) = popup.selected_match() else {
                     self.active_popup = ActivePopup::None;
                     return (InputResult::None, true);
@@ -575,6 +766,55 @@ impl ChatComposer {
         }
     }
 
+    /// Handle key events when the `#` content-search popup is visible.
+    fn handle_key_event_with_content_popup(&mut self, key_event: KeyEvent) -> (InputResult, bool) {
+        let ActivePopup::Content(popup) = &mut self.active_popup else {
+            unreachable!();
+        };
+
+        match key_event {
+            KeyEvent {
+                code: KeyCode::Up, ..
+            } => {
+                popup.move_up();
+                (InputResult::None, true)
+            }
+            KeyEvent {
+                code: KeyCode::Down,
+                ..
+            } => {
+                popup.move_down();
+                (InputResult::None, true)
+            }
+            KeyEvent {
+                code: KeyCode::Esc, ..
+            } => {
+                // Hide popup without modifying text, remember token to avoid immediate reopen.
+                if let Some(tok) = Self::current_hash_token(&self.textarea) {
+                    self.dismissed_content_popup_token = Some(tok);
+                }
+                self.active_popup = ActivePopup::None;
+                (InputResult::None, true)
+            }
+            key_event
+                if keymap::matches(self.keymap.accept_popup, &key_event)
+                    || keymap::matches(self.keymap.submit, &key_event) =>
+            {
+                if let Some(sel) = popup.selected_match() {
+                    // Mention the matching line with some surrounding context.
+                    let line = sel.line_number;
+                    let start = line.saturating_sub(CONTENT_MENTION_CONTEXT_LINES).max(1);
+                    let end = line + CONTENT_MENTION_CONTEXT_LINES;
+                    let mention = format!("@{}#L{start}-{end}", sel.path);
+                    self.replace_current_token(&mention);
+                }
+                self.active_popup = ActivePopup::None;
+                (InputResult::None, true)
+            }
+            input => self.handle_input_basic(input),
+        }
+    }
+
     fn is_image_path(path: &str) -> bool {
         let lower = path.to_ascii_lowercase();
         lower.ends_with(".png") || lower.ends_with(".jpg") || lower.ends_with(".jpeg")
@@ -582,7 +822,22 @@ impl ChatComposer {
 
     /// Extract the `@token` that the cursor is currently positioned on, if any.
     ///
-    /// The returned string **does not** include the leading `@`.
+    /// The returned string **does not** include the leading `@`. See
+    /// `current_prefixed_token` for how the token is located.
+    fn current_at_token(textarea: &TextArea) -> Option<String> {
+        Self::current_prefixed_token(textarea, '@')
+    }
+
+    /// Like `current_at_token`, but for the `#token` that triggers a
+    /// content search.
+    fn current_hash_token(textarea: &TextArea) -> Option<String> {
+        Self::current_prefixed_token(textarea, '#')
+    }
+
+    /// Extract the token starting with `prefix` that the cursor is currently
+    /// positioned on, if any.
+    ///
+    /// The returned string **does not** include the leading `prefix`.
     ///
     /// Behavior:
     /// - The cursor may be anywhere *inside* the token (including on the
@@ -591,7 +846,7 @@ impl ChatComposer {
     /// - If the token under the cursor starts with `@`, that token is
     ///   returned without the leading `@`. This includes the case where the
     ///   token is just "@" (empty query), which is used to trigger a UI hint
-    fn current_at_token(textarea: &TextArea) -> Option<String> {
+    fn current_prefixed_token(textarea: &TextArea, prefix: char) -> Option<String> {
         let cursor_offset = textarea.cursor();
         let text = textarea.text();
 
@@ -660,36 +915,44 @@ impl ChatComposer {
             None
         };
 
-        let left_at = token_left
-            .filter(|t| t.starts_with('@'))
-            .map(|t| t[1..].to_string());
-        let right_at = token_right
-            .filter(|t| t.starts_with('@'))
-            .map(|t| t[1..].to_string());
+        let strip_prefix = |t: &str| t.strip_prefix(prefix).map(str::to_string);
+        let left_at = token_left.and_then(strip_prefix);
+        let right_at = token_right.and_then(strip_prefix);
 
         if at_whitespace {
             if right_at.is_some() {
                 return right_at;
             }
-            if token_left.is_some_and(|t| t == "@") {
+            if token_left.is_some_and(|t| t.strip_prefix(prefix) == Some("")) {
                 return None;
             }
             return left_at;
         }
-        if after_cursor.starts_with('@') {
+        if after_cursor.starts_with(prefix) {
             return right_at.or(left_at);
         }
         left_at.or(right_at)
     }
 
-    /// Replace the active `@token` (the one under the cursor) with `path`.
+    /// Replace the active `@token` (the one under the cursor) with an
+    /// `@path` mention, keeping any `#L` line range already typed.
     ///
     /// The algorithm mirrors `current_at_token` so replacement works no matter
     /// where the cursor is within the token and regardless of how many
     /// `@tokens` exist in the line.
     fn insert_selected_path(&mut self, path: &str) {
-        let cursor_offset = self.textarea.cursor();
         let text = self.textarea.text();
+        let (start_idx, end_idx) = Self::current_token_range(text, self.textarea.cursor());
+        let range = text[start_idx..end_idx]
+            .find('#')
+            .map(|idx| &text[start_idx + idx..end_idx])
+            .unwrap_or_default();
+        let mention = format!("@{path}{range}");
+        self.replace_current_token(&mention);
+    }
+
+    /// Byte range of the whitespace-delimited token around `cursor_offset`.
+    fn current_token_range(text: &str, cursor_offset: usize) -> (usize, usize) {
         // Clamp to a valid char boundary to avoid panics when slicing.
         let safe_cursor = Self::clamp_to_char_boundary(text, cursor_offset);
 
@@ -708,18 +971,27 @@ impl ChatComposer {
             .find(|(_, c)| c.is_whitespace())
             .map(|(idx, _)| idx)
             .unwrap_or(after_cursor.len());
-        let end_idx = safe_cursor + end_rel_idx;
+        (start_idx, safe_cursor + end_rel_idx)
+    }
+
+    /// Replace the token under the cursor with `replacement` followed by a
+    /// space, leaving the cursor after the space.
+    fn replace_current_token(&mut self, replacement: &str) {
+        let text = self.textarea.text();
+        let (start_idx, end_idx) = Self::current_token_range(text, self.textarea.cursor());
 
-        // Replace the slice `[start_idx, end_idx)` with the chosen path and a trailing space.
+        // Replace the slice `[start_idx, end_idx)` with the replacement and a trailing space.
         let mut new_text =
-            String::with_capacity(text.len() - (end_idx - start_idx) + path.len() + 1);
+            String::with_capacity(text.len() - (end_idx - start_idx) + replacement.len() + 1);
         new_text.push_str(&text[..start_idx]);
-        new_text.push_str(path);
+        new_text.push_str(replacement);
         new_text.push(' ');
         new_text.push_str(&text[end_idx..]);
 
         self.textarea.set_text(&new_text);
-        let new_cursor = start_idx.saturating_add(path.len()).saturating_add(1);
+        let new_cursor = start_idx
+            .saturating_add(replacement.len())
+            .saturating_add(1);
         self.textarea.set_cursor(new_cursor);
     }
 
@@ -740,18 +1012,18 @@ impl ChatComposer {
             // empty or when the cursor is at the correct position, to avoid
             // interfering with normal cursor movement.
             // -------------------------------------------------------------
-            KeyEvent {
-                code: KeyCode::Up | KeyCode::Down,
-                ..
-            } => {
+            key_event
+                if keymap::matches(self.keymap.history_prev, &key_event)
+                    || keymap::matches(self.keymap.history_next, &key_event) =>
+            {
                 if self
                     .history
                     .should_handle_navigation(self.textarea.text(), self.textarea.cursor())
                 {
-                    let replace_text = match key_event.code {
-                        KeyCode::Up => self.history.navigate_up(&self.app_event_tx),
-                        KeyCode::Down => self.history.navigate_down(&self.app_event_tx),
-                        _ => unreachable!(),
+                    let replace_text = if keymap::matches(self.keymap.history_prev, &key_event) {
+                        self.history.navigate_up(&self.app_event_tx)
+                    } else {
+                        self.history.navigate_down(&self.app_event_tx)
                     };
                     if let Some(text) = replace_text {
                         self.textarea.set_text(&text);
@@ -761,11 +1033,12 @@ impl ChatComposer {
                 }
                 self.handle_input_basic(key_event)
             }
-            KeyEvent {
-                code: KeyCode::Enter,
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => {
+            key_event if keymap::matches(self.keymap.newline, &key_event) => {
+                // Route through the regular Enter handling so paste bursts and
+                // placeholders behave the same as with the default binding.
+                self.handle_input_basic(KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT))
+            }
+            key_event if keymap::matches(self.keymap.submit, &key_event) => {
                 // If we're in a paste-like burst capture, treat Enter as part of the burst
                 // and accumulate it rather than submitting or inserting immediately.
                 // Do not treat Enter as paste inside a slash-command context.
@@ -850,6 +1123,7 @@ impl ChatComposer {
                 // Keep popup sync consistent with key handling: prefer slash popup; only
                 // sync file popup when slash popup is NOT active.
                 self.sync_command_popup();
+                self.request_mention_estimates();
                 if matches!(self.active_popup, ActivePopup::Command(_)) {
                     self.dismissed_file_popup_token = None;
                 } else {
@@ -946,7 +1220,6 @@ impl ChatComposer {
 
         // Normal input handling
         self.textarea.input(input);
-        let text_after = self.textarea.text();
 
         // Update paste-burst heuristic for plain Char (no Ctrl/Alt) events.
         let crossterm::event::KeyEvent {
@@ -969,9 +1242,17 @@ impl ChatComposer {
             }
         }
 
-        // Check if any placeholders were removed and remove their corresponding pending pastes
+        self.prune_removed_elements();
+
+        (InputResult::None, true)
+    }
+
+    /// Drop pending pastes and attached images whose placeholders are no
+    /// longer in the text.
+    fn prune_removed_elements(&mut self) {
+        let text = self.textarea.text();
         self.pending_pastes
-            .retain(|(placeholder, _)| text_after.contains(placeholder));
+            .retain(|(placeholder, _)| text.contains(placeholder));
 
         // Keep attached images in proportion to how many matching placeholders exist in the text.
         // This handles duplicate placeholders that share the same visible label.
@@ -980,7 +1261,7 @@ impl ChatComposer {
             for img in &self.attached_images {
                 needed
                     .entry(img.placeholder.clone())
-                    .or_insert_with(|| text_after.matches(&img.placeholder).count());
+                    .or_insert_with(|| text.matches(&img.placeholder).count());
             }
 
             let mut used: HashMap<String, usize> = HashMap::new();
@@ -995,8 +1276,6 @@ impl ChatComposer {
             }
             self.attached_images = kept;
         }
-
-        (InputResult::None, true)
     }
 
     /// Attempts to remove an image or paste placeholder if the cursor is at the end of one.
@@ -1175,20 +1454,25 @@ impl ChatComposer {
     /// Synchronize `self.file_search_popup` with the current text in the textarea.
     /// Note this is only called when self.active_popup is NOT Command.
     fn sync_file_search_popup(&mut self) {
-        // Determine if there is an @token underneath the cursor.
-        let query = match Self::current_at_token(&self.textarea) {
-            Some(token) => token,
-            None => {
-                self.active_popup = ActivePopup::None;
+        // Determine if there is an @token underneath the cursor. Symbol
+        // mentions are not paths, and a `#L10-20` suffix is not part of one.
+        let token = match Self::current_at_token(&self.textarea) {
+            Some(token) if !token.starts_with("fn:") && !token.starts_with("type:") => token,
+            _ => {
                 self.dismissed_file_popup_token = None;
+                self.sync_content_search_popup();
                 return;
             }
         };
 
-        // If user dismissed popup for this exact query, don't reopen until text changes.
-        if self.dismissed_file_popup_token.as_ref() == Some(&query) {
+        // If user dismissed popup for this exact token, don't reopen until text changes.
+        if self.dismissed_file_popup_token.as_ref() == Some(&token) {
             return;
         }
+        let query = match token.split_once('#') {
+            Some((path, _)) => path.to_string(),
+            None => token,
+        };
 
         if !query.is_empty() {
             self.app_event_tx
@@ -1218,6 +1502,38 @@ impl ChatComposer {
         self.dismissed_file_popup_token = None;
     }
 
+    /// Synchronize the `#` content-search popup with the token under the
+    /// cursor. Only called when there is no `@` file mention being edited.
+    fn sync_content_search_popup(&mut self) {
+        let Some(query) = Self::current_hash_token(&self.textarea) else {
+            self.active_popup = ActivePopup::None;
+            self.dismissed_content_popup_token = None;
+            return;
+        };
+
+        // If user dismissed popup for this exact token, don't reopen until text changes.
+        if self.dismissed_content_popup_token.as_ref() == Some(&query) {
+            return;
+        }
+
+        if !query.is_empty() {
+            self.app_event_tx
+                .send(AppEvent::StartContentSearch(query.clone()));
+        }
+
+        if !matches!(self.active_popup, ActivePopup::Content(_)) {
+            self.active_popup = ActivePopup::Content(ContentSearchPopup::new());
+        }
+        if let ActivePopup::Content(popup) = &mut self.active_popup {
+            if query.is_empty() {
+                popup.set_empty_prompt();
+            } else {
+                popup.set_query(&query);
+            }
+        }
+        self.dismissed_content_popup_token = None;
+    }
+
     fn set_has_focus(&mut self, has_focus: bool) {
         self.has_focus = has_focus;
     }
@@ -1239,6 +1555,7 @@ impl WidgetRef for ChatComposer {
                 0,
             ),
             ActivePopup::File(popup) => (Constraint::Max(popup.calculate_required_height()), 0),
+            ActivePopup::Content(popup) => (Constraint::Max(popup.calculate_required_height()), 0),
             ActivePopup::None => (
                 Constraint::Length(FOOTER_HEIGHT_WITH_HINT),
                 FOOTER_SPACING_HEIGHT,
@@ -1253,6 +1570,9 @@ impl WidgetRef for ChatComposer {
             ActivePopup::File(popup) => {
                 popup.render_ref(popup_rect, buf);
             }
+            ActivePopup::Content(popup) => {
+                popup.render_ref(popup_rect, buf);
+            }
             ActivePopup::None => {
                 let hint_rect = if hint_spacing > 0 {
                     let [_, hint_rect] = Layout::vertical([
@@ -1277,17 +1597,21 @@ impl WidgetRef for ChatComposer {
                         ctrl_c_followup.into(),
                     ]
                 } else {
-                    let newline_hint_key = if self.use_shift_enter_hint {
-                        key_hint::shift('⏎')
+                    // Ctrl+J always inserts a newline; advertise it when the
+                    // terminal cannot report the default Shift+Enter.
+                    let newline_hint_key = if self.use_shift_enter_hint
+                        || self.keymap.newline != Keymap::default().newline
+                    {
+                        key_hint::chord(self.keymap.newline)
                     } else {
                         key_hint::ctrl('J')
                     };
                     vec![
-                        key_hint::plain('⏎'),
+                        key_hint::chord(self.keymap.submit),
                         " send   ".into(),
                         newline_hint_key,
                         " newline   ".into(),
-                        key_hint::ctrl('T'),
+                        key_hint::chord(self.keymap.open_transcript),
                         " transcript   ".into(),
                         key_hint::ctrl('C'),
                         " quit".into(),
@@ -1296,10 +1620,23 @@ impl WidgetRef for ChatComposer {
 
                 if !self.ctrl_c_quit_hint && self.esc_backtrack_hint {
                     hint.push("   ".into());
-                    hint.push(key_hint::plain("Esc"));
+                    hint.push(key_hint::chord(self.keymap.backtrack));
                     hint.push(" edit prev".into());
                 }
 
+                if let Some((count, tokens)) = self.mention_summary() {
+                    let noun = if count == 1 {
+                        "attachment"
+                    } else {
+                        "attachments"
+                    };
+                    hint.push("   ".into());
+                    hint.push(
+                        format!("{count} {noun} ≈{} tokens", format_si_suffix(tokens as u64))
+                            .cyan(),
+                    );
+                }
+
                 // Append token/context usage info to the footer hints when available.
                 if let Some(token_usage_info) = &self.token_usage_info {
                     let token_usage = &token_usage_info.total_token_usage;
@@ -1494,6 +1831,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_current_hash_token() {
+        let test_cases = vec![
+            ("#parse", 6, Some("parse".to_string()), "Basic # token"),
+            (
+                "fix #",
+                5,
+                Some("".to_string()),
+                "Only # triggers empty query",
+            ),
+            ("# Heading", 1, None, "Markdown heading"),
+            ("@src/lib.rs#L10", 15, None, "Line range of an @ mention"),
+        ];
+
+        for (input, cursor_pos, expected, description) in test_cases {
+            let mut textarea = TextArea::new();
+            textarea.insert_str(input);
+            textarea.set_cursor(cursor_pos);
+
+            let result = ChatComposer::current_hash_token(&textarea);
+            assert_eq!(
+                result, expected,
+                "Failed for case: {description} - input: '{input}', cursor: {cursor_pos}"
+            );
+        }
+    }
+
     #[test]
     fn test_current_at_token_cursor_positions() {
         let test_cases = vec![
@@ -1831,6 +2195,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn rebound_submit_and_newline_keys() {
+        use codex_core::keymap::KeyChord;
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            true,
+        );
+        let chord = |s: &str| s.parse::<KeyChord>().expect("valid chord");
+        composer.set_keymap(Keymap {
+            submit: chord("ctrl-s"),
+            newline: chord("enter"),
+            ..Keymap::default()
+        });
+
+        type_chars_humanlike(&mut composer, &['h', 'i']);
+        let (result, _) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(result, InputResult::None);
+        type_chars_humanlike(&mut composer, &['x']);
+        assert_eq!(composer.textarea.text(), "hi\nx");
+
+        let (result, _) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert_eq!(result, InputResult::Submitted("hi\nx".to_string()));
+    }
+
     #[test]
     fn slash_init_dispatches_command_and_does_not_submit_literal_text() {
         use crossterm::event::KeyCode;
@@ -1928,6 +2327,51 @@ mod tests {
         assert_eq!(composer.textarea.text(), "@");
     }
 
+    #[test]
+    fn selecting_content_match_inserts_line_range_mention() {
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        type_chars_humanlike(
+            &mut composer,
+            &['s', 'e', 'e', ' ', '#', 'p', 'a', 'r', 's', 'e'],
+        );
+        assert!(matches!(composer.active_popup, ActivePopup::Content(_)));
+        let mut last_query = None;
+        while let Ok(event) = rx.try_recv() {
+            if let AppEvent::StartContentSearch(query) = event {
+                last_query = Some(query);
+            }
+        }
+        assert_eq!(last_query.as_deref(), Some("parse"));
+
+        composer.on_content_search_result(
+            "parse".to_string(),
+            vec![ContentMatch {
+                score: 0,
+                path: "src/parser.rs".to_string(),
+                line_number: 42,
+                line: "fn parse_command() {".to_string(),
+                indices: None,
+            }],
+        );
+        let _ = composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(composer.textarea.text(), "see @src/parser.rs#L32-52 ");
+        assert!(matches!(composer.active_popup, ActivePopup::None));
+    }
+
     #[test]
     fn test_multiple_pastes_submission() {
         use crossterm::event::KeyCode;
@@ -2155,6 +2599,42 @@ mod tests {
         assert_eq!(vec![path], imgs);
     }
 
+    #[test]
+    fn external_edit_preserves_surviving_placeholders() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        let image = PathBuf::from("/tmp/image3.png");
+        composer.attach_image(image.clone(), 10, 5, "PNG");
+        let pasted = "x".repeat(LARGE_PASTE_CHAR_THRESHOLD + 1);
+        composer.handle_paste(pasted.clone());
+        let paste_placeholder = format!("[Pasted Content {} chars]", pasted.len());
+        assert_eq!(
+            composer.current_text(),
+            format!("[image 10x5 PNG]{paste_placeholder}")
+        );
+
+        // The editor kept the image, dropped the paste and added text around it.
+        composer.set_text_preserving_elements("look at [image 10x5 PNG]\nplease");
+        assert_eq!(composer.current_text(), "look at [image 10x5 PNG]\nplease");
+        assert!(composer.pending_pastes.is_empty());
+        assert_eq!(composer.textarea.element_count(), 1);
+
+        let (result, _) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(
+            result,
+            InputResult::Submitted("look at [image 10x5 PNG]\nplease".to_string())
+        );
+        assert_eq!(composer.take_recent_submission_images(), vec![image]);
+    }
+
     #[test]
     fn attach_image_without_text_submits_empty_text_and_images() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
@@ -2333,6 +2813,7 @@ mod tests {
             name: "my-prompt".to_string(),
             path: "/tmp/my-prompt.md".to_string().into(),
             content: prompt_text.to_string(),
+            ..Default::default()
         }]);
 
         type_chars_humanlike(
@@ -2445,4 +2926,40 @@ mod tests {
         assert_eq!(composer.textarea.text(), "z".repeat(count));
         assert!(composer.pending_pastes.is_empty());
     }
+
+    #[test]
+    fn custom_prompt_arguments_are_expanded() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_custom_prompts(vec![CustomPrompt {
+            name: "fix".to_string(),
+            path: "/tmp/fix.md".to_string().into(),
+            content: "Fix $1 in $FILE".to_string(),
+            effort: Some(ReasoningEffortConfig::High),
+            ..Default::default()
+        }]);
+
+        composer
+            .textarea
+            .set_text("/fix \"the crash\" @src/main.rs");
+        composer.sync_command_popup();
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(
+            InputResult::SubmittedWithOverrides {
+                text: "Fix the crash in src/main.rs".to_string(),
+                model: None,
+                effort: Some(ReasoningEffortConfig::High),
+            },
+            result
+        );
+    }
 }
@@ -12,6 +12,16 @@ use codex_common::fuzzy_match::fuzzy_match;
 use codex_protocol::custom_prompts::CustomPrompt;
 use std::collections::HashSet;
 
+/// Popup description of a custom prompt: its argument hint followed by the
+/// description from its front matter.
+fn prompt_description(prompt: &CustomPrompt) -> String {
+    let description = prompt.description.as_deref().unwrap_or("send saved prompt");
+    match &prompt.argument_hint {
+        Some(hint) => format!("{hint}  {description}"),
+        None => description.to_string(),
+    }
+}
+
 /// A selectable item in the popup: either a built-in command or a user prompt.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum CommandItem {
@@ -57,8 +67,8 @@ impl CommandPopup {
         self.prompts.get(idx).map(|p| p.name.as_str())
     }
 
-    pub(crate) fn prompt_content(&self, idx: usize) -> Option<&str> {
-        self.prompts.get(idx).map(|p| p.content.as_str())
+    pub(crate) fn prompt(&self, idx: usize) -> Option<&CustomPrompt> {
+        self.prompts.get(idx)
     }
 
     /// Update the filter string based on the current composer text. The text
@@ -114,7 +124,7 @@ impl CommandPopup {
                         name: format!("/{}", self.prompts[i].name),
                         match_indices: indices.map(|v| v.into_iter().map(|i| i + 1).collect()),
                         is_current: false,
-                        description: Some("send saved prompt".to_string()),
+                        description: Some(prompt_description(&self.prompts[i])),
                     },
                 })
                 .collect()
@@ -215,7 +225,7 @@ impl WidgetRef for CommandPopup {
                         name: format!("/{}", self.prompts[i].name),
                         match_indices: indices.map(|v| v.into_iter().map(|i| i + 1).collect()),
                         is_current: false,
-                        description: Some("send saved prompt".to_string()),
+                        description: Some(prompt_description(&self.prompts[i])),
                     },
                 })
                 .collect()
@@ -292,11 +302,13 @@ mod tests {
                 name: "foo".to_string(),
                 path: "/tmp/foo.md".to_string().into(),
                 content: "hello from foo".to_string(),
+                ..Default::default()
             },
             CustomPrompt {
                 name: "bar".to_string(),
                 path: "/tmp/bar.md".to_string().into(),
                 content: "hello from bar".to_string(),
+                ..Default::default()
             },
         ];
         let popup = CommandPopup::new(prompts);
@@ -319,6 +331,7 @@ mod tests {
             name: "init".to_string(),
             path: "/tmp/init.md".to_string().into(),
             content: "should be ignored".to_string(),
+            ..Default::default()
         }]);
         let items = popup.filtered_items();
         let has_collision_prompt = items.into_iter().any(|it| match it {
@@ -0,0 +1,136 @@
+use codex_file_search::ContentMatch;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::widgets::WidgetRef;
+
+use super::popup_consts::MAX_POPUP_ROWS;
+use super::scroll_state::ScrollState;
+use super::selection_popup_common::GenericDisplayRow;
+use super::selection_popup_common::render_rows;
+
+/// Visual state for the `#` content-search popup. Mirrors `FileSearchPopup`,
+/// but each row is a matching line rather than a file.
+pub(crate) struct ContentSearchPopup {
+    /// Query corresponding to the `matches` currently shown.
+    display_query: String,
+    /// Latest query typed by the user. May differ from `display_query` when
+    /// a search is still in-flight.
+    pending_query: String,
+    /// When `true` we are still waiting for results for `pending_query`.
+    waiting: bool,
+    /// Cached matches; paths relative to the search dir.
+    matches: Vec<ContentMatch>,
+    /// Shared selection/scroll state.
+    state: ScrollState,
+}
+
+impl ContentSearchPopup {
+    pub(crate) fn new() -> Self {
+        Self {
+            display_query: String::new(),
+            pending_query: String::new(),
+            waiting: true,
+            matches: Vec::new(),
+            state: ScrollState::new(),
+        }
+    }
+
+    /// Update the query and reset state to *waiting*.
+    pub(crate) fn set_query(&mut self, query: &str) {
+        if query == self.pending_query {
+            return;
+        }
+
+        // Determine if current matches are still relevant.
+        let keep_existing = query.starts_with(&self.display_query);
+
+        self.pending_query.clear();
+        self.pending_query.push_str(query);
+        self.waiting = true;
+
+        if !keep_existing {
+            self.matches.clear();
+            self.state.reset();
+        }
+    }
+
+    /// Put the popup into an "idle" state used for an empty query (just "#").
+    pub(crate) fn set_empty_prompt(&mut self) {
+        self.display_query.clear();
+        self.pending_query.clear();
+        self.waiting = false;
+        self.matches.clear();
+        self.state.reset();
+    }
+
+    /// Replace matches. Only applied when `query` matches `pending_query`.
+    pub(crate) fn set_matches(&mut self, query: &str, matches: Vec<ContentMatch>) {
+        if query != self.pending_query {
+            return; // stale
+        }
+
+        self.display_query = query.to_string();
+        self.matches = matches;
+        self.waiting = false;
+        let len = self.matches.len();
+        self.state.clamp_selection(len);
+        self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
+    }
+
+    /// Move selection cursor up.
+    pub(crate) fn move_up(&mut self) {
+        let len = self.matches.len();
+        self.state.move_up_wrap(len);
+        self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
+    }
+
+    /// Move selection cursor down.
+    pub(crate) fn move_down(&mut self) {
+        let len = self.matches.len();
+        self.state.move_down_wrap(len);
+        self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
+    }
+
+    pub(crate) fn selected_match(&self) -> Option<&ContentMatch> {
+        self.state
+            .selected_idx
+            .and_then(|idx| self.matches.get(idx))
+    }
+
+    pub(crate) fn calculate_required_height(&self) -> u16 {
+        self.matches.len().clamp(1, MAX_POPUP_ROWS) as u16
+    }
+}
+
+impl WidgetRef for &ContentSearchPopup {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let rows_all: Vec<GenericDisplayRow> = self
+            .matches
+            .iter()
+            .map(|m| GenericDisplayRow {
+                name: format!("{}:{}", m.path, m.line_number),
+                match_indices: None,
+                is_current: false,
+                description: Some(m.line.trim().to_string()),
+            })
+            .collect();
+
+        let empty_message = if self.waiting {
+            "loading..."
+        } else if self.pending_query.is_empty() {
+            "type to search file contents"
+        } else {
+            "no matches"
+        };
+
+        render_rows(
+            area,
+            buf,
+            &rows_all,
+            &self.state,
+            MAX_POPUP_ROWS,
+            false,
+            empty_message,
+        );
+    }
+}
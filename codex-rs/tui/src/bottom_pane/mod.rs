@@ -2,10 +2,14 @@
 use std::path::PathBuf;
 
 use crate::app_event_sender::AppEventSender;
+use crate::keymap;
+use crate::mentions::Attachment;
 use crate::tui::FrameRequester;
 use crate::user_approval_widget::ApprovalRequest;
 use bottom_pane_view::BottomPaneView;
+use codex_core::keymap::Keymap;
 use codex_core::protocol::TokenUsageInfo;
+use codex_file_search::ContentMatch;
 use codex_file_search::FileMatch;
 use crossterm::event::KeyEvent;
 use ratatui::buffer::Buffer;
@@ -20,6 +24,7 @@ mod bottom_pane_view;
 mod chat_composer;
 mod chat_composer_history;
 mod command_popup;
+mod content_search_popup;
 mod file_search_popup;
 mod list_selection_view;
 mod paste_burst;
@@ -64,6 +69,8 @@ pub(crate) struct BottomPane {
     status: Option<StatusIndicatorWidget>,
     /// Queued user messages to show under the status indicator.
     queued_user_messages: Vec<String>,
+
+    keymap: Keymap,
 }
 
 pub(crate) struct BottomPaneParams {
@@ -73,20 +80,28 @@ pub(crate) struct BottomPaneParams {
     pub(crate) enhanced_keys_supported: bool,
     pub(crate) placeholder_text: String,
     pub(crate) disable_paste_burst: bool,
+    pub(crate) keymap: Keymap,
+    /// Workspace root that `@` mentions are resolved against.
+    pub(crate) mention_root: Option<PathBuf>,
 }
 
 impl BottomPane {
     const BOTTOM_PAD_LINES: u16 = 1;
     pub fn new(params: BottomPaneParams) -> Self {
         let enhanced_keys_supported = params.enhanced_keys_supported;
+        let mut composer = ChatComposer::new(
+            params.has_input_focus,
+            params.app_event_tx.clone(),
+            enhanced_keys_supported,
+            params.placeholder_text,
+            params.disable_paste_burst,
+        );
+        composer.set_keymap(params.keymap.clone());
+        if let Some(root) = params.mention_root {
+            composer.set_mention_root(root);
+        }
         Self {
-            composer: ChatComposer::new(
-                params.has_input_focus,
-                params.app_event_tx.clone(),
-                enhanced_keys_supported,
-                params.placeholder_text,
-                params.disable_paste_burst,
-            ),
+            composer,
             active_view: None,
             app_event_tx: params.app_event_tx,
             frame_requester: params.frame_requester,
@@ -96,6 +111,7 @@ impl BottomPane {
             status: None,
             queued_user_messages: Vec::new(),
             esc_backtrack_hint: false,
+            keymap: params.keymap,
         }
     }
 
@@ -168,9 +184,9 @@ impl BottomPane {
             self.request_redraw();
             InputResult::None
         } else {
-            // If a task is running and a status line is visible, allow Esc to
-            // send an interrupt even while the composer has focus.
-            if matches!(key_event.code, crossterm::event::KeyCode::Esc)
+            // If a task is running and a status line is visible, allow the
+            // interrupt key to interrupt even while the composer has focus.
+            if keymap::matches(self.keymap.interrupt, &key_event)
                 && self.is_task_running
                 && let Some(status) = &self.status
             {
@@ -243,12 +259,22 @@ impl BottomPane {
         self.request_redraw();
     }
 
-    /// Get the current composer text (for tests and programmatic checks).
-    #[cfg(test)]
+    /// Get the current composer text, including paste and image placeholders.
     pub(crate) fn composer_text(&self) -> String {
         self.composer.current_text()
     }
 
+    /// Replace the composer text after it was edited in an external editor.
+    pub(crate) fn set_composer_text_from_editor(&mut self, text: &str) {
+        self.composer.set_text_preserving_elements(text);
+        self.request_redraw();
+    }
+
+    /// True when the composer is visible, i.e. no modal view replaces it.
+    pub(crate) fn composer_is_visible(&self) -> bool {
+        self.active_view.is_none()
+    }
+
     /// Update the animated header shown to the left of the brackets in the
     /// status indicator (defaults to "Working"). No-ops if the status
     /// indicator is not active.
@@ -302,10 +328,12 @@ impl BottomPane {
 
         if running {
             if self.status.is_none() {
-                self.status = Some(StatusIndicatorWidget::new(
+                let mut status = StatusIndicatorWidget::new(
                     self.app_event_tx.clone(),
                     self.frame_requester.clone(),
-                ));
+                );
+                status.set_interrupt_key(self.keymap.interrupt);
+                self.status = Some(status);
             }
             if let Some(status) = self.status.as_mut() {
                 status.set_queued_messages(self.queued_user_messages.clone());
@@ -346,6 +374,18 @@ impl BottomPane {
     }
 
     /// Update custom prompts available for the slash popup.
+    pub(crate) fn set_keymap(&mut self, keymap: Keymap) {
+        if let Some(status) = self.status.as_mut() {
+            status.set_interrupt_key(keymap.interrupt);
+        }
+        self.composer.set_keymap(keymap.clone());
+        self.keymap = keymap;
+    }
+
+    pub(crate) fn set_disable_paste_burst(&mut self, disabled: bool) {
+        self.composer.set_disable_paste_burst(disabled);
+    }
+
     pub(crate) fn set_custom_prompts(&mut self, prompts: Vec<CustomPrompt>) {
         self.composer.set_custom_prompts(prompts);
         self.request_redraw();
@@ -453,6 +493,16 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    pub(crate) fn on_content_search_result(&mut self, query: String, matches: Vec<ContentMatch>) {
+        self.composer.on_content_search_result(query, matches);
+        self.request_redraw();
+    }
+
+    pub(crate) fn on_mention_estimate(&mut self, token: String, tokens: Option<usize>) {
+        self.composer.on_mention_estimate(token, tokens);
+        self.request_redraw();
+    }
+
     pub(crate) fn attach_image(
         &mut self,
         path: PathBuf,
@@ -470,6 +520,10 @@ impl BottomPane {
     pub(crate) fn take_recent_submission_images(&mut self) -> Vec<PathBuf> {
         self.composer.take_recent_submission_images()
     }
+
+    pub(crate) fn resolve_mentions(&mut self, text: &str) -> (Vec<Attachment>, Vec<String>) {
+        self.composer.resolve_mentions(text)
+    }
 }
 
 impl WidgetRef for &BottomPane {
@@ -519,6 +573,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            mention_root: None,
         });
         pane.push_approval_request(exec_request());
         assert_eq!(CancellationEvent::Handled, pane.on_ctrl_c());
@@ -539,6 +595,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            mention_root: None,
         });
 
         // Create an approval modal (active view).
@@ -570,6 +628,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            mention_root: None,
         });
 
         // Start a running task so the status indicator is active above the composer.
@@ -638,6 +698,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            mention_root: None,
         });
 
         // Begin a task: show initial status.
@@ -669,6 +731,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            mention_root: None,
         });
 
         // Activate spinner (status view replaces composer) with no live ring.
@@ -720,6 +784,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            mention_root: None,
         });
 
         pane.set_task_running(true);
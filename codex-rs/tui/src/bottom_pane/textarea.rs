@@ -648,6 +648,11 @@ impl TextArea {
         self.set_cursor(end);
     }
 
+    #[cfg(test)]
+    pub(crate) fn element_count(&self) -> usize {
+        self.elements.len()
+    }
+
     fn add_element(&mut self, range: Range<usize>) {
         let elem = TextElement { range };
         self.elements.push(elem);
@@ -27,6 +27,7 @@ use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::ProjectDocLoadedEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TokenUsage;
@@ -90,6 +91,7 @@ use codex_core::ConversationManager;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_file_search::ContentMatch;
 use codex_file_search::FileMatch;
 use codex_protocol::mcp_protocol::ConversationId;
 
@@ -141,11 +143,18 @@ pub(crate) struct ChatWidget {
     queued_user_messages: VecDeque<UserMessage>,
     // Pending notification to show when unfocused on next Draw
     pending_notification: Option<Notification>,
+    // Latest aggregated diff of the files changed during this session's turns
+    last_turn_diff: Option<String>,
 }
 
 struct UserMessage {
     text: String,
     image_paths: Vec<PathBuf>,
+    /// Model for this turn only, set by a custom prompt's front matter.
+    model: Option<String>,
+    /// Reasoning effort for this turn only, set by a custom prompt's front
+    /// matter.
+    effort: Option<ReasoningEffortConfig>,
 }
 
 impl From<String> for UserMessage {
@@ -153,6 +162,8 @@ impl From<String> for UserMessage {
         Self {
             text,
             image_paths: Vec::new(),
+            model: None,
+            effort: None,
         }
     }
 }
@@ -161,7 +172,12 @@ fn create_initial_user_message(text: String, image_paths: Vec<PathBuf>) -> Optio
     if text.is_empty() && image_paths.is_empty() {
         None
     } else {
-        Some(UserMessage { text, image_paths })
+        Some(UserMessage {
+            text,
+            image_paths,
+            model: None,
+            effort: None,
+        })
     }
 }
 
@@ -424,12 +440,21 @@ impl ChatWidget {
 
     fn on_turn_diff(&mut self, unified_diff: String) {
         debug!("TurnDiffEvent: {unified_diff}");
+        self.last_turn_diff = Some(unified_diff);
     }
 
     fn on_background_event(&mut self, message: String) {
         debug!("BackgroundEvent: {message}");
     }
 
+    fn on_project_doc_loaded(&mut self, event: ProjectDocLoadedEvent) {
+        let path = display_path_for(&event.path, &self.config.cwd);
+        let hint = event
+            .truncated
+            .then(|| "truncated to fit project_doc_max_bytes".to_string());
+        self.add_info_message(format!("Loaded instructions from {path}"), hint);
+    }
+
     fn on_stream_error(&mut self, message: String) {
         // Show stream errors in the transcript so users see retry/backoff info.
         self.add_to_history(history_cell::new_stream_error_event(message));
@@ -567,6 +592,8 @@ impl ChatWidget {
             id,
             reason: ev.reason,
             grant_root: ev.grant_root,
+            changes: ev.changes.clone(),
+            cwd: self.config.cwd.clone(),
         };
         self.bottom_pane.push_approval_request(request);
         self.request_redraw();
@@ -680,6 +707,8 @@ impl ChatWidget {
                 enhanced_keys_supported,
                 placeholder_text: placeholder,
                 disable_paste_burst: config.disable_paste_burst,
+                keymap: config.tui_keymap.clone(),
+                mention_root: Some(config.cwd.clone()),
             }),
             active_exec_cell: None,
             config: config.clone(),
@@ -701,6 +730,7 @@ impl ChatWidget {
             show_welcome_banner: true,
             suppress_session_configured_redraw: false,
             pending_notification: None,
+            last_turn_diff: None,
         }
     }
 
@@ -736,6 +766,8 @@ impl ChatWidget {
                 enhanced_keys_supported,
                 placeholder_text: placeholder,
                 disable_paste_burst: config.disable_paste_burst,
+                keymap: config.tui_keymap.clone(),
+                mention_root: Some(config.cwd.clone()),
             }),
             active_exec_cell: None,
             config: config.clone(),
@@ -757,6 +789,7 @@ impl ChatWidget {
             show_welcome_banner: true,
             suppress_session_configured_redraw: true,
             pending_notification: None,
+            last_turn_diff: None,
         }
     }
 
@@ -810,27 +843,22 @@ impl ChatWidget {
                     self.request_redraw();
                 }
             }
-            _ => {
-                match self.bottom_pane.handle_key_event(key_event) {
-                    InputResult::Submitted(text) => {
-                        // If a task is running, queue the user input to be sent after the turn completes.
-                        let user_message = UserMessage {
-                            text,
-                            image_paths: self.bottom_pane.take_recent_submission_images(),
-                        };
-                        if self.bottom_pane.is_task_running() {
-                            self.queued_user_messages.push_back(user_message);
-                            self.refresh_queued_user_messages();
-                        } else {
-                            self.submit_user_message(user_message);
-                        }
-                    }
-                    InputResult::Command(cmd) => {
-                        self.dispatch_command(cmd);
-                    }
-                    InputResult::None => {}
+            _ => match self.bottom_pane.handle_key_event(key_event) {
+                InputResult::Submitted(text) => {
+                    self.submit_or_queue(text, None, None);
                 }
-            }
+                InputResult::SubmittedWithOverrides {
+                    text,
+                    model,
+                    effort,
+                } => {
+                    self.submit_or_queue(text, model, effort);
+                }
+                InputResult::Command(cmd) => {
+                    self.dispatch_command(cmd);
+                }
+                InputResult::None => {}
+            },
         }
     }
 
@@ -935,6 +963,7 @@ impl ChatWidget {
                                 PathBuf::from("/tmp/test.txt"),
                                 FileChange::Add {
                                     content: "test".to_string(),
+                                    mode: None,
                                 },
                             ),
                             (
@@ -942,6 +971,7 @@ impl ChatWidget {
                                 FileChange::Update {
                                     unified_diff: "+test\n-test2".to_string(),
                                     move_path: None,
+                                    mode: None,
                                 },
                             ),
                         ]),
@@ -994,8 +1024,34 @@ impl ChatWidget {
         self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
     }
 
+    fn submit_or_queue(
+        &mut self,
+        text: String,
+        model: Option<String>,
+        effort: Option<ReasoningEffortConfig>,
+    ) {
+        // If a task is running, queue the user input to be sent after the turn completes.
+        let user_message = UserMessage {
+            text,
+            image_paths: self.bottom_pane.take_recent_submission_images(),
+            model,
+            effort,
+        };
+        if self.bottom_pane.is_task_running() {
+            self.queued_user_messages.push_back(user_message);
+            self.refresh_queued_user_messages();
+        } else {
+            self.submit_user_message(user_message);
+        }
+    }
+
     fn submit_user_message(&mut self, user_message: UserMessage) {
-        let UserMessage { text, image_paths } = user_message;
+        let UserMessage {
+            text,
+            image_paths,
+            model,
+            effort,
+        } = user_message;
         let mut items: Vec<InputItem> = Vec::new();
 
         if !text.is_empty() {
@@ -1006,15 +1062,43 @@ impl ChatWidget {
             items.push(InputItem::LocalImage { path });
         }
 
+        let (attachments, mention_errors) = self.bottom_pane.resolve_mentions(&text);
+        items.extend(attachments.into_iter().map(|a| a.into_input_item()));
+
         if items.is_empty() {
             return;
         }
 
-        self.codex_op_tx
-            .send(Op::UserInput { items })
-            .unwrap_or_else(|e| {
+        // Overrides from a custom prompt apply to this turn only. Core keeps
+        // the context of a `UserTurn` for later turns, so switch it back
+        // right away; the turn just started keeps the overridden model.
+        let mut ops = Vec::new();
+        if model.is_some() || effort.is_some() {
+            ops.push(Op::UserTurn {
+                items,
+                cwd: self.config.cwd.clone(),
+                approval_policy: self.config.approval_policy,
+                sandbox_policy: self.config.sandbox_policy.clone(),
+                model: model.unwrap_or_else(|| self.config.model.clone()),
+                effort: effort.or(self.config.model_reasoning_effort),
+                summary: self.config.model_reasoning_summary,
+            });
+            ops.push(Op::OverrideTurnContext {
+                cwd: None,
+                approval_policy: None,
+                sandbox_policy: None,
+                model: Some(self.config.model.clone()),
+                effort: Some(self.config.model_reasoning_effort),
+                summary: None,
+            });
+        } else {
+            ops.push(Op::UserInput { items });
+        }
+        for op in ops {
+            self.codex_op_tx.send(op).unwrap_or_else(|e| {
                 tracing::error!("failed to send message: {e}");
             });
+        }
 
         // Persist the text to cross-session message history.
         if !text.is_empty() {
@@ -1029,6 +1113,11 @@ impl ChatWidget {
         if !text.is_empty() {
             self.add_to_history(history_cell::new_user_prompt(text));
         }
+        for error in mention_errors {
+            self.add_to_history(history_cell::new_error_event(format!(
+                "Could not attach {error}"
+            )));
+        }
     }
 
     /// Replay a subset of initial events into the UI to seed the transcript when
@@ -1120,6 +1209,7 @@ impl ChatWidget {
                 self.on_background_event(message)
             }
             EventMsg::StreamError(StreamErrorEvent { message }) => self.on_stream_error(message),
+            EventMsg::ProjectDocLoaded(ev) => self.on_project_doc_loaded(ev),
             EventMsg::UserMessage(ev) => {
                 if from_replay {
                     self.on_user_message_event(ev);
@@ -1206,6 +1296,11 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    /// The latest diff reported for the agent's turns, if any.
+    pub(crate) fn last_turn_diff(&self) -> Option<&str> {
+        self.last_turn_diff.as_deref()
+    }
+
     pub(crate) fn add_status_output(&mut self) {
         let default_usage;
         let usage_ref = if let Some(ti) = &self.token_info {
@@ -1320,6 +1415,50 @@ impl ChatWidget {
         );
     }
 
+    /// Ask before applying approval, sandbox or MCP server changes picked up
+    /// from an edited config file. Keeping the current settings is selected
+    /// by default.
+    pub(crate) fn open_config_permissions_popup(
+        &mut self,
+        keys: &[&str],
+        ops: Vec<Op>,
+        approval: Option<AskForApproval>,
+        sandbox: Option<SandboxPolicy>,
+    ) {
+        let apply: SelectionAction = Box::new(move |tx| {
+            for op in &ops {
+                tx.send(AppEvent::CodexOp(op.clone()));
+            }
+            if let Some(approval) = approval {
+                tx.send(AppEvent::UpdateAskForApprovalPolicy(approval));
+            }
+            if let Some(sandbox) = &sandbox {
+                tx.send(AppEvent::UpdateSandboxPolicy(sandbox.clone()));
+            }
+        });
+        let items = vec![
+            SelectionItem {
+                name: "Keep current settings".to_string(),
+                description: Some("Ignore the changes until Codex restarts".to_string()),
+                is_current: true,
+                actions: Vec::new(),
+            },
+            SelectionItem {
+                name: "Apply changes".to_string(),
+                description: Some("Apply the new settings to this session".to_string()),
+                is_current: false,
+                actions: vec![apply],
+            },
+        ];
+
+        self.bottom_pane.show_selection_view(
+            "Apply permission changes from config?".to_string(),
+            Some(format!("The config now sets {}", keys.join(", "))),
+            Some("Press Enter to confirm or Esc to keep the current settings".to_string()),
+            items,
+        );
+    }
+
     /// Set the approval policy in the widget's config copy.
     pub(crate) fn set_approval_policy(&mut self, policy: AskForApproval) {
         self.config.approval_policy = policy;
@@ -1341,6 +1480,15 @@ impl ChatWidget {
         self.config.model = model.to_string();
     }
 
+    /// Update the widget's config copy after `config.toml` was reloaded and
+    /// pass the input settings on to the composer.
+    pub(crate) fn update_config(&mut self, update: impl FnOnce(&mut Config)) {
+        update(&mut self.config);
+        self.bottom_pane.set_keymap(self.config.tui_keymap.clone());
+        self.bottom_pane
+            .set_disable_paste_burst(self.config.disable_paste_burst);
+    }
+
     pub(crate) fn add_info_message(&mut self, message: String, hint: Option<String>) {
         self.add_to_history(history_cell::new_info_event(message, hint));
         self.request_redraw();
@@ -1364,6 +1512,18 @@ impl ChatWidget {
         self.bottom_pane.on_file_search_result(query, matches);
     }
 
+    pub(crate) fn apply_content_search_result(
+        &mut self,
+        query: String,
+        matches: Vec<ContentMatch>,
+    ) {
+        self.bottom_pane.on_content_search_result(query, matches);
+    }
+
+    pub(crate) fn apply_mention_estimate(&mut self, token: String, tokens: Option<usize>) {
+        self.bottom_pane.on_mention_estimate(token, tokens);
+    }
+
     /// Handle Ctrl-C key press.
     fn on_ctrl_c(&mut self) {
         if self.bottom_pane.on_ctrl_c() == CancellationEvent::Handled {
@@ -1399,6 +1559,18 @@ impl ChatWidget {
         self.bottom_pane.set_composer_text(text);
     }
 
+    pub(crate) fn composer_text(&self) -> String {
+        self.bottom_pane.composer_text()
+    }
+
+    pub(crate) fn set_composer_text_from_editor(&mut self, text: &str) {
+        self.bottom_pane.set_composer_text_from_editor(text);
+    }
+
+    pub(crate) fn composer_is_visible(&self) -> bool {
+        self.bottom_pane.composer_is_visible()
+    }
+
     pub(crate) fn show_esc_backtrack_hint(&mut self) {
         self.bottom_pane.show_esc_backtrack_hint();
     }
@@ -6,6 +6,7 @@ use codex_core::CodexAuth;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
+use codex_core::keymap::Keymap;
 use codex_core::plan_tool::PlanItemArg;
 use codex_core::plan_tool::StepStatus;
 use codex_core::plan_tool::UpdatePlanArgs;
@@ -228,6 +229,8 @@ fn make_chatwidget_manual() -> (
         enhanced_keys_supported: false,
         placeholder_text: "Ask Codex to do anything".to_string(),
         disable_paste_burst: false,
+        keymap: Keymap::default(),
+        mention_root: None,
     });
     let auth_manager = AuthManager::from_auth_for_testing(CodexAuth::from_api_key("test"));
     let widget = ChatWidget {
@@ -252,6 +255,7 @@ fn make_chatwidget_manual() -> (
         queued_user_messages: VecDeque::new(),
         suppress_session_configured_redraw: false,
         pending_notification: None,
+        last_turn_diff: None,
     };
     (widget, rx, op_rx)
 }
@@ -428,6 +432,7 @@ fn end_exec(chat: &mut ChatWidget, call_id: &str, stdout: &str, stderr: &str, ex
             exit_code,
             duration: std::time::Duration::from_millis(5),
             formatted_output: aggregated,
+            redactions: 0,
         }),
     });
 }
@@ -898,6 +903,7 @@ fn approval_modal_patch_snapshot() {
         PathBuf::from("README.md"),
         FileChange::Add {
             content: "hello\nworld\n".into(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -960,6 +966,83 @@ fn interrupt_restores_queued_messages_into_composer() {
     let _ = drain_insert_history(&mut rx);
 }
 
+#[test]
+fn prompt_model_override_applies_to_one_turn_only() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+    let configured_model = chat.config.model.clone();
+    let configured_effort = chat.config.model_reasoning_effort;
+
+    chat.submit_user_message(UserMessage {
+        text: "review this".to_string(),
+        image_paths: Vec::new(),
+        model: Some("o3".to_string()),
+        effort: Some(ReasoningEffortConfig::High),
+    });
+    match op_rx.try_recv() {
+        Ok(Op::UserTurn { model, effort, .. }) => {
+            assert_eq!(model, "o3");
+            assert_eq!(effort, Some(ReasoningEffortConfig::High));
+        }
+        other => panic!("expected a UserTurn, got {other:?}"),
+    }
+    match op_rx.try_recv() {
+        Ok(Op::OverrideTurnContext { model, effort, .. }) => {
+            assert_eq!(model, Some(configured_model.clone()));
+            assert_eq!(effort, Some(configured_effort));
+        }
+        other => panic!("expected an OverrideTurnContext, got {other:?}"),
+    }
+    assert!(matches!(op_rx.try_recv(), Ok(Op::AddToHistory { .. })));
+    assert_eq!(chat.config.model, configured_model);
+
+    // The next message goes out with the session's own settings.
+    chat.submit_user_message(UserMessage::from("and now this".to_string()));
+    assert!(matches!(op_rx.try_recv(), Ok(Op::UserInput { .. })));
+}
+
+#[test]
+fn config_permission_changes_wait_for_confirmation() {
+    let sandbox_change = Op::OverrideTurnContext {
+        cwd: None,
+        approval_policy: None,
+        sandbox_policy: Some(SandboxPolicy::DangerFullAccess),
+        model: None,
+        effort: None,
+        summary: None,
+    };
+
+    // Enter on the default choice keeps the current settings.
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.open_config_permissions_popup(
+        &["sandbox"],
+        vec![sandbox_change.clone()],
+        None,
+        Some(SandboxPolicy::DangerFullAccess),
+    );
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert!(rx.try_recv().is_err());
+
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.open_config_permissions_popup(
+        &["sandbox"],
+        vec![sandbox_change.clone()],
+        None,
+        Some(SandboxPolicy::DangerFullAccess),
+    );
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(op)) => assert_eq!(op, sandbox_change),
+        other => panic!("expected the sandbox change, got {other:?}"),
+    }
+    assert!(matches!(
+        rx.try_recv(),
+        Ok(AppEvent::UpdateSandboxPolicy(
+            SandboxPolicy::DangerFullAccess
+        ))
+    ));
+}
+
 // Snapshot test: ChatWidget at very small heights (idle)
 // Ensures overall layout behaves when terminal height is extremely constrained.
 #[test]
@@ -1093,6 +1176,7 @@ fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -1119,6 +1203,7 @@ fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let begin = PatchApplyBeginEvent {
@@ -1165,6 +1250,7 @@ fn apply_patch_manual_approval_adjusts_header() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -1183,6 +1269,7 @@ fn apply_patch_manual_approval_adjusts_header() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -1212,6 +1299,7 @@ fn apply_patch_manual_flow_snapshot() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -1232,6 +1320,7 @@ fn apply_patch_manual_flow_snapshot() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -1265,6 +1354,7 @@ fn apply_patch_approval_sends_op_with_submission_id() {
         PathBuf::from("file.rs"),
         FileChange::Add {
             content: "fn main(){}\n".into(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -1284,7 +1374,7 @@ fn apply_patch_approval_sends_op_with_submission_id() {
     // Expect a CodexOp with PatchApproval carrying the submission id, not call id
     let mut found = false;
     while let Ok(app_ev) = rx.try_recv() {
-        if let AppEvent::CodexOp(Op::PatchApproval { id, decision }) = app_ev {
+        if let AppEvent::CodexOp(Op::PatchApproval { id, decision, .. }) = app_ev {
             assert_eq!(id, "sub-123");
             assert!(matches!(
                 decision,
@@ -1305,7 +1395,10 @@ fn apply_patch_full_flow_integration_like() {
     let mut changes = HashMap::new();
     changes.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -1334,7 +1427,7 @@ fn apply_patch_full_flow_integration_like() {
         .try_recv()
         .expect("expected op forwarded to codex channel");
     match forwarded {
-        Op::PatchApproval { id, decision } => {
+        Op::PatchApproval { id, decision, .. } => {
             assert_eq!(id, "sub-xyz");
             assert!(matches!(
                 decision,
@@ -1348,7 +1441,10 @@ fn apply_patch_full_flow_integration_like() {
     let mut changes2 = HashMap::new();
     changes2.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -1379,7 +1475,10 @@ fn apply_patch_untrusted_shows_approval_modal() {
     let mut changes = HashMap::new();
     changes.insert(
         PathBuf::from("a.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-1".into(),
@@ -1427,6 +1526,7 @@ fn apply_patch_request_shows_diff_summary() {
         FileChange::Add {
             // Two lines (no trailing empty line counted)
             content: "line one\nline two\n".into(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -1695,6 +1795,7 @@ fn chatwidget_exec_and_status_layout_vt100_snapshot() {
             exit_code: 0,
             duration: std::time::Duration::from_millis(16000),
             formatted_output: String::new(),
+            redactions: 0,
         }),
     });
     chat.handle_codex_event(Event {
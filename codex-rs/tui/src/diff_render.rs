@@ -1,5 +1,3 @@
-use ratatui::style::Color;
-use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::style::Stylize;
 use ratatui::text::Line as RtLine;
@@ -10,12 +8,15 @@ use std::path::PathBuf;
 
 use crate::exec_command::relativize_to_home;
 use crate::history_cell::PatchEventType;
+use crate::render::highlight::highlight_code_to_lines;
+use crate::theme;
 use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::FileChange;
 
 const SPACES_AFTER_LINE_NUMBER: usize = 6;
 
 // Internal representation for diff line rendering
+#[derive(Clone, Copy)]
 enum DiffLineType {
     Insert,
     Delete,
@@ -54,32 +55,63 @@ struct Row {
 }
 
 fn collect_rows(changes: &HashMap<PathBuf, FileChange>) -> Vec<Row> {
-    let mut rows: Vec<Row> = Vec::new();
-    for (path, change) in changes.iter() {
-        let (added, removed) = match change {
-            FileChange::Add { content } => (content.lines().count(), 0),
-            FileChange::Delete { content } => (0, content.lines().count()),
-            FileChange::Update { unified_diff, .. } => calculate_add_remove_from_diff(unified_diff),
-        };
-        let move_path = match change {
-            FileChange::Update {
-                move_path: Some(new),
-                ..
-            } => Some(new.clone()),
-            _ => None,
-        };
-        rows.push(Row {
-            path: path.clone(),
-            move_path,
-            added,
-            removed,
-            change: change.clone(),
-        });
-    }
+    let mut rows: Vec<Row> = changes
+        .iter()
+        .map(|(path, change)| row_for(path, change))
+        .collect();
     rows.sort_by_key(|r| r.path.clone());
     rows
 }
 
+fn row_for(path: &Path, change: &FileChange) -> Row {
+    let (added, removed) = match change {
+        FileChange::Add { content, .. } => (content.lines().count(), 0),
+        FileChange::AddBinary { .. } | FileChange::AddSymlink { .. } => (0, 0),
+        FileChange::Delete { content } => (0, content.lines().count()),
+        FileChange::Update { unified_diff, .. } => calculate_add_remove_from_diff(unified_diff),
+    };
+    let move_path = match change {
+        FileChange::Update {
+            move_path: Some(new),
+            ..
+        } => Some(new.clone()),
+        _ => None,
+    };
+    Row {
+        path: path.to_path_buf(),
+        move_path,
+        added,
+        removed,
+        change: change.clone(),
+    }
+}
+
+/// One summary line per file (`path (+N -M)`), in the order given. Used by
+/// the diff viewer's file list.
+pub(crate) fn create_diff_file_list(
+    changes: &[(PathBuf, FileChange)],
+    cwd: &Path,
+) -> Vec<RtLine<'static>> {
+    changes
+        .iter()
+        .map(|(path, change)| {
+            let row = row_for(path, change);
+            let marker = match row.change {
+                FileChange::Add { .. }
+                | FileChange::AddBinary { .. }
+                | FileChange::AddSymlink { .. } => "A ",
+                FileChange::Delete { .. } => "D ",
+                FileChange::Update { .. } => "M ",
+            };
+            let mut spans: Vec<RtSpan<'static>> = vec!["  ".into(), marker.dim()];
+            spans.extend(render_path(&row, cwd));
+            spans.push(" ".into());
+            spans.extend(render_line_count_summary(row.added, row.removed));
+            RtLine::from(spans)
+        })
+        .collect()
+}
+
 enum HeaderKind {
     ProposedChange,
     Edited,
@@ -94,25 +126,7 @@ fn render_changes_block(
 ) -> Vec<RtLine<'static>> {
     let mut out: Vec<RtLine<'static>> = Vec::new();
     let term_cols = wrap_cols;
-
-    fn render_line_count_summary(added: usize, removed: usize) -> Vec<RtSpan<'static>> {
-        let mut spans = Vec::new();
-        spans.push("(".into());
-        spans.push(format!("+{added}").green());
-        spans.push(" ".into());
-        spans.push(format!("-{removed}").red());
-        spans.push(")".into());
-        spans
-    }
-
-    let render_path = |row: &Row| -> Vec<RtSpan<'static>> {
-        let mut spans = Vec::new();
-        spans.push(display_path_for(&row.path, cwd).into());
-        if let Some(move_path) = &row.move_path {
-            spans.push(format!(" → {}", display_path_for(move_path, cwd)).into());
-        }
-        spans
-    };
+    let render_path = |row: &Row| render_path(row, cwd);
 
     // Header
     let total_added: usize = rows.iter().map(|r| r.added).sum();
@@ -136,7 +150,9 @@ fn render_changes_block(
         HeaderKind::Edited => {
             if let [row] = &rows[..] {
                 let verb = match &row.change {
-                    FileChange::Add { .. } => "Added",
+                    FileChange::Add { .. }
+                    | FileChange::AddBinary { .. }
+                    | FileChange::AddSymlink { .. } => "Added",
                     FileChange::Delete { .. } => "Deleted",
                     _ => "Edited",
                 };
@@ -189,82 +205,316 @@ fn render_changes_block(
             out.push(RtLine::from(header));
         }
 
+        if let Some(note) = file_change_note(&r.change) {
+            out.push(RtLine::from(format!("    ({note})").dim()));
+        }
         match r.change {
-            FileChange::Add { content } => {
+            FileChange::Add { content, .. } => {
+                let highlighted = highlight_for_path(&r.path, &content);
                 for (i, raw) in content.lines().enumerate() {
                     out.extend(push_wrapped_diff_line(
                         i + 1,
                         DiffLineType::Insert,
                         raw,
+                        highlighted.get(i),
                         term_cols,
                     ));
                 }
             }
             FileChange::Delete { content } => {
+                let highlighted = highlight_for_path(&r.path, &content);
                 for (i, raw) in content.lines().enumerate() {
                     out.extend(push_wrapped_diff_line(
                         i + 1,
                         DiffLineType::Delete,
                         raw,
+                        highlighted.get(i),
                         term_cols,
                     ));
                 }
             }
             FileChange::Update { unified_diff, .. } => {
-                if let Ok(patch) = diffy::Patch::from_str(&unified_diff) {
-                    let mut is_first_hunk = true;
-                    for h in patch.hunks() {
-                        if !is_first_hunk {
-                            out.push(RtLine::from(vec!["    ".into(), "⋮".dim()]));
-                        }
-                        is_first_hunk = false;
-
-                        let mut old_ln = h.old_range().start();
-                        let mut new_ln = h.new_range().start();
-                        for l in h.lines() {
-                            match l {
-                                diffy::Line::Insert(text) => {
-                                    let s = text.trim_end_matches('\n');
-                                    out.extend(push_wrapped_diff_line(
-                                        new_ln,
-                                        DiffLineType::Insert,
-                                        s,
-                                        term_cols,
-                                    ));
-                                    new_ln += 1;
-                                }
-                                diffy::Line::Delete(text) => {
-                                    let s = text.trim_end_matches('\n');
-                                    out.extend(push_wrapped_diff_line(
-                                        old_ln,
-                                        DiffLineType::Delete,
-                                        s,
-                                        term_cols,
-                                    ));
-                                    old_ln += 1;
-                                }
-                                diffy::Line::Context(text) => {
-                                    let s = text.trim_end_matches('\n');
-                                    out.extend(push_wrapped_diff_line(
-                                        new_ln,
-                                        DiffLineType::Context,
-                                        s,
-                                        term_cols,
-                                    ));
-                                    old_ln += 1;
-                                    new_ln += 1;
-                                }
-                            }
-                        }
-                    }
+                out.extend(render_unified_diff(&r.path, &unified_diff, term_cols));
+            }
+            FileChange::AddBinary { .. } | FileChange::AddSymlink { .. } => {}
+        }
+    }
+
+    out
+}
+
+/// What a change does besides editing text: new permissions, binary
+/// contents, or a symlink target.
+pub(crate) fn file_change_note(change: &FileChange) -> Option<String> {
+    match change {
+        FileChange::Add {
+            mode: Some(mode), ..
+        }
+        | FileChange::Update {
+            mode: Some(mode), ..
+        } => Some(format!("mode {mode:o}")),
+        FileChange::AddBinary { size, mode } => {
+            let mut note = format!("binary file, {size} bytes");
+            if let Some(mode) = mode {
+                note.push_str(&format!(", mode {mode:o}"));
+            }
+            Some(note)
+        }
+        FileChange::AddSymlink { target } => Some(format!("symlink to {}", target.display())),
+        _ => None,
+    }
+}
+
+fn render_line_count_summary(added: usize, removed: usize) -> Vec<RtSpan<'static>> {
+    let mut spans = Vec::new();
+    spans.push("(".into());
+    let palette = theme::palette();
+    spans.push(RtSpan::styled(format!("+{added}"), palette.added()));
+    spans.push(" ".into());
+    spans.push(RtSpan::styled(format!("-{removed}"), palette.removed()));
+    spans.push(")".into());
+    spans
+}
+
+fn render_path(row: &Row, cwd: &Path) -> Vec<RtSpan<'static>> {
+    let mut spans = Vec::new();
+    spans.push(display_path_for(&row.path, cwd).into());
+    if let Some(move_path) = &row.move_path {
+        spans.push(format!(" → {}", display_path_for(move_path, cwd)).into());
+    }
+    spans
+}
+
+/// Syntax-highlight `code` based on the extension of `path`, one line per
+/// source line. Empty when the language is unknown.
+fn highlight_for_path(path: &Path, code: &str) -> Vec<RtLine<'static>> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(|lang| highlight_code_to_lines(code, lang))
+        .unwrap_or_default()
+}
+
+/// One line of a parsed hunk, numbered in the old and/or new file.
+struct NumberedLine<'a> {
+    kind: DiffLineType,
+    old_ln: usize,
+    new_ln: usize,
+    text: &'a str,
+    highlighted: Option<&'a RtLine<'static>>,
+}
+
+/// Parse `unified_diff` and number its lines, hunk by hunk. The old and the
+/// new side of the hunks are highlighted as two separate snippets, so
+/// multi-line constructs (strings, comments) carry across the lines of one
+/// side without bleeding into the other.
+fn with_numbered_hunks(
+    path: &Path,
+    unified_diff: &str,
+    mut render_hunk: impl FnMut(usize, Vec<NumberedLine<'_>>),
+) {
+    let Ok(patch) = diffy::Patch::from_str(unified_diff) else {
+        return;
+    };
+    let side_text = |new_side: bool| -> String {
+        patch
+            .hunks()
+            .iter()
+            .flat_map(|h| h.lines())
+            .filter_map(|l| match l {
+                diffy::Line::Context(text) => Some(text),
+                diffy::Line::Insert(text) => new_side.then_some(text),
+                diffy::Line::Delete(text) => (!new_side).then_some(text),
+            })
+            .map(|text| format!("{}\n", text.trim_end_matches('\n')))
+            .collect()
+    };
+    let old_lines = highlight_for_path(path, &side_text(false));
+    let new_lines = highlight_for_path(path, &side_text(true));
+    let mut old_highlighted = old_lines.iter();
+    let mut new_highlighted = new_lines.iter();
+    for (idx, h) in patch.hunks().iter().enumerate() {
+        let mut old_ln = h.old_range().start();
+        let mut new_ln = h.new_range().start();
+        let mut lines = Vec::new();
+        for l in h.lines() {
+            let (kind, text, highlighted) = match l {
+                diffy::Line::Insert(text) => (DiffLineType::Insert, text, new_highlighted.next()),
+                diffy::Line::Delete(text) => (DiffLineType::Delete, text, old_highlighted.next()),
+                diffy::Line::Context(text) => {
+                    old_highlighted.next();
+                    (DiffLineType::Context, text, new_highlighted.next())
+                }
+            };
+            lines.push(NumberedLine {
+                kind,
+                old_ln,
+                new_ln,
+                text: text.trim_end_matches('\n'),
+                highlighted,
+            });
+            match l {
+                diffy::Line::Insert(_) => new_ln += 1,
+                diffy::Line::Delete(_) => old_ln += 1,
+                diffy::Line::Context(_) => {
+                    old_ln += 1;
+                    new_ln += 1;
                 }
             }
         }
+        render_hunk(idx, lines);
     }
+}
+
+/// The lines of `unified_diff` as `(sign, line number, text)`, numbered the
+/// way they are rendered: removed lines in the old file, others in the new.
+pub(crate) fn numbered_diff_lines(unified_diff: &str) -> Vec<(char, usize, String)> {
+    let mut out = Vec::new();
+    with_numbered_hunks(Path::new(""), unified_diff, |_, lines| {
+        out.extend(lines.into_iter().map(|line| match line.kind {
+            DiffLineType::Insert => ('+', line.new_ln, line.text.to_string()),
+            DiffLineType::Delete => ('-', line.old_ln, line.text.to_string()),
+            DiffLineType::Context => (' ', line.new_ln, line.text.to_string()),
+        }));
+    });
+    out
+}
 
+/// Render the hunks of `unified_diff` as numbered, wrapped diff lines, the
+/// way file updates are shown in history.
+pub(crate) fn render_unified_diff(
+    path: &Path,
+    unified_diff: &str,
+    wrap_cols: usize,
+) -> Vec<RtLine<'static>> {
+    let mut out: Vec<RtLine<'static>> = Vec::new();
+    with_numbered_hunks(path, unified_diff, |idx, lines| {
+        if idx > 0 {
+            out.push(RtLine::from(vec!["    ".into(), "⋮".dim()]));
+        }
+        for line in lines {
+            let line_number = match line.kind {
+                DiffLineType::Delete => line.old_ln,
+                DiffLineType::Insert | DiffLineType::Context => line.new_ln,
+            };
+            out.extend(push_wrapped_diff_line(
+                line_number,
+                line.kind,
+                line.text,
+                line.highlighted,
+                wrap_cols,
+            ));
+        }
+    });
+    out
+}
+
+/// Render the hunks of `unified_diff` in two columns: the old file on the
+/// left and the new file on the right. Runs of removed lines are paired with
+/// the added lines that follow them.
+pub(crate) fn render_side_by_side_diff(
+    path: &Path,
+    unified_diff: &str,
+    wrap_cols: usize,
+) -> Vec<RtLine<'static>> {
+    let column_cols = wrap_cols.saturating_sub(1) / 2;
+    let mut out: Vec<RtLine<'static>> = Vec::new();
+    with_numbered_hunks(path, unified_diff, |idx, lines| {
+        if idx > 0 {
+            out.push(RtLine::from(vec!["    ".into(), "⋮".dim()]));
+        }
+        let mut lines = lines.into_iter().peekable();
+        while let Some(line) = lines.next() {
+            let (left, right) = match line.kind {
+                DiffLineType::Context => {
+                    let left = push_wrapped_diff_line(
+                        line.old_ln,
+                        DiffLineType::Context,
+                        line.text,
+                        line.highlighted,
+                        column_cols,
+                    );
+                    let right = push_wrapped_diff_line(
+                        line.new_ln,
+                        DiffLineType::Context,
+                        line.text,
+                        line.highlighted,
+                        column_cols,
+                    );
+                    (left, right)
+                }
+                DiffLineType::Delete | DiffLineType::Insert => {
+                    let mut deleted = Vec::new();
+                    let mut inserted = Vec::new();
+                    let mut next = Some(line);
+                    while let Some(line) = next {
+                        if matches!(line.kind, DiffLineType::Insert) {
+                            inserted.push(line);
+                        } else {
+                            deleted.push(line);
+                        }
+                        // A removal after additions starts a new pairing run.
+                        next = lines.next_if(|l| match l.kind {
+                            DiffLineType::Delete => inserted.is_empty(),
+                            DiffLineType::Insert => true,
+                            DiffLineType::Context => false,
+                        });
+                    }
+                    for pair in 0..deleted.len().max(inserted.len()) {
+                        let left = deleted.get(pair).map(|l| {
+                            push_wrapped_diff_line(
+                                l.old_ln,
+                                DiffLineType::Delete,
+                                l.text,
+                                l.highlighted,
+                                column_cols,
+                            )
+                        });
+                        let right = inserted.get(pair).map(|l| {
+                            push_wrapped_diff_line(
+                                l.new_ln,
+                                DiffLineType::Insert,
+                                l.text,
+                                l.highlighted,
+                                column_cols,
+                            )
+                        });
+                        out.extend(join_columns(
+                            left.unwrap_or_default(),
+                            right.unwrap_or_default(),
+                            column_cols,
+                        ));
+                    }
+                    continue;
+                }
+            };
+            out.extend(join_columns(left, right, column_cols));
+        }
+    });
     out
 }
 
+/// Place `left` and `right` next to each other, padding the left column to
+/// `column_cols` and the shorter column with blank rows.
+fn join_columns(
+    left: Vec<RtLine<'static>>,
+    right: Vec<RtLine<'static>>,
+    column_cols: usize,
+) -> Vec<RtLine<'static>> {
+    let rows = left.len().max(right.len());
+    let mut left = left.into_iter();
+    let mut right = right.into_iter();
+    (0..rows)
+        .map(|_| {
+            let mut spans = left.next().map(|l| l.spans).unwrap_or_default();
+            let used: usize = spans.iter().map(RtSpan::width).sum();
+            spans.push(" ".repeat(column_cols.saturating_sub(used)).into());
+            spans.push("│".dim());
+            spans.extend(right.next().map(|l| l.spans).unwrap_or_default());
+            RtLine::from(spans)
+        })
+        .collect()
+}
+
 pub(crate) fn display_path_for(path: &Path, cwd: &Path) -> String {
     let path_in_same_repo = match (get_git_repo_root(cwd), get_git_repo_root(path)) {
         (Some(cwd_repo), Some(path_repo)) => cwd_repo == path_repo,
@@ -299,11 +549,11 @@ fn push_wrapped_diff_line(
     line_number: usize,
     kind: DiffLineType,
     text: &str,
+    highlighted: Option<&RtLine<'static>>,
     term_cols: usize,
 ) -> Vec<RtLine<'static>> {
     let indent = "    ";
     let ln_str = line_number.to_string();
-    let mut remaining_text: &str = text;
 
     // Reserve a fixed number of spaces after the line number so that content starts
     // at a consistent column. Content includes a 1-character diff sign prefix
@@ -312,54 +562,82 @@ fn push_wrapped_diff_line(
     let gap_after_ln = SPACES_AFTER_LINE_NUMBER.saturating_sub(ln_str.len());
     let prefix_cols = indent.len() + ln_str.len() + gap_after_ln;
 
-    let mut first = true;
     let (sign_char, line_style) = match kind {
         DiffLineType::Insert => ('+', style_add()),
         DiffLineType::Delete => ('-', style_del()),
         DiffLineType::Context => (' ', style_context()),
     };
-    let mut lines: Vec<RtLine<'static>> = Vec::new();
 
-    loop {
-        // Fit the content for the current terminal row:
-        // compute how many columns are available after the prefix, then split
-        // at a UTF-8 character boundary so this row's chunk fits exactly.
-        let available_content_cols = term_cols.saturating_sub(prefix_cols + 1).max(1);
-        let split_at_byte_index = remaining_text
-            .char_indices()
-            .nth(available_content_cols)
-            .map(|(i, _)| i)
-            .unwrap_or_else(|| remaining_text.len());
-        let (chunk, rest) = remaining_text.split_at(split_at_byte_index);
-        remaining_text = rest;
-
-        if first {
-            // Build gutter (indent + line number + spacing) as a dimmed span
+    // Context lines take the full syntax colors. Inserted and deleted lines
+    // keep their green/red so the diff stays readable, and only borrow the
+    // token modifiers (e.g. dimmed comments).
+    let segments: Vec<(&str, Style)> = match highlighted {
+        Some(line) => line
+            .spans
+            .iter()
+            .map(|span| {
+                let style = match kind {
+                    DiffLineType::Context => line_style.patch(span.style),
+                    DiffLineType::Insert | DiffLineType::Delete => {
+                        line_style.add_modifier(span.style.add_modifier)
+                    }
+                };
+                (span.content.as_ref(), style)
+            })
+            .collect(),
+        None => vec![(text, line_style)],
+    };
+
+    // Fit the content to the terminal rows: compute how many columns are
+    // available after the prefix, then split segments at UTF-8 character
+    // boundaries so each row's chunks fit exactly.
+    let available_content_cols = term_cols.saturating_sub(prefix_cols + 1).max(1);
+    let mut rows: Vec<Vec<RtSpan<'static>>> = vec![Vec::new()];
+    let mut used_cols = 0;
+    for (segment, style) in segments {
+        let mut remaining_text = segment;
+        while !remaining_text.is_empty() {
+            if used_cols == available_content_cols {
+                rows.push(Vec::new());
+                used_cols = 0;
+            }
+            let split_at_byte_index = remaining_text
+                .char_indices()
+                .nth(available_content_cols - used_cols)
+                .map(|(i, _)| i)
+                .unwrap_or_else(|| remaining_text.len());
+            let (chunk, rest) = remaining_text.split_at(split_at_byte_index);
+            used_cols += chunk.chars().count();
+            if let Some(row) = rows.last_mut() {
+                row.push(RtSpan::styled(chunk.to_string(), style));
+            }
+            remaining_text = rest;
+        }
+    }
+
+    let mut lines: Vec<RtLine<'static>> = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        let mut spans = if i == 0 {
+            // Build gutter (indent + line number + spacing) as a dimmed span,
+            // followed by a sign ('+'/'-'/' ') styled per diff kind
             let gutter = format!("{indent}{ln_str}{}", " ".repeat(gap_after_ln));
-            // Content with a sign ('+'/'-'/' ') styled per diff kind
-            let content = format!("{sign_char}{chunk}");
-            lines.push(RtLine::from(vec![
+            vec![
                 RtSpan::styled(gutter, style_gutter()),
-                RtSpan::styled(content, line_style),
-            ]));
-            first = false;
+                RtSpan::styled(sign_char.to_string(), line_style),
+            ]
         } else {
             // Continuation lines keep a space for the sign column so content aligns
             let gutter = format!("{indent}{} ", " ".repeat(ln_str.len() + gap_after_ln));
-            lines.push(RtLine::from(vec![
-                RtSpan::styled(gutter, style_gutter()),
-                RtSpan::styled(chunk.to_string(), line_style),
-            ]));
-        }
-        if remaining_text.is_empty() {
-            break;
-        }
+            vec![RtSpan::styled(gutter, style_gutter())]
+        };
+        spans.extend(row);
+        lines.push(RtLine::from(spans));
     }
     lines
 }
 
 fn style_gutter() -> Style {
-    Style::default().add_modifier(Modifier::DIM)
+    theme::palette().secondary()
 }
 
 fn style_context() -> Style {
@@ -367,11 +645,11 @@ fn style_context() -> Style {
 }
 
 fn style_add() -> Style {
-    Style::default().fg(Color::Green)
+    theme::palette().added()
 }
 
 fn style_del() -> Style {
-    Style::default().fg(Color::Red)
+    theme::palette().removed()
 }
 
 #[cfg(test)]
@@ -380,6 +658,7 @@ mod tests {
     use insta::assert_snapshot;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
+    use ratatui::style::Color;
     use ratatui::text::Text;
     use ratatui::widgets::Paragraph;
     use ratatui::widgets::WidgetRef;
@@ -427,6 +706,7 @@ mod tests {
             PathBuf::from("README.md"),
             FileChange::Add {
                 content: "first line\nsecond line\n".to_string(),
+                mode: None,
             },
         );
 
@@ -435,6 +715,75 @@ mod tests {
         snapshot_lines("add_details", lines, 80, 10);
     }
 
+    #[test]
+    fn notes_modes_binary_files_and_symlinks() {
+        let changes: HashMap<PathBuf, FileChange> = HashMap::from([
+            (
+                PathBuf::from("run.sh"),
+                FileChange::Add {
+                    content: "echo hi\n".to_string(),
+                    mode: Some(0o755),
+                },
+            ),
+            (
+                PathBuf::from("logo.png"),
+                FileChange::AddBinary {
+                    size: 2048,
+                    mode: None,
+                },
+            ),
+            (
+                PathBuf::from("latest"),
+                FileChange::AddSymlink {
+                    target: PathBuf::from("releases/v2"),
+                },
+            ),
+        ]);
+
+        let text: Vec<String> = diff_summary_for_tests(&changes, PatchEventType::ApprovalRequest)
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+
+        for note in [
+            "    (mode 755)",
+            "    (binary file, 2048 bytes)",
+            "    (symlink to releases/v2)",
+        ] {
+            assert!(
+                text.iter().any(|line| line == note),
+                "missing {note:?} in {text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn side_by_side_pairs_removed_and_added_lines() {
+        let patch = diffy::create_patch("one\ntwo\nthree\n", "one\n2\nthree\nfour\n").to_string();
+        let lines = render_side_by_side_diff(Path::new("x.txt"), &patch, 61);
+        let text: Vec<String> = lines
+            .iter()
+            .map(|l| {
+                l.spans
+                    .iter()
+                    .map(|s| s.content.as_ref())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        let row = |left: &str, right: &str| format!("{left:<30}│{right}");
+        assert_eq!(
+            text,
+            vec![
+                row("    1      one", "    1      one"),
+                row("    2     -two", "    2     +2"),
+                row("    3      three", "    3      three"),
+                row("", "    4     +four"),
+            ]
+        );
+    }
+
     #[test]
     fn ui_snapshot_update_details_with_rename() {
         let mut changes: HashMap<PathBuf, FileChange> = HashMap::new();
@@ -448,6 +797,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(PathBuf::from("src/lib_new.rs")),
+                mode: None,
             },
         );
 
@@ -462,7 +812,7 @@ mod tests {
         let long_line = "this is a very long line that should wrap across multiple terminal columns and continue";
 
         // Call the wrapping function directly so we can precisely control the width
-        let lines = push_wrapped_diff_line(1, DiffLineType::Insert, long_line, 80);
+        let lines = push_wrapped_diff_line(1, DiffLineType::Insert, long_line, None, 80);
 
         // Render into a small terminal to capture the visual layout
         snapshot_lines("wrap_behavior_insert", lines, 90, 8);
@@ -481,6 +831,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -502,6 +853,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -524,6 +876,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -545,6 +898,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -571,6 +925,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(PathBuf::from("new_name.rs")),
+                mode: None,
             },
         );
 
@@ -596,6 +951,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch_a,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -604,6 +960,7 @@ mod tests {
             PathBuf::from("b.txt"),
             FileChange::Add {
                 content: "new\n".to_string(),
+                mode: None,
             },
         );
 
@@ -624,6 +981,7 @@ mod tests {
             PathBuf::from("new_file.txt"),
             FileChange::Add {
                 content: "alpha\nbeta\n".to_string(),
+                mode: None,
             },
         );
 
@@ -677,6 +1035,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -707,6 +1066,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -741,6 +1101,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(abs_new),
+                mode: None,
             },
         );
 
@@ -755,4 +1116,51 @@ mod tests {
 
         snapshot_lines("apply_update_block_relativizes_path", lines, 80, 10);
     }
+
+    #[test]
+    fn highlights_context_lines_and_keeps_diff_colors() {
+        let original = "fn a() {}\nlet x = 1;\n";
+        let modified = "fn a() {}\nlet x = 2;\n";
+        let patch = diffy::create_patch(original, modified).to_string();
+
+        let mut changes: HashMap<PathBuf, FileChange> = HashMap::new();
+        changes.insert(
+            PathBuf::from("src/lib.rs"),
+            FileChange::Update {
+                unified_diff: patch,
+                move_path: None,
+                mode: None,
+            },
+        );
+
+        let lines = diff_summary_for_tests(&changes, PatchEventType::ApprovalRequest);
+        let spans: Vec<&RtSpan<'static>> = lines.iter().flat_map(|l| l.spans.iter()).collect();
+        // Keywords on context lines take the syntax color...
+        assert!(
+            spans
+                .iter()
+                .any(|s| s.content == "fn" && s.style.fg == Some(Color::Magenta))
+        );
+        // ...while inserted and deleted lines keep their diff color.
+        for s in spans.iter().filter(|s| s.content == "let") {
+            assert!(matches!(s.style.fg, Some(Color::Green) | Some(Color::Red)));
+        }
+    }
+
+    #[test]
+    fn old_and_new_lines_are_highlighted_separately() {
+        // Read as one interleaved snippet, the removed line's open string
+        // would swallow the context after the change.
+        let original = "let s = \"a\nb\";\nfn c() {}\n";
+        let modified = "let s = \"x\nb\";\nfn c() {}\n";
+        let patch = diffy::create_patch(original, modified).to_string();
+
+        let lines = render_unified_diff(Path::new("src/lib.rs"), &patch, 80);
+        let spans: Vec<&RtSpan<'static>> = lines.iter().flat_map(|l| l.spans.iter()).collect();
+        assert!(
+            spans
+                .iter()
+                .any(|s| s.content == "fn" && s.style.fg == Some(Color::Magenta))
+        );
+    }
 }
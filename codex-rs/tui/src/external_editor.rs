@@ -0,0 +1,80 @@
+//! Composing prompts in the user's `$VISUAL` / `$EDITOR`.
+//!
+//! The composer contents are written to a temporary file, the editor is run
+//! on it with the terminal handed over (see [`crate::tui::Tui::with_released_terminal`]),
+//! and the saved file is read back into the composer.
+
+use std::io::Write;
+use std::process::Command;
+
+/// Resolve the editor command from `$VISUAL`, falling back to `$EDITOR`.
+/// Values may include arguments, e.g. `code --wait`.
+pub(crate) fn editor_command() -> Option<Vec<String>> {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find_map(|value| shlex::split(&value).filter(|argv| !argv.is_empty()))
+}
+
+/// Open `text` in `command` and return the saved contents once the editor
+/// exits successfully.
+pub(crate) fn edit_text(command: &[String], text: &str) -> std::io::Result<String> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(std::io::Error::other("editor command is empty"));
+    };
+    // A `.md` suffix gives editors a hint for syntax highlighting.
+    let mut file = tempfile::Builder::new()
+        .prefix("codex-prompt-")
+        .suffix(".md")
+        .tempfile()?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+
+    let status = Command::new(program).args(args).arg(file.path()).status()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!(
+            "{program} exited with {status}"
+        )));
+    }
+
+    // Re-read by path: many editors save by replacing the file.
+    let edited = std::fs::read_to_string(file.path())?;
+    Ok(strip_trailing_newline(edited))
+}
+
+/// Editors conventionally terminate the last line; that newline is not part
+/// of the prompt.
+fn strip_trailing_newline(mut text: String) -> String {
+    if text.ends_with('\n') {
+        text.pop();
+        if text.ends_with('\r') {
+            text.pop();
+        }
+    }
+    text
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn reads_back_saved_contents() {
+        // A shell script stands in for an interactive editor; the file path
+        // is appended as `$0`.
+        let command = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            r#"sed 's/draft/final/' "$0" > "$0.tmp" && mv "$0.tmp" "$0""#.to_string(),
+        ];
+        let edited = edit_text(&command, "a draft prompt\n[image 10x5 PNG]").expect("edit");
+        assert_eq!(edited, "a final prompt\n[image 10x5 PNG]");
+    }
+
+    #[test]
+    fn failing_editor_is_an_error() {
+        let command = vec!["false".to_string()];
+        assert!(edit_text(&command, "text").is_err());
+    }
+}
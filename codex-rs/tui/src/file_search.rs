@@ -1,7 +1,10 @@
-//! Helper that owns the debounce/cancellation logic for `@` file searches.
+//! Helper that owns the debounce/cancellation logic for `@` file searches and
+//! `#` content searches.
 //!
 //! `ChatComposer` publishes *every* change of the `@token` as
-//! `AppEvent::StartFileSearch(query)`.
+//! `AppEvent::StartFileSearch(query)` (and of the `#token` as
+//! `AppEvent::StartContentSearch(query)`); one manager exists per
+//! [`SearchKind`].
 //! This struct receives those events and decides when to actually spawn the
 //! expensive search (handled in the main `App` thread). It tries to ensure:
 //!
@@ -19,6 +22,7 @@
 //!    the user typed, it is cancelled.
 
 use codex_file_search as file_search;
+use codex_file_search::ContentMatchMode;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -32,6 +36,7 @@ use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 
 const MAX_FILE_SEARCH_RESULTS: NonZeroUsize = NonZeroUsize::new(8).unwrap();
+const MAX_CONTENT_SEARCH_RESULTS: NonZeroUsize = NonZeroUsize::new(20).unwrap();
 const NUM_FILE_SEARCH_THREADS: NonZeroUsize = NonZeroUsize::new(2).unwrap();
 
 /// How long to wait after a keystroke before firing the first search when none
@@ -40,12 +45,22 @@ const FILE_SEARCH_DEBOUNCE: Duration = Duration::from_millis(100);
 
 const ACTIVE_SEARCH_COMPLETE_POLL_INTERVAL: Duration = Duration::from_millis(20);
 
+/// What a [`FileSearchManager`] searches for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SearchKind {
+    /// Fuzzy match file paths (`@` mentions).
+    Files,
+    /// Search lines inside files (`#` mentions).
+    Content,
+}
+
 /// State machine for file-search orchestration.
 pub(crate) struct FileSearchManager {
     /// Unified state guarded by one mutex.
     state: Arc<Mutex<SearchState>>,
 
     search_dir: PathBuf,
+    kind: SearchKind,
     app_tx: AppEventSender,
 }
 
@@ -66,7 +81,7 @@ struct ActiveSearch {
 }
 
 impl FileSearchManager {
-    pub fn new(search_dir: PathBuf, tx: AppEventSender) -> Self {
+    pub fn new(search_dir: PathBuf, kind: SearchKind, tx: AppEventSender) -> Self {
         Self {
             state: Arc::new(Mutex::new(SearchState {
                 latest_query: String::new(),
@@ -74,11 +89,12 @@ impl FileSearchManager {
                 active_search: None,
             })),
             search_dir,
+            kind,
             app_tx: tx,
         }
     }
 
-    /// Call whenever the user edits the `@` token.
+    /// Call whenever the user edits the `@` (or `#`) token.
     pub fn on_user_query(&self, query: String) {
         {
             #[expect(clippy::unwrap_used)]
@@ -116,6 +132,7 @@ impl FileSearchManager {
         // debounce timer.
         let state = self.state.clone();
         let search_dir = self.search_dir.clone();
+        let kind = self.kind;
         let tx_clone = self.app_tx.clone();
         thread::spawn(move || {
             // Always do a minimum debounce, but then poll until the
@@ -148,6 +165,7 @@ impl FileSearchManager {
             FileSearchManager::spawn_file_search(
                 query,
                 search_dir,
+                kind,
                 tx_clone,
                 cancellation_token,
                 state,
@@ -158,27 +176,48 @@ impl FileSearchManager {
     fn spawn_file_search(
         query: String,
         search_dir: PathBuf,
+        kind: SearchKind,
         tx: AppEventSender,
         cancellation_token: Arc<AtomicBool>,
         search_state: Arc<Mutex<SearchState>>,
     ) {
         let compute_indices = true;
         std::thread::spawn(move || {
-            let matches = file_search::run(
-                &query,
-                MAX_FILE_SEARCH_RESULTS,
-                &search_dir,
-                Vec::new(),
-                NUM_FILE_SEARCH_THREADS,
-                cancellation_token.clone(),
-                compute_indices,
-            )
-            .map(|res| res.matches)
-            .unwrap_or_default();
+            let event = match kind {
+                SearchKind::Files => {
+                    let matches = file_search::run(
+                        &query,
+                        MAX_FILE_SEARCH_RESULTS,
+                        &search_dir,
+                        Vec::new(),
+                        NUM_FILE_SEARCH_THREADS,
+                        cancellation_token.clone(),
+                        compute_indices,
+                    )
+                    .map(|res| res.matches)
+                    .unwrap_or_default();
+                    AppEvent::FileSearchResult { query, matches }
+                }
+                SearchKind::Content => {
+                    let matches = file_search::run_content(
+                        &content_search_pattern(&query),
+                        ContentMatchMode::Regex,
+                        MAX_CONTENT_SEARCH_RESULTS,
+                        &search_dir,
+                        Vec::new(),
+                        NUM_FILE_SEARCH_THREADS,
+                        cancellation_token.clone(),
+                        compute_indices,
+                    )
+                    .map(|res| res.matches)
+                    .unwrap_or_default();
+                    AppEvent::ContentSearchResult { query, matches }
+                }
+            };
 
             let is_cancelled = cancellation_token.load(Ordering::Relaxed);
             if !is_cancelled {
-                tx.send(AppEvent::FileSearchResult { query, matches });
+                tx.send(event);
             }
 
             // Reset the active search state. Do a pointer comparison to verify
@@ -196,3 +235,19 @@ impl FileSearchManager {
         });
     }
 }
+
+/// Regex used for a `#` query: the query itself when it is a valid regex,
+/// otherwise the literal text. Matching is case-insensitive unless the query
+/// contains an uppercase letter.
+fn content_search_pattern(query: &str) -> String {
+    let pattern = if regex_lite::Regex::new(query).is_ok() {
+        query.to_string()
+    } else {
+        regex_lite::escape(query)
+    };
+    if query.chars().any(char::is_uppercase) {
+        pattern
+    } else {
+        format!("(?i){pattern}")
+    }
+}
@@ -4,10 +4,14 @@
 //! `codex-cli`: it returns the diff for tracked changes as well as any
 //! untracked files. When the current directory is not inside a Git
 //! repository, the function returns `Ok((false, String::new()))`.
+//!
+//! The diff viewer also uses [`apply_hunk`] to stage or revert single hunks.
 
 use std::io;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 
 /// Return value of [`get_git_diff`].
@@ -15,18 +19,29 @@ use tokio::process::Command;
 /// * `bool` – Whether the current working directory is inside a Git repo.
 /// * `String` – The concatenated diff (may be empty).
 pub(crate) async fn get_git_diff() -> io::Result<(bool, String)> {
+    git_diff_in(Path::new(".")).await
+}
+
+async fn git_diff_in(cwd: &Path) -> io::Result<(bool, String)> {
     // First check if we are inside a Git repository.
-    if !inside_git_repo().await? {
+    if !inside_git_repo(cwd).await? {
         return Ok((false, String::new()));
     }
 
-    // Run tracked diff and untracked file listing in parallel.
-    let (tracked_diff_res, untracked_output_res) = tokio::join!(
-        run_git_capture_diff(&["diff", "--color"]),
-        run_git_capture_stdout(&["ls-files", "--others", "--exclude-standard"]),
+    // Run tracked diff and untracked file listing in parallel. Both report
+    // paths relative to the repository root, which is what `apply_hunk`
+    // expects.
+    let (tracked_diff_res, untracked_output_res, root_res) = tokio::join!(
+        run_git_capture_diff(cwd, &["diff"]),
+        run_git_capture_stdout(
+            cwd,
+            &["ls-files", "--others", "--exclude-standard", "--full-name"]
+        ),
+        repo_root(cwd),
     );
     let tracked_diff = tracked_diff_res?;
     let untracked_output = untracked_output_res?;
+    let root = root_res?;
 
     let mut untracked_diff = String::new();
     let null_device: &Path = if cfg!(windows) {
@@ -44,9 +59,11 @@ pub(crate) async fn get_git_diff() -> io::Result<(bool, String)> {
     {
         let null_path = null_path.clone();
         let file = file.to_string();
+        let root = root.clone();
         join_set.spawn(async move {
-            let args = ["diff", "--color", "--no-index", "--", &null_path, &file];
-            run_git_capture_diff(&args).await
+            // `--full-name` paths are relative to the root, so diff from there.
+            let args = ["diff", "--no-index", "--", &null_path, &file];
+            run_git_capture_diff(&root, &args).await
         });
     }
     while let Some(res) = join_set.join_next().await {
@@ -61,11 +78,63 @@ pub(crate) async fn get_git_diff() -> io::Result<(bool, String)> {
     Ok((true, format!("{tracked_diff}{untracked_diff}")))
 }
 
-/// Helper that executes `git` with the given `args` and returns `stdout` as a
-/// UTF-8 string. Any non-zero exit status is considered an *error*.
-async fn run_git_capture_stdout(args: &[&str]) -> io::Result<String> {
+/// What to do with a single hunk from the diff viewer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HunkAction {
+    /// Add the hunk to the index.
+    Stage,
+    /// Undo the hunk in the working tree.
+    Revert,
+}
+
+/// Stage or revert the hunk in `patch` (a file header followed by one hunk,
+/// with paths relative to the repository root) using `git apply`.
+pub(crate) async fn apply_hunk(patch: &str, action: HunkAction) -> io::Result<()> {
+    apply_hunk_in(Path::new("."), patch, action).await
+}
+
+async fn apply_hunk_in(cwd: &Path, patch: &str, action: HunkAction) -> io::Result<()> {
+    let root = repo_root(cwd).await?;
+    let args: &[&str] = match action {
+        HunkAction::Stage => &["apply", "--cached", "-"],
+        HunkAction::Revert => &["apply", "--reverse", "-"],
+    };
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(&root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(patch.as_bytes()).await?;
+    }
+    let output = child.wait_with_output().await?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            stderr.trim()
+        )))
+    }
+}
+
+/// Top-level directory of the repository containing `cwd`.
+async fn repo_root(cwd: &Path) -> io::Result<PathBuf> {
+    let root = run_git_capture_stdout(cwd, &["rev-parse", "--show-toplevel"]).await?;
+    Ok(PathBuf::from(root.trim_end()))
+}
+
+/// Helper that executes `git` with the given `args` in `cwd` and returns
+/// `stdout` as a UTF-8 string. Any non-zero exit status is considered an
+/// *error*.
+async fn run_git_capture_stdout(cwd: &Path, args: &[&str]) -> io::Result<String> {
     let output = Command::new("git")
         .args(args)
+        .current_dir(cwd)
         .stdout(Stdio::piped())
         .stderr(Stdio::null())
         .output()
@@ -83,9 +152,10 @@ async fn run_git_capture_stdout(args: &[&str]) -> io::Result<String> {
 
 /// Like [`run_git_capture_stdout`] but treats exit status 1 as success and
 /// returns stdout. Git returns 1 for diffs when differences are present.
-async fn run_git_capture_diff(args: &[&str]) -> io::Result<String> {
+async fn run_git_capture_diff(cwd: &Path, args: &[&str]) -> io::Result<String> {
     let output = Command::new("git")
         .args(args)
+        .current_dir(cwd)
         .stdout(Stdio::piped())
         .stderr(Stdio::null())
         .output()
@@ -101,10 +171,11 @@ async fn run_git_capture_diff(args: &[&str]) -> io::Result<String> {
     }
 }
 
-/// Determine if the current directory is inside a Git repository.
-async fn inside_git_repo() -> io::Result<bool> {
+/// Determine if `cwd` is inside a Git repository.
+async fn inside_git_repo(cwd: &Path) -> io::Result<bool> {
     let status = Command::new("git")
         .args(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(cwd)
         .stdout(Stdio::null())
         .stderr(Stdio::null())
         .status()
@@ -117,3 +188,35 @@ async fn inside_git_repo() -> io::Result<bool> {
         Err(e) => Err(e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    async fn git(cwd: &Path, args: &[&str]) -> String {
+        run_git_capture_stdout(cwd, args)
+            .await
+            .expect("git succeeds")
+    }
+
+    #[tokio::test]
+    async fn untracked_hunks_apply_from_a_subdirectory() {
+        let repo = TempDir::new().unwrap();
+        git(repo.path(), &["init", "--quiet"]).await;
+        let sub = repo.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::write(sub.join("new.txt"), "hello\n").unwrap();
+
+        let (in_repo, diff) = git_diff_in(&sub).await.unwrap();
+        assert!(in_repo);
+        assert!(diff.contains("+++ b/sub/new.txt"), "{diff}");
+
+        apply_hunk_in(&sub, &diff, HunkAction::Stage).await.unwrap();
+        assert_eq!(
+            git(repo.path(), &["diff", "--cached", "--name-only"]).await,
+            "sub/new.txt\n"
+        );
+    }
+}
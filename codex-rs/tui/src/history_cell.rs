@@ -6,6 +6,7 @@ use crate::render::line_utils::line_to_static;
 use crate::render::line_utils::prefix_lines;
 use crate::render::line_utils::push_owned_lines;
 use crate::text_formatting::format_and_truncate_tool_result;
+use crate::theme;
 use crate::ui_consts::LIVE_PREFIX_COLS;
 use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
@@ -67,6 +68,17 @@ pub(crate) enum PatchEventType {
     ApplyBegin { auto_approved: bool },
 }
 
+/// Coarse classification of history cells, used by the transcript overlay to
+/// filter and jump between cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HistoryCellKind {
+    User,
+    Agent,
+    Command,
+    Patch,
+    Other,
+}
+
 /// Represents an event to display in the conversation history. Returns its
 /// `Vec<Line<'static>>` representation to make it easier to display in a
 /// scrollable list.
@@ -88,6 +100,10 @@ pub(crate) trait HistoryCell: std::fmt::Debug + Send + Sync {
     fn is_stream_continuation(&self) -> bool {
         false
     }
+
+    fn kind(&self) -> HistoryCellKind {
+        HistoryCellKind::Other
+    }
 }
 
 #[derive(Debug)]
@@ -119,6 +135,10 @@ impl HistoryCell for UserHistoryCell {
         lines.extend(self.message.lines().map(|l| l.to_string().into()));
         lines
     }
+
+    fn kind(&self) -> HistoryCellKind {
+        HistoryCellKind::User
+    }
 }
 
 #[derive(Debug)]
@@ -162,6 +182,10 @@ impl HistoryCell for AgentMessageCell {
     fn is_stream_continuation(&self) -> bool {
         !self.is_first_line
     }
+
+    fn kind(&self) -> HistoryCellKind {
+        HistoryCellKind::Agent
+    }
 }
 
 #[derive(Debug)]
@@ -206,6 +230,10 @@ impl HistoryCell for PatchHistoryCell {
             width as usize,
         )
     }
+
+    fn kind(&self) -> HistoryCellKind {
+        HistoryCellKind::Patch
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -237,7 +265,13 @@ impl HistoryCell for ExecCell {
             let cmd_display = strip_bash_lc_and_escape(&call.command);
             for (i, part) in cmd_display.lines().enumerate() {
                 if i == 0 {
-                    lines.push(vec!["$ ".magenta(), part.to_string().into()].into());
+                    lines.push(
+                        vec![
+                            Span::styled("$ ", theme::palette().command()),
+                            part.to_string().into(),
+                        ]
+                        .into(),
+                    );
                 } else {
                     lines.push(vec!["    ".into(), part.to_string().into()].into());
                 }
@@ -253,7 +287,7 @@ impl HistoryCell for ExecCell {
                     Line::from("✓".green().bold())
                 } else {
                     Line::from(vec![
-                        "✗".red().bold(),
+                        Span::styled("✗", theme::palette().error().bold()),
                         format!(" ({})", output.exit_code).into(),
                     ])
                 };
@@ -264,6 +298,10 @@ impl HistoryCell for ExecCell {
         }
         lines
     }
+
+    fn kind(&self) -> HistoryCellKind {
+        HistoryCellKind::Command
+    }
 }
 
 impl ExecCell {
@@ -392,7 +430,7 @@ impl ExecCell {
         let success = call.output.as_ref().map(|o| o.exit_code == 0);
         let bullet = match success {
             Some(true) => "•".green().bold(),
-            Some(false) => "•".red().bold(),
+            Some(false) => Span::styled("•", theme::palette().error().bold()),
             None => spinner(call.start_time),
         };
         let title = if self.is_active() { "Running" } else { "Ran" };
@@ -898,7 +936,10 @@ fn spinner(start_time: Option<Instant>) -> Span<'static> {
 }
 
 pub(crate) fn new_active_mcp_tool_call(invocation: McpInvocation) -> PlainHistoryCell {
-    let title_line = Line::from(vec!["tool".magenta(), " running...".dim()]);
+    let title_line = Line::from(vec![
+        Span::styled("tool", theme::palette().command()),
+        " running...".dim(),
+    ]);
     let lines: Vec<Line> = vec![title_line, format_mcp_invocation(invocation)];
 
     PlainHistoryCell { lines }
@@ -963,12 +1004,12 @@ pub(crate) fn new_completed_mcp_tool_call(
     let duration = format_duration(duration);
     let status_str = if success { "success" } else { "failed" };
     let title_line = Line::from(vec![
-        "tool".magenta(),
+        Span::styled("tool", theme::palette().command()),
         " ".into(),
         if success {
             status_str.green()
         } else {
-            status_str.red()
+            Span::styled(status_str, theme::palette().error())
         },
         format!(", duration: {duration}").dim(),
     ]);
@@ -1015,7 +1056,13 @@ pub(crate) fn new_completed_mcp_tool_call(
             }
         }
         Err(e) => {
-            lines.push(vec!["Error: ".red().bold(), e.into()].into());
+            lines.push(
+                vec![
+                    Span::styled("Error: ", theme::palette().error().bold()),
+                    e.into(),
+                ]
+                .into(),
+            );
         }
     };
 
@@ -1060,6 +1107,15 @@ pub(crate) fn new_status_output(
         SandboxPolicy::WorkspaceWrite { .. } => "workspace-write",
     };
     lines.push(vec!["  • Sandbox: ".into(), sandbox_name.into()].into());
+    if config.project_config_path.is_some() {
+        lines.push(
+            vec![
+                "  • Project Config: ".into(),
+                lookup("project config").into(),
+            ]
+            .into(),
+        );
+    }
 
     // AGENTS.md files discovered via core's project_doc logic
     let agents_list = {
@@ -1268,7 +1324,13 @@ pub(crate) fn new_error_event(message: String) -> PlainHistoryCell {
     // Use a hair space (U+200A) to create a subtle, near-invisible separation
     // before the text. VS16 is intentionally omitted to keep spacing tighter
     // in terminals like Ghostty.
-    let lines: Vec<Line<'static>> = vec![vec![format!("■ {message}").red()].into()];
+    let lines: Vec<Line<'static>> = vec![
+        vec![Span::styled(
+            format!("■ {message}"),
+            theme::palette().error(),
+        )]
+        .into(),
+    ];
     PlainHistoryCell { lines }
 }
 
@@ -1409,7 +1471,10 @@ pub(crate) fn new_reasoning_block(
     config: &Config,
 ) -> TranscriptOnlyHistoryCell {
     let mut lines: Vec<Line<'static>> = Vec::new();
-    lines.push(Line::from("thinking".magenta().italic()));
+    lines.push(Line::from(Span::styled(
+        "thinking",
+        theme::palette().reasoning().italic(),
+    )));
     append_markdown(&full_reasoning_buffer, &mut lines, config);
     TranscriptOnlyHistoryCell { lines }
 }
@@ -1437,11 +1502,17 @@ pub(crate) fn new_reasoning_summary_block(
                     let summary_buffer = full_reasoning_buffer[after_close_idx..].to_string();
 
                     let mut header_lines: Vec<Line<'static>> = Vec::new();
-                    header_lines.push(Line::from("Thinking".magenta().italic()));
+                    header_lines.push(Line::from(Span::styled(
+                        "Thinking",
+                        theme::palette().reasoning().italic(),
+                    )));
                     append_markdown(&header_buffer, &mut header_lines, config);
 
                     let mut summary_lines: Vec<Line<'static>> = Vec::new();
-                    summary_lines.push(Line::from("Thinking".magenta().bold()));
+                    summary_lines.push(Line::from(Span::styled(
+                        "Thinking",
+                        theme::palette().reasoning().bold(),
+                    )));
                     append_markdown(&summary_buffer, &mut summary_lines, config);
 
                     return vec![
@@ -1,3 +1,5 @@
+use codex_core::keymap::Key;
+use codex_core::keymap::KeyChord;
 use ratatui::style::Color;
 use ratatui::style::Style;
 use ratatui::text::Span;
@@ -47,3 +49,43 @@ pub(crate) fn shift(key: impl Display) -> Span<'static> {
 pub(crate) fn plain(key: impl Display) -> Span<'static> {
     Span::styled(format!("{key}"), key_hint_style())
 }
+
+/// Hint for a configurable key chord, e.g. `⌃T` or `⇧⏎`.
+pub(crate) fn chord(chord: KeyChord) -> Span<'static> {
+    Span::styled(chord_label(chord), key_hint_style())
+}
+
+/// Text used to display a configurable key chord.
+pub(crate) fn chord_label(chord: KeyChord) -> String {
+    let mut label = String::new();
+    if chord.ctrl {
+        label.push_str(CTRL_PREFIX);
+    }
+    if chord.alt {
+        label.push_str(ALT_PREFIX);
+    }
+    if chord.shift {
+        label.push_str(SHIFT_PREFIX);
+    }
+    let has_modifiers = !label.is_empty();
+    match chord.key {
+        Key::Char(' ') => label.push_str("Space"),
+        Key::Char(c) if has_modifiers => label.push(c.to_ascii_uppercase()),
+        Key::Char(c) => label.push(c),
+        Key::Enter => label.push('⏎'),
+        Key::Esc => label.push_str("Esc"),
+        Key::Tab => label.push_str("Tab"),
+        Key::Backspace => label.push_str("Backspace"),
+        Key::Delete => label.push_str("Del"),
+        Key::Up => label.push('↑'),
+        Key::Down => label.push('↓'),
+        Key::Left => label.push('←'),
+        Key::Right => label.push('→'),
+        Key::Home => label.push_str("Home"),
+        Key::End => label.push_str("End"),
+        Key::PageUp => label.push_str("PgUp"),
+        Key::PageDown => label.push_str("PgDn"),
+        Key::F(n) => label.push_str(&format!("F{n}")),
+    }
+    label
+}
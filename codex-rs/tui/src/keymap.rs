@@ -0,0 +1,89 @@
+//! Matching crossterm key events against the chords configured under
+//! `[tui.keymap]`.
+
+use codex_core::keymap::Key;
+use codex_core::keymap::KeyChord;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+
+/// Whether `event` is the key press described by `chord`.
+pub(crate) fn matches(chord: KeyChord, event: &KeyEvent) -> bool {
+    let modifiers = event.modifiers;
+    if modifiers.contains(KeyModifiers::CONTROL) != chord.ctrl
+        || modifiers.contains(KeyModifiers::ALT) != chord.alt
+    {
+        return false;
+    }
+    let shift = modifiers.contains(KeyModifiers::SHIFT);
+    match (chord.key, event.code) {
+        (Key::Char(c), KeyCode::Char(pressed)) if c.is_ascii_alphabetic() => {
+            // Terminals report Shift on letters as a modifier, as an
+            // upper-case character, or both.
+            c == pressed.to_ascii_lowercase()
+                && chord.shift == (shift || pressed.is_ascii_uppercase())
+        }
+        // Shifted symbols such as `?` arrive already shifted, with or without
+        // the modifier.
+        (Key::Char(c), KeyCode::Char(pressed)) => c == pressed,
+        (Key::Tab, KeyCode::BackTab) => chord.shift,
+        (key, code) => shift == chord.shift && key_code(key) == code,
+    }
+}
+
+fn key_code(key: Key) -> KeyCode {
+    match key {
+        Key::Char(c) => KeyCode::Char(c),
+        Key::Enter => KeyCode::Enter,
+        Key::Esc => KeyCode::Esc,
+        Key::Tab => KeyCode::Tab,
+        Key::Backspace => KeyCode::Backspace,
+        Key::Delete => KeyCode::Delete,
+        Key::Up => KeyCode::Up,
+        Key::Down => KeyCode::Down,
+        Key::Left => KeyCode::Left,
+        Key::Right => KeyCode::Right,
+        Key::Home => KeyCode::Home,
+        Key::End => KeyCode::End,
+        Key::PageUp => KeyCode::PageUp,
+        Key::PageDown => KeyCode::PageDown,
+        Key::F(n) => KeyCode::F(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(s: &str) -> KeyChord {
+        s.parse().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    #[test]
+    fn matches_modifiers_exactly() {
+        let ctrl_t = KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL);
+        assert!(matches(chord("ctrl-t"), &ctrl_t));
+        assert!(!matches(chord("t"), &ctrl_t));
+        assert!(!matches(chord("ctrl-alt-t"), &ctrl_t));
+
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        let shift_enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT);
+        assert!(matches(chord("enter"), &enter));
+        assert!(!matches(chord("enter"), &shift_enter));
+        assert!(matches(chord("shift-enter"), &shift_enter));
+    }
+
+    #[test]
+    fn shift_on_letters_and_symbols() {
+        let upper_p = KeyEvent::new(KeyCode::Char('P'), KeyModifiers::SHIFT);
+        assert!(matches(chord("shift-p"), &upper_p));
+        assert!(!matches(chord("p"), &upper_p));
+
+        let question = KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT);
+        assert!(matches(chord("?"), &question));
+
+        let back_tab = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert!(matches(chord("shift-tab"), &back_tab));
+        assert!(!matches(chord("tab"), &back_tab));
+    }
+}
@@ -15,6 +15,7 @@ use codex_core::config::GPT_5_CODEX_MEDIUM_MODEL;
 use codex_core::config::find_codex_home;
 use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::config::persist_model_selection;
+use codex_core::config_reload::ConfigReloader;
 use codex_core::find_conversation_path_by_id_str;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
@@ -41,16 +42,19 @@ mod clipboard_paste;
 pub mod custom_terminal;
 mod diff_render;
 mod exec_command;
+mod external_editor;
 mod file_search;
 mod frames;
 mod get_git_diff;
 mod history_cell;
 pub mod insert_history;
 mod key_hint;
+mod keymap;
 pub mod live_wrap;
 mod markdown;
 mod markdown_render;
 mod markdown_stream;
+mod mentions;
 mod new_model_popup;
 pub mod onboarding;
 mod pager_overlay;
@@ -61,9 +65,13 @@ mod shimmer;
 mod slash_command;
 mod status_indicator_widget;
 mod streaming;
+mod symbol_index;
+mod terminal_background;
 mod text_formatting;
+mod theme;
 mod tui;
 mod ui_consts;
+mod unified_diff;
 mod user_approval_widget;
 mod version;
 mod wrapping;
@@ -154,7 +162,7 @@ pub async fn run_main(
         // Load configuration and support CLI overrides.
 
         #[allow(clippy::print_stderr)]
-        match Config::load_with_cli_overrides(cli_kv_overrides.clone(), overrides) {
+        match Config::load_with_cli_overrides(cli_kv_overrides.clone(), overrides.clone()) {
             Ok(config) => config,
             Err(err) => {
                 eprintln!("Error loading configuration: {err}");
@@ -174,7 +182,7 @@ pub async fn run_main(
             }
         };
 
-        match load_config_as_toml_with_cli_overrides(&codex_home, cli_kv_overrides) {
+        match load_config_as_toml_with_cli_overrides(&codex_home, cli_kv_overrides.clone()) {
             Ok(config_toml) => config_toml,
             Err(err) => {
                 eprintln!("Error loading config.toml: {err}");
@@ -270,6 +278,9 @@ async fn run_ratatui_app(
         tracing::error!("panic: {info}");
         prev_hook(info);
     }));
+    // Query the terminal background before crossterm starts reading input,
+    // which would otherwise swallow the reply.
+    theme::init(&config);
     let mut terminal = tui::init()?;
     terminal.clear()?;
 
@@ -341,11 +352,45 @@ async fn run_ratatui_app(
         )
         .await?;
         if let Some(TrustDirectorySelection::Trust) = directory_trust_decision {
-            config.approval_policy = AskForApproval::OnRequest;
-            config.sandbox_policy = SandboxPolicy::new_workspace_write_policy();
+            // Project config settings held back until the project was trusted
+            // take effect now.
+            if !config.ignored_project_settings.is_empty() {
+                match Config::load_with_cli_overrides(cli_kv_overrides.clone(), overrides.clone()) {
+                    Ok(reloaded) => config = reloaded,
+                    Err(err) => error!("Failed to reload config for trusted project: {err}"),
+                }
+            }
+            apply_trusted_project_defaults(&mut config);
         }
     }
 
+    // Tell the user which project settings are held back so they can trust
+    // the project if they want them.
+    if let Some(path) = &config.project_config_path
+        && !config.ignored_project_settings.is_empty()
+    {
+        use ratatui::style::Stylize as _;
+        use ratatui::text::Line;
+
+        tui.insert_history_lines(vec![
+            Line::from(vec![
+                "⚠ ".magenta(),
+                "Ignoring ".into(),
+                config.ignored_project_settings.join(", ").bold(),
+                format!(
+                    " from {} because this project is not trusted.",
+                    path.display()
+                )
+                .into(),
+            ]),
+            Line::from(
+                "  Set trust_level = \"trusted\" for the project in config.toml to apply them."
+                    .dim(),
+            ),
+            "".into(),
+        ]);
+    }
+
     // Determine resume behavior: explicit id, then resume last, then picker.
     let resume_selection = if let Some(id_str) = cli.resume_session_id.as_deref() {
         match find_conversation_path_by_id_str(&config.codex_home, id_str).await? {
@@ -409,10 +454,20 @@ async fn run_ratatui_app(
 
     let Cli { prompt, images, .. } = cli;
 
+    // Watch config.toml so edits apply without restarting the session.
+    let config_reloader = match ConfigReloader::new(cli_kv_overrides, overrides) {
+        Ok(reloader) => Some(reloader),
+        Err(err) => {
+            error!("Failed to load config for reloading: {err}");
+            None
+        }
+    };
+
     let app_result = App::run(
         &mut tui,
         auth_manager,
         config,
+        config_reloader,
         active_profile,
         prompt,
         images,
@@ -490,8 +545,7 @@ fn determine_repo_trust_state(
     } else if config_toml.is_cwd_trusted(&config.cwd) {
         // if the current cwd project is trusted and no config has been set
         // skip the trust flow and set the approval policy and sandbox mode
-        config.approval_policy = AskForApproval::OnRequest;
-        config.sandbox_policy = SandboxPolicy::new_workspace_write_policy();
+        apply_trusted_project_defaults(config);
         Ok(false)
     } else {
         // if none of the above conditions are met, show the trust screen
@@ -499,6 +553,15 @@ fn determine_repo_trust_state(
     }
 }
 
+/// Trusted projects default to asking on request inside a writable workspace.
+/// A workspace-write or full-access sandbox from the project config is kept.
+fn apply_trusted_project_defaults(config: &mut Config) {
+    config.approval_policy = AskForApproval::OnRequest;
+    if matches!(config.sandbox_policy, SandboxPolicy::ReadOnly) {
+        config.sandbox_policy = SandboxPolicy::new_workspace_write_policy();
+    }
+}
+
 fn should_show_onboarding(
     login_status: LoginStatus,
     config: &Config,
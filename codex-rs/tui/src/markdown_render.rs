@@ -1,4 +1,6 @@
 use crate::citation_regex::CITATION_REGEX;
+use crate::render::highlight::highlight_code_to_lines;
+use crate::theme;
 use pulldown_cmark::CodeBlockKind;
 use pulldown_cmark::CowStr;
 use pulldown_cmark::Event;
@@ -75,6 +77,11 @@ where
     scheme: Option<String>,
     cwd: Option<std::path::PathBuf>,
     in_code_block: bool,
+    /// Language from the info string of the fenced code block being read.
+    code_block_lang: Option<String>,
+    /// Text of the current code block, rendered once the block ends so it can
+    /// be highlighted as a whole.
+    code_block_text: String,
 }
 
 impl<'a, I> Writer<'a, I>
@@ -95,6 +102,8 @@ where
             scheme,
             cwd,
             in_code_block: false,
+            code_block_lang: None,
+            code_block_text: String::new(),
         }
     }
 
@@ -241,16 +250,9 @@ where
             self.push_line(Line::default());
         }
         self.pending_marker_line = false;
-        if self.in_code_block
-            && !self.needs_newline
-            && self
-                .text
-                .lines
-                .last()
-                .map(|line| !line.spans.is_empty())
-                .unwrap_or(false)
-        {
-            self.push_line(Line::default());
+        if self.in_code_block {
+            self.code_block_text.push_str(&text);
+            return;
         }
         for (i, line) in text.lines().enumerate() {
             if self.needs_newline {
@@ -261,9 +263,7 @@ where
                 self.push_line(Line::default());
             }
             let mut content = line.to_string();
-            if !self.in_code_block
-                && let (Some(scheme), Some(cwd)) = (&self.scheme, &self.cwd)
-            {
+            if let (Some(scheme), Some(cwd)) = (&self.scheme, &self.cwd) {
                 let cow = rewrite_file_citations_with_scheme(&content, Some(scheme.as_str()), cwd);
                 if let std::borrow::Cow::Owned(s) = cow {
                     content = s;
@@ -283,7 +283,7 @@ where
             self.push_line(Line::default());
             self.pending_marker_line = false;
         }
-        let span = Span::from(code.into_string()).dim();
+        let span = Span::styled(code.into_string(), theme::palette().inline_code());
         self.push_span(span);
     }
 
@@ -354,11 +354,13 @@ where
         self.needs_newline = false;
     }
 
-    fn start_codeblock(&mut self, _lang: Option<String>, indent: Option<Span<'static>>) {
+    fn start_codeblock(&mut self, lang: Option<String>, indent: Option<Span<'static>>) {
         if !self.text.lines.is_empty() {
             self.push_blank_line();
         }
         self.in_code_block = true;
+        self.code_block_lang = lang;
+        self.code_block_text.clear();
         self.indent_stack.push(IndentContext::new(
             vec![indent.unwrap_or_default()],
             None,
@@ -373,12 +375,38 @@ where
     }
 
     fn end_codeblock(&mut self) {
+        self.flush_code_block();
         // self.push_line("```".into());
         self.needs_newline = true;
         self.in_code_block = false;
+        self.code_block_lang = None;
         self.indent_stack.pop();
     }
 
+    fn flush_code_block(&mut self) {
+        let code = std::mem::take(&mut self.code_block_text);
+        let lines = self
+            .code_block_lang
+            .as_deref()
+            .and_then(|lang| highlight_code_to_lines(&code, lang))
+            .unwrap_or_else(|| {
+                let style = self.inline_styles.last().copied().unwrap_or_default();
+                code.lines()
+                    .map(|line| Line::from(Span::styled(line.to_string(), style)))
+                    .collect()
+            });
+        for (i, line) in lines.into_iter().enumerate() {
+            if self.needs_newline || i > 0 {
+                self.push_line(Line::default());
+                self.needs_newline = false;
+            }
+            for span in line.spans {
+                self.push_span(span);
+            }
+        }
+        self.needs_newline = false;
+    }
+
     fn push_inline_style(&mut self, style: Style) {
         let current = self.inline_styles.last().copied().unwrap_or_default();
         let merged = current.patch(style);
@@ -0,0 +1,428 @@
+//! `@` mentions that attach file contents to a prompt.
+//!
+//! Besides the paths inserted by the file search popup, a few forms pull
+//! content into the prompt so the model does not have to spend a tool call
+//! reading it:
+//!
+//! - `@path/to/file.rs`: the whole file,
+//! - `@path/to/file.rs#L10-80` (or `#L10`): a line range,
+//! - `@path/to/dir/`: a listing of the directory,
+//! - `@fn:name` / `@type:name`: the definition of a symbol, found with a
+//!   tree-sitter index of the workspace.
+//!
+//! Attachments are capped in size and sent as [`InputItem::Attachment`]
+//! items next to the prompt text.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+use codex_core::protocol::InputItem;
+use ignore::WalkBuilder;
+
+use crate::symbol_index::SymbolIndex;
+use crate::symbol_index::SymbolKind;
+
+/// Contents of a single attachment are cut after this many bytes.
+pub(crate) const MAX_ATTACHMENT_BYTES: usize = 32 * 1024;
+/// Mentions past this many bytes of attachments in one prompt are skipped.
+pub(crate) const MAX_TOTAL_ATTACHMENT_BYTES: usize = 128 * 1024;
+/// Entries listed for a directory mention.
+const MAX_DIR_ENTRIES: usize = 200;
+const MAX_DIR_DEPTH: usize = 3;
+/// Definitions attached for a symbol with several matches.
+const MAX_SYMBOL_MATCHES: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Mention {
+    File {
+        path: String,
+        /// 1-based, inclusive line range.
+        lines: Option<(usize, usize)>,
+    },
+    Dir {
+        path: String,
+    },
+    Symbol {
+        kind: SymbolKind,
+        name: String,
+    },
+}
+
+impl Mention {
+    /// Whether the mention uses attachment syntax explicitly, so failing to
+    /// resolve it is worth reporting. A bare `@word` may just be prose.
+    fn is_explicit(&self) -> bool {
+        !matches!(self, Mention::File { lines: None, .. })
+    }
+}
+
+/// Parse the text after `@` as a mention.
+pub(crate) fn parse_mention(token: &str) -> Option<Mention> {
+    if let Some(name) = token.strip_prefix("fn:") {
+        return symbol_mention(SymbolKind::Function, name);
+    }
+    if let Some(name) = token.strip_prefix("type:") {
+        return symbol_mention(SymbolKind::Type, name);
+    }
+    if let Some((path, range)) = token.split_once("#L") {
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (start, end.strip_prefix('L').unwrap_or(end)),
+            None => (range, range),
+        };
+        let start: usize = start.parse().ok()?;
+        let end: usize = end.parse().ok()?;
+        if path.is_empty() || start == 0 || end < start {
+            return None;
+        }
+        return Some(Mention::File {
+            path: path.to_string(),
+            lines: Some((start, end)),
+        });
+    }
+    if token.is_empty() || token.contains('#') {
+        return None;
+    }
+    if token.ends_with('/') {
+        return Some(Mention::Dir {
+            path: token.to_string(),
+        });
+    }
+    Some(Mention::File {
+        path: token.to_string(),
+        lines: None,
+    })
+}
+
+fn symbol_mention(kind: SymbolKind, name: &str) -> Option<Mention> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    valid.then(|| Mention::Symbol {
+        kind,
+        name: name.to_string(),
+    })
+}
+
+/// The `@` mentions in `text` as `(token, mention)`, where `token` is the
+/// mention as typed, including the `@`.
+pub(crate) fn find_mentions(text: &str) -> Vec<(String, Mention)> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            let word = word.trim_end_matches([',', ';', ')', '!', '?', '"', '\'']);
+            let mention = parse_mention(word.strip_prefix('@')?)?;
+            Some((word.to_string(), mention))
+        })
+        .collect()
+}
+
+/// Resolved contents of a mention.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Attachment {
+    pub(crate) mention: String,
+    pub(crate) path: PathBuf,
+    pub(crate) lines: Option<(usize, usize)>,
+    pub(crate) contents: String,
+}
+
+impl Attachment {
+    /// Rough token count, at about four bytes per token.
+    pub(crate) fn estimated_tokens(&self) -> usize {
+        self.contents.len().div_ceil(4)
+    }
+
+    pub(crate) fn into_input_item(self) -> InputItem {
+        InputItem::Attachment {
+            mention: self.mention,
+            path: self.path,
+            start_line: self.lines.map(|(start, _)| start as u64),
+            end_line: self.lines.map(|(_, end)| end as u64),
+            contents: self.contents,
+        }
+    }
+}
+
+/// Resolves mentions against a workspace root. Resolving reads files and
+/// may build the symbol index, so it belongs on a blocking thread; clones
+/// share the index.
+#[derive(Clone)]
+pub(crate) struct MentionResolver {
+    root: PathBuf,
+    symbols: Arc<OnceLock<SymbolIndex>>,
+}
+
+impl MentionResolver {
+    pub(crate) fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            symbols: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Resolve every mention in `text`. Returns the attachments and a
+    /// message for each explicit mention that could not be attached.
+    pub(crate) fn resolve_all(&self, text: &str) -> (Vec<Attachment>, Vec<String>) {
+        let mut attachments: Vec<Attachment> = Vec::new();
+        let mut errors = Vec::new();
+        let mut total = 0;
+        for (token, mention) in find_mentions(text) {
+            if attachments.iter().any(|a| a.mention == token) {
+                continue;
+            }
+            match self.resolve(&token, &mention) {
+                Ok(resolved) => {
+                    for attachment in resolved {
+                        // A skipped attachment does not count, so smaller
+                        // mentions after it can still fit.
+                        if total + attachment.contents.len() > MAX_TOTAL_ATTACHMENT_BYTES {
+                            errors.push(format!(
+                                "{token}: skipped, attachments are limited to {} KiB per message",
+                                MAX_TOTAL_ATTACHMENT_BYTES / 1024
+                            ));
+                            break;
+                        }
+                        total += attachment.contents.len();
+                        attachments.push(attachment);
+                    }
+                }
+                Err(err) if mention.is_explicit() => errors.push(format!("{token}: {err}")),
+                Err(_) => {}
+            }
+        }
+        (attachments, errors)
+    }
+
+    /// Resolve a single mention. Symbols can resolve to several definitions.
+    pub(crate) fn resolve(
+        &self,
+        token: &str,
+        mention: &Mention,
+    ) -> Result<Vec<Attachment>, String> {
+        match mention {
+            Mention::File { path, lines } => {
+                let (contents, lines) = read_lines(&self.root.join(path), *lines)?;
+                Ok(vec![Attachment {
+                    mention: token.to_string(),
+                    path: PathBuf::from(path),
+                    lines,
+                    contents,
+                }])
+            }
+            Mention::Dir { path } => Ok(vec![Attachment {
+                mention: token.to_string(),
+                path: PathBuf::from(path),
+                lines: None,
+                contents: list_dir(&self.root.join(path))?,
+            }]),
+            Mention::Symbol { kind, name } => {
+                let index = self.symbols.get_or_init(|| SymbolIndex::build(&self.root));
+                let defs = index.lookup(*kind, name);
+                if defs.is_empty() {
+                    return Err("no definition found".to_string());
+                }
+                defs.into_iter()
+                    .take(MAX_SYMBOL_MATCHES)
+                    .map(|def| {
+                        let (contents, lines) = read_lines(
+                            &self.root.join(&def.path),
+                            Some((def.start_line, def.end_line)),
+                        )?;
+                        Ok(Attachment {
+                            mention: token.to_string(),
+                            path: def.path.clone(),
+                            lines,
+                            contents,
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Read `path`, or only `lines` of it, capped at [`MAX_ATTACHMENT_BYTES`].
+/// Returns the text and the line range actually read.
+fn read_lines(
+    path: &Path,
+    lines: Option<(usize, usize)>,
+) -> Result<(String, Option<(usize, usize)>), String> {
+    let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+    if bytes.iter().take(8000).any(|b| *b == 0) {
+        return Err("binary files cannot be attached".to_string());
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let (selected, lines) = match lines {
+        None => (text.to_string(), None),
+        Some((start, end)) => {
+            let total = text.lines().count();
+            if start > total {
+                return Err(format!("the file has only {total} lines"));
+            }
+            let end = end.min(total);
+            let selected: Vec<&str> = text.lines().skip(start - 1).take(end - start + 1).collect();
+            (selected.join("\n"), Some((start, end)))
+        }
+    };
+    Ok((truncate_contents(selected), lines))
+}
+
+fn truncate_contents(mut contents: String) -> String {
+    if contents.len() <= MAX_ATTACHMENT_BYTES {
+        return contents;
+    }
+    let mut cut = MAX_ATTACHMENT_BYTES;
+    while !contents.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    if let Some(newline) = contents[..cut].rfind('\n') {
+        cut = newline;
+    }
+    let omitted = contents.len() - cut;
+    contents.truncate(cut);
+    contents.push_str(&format!("\n[… {omitted} more bytes not attached]"));
+    contents
+}
+
+/// A listing of `dir` a few levels deep, respecting `.gitignore`.
+fn list_dir(dir: &Path) -> Result<String, String> {
+    if !dir.is_dir() {
+        return Err("not a directory".to_string());
+    }
+    let mut entries = Vec::new();
+    let mut omitted = 0;
+    for entry in WalkBuilder::new(dir)
+        .max_depth(Some(MAX_DIR_DEPTH))
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build()
+        .flatten()
+    {
+        let Ok(relative) = entry.path().strip_prefix(dir) else {
+            continue;
+        };
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        if entries.len() >= MAX_DIR_ENTRIES {
+            omitted += 1;
+            continue;
+        }
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        let suffix = if is_dir { "/" } else { "" };
+        entries.push(format!("{}{suffix}", relative.display()));
+    }
+    if omitted > 0 {
+        entries.push(format!("[… {omitted} more entries]"));
+    }
+    Ok(truncate_contents(entries.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_mention_forms() {
+        assert_eq!(
+            parse_mention("src/lib.rs#L10-80"),
+            Some(Mention::File {
+                path: "src/lib.rs".to_string(),
+                lines: Some((10, 80)),
+            })
+        );
+        assert_eq!(
+            parse_mention("src/lib.rs#L7"),
+            Some(Mention::File {
+                path: "src/lib.rs".to_string(),
+                lines: Some((7, 7)),
+            })
+        );
+        assert_eq!(
+            parse_mention("src/"),
+            Some(Mention::Dir {
+                path: "src/".to_string()
+            })
+        );
+        assert_eq!(
+            parse_mention("fn:parse_command"),
+            Some(Mention::Symbol {
+                kind: SymbolKind::Function,
+                name: "parse_command".to_string(),
+            })
+        );
+        assert_eq!(parse_mention("src/lib.rs#L80-10"), None);
+        assert_eq!(parse_mention("fn:"), None);
+        assert_eq!(
+            find_mentions("look at @a.rs#L1-2, and mail me@example.com")
+                .into_iter()
+                .map(|(token, _)| token)
+                .collect::<Vec<_>>(),
+            vec!["@a.rs#L1-2".to_string()]
+        );
+    }
+
+    #[test]
+    fn resolves_files_ranges_and_dirs() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join("src")).expect("mkdir");
+        std::fs::write(dir.path().join("src/a.txt"), "one\ntwo\nthree\n").expect("write");
+        let resolver = MentionResolver::new(dir.path().to_path_buf());
+
+        let (attachments, errors) =
+            resolver.resolve_all("see @src/a.txt#L2-9 and @src/ but not @missing.txt or @nope#L1");
+        assert_eq!(
+            attachments,
+            vec![
+                Attachment {
+                    mention: "@src/a.txt#L2-9".to_string(),
+                    path: PathBuf::from("src/a.txt"),
+                    lines: Some((2, 3)),
+                    contents: "two\nthree".to_string(),
+                },
+                Attachment {
+                    mention: "@src/".to_string(),
+                    path: PathBuf::from("src/"),
+                    lines: None,
+                    contents: "a.txt".to_string(),
+                },
+            ]
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("@nope#L1: "));
+    }
+
+    #[test]
+    fn mentions_after_an_oversized_one_still_fit() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        // Each file is cut to MAX_ATTACHMENT_BYTES, so five of them are over
+        // the per-message limit.
+        let large = "x".repeat(MAX_ATTACHMENT_BYTES - 100);
+        let mut text = String::new();
+        for name in ["a", "b", "c", "d", "e"] {
+            std::fs::write(dir.path().join(format!("{name}.txt")), &large).expect("write");
+            text.push_str(&format!("@{name}.txt "));
+        }
+        std::fs::write(dir.path().join("small.txt"), "small").expect("write");
+        text.push_str("@small.txt");
+        let resolver = MentionResolver::new(dir.path().to_path_buf());
+
+        let (attachments, errors) = resolver.resolve_all(&text);
+        let mentions: Vec<&str> = attachments.iter().map(|a| a.mention.as_str()).collect();
+        assert_eq!(
+            mentions,
+            vec!["@a.txt", "@b.txt", "@c.txt", "@d.txt", "@small.txt"]
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("@e.txt: skipped"));
+    }
+
+    #[test]
+    fn truncates_large_attachments() {
+        let contents = "x".repeat(10) + "\n" + &"y".repeat(MAX_ATTACHMENT_BYTES);
+        let truncated = truncate_contents(contents);
+        assert!(truncated.starts_with("xxxxxxxxxx\n["));
+        assert!(truncated.ends_with("more bytes not attached]"));
+    }
+}
@@ -1,15 +1,35 @@
+use std::collections::HashMap;
 use std::io::Result;
+use std::ops::Range;
+use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::diff_render::create_diff_file_list;
+use crate::diff_render::render_side_by_side_diff;
+use crate::diff_render::render_unified_diff;
+use crate::get_git_diff::HunkAction;
+use crate::history_cell::HistoryCellKind;
+use crate::key_hint;
+use crate::keymap;
 use crate::render::line_utils::push_owned_lines;
+use crate::theme;
 use crate::tui;
 use crate::tui::TuiEvent;
+use crate::unified_diff::DiffFile;
+use crate::unified_diff::parse_unified_diff;
+use codex_core::git_info::get_git_repo_root;
+use codex_core::keymap::Keymap;
+use codex_core::protocol::FileChange;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Color;
+use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::style::Styled;
 use ratatui::style::Stylize;
@@ -20,29 +40,43 @@ use ratatui::widgets::WidgetRef;
 
 pub(crate) enum Overlay {
     Transcript(TranscriptOverlay),
-    Static(StaticOverlay),
+    Diff(DiffOverlay),
 }
 
 impl Overlay {
-    pub(crate) fn new_transcript(lines: Vec<Line<'static>>) -> Self {
-        Self::Transcript(TranscriptOverlay::new(lines))
+    pub(crate) fn new_transcript(
+        lines: Vec<Line<'static>>,
+        cells: Vec<TranscriptCell>,
+        keymap: Keymap,
+    ) -> Self {
+        Self::Transcript(TranscriptOverlay::new(lines, cells, keymap))
     }
 
-    pub(crate) fn new_static_with_title(lines: Vec<Line<'static>>, title: String) -> Self {
-        Self::Static(StaticOverlay::with_title(lines, title))
+    pub(crate) fn new_diff(
+        working_tree_diff: String,
+        turn_diff: Option<&str>,
+        cwd: PathBuf,
+        app_event_tx: AppEventSender,
+    ) -> Self {
+        Self::Diff(DiffOverlay::new(
+            working_tree_diff,
+            turn_diff,
+            cwd,
+            app_event_tx,
+        ))
     }
 
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match self {
             Overlay::Transcript(o) => o.handle_event(tui, event),
-            Overlay::Static(o) => o.handle_event(tui, event),
+            Overlay::Diff(o) => o.handle_event(tui, event),
         }
     }
 
     pub(crate) fn is_done(&self) -> bool {
         match self {
             Overlay::Transcript(o) => o.is_done(),
-            Overlay::Static(o) => o.is_done(),
+            Overlay::Diff(o) => o.is_done(),
         }
     }
 }
@@ -78,6 +112,15 @@ struct PagerView {
     title: String,
     wrap_cache: Option<WrapCache>,
     last_content_height: Option<usize>,
+    /// Byte ranges within source lines to restyle, e.g. search matches.
+    line_highlights: HashMap<usize, Vec<(Range<usize>, Style)>>,
+    /// Source line ranges to show; all lines when `None`.
+    visible_ranges: Option<Vec<Range<usize>>>,
+    /// Bumped whenever highlights or visibility change so the wrap cache is
+    /// rebuilt.
+    generation: u64,
+    /// Source line to scroll to on the next render, once wrapping is known.
+    pending_scroll_to: Option<usize>,
 }
 
 impl PagerView {
@@ -88,6 +131,10 @@ impl PagerView {
             title,
             wrap_cache: None,
             last_content_height: None,
+            line_highlights: HashMap::new(),
+            visible_ranges: None,
+            generation: 0,
+            pending_scroll_to: None,
         }
     }
 
@@ -96,6 +143,7 @@ impl PagerView {
         let content_area = self.scroll_area(area);
         self.update_last_content_height(content_area.height);
         self.ensure_wrapped(content_area.width);
+        self.apply_pending_scroll();
         // Compute page bounds without holding an immutable borrow on cache while mutating self
         let wrapped_len = self
             .wrap_cache
@@ -124,6 +172,7 @@ impl PagerView {
         let content_area = self.scroll_area(area);
         self.update_last_content_height(content_area.height);
         self.ensure_wrapped(content_area.width);
+        self.apply_pending_scroll();
         // Compute page bounds first to avoid borrow conflicts
         let wrapped_len = self
             .wrap_cache
@@ -254,6 +303,45 @@ impl PagerView {
         Ok(())
     }
 
+    fn set_line_highlights(&mut self, highlights: HashMap<usize, Vec<(Range<usize>, Style)>>) {
+        self.line_highlights = highlights;
+        self.generation += 1;
+    }
+
+    fn set_visible_ranges(&mut self, ranges: Option<Vec<Range<usize>>>) {
+        self.visible_ranges = ranges;
+        self.generation += 1;
+    }
+
+    fn is_line_visible(&self, line: usize) -> bool {
+        self.visible_ranges
+            .as_ref()
+            .is_none_or(|ranges| ranges.iter().any(|range| range.contains(&line)))
+    }
+
+    /// Scroll so that source line `line` is at the top on the next render.
+    fn scroll_to_line(&mut self, line: usize) {
+        self.pending_scroll_to = Some(line);
+    }
+
+    fn apply_pending_scroll(&mut self) {
+        let Some(target) = self.pending_scroll_to.take() else {
+            return;
+        };
+        if let Some(cache) = &self.wrap_cache
+            && let Some(row) = cache.src_idx.iter().position(|&src| src >= target)
+        {
+            self.scroll_offset = row;
+        }
+    }
+
+    /// Source line shown in the first row of the last render.
+    fn top_line(&self) -> Option<usize> {
+        let cache = self.wrap_cache.as_ref()?;
+        let last = cache.src_idx.len().checked_sub(1)?;
+        cache.src_idx.get(self.scroll_offset.min(last)).copied()
+    }
+
     fn update_last_content_height(&mut self, height: u16) {
         self.last_content_height = Some(height as usize);
     }
@@ -272,13 +360,18 @@ struct WrapCache {
     wrapped: Vec<Line<'static>>,
     src_idx: Vec<usize>,
     base_len: usize,
+    generation: u64,
 }
 
 impl PagerView {
     fn ensure_wrapped(&mut self, width: u16) {
         let width = width.max(1);
         let needs = match self.wrap_cache {
-            Some(ref c) => c.width != width || c.base_len != self.lines.len(),
+            Some(ref c) => {
+                c.width != width
+                    || c.base_len != self.lines.len()
+                    || c.generation != self.generation
+            }
             None => true,
         };
         if !needs {
@@ -287,6 +380,17 @@ impl PagerView {
         let mut wrapped: Vec<Line<'static>> = Vec::new();
         let mut src_idx: Vec<usize> = Vec::new();
         for (i, line) in self.lines.iter().enumerate() {
+            if !self.is_line_visible(i) {
+                continue;
+            }
+            let restyled;
+            let line = match self.line_highlights.get(&i) {
+                Some(highlights) => {
+                    restyled = restyle_ranges(line, highlights);
+                    &restyled
+                }
+                None => line,
+            };
             let ws = crate::wrapping::word_wrap_line(line, width as usize);
             src_idx.extend(std::iter::repeat_n(i, ws.len()));
             push_owned_lines(&ws, &mut wrapped);
@@ -296,6 +400,7 @@ impl PagerView {
             wrapped,
             src_idx,
             base_len: self.lines.len(),
+            generation: self.generation,
         });
     }
 
@@ -315,7 +420,6 @@ impl PagerView {
         end: usize,
         highlight: Option<(usize, usize)>,
     ) -> std::borrow::Cow<'a, [Line<'static>]> {
-        use ratatui::style::Modifier;
         let (hi_start, hi_end) = match highlight {
             Some(r) => r,
             None => return std::borrow::Cow::Borrowed(&wrapped[start..end]),
@@ -365,29 +469,122 @@ impl PagerView {
     }
 }
 
+/// Restyle the byte ranges of `line` (offsets into its concatenated span
+/// contents) by patching `style` onto the existing span styles.
+fn restyle_ranges(line: &Line<'static>, highlights: &[(Range<usize>, Style)]) -> Line<'static> {
+    let mut spans: Vec<Span<'static>> = Vec::with_capacity(line.spans.len());
+    let mut offset = 0;
+    for span in &line.spans {
+        let content = span.content.as_ref();
+        let span_end = offset + content.len();
+        let mut cuts = vec![offset, span_end];
+        for (range, _) in highlights {
+            cuts.extend(
+                [range.start, range.end]
+                    .into_iter()
+                    .filter(|&cut| cut > offset && cut < span_end),
+            );
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+        for piece in cuts.windows(2) {
+            let (start, end) = (piece[0], piece[1]);
+            let style = highlights
+                .iter()
+                .find(|(range, _)| range.start <= start && start < range.end)
+                .map_or(span.style, |(_, style)| span.style.patch(*style));
+            spans.push(Span::styled(
+                content[start - offset..end - offset].to_string(),
+                style,
+            ));
+        }
+        offset = span_end;
+    }
+    let mut out = line.clone();
+    out.spans = spans;
+    out
+}
+
+/// Where a history cell starts in the transcript and what kind it is. A cell
+/// extends to the start of the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TranscriptCell {
+    pub(crate) start: usize,
+    pub(crate) kind: HistoryCellKind,
+}
+
+/// Cell kinds the transcript can be narrowed to, cycled with `f`.
+const CELL_FILTERS: &[(Option<HistoryCellKind>, &str)] = &[
+    (None, "all"),
+    (Some(HistoryCellKind::User), "user messages"),
+    (Some(HistoryCellKind::Agent), "agent messages"),
+    (Some(HistoryCellKind::Command), "commands"),
+    (Some(HistoryCellKind::Patch), "patches"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SearchMatch {
+    line: usize,
+    range: Range<usize>,
+}
+
+#[derive(Debug, Default)]
+struct TranscriptSearch {
+    /// Query being typed after `/`; `None` when not editing.
+    input: Option<String>,
+    query: String,
+    matches: Vec<SearchMatch>,
+    current: Option<usize>,
+}
+
 pub(crate) struct TranscriptOverlay {
     view: PagerView,
+    cells: Vec<TranscriptCell>,
     highlight_range: Option<(usize, usize)>,
+    search: TranscriptSearch,
+    /// Index into `CELL_FILTERS`.
+    filter: usize,
+    /// Cell reached by the last search match or cell jump.
+    selected_cell: Option<usize>,
     is_done: bool,
+    keymap: Keymap,
 }
 
 impl TranscriptOverlay {
-    pub(crate) fn new(transcript_lines: Vec<Line<'static>>) -> Self {
+    pub(crate) fn new(
+        transcript_lines: Vec<Line<'static>>,
+        cells: Vec<TranscriptCell>,
+        keymap: Keymap,
+    ) -> Self {
         Self {
             view: PagerView::new(
                 transcript_lines,
                 "T R A N S C R I P T".to_string(),
                 usize::MAX,
             ),
+            cells,
             highlight_range: None,
+            search: TranscriptSearch::default(),
+            filter: 0,
+            selected_cell: None,
             is_done: false,
+            keymap,
         }
     }
 
-    pub(crate) fn insert_lines(&mut self, lines: Vec<Line<'static>>) {
+    /// Append the lines of a history cell. `cell` is `None` for stream
+    /// continuations, which extend the previous cell.
+    pub(crate) fn insert_cell(&mut self, lines: Vec<Line<'static>>, cell: Option<TranscriptCell>) {
         let follow_bottom = self.view.is_scrolled_to_bottom();
         self.view.lines.extend(lines);
         self.view.wrap_cache = None;
+        self.cells.extend(cell);
+        if self.filter != 0 {
+            self.view.set_visible_ranges(self.filtered_ranges());
+        }
+        if !self.search.query.is_empty() {
+            self.refresh_matches();
+        }
         if follow_bottom {
             self.view.scroll_offset = usize::MAX;
         }
@@ -397,17 +594,250 @@ impl TranscriptOverlay {
         self.highlight_range = range;
     }
 
+    /// True while a search query is being typed; all keys go to the overlay.
+    pub(crate) fn is_editing_search(&self) -> bool {
+        self.search.input.is_some()
+    }
+
+    /// First line of the cell reached by the last search match or cell jump.
+    pub(crate) fn selected_line(&self) -> Option<usize> {
+        self.selected_cell
+            .and_then(|idx| self.cells.get(idx))
+            .map(|cell| cell.start)
+    }
+
+    /// Show every cell again, e.g. before the backtrack preview scrolls to a
+    /// message by its position in the full transcript.
+    pub(crate) fn clear_filter(&mut self) {
+        if self.filter != 0 {
+            self.filter = 0;
+            self.view.set_visible_ranges(None);
+            self.refresh_matches();
+        }
+    }
+
+    fn filter_kind(&self) -> Option<HistoryCellKind> {
+        CELL_FILTERS.get(self.filter).and_then(|(kind, _)| *kind)
+    }
+
+    fn cell_range(&self, idx: usize) -> Range<usize> {
+        let start = self.cells.get(idx).map_or(0, |cell| cell.start);
+        let end = self
+            .cells
+            .get(idx + 1)
+            .map_or(self.view.lines.len(), |cell| cell.start);
+        start..end
+    }
+
+    fn cell_is_visible(&self, idx: usize) -> bool {
+        self.filter_kind()
+            .is_none_or(|kind| self.cells.get(idx).is_some_and(|cell| cell.kind == kind))
+    }
+
+    fn cell_for_line(&self, line: usize) -> Option<usize> {
+        self.cells.iter().rposition(|cell| cell.start <= line)
+    }
+
+    fn filtered_ranges(&self) -> Option<Vec<Range<usize>>> {
+        let kind = self.filter_kind()?;
+        Some(
+            (0..self.cells.len())
+                .filter(|&idx| self.cells[idx].kind == kind)
+                .map(|idx| self.cell_range(idx))
+                .collect(),
+        )
+    }
+
+    fn cycle_filter(&mut self) {
+        self.filter = (self.filter + 1) % CELL_FILTERS.len();
+        self.selected_cell = None;
+        self.view.set_visible_ranges(self.filtered_ranges());
+        self.view.scroll_offset = usize::MAX;
+        self.refresh_matches();
+    }
+
+    /// Recompute matches for the current query over the visible lines. The
+    /// search is case-insensitive unless the query contains upper case.
+    fn refresh_matches(&mut self) {
+        let query = &self.search.query;
+        let case_sensitive = query.chars().any(char::is_uppercase);
+        let needle = if case_sensitive {
+            query.clone()
+        } else {
+            query.to_ascii_lowercase()
+        };
+        let mut matches = Vec::new();
+        if !needle.is_empty() {
+            for (idx, line) in self.view.lines.iter().enumerate() {
+                if !self.view.is_line_visible(idx) {
+                    continue;
+                }
+                let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+                let haystack = if case_sensitive {
+                    text
+                } else {
+                    text.to_ascii_lowercase()
+                };
+                let mut from = 0;
+                while let Some(pos) = haystack[from..].find(&needle) {
+                    let start = from + pos;
+                    from = start + needle.len();
+                    matches.push(SearchMatch {
+                        line: idx,
+                        range: start..from,
+                    });
+                }
+            }
+        }
+        self.search.current = self
+            .search
+            .current
+            .filter(|&current| current < matches.len());
+        self.search.matches = matches;
+        self.update_match_highlights();
+    }
+
+    fn update_match_highlights(&mut self) {
+        let mut highlights: HashMap<usize, Vec<(Range<usize>, Style)>> = HashMap::new();
+        for (idx, m) in self.search.matches.iter().enumerate() {
+            let style = if Some(idx) == self.search.current {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::REVERSED | Modifier::BOLD)
+            } else {
+                Style::default().add_modifier(Modifier::REVERSED)
+            };
+            highlights
+                .entry(m.line)
+                .or_default()
+                .push((m.range.clone(), style));
+        }
+        self.view.set_line_highlights(highlights);
+    }
+
+    /// Move to the next (or previous) match, starting from the top of the
+    /// screen when no match is selected yet.
+    fn step_match(&mut self, forward: bool) {
+        let len = self.search.matches.len();
+        if len == 0 {
+            return;
+        }
+        let top = self.view.top_line().unwrap_or(0);
+        let next = match (self.search.current, forward) {
+            (Some(current), true) => (current + 1) % len,
+            (Some(current), false) => (current + len - 1) % len,
+            (None, true) => self
+                .search
+                .matches
+                .iter()
+                .position(|m| m.line >= top)
+                .unwrap_or(0),
+            (None, false) => self
+                .search
+                .matches
+                .iter()
+                .rposition(|m| m.line < top)
+                .unwrap_or(len - 1),
+        };
+        self.search.current = Some(next);
+        let line = self.search.matches[next].line;
+        self.selected_cell = self.cell_for_line(line);
+        self.view.scroll_to_line(line);
+        self.update_match_highlights();
+    }
+
+    /// Jump to the next (or previous) visible cell.
+    fn step_cell(&mut self, forward: bool) {
+        let reference = self
+            .selected_cell
+            .or_else(|| self.view.top_line().and_then(|top| self.cell_for_line(top)));
+        let target = if forward {
+            let from = reference.map_or(0, |idx| idx + 1);
+            (from..self.cells.len()).find(|&idx| self.cell_is_visible(idx))
+        } else {
+            let to = reference.unwrap_or(self.cells.len());
+            (0..to).rev().find(|&idx| self.cell_is_visible(idx))
+        };
+        if let Some(idx) = target {
+            self.selected_cell = Some(idx);
+            self.view.scroll_to_line(self.cells[idx].start);
+        }
+    }
+
+    /// Handle a key while the search query is being typed.
+    fn handle_search_input(&mut self, key_event: KeyEvent) {
+        let Some(input) = self.search.input.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Enter => {
+                self.search.query = self.search.input.take().unwrap_or_default();
+                self.search.current = None;
+                self.refresh_matches();
+                self.step_match(true);
+            }
+            KeyCode::Esc => {
+                self.search.input = None;
+            }
+            KeyCode::Backspace => {
+                if input.pop().is_none() {
+                    self.search.input = None;
+                }
+            }
+            KeyCode::Char(c)
+                if !key_event
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                input.push(c);
+            }
+            _ => {}
+        }
+    }
+
     fn render_hints(&self, area: Rect, buf: &mut Buffer) {
         let line1 = Rect::new(area.x, area.y, area.width, 1);
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
+        let line3 = Rect::new(area.x, area.y.saturating_add(2), area.width, 1);
         render_key_hints(line1, buf, PAGER_KEY_HINTS);
-        let mut pairs: Vec<(&str, &str)> = vec![("q", "quit"), ("Esc", "edit prev")];
-        if let Some((start, end)) = self.highlight_range
-            && end > start
-        {
+        let backtrack_key = key_hint::chord_label(self.keymap.backtrack);
+        let mut pairs: Vec<(&str, &str)> = vec![("q", "quit"), (&backtrack_key, "edit prev")];
+        let previewing = matches!(self.highlight_range, Some((start, end)) if end > start);
+        if previewing {
             pairs.push(("⏎", "edit message"));
         }
         render_key_hints(line2, buf, &pairs);
+
+        if let Some(input) = &self.search.input {
+            let spans: Vec<Span<'static>> = vec![" /".cyan(), input.clone().into(), "▏".dim()];
+            Paragraph::new(Line::from(spans)).render_ref(line3, buf);
+            return;
+        }
+        let match_status = match (self.search.query.is_empty(), self.search.current) {
+            (true, _) => None,
+            (false, _) if self.search.matches.is_empty() => Some("no matches".to_string()),
+            (false, Some(current)) => Some(format!(
+                "match {}/{}",
+                current + 1,
+                self.search.matches.len()
+            )),
+            (false, None) => Some(format!("{} matches", self.search.matches.len())),
+        };
+        let filter = format!(
+            "filter: {}",
+            CELL_FILTERS
+                .get(self.filter)
+                .map_or("all", |(_, label)| label)
+        );
+        let mut pairs: Vec<(&str, &str)> = Vec::new();
+        if let Some(status) = &match_status {
+            pairs.push(("n/N", status.as_str()));
+        }
+        pairs.extend([("/", "search"), ("f", filter.as_str()), ("[/]", "cell")]);
+        if !previewing && self.selected_cell.is_some() {
+            pairs.push(("⏎", "edit from here"));
+        }
+        render_key_hints(line3, buf, &pairs);
     }
 
     pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
@@ -423,15 +853,23 @@ impl TranscriptOverlay {
 impl TranscriptOverlay {
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match event {
+            TuiEvent::Key(key_event) if self.search.input.is_some() => {
+                if matches!(key_event.kind, KeyEventKind::Press | KeyEventKind::Repeat) {
+                    self.handle_search_input(key_event);
+                    tui.frame_requester().schedule_frame();
+                }
+                Ok(())
+            }
             TuiEvent::Key(key_event) => match key_event {
                 KeyEvent {
-                    code: KeyCode::Char('q'),
                     kind: KeyEventKind::Press,
                     ..
+                } if keymap::matches(self.keymap.open_transcript, &key_event) => {
+                    self.is_done = true;
+                    Ok(())
                 }
-                | KeyEvent {
-                    code: KeyCode::Char('t'),
-                    modifiers: crossterm::event::KeyModifiers::CONTROL,
+                KeyEvent {
+                    code: KeyCode::Char('q'),
                     kind: KeyEventKind::Press,
                     ..
                 }
@@ -444,6 +882,22 @@ impl TranscriptOverlay {
                     self.is_done = true;
                     Ok(())
                 }
+                KeyEvent {
+                    code: KeyCode::Char(c @ ('/' | 'n' | 'N' | 'f' | '[' | ']')),
+                    kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                    ..
+                } => {
+                    match c {
+                        '/' => self.search.input = Some(String::new()),
+                        'n' => self.step_match(true),
+                        'N' => self.step_match(false),
+                        'f' => self.cycle_filter(),
+                        ']' => self.step_cell(true),
+                        _ => self.step_cell(false),
+                    }
+                    tui.frame_requester().schedule_frame();
+                    Ok(())
+                }
                 other => self.view.handle_key_event(tui, other),
             },
             TuiEvent::Draw => {
@@ -463,56 +917,481 @@ impl TranscriptOverlay {
     }
 }
 
-pub(crate) struct StaticOverlay {
+/// Which diff the diff overlay shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffSource {
+    /// `git diff` of the working tree, including untracked files.
+    WorkingTree,
+    /// Files the agent changed during this session's turns.
+    Turns,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffMode {
+    /// List of changed files.
+    Files,
+    /// Hunks of the selected file.
+    Hunks,
+}
+
+/// Terminals at least this wide show hunks side by side unless toggled off.
+const SIDE_BY_SIDE_MIN_WIDTH: u16 = 160;
+
+/// Lines above the file list in `DiffMode::Files`: a summary and a blank.
+const FILE_LIST_OFFSET: usize = 2;
+
+/// Interactive viewer for `/diff`: a list of changed files, and the hunks of
+/// one file that can be staged, reverted or sent to the agent as a comment.
+pub(crate) struct DiffOverlay {
     view: PagerView,
+    /// Raw `git diff` output, shown as is when it contains no files (for
+    /// example when the directory is not a git repository).
+    working_tree_text: String,
+    working_tree: Vec<DiffFile>,
+    turns: Vec<DiffFile>,
+    source: DiffSource,
+    mode: DiffMode,
+    selected_file: usize,
+    selected_hunk: usize,
+    /// Line in the view where each hunk of the open file starts.
+    hunk_starts: Vec<usize>,
+    /// Layout picked with `v`; `None` follows the terminal width.
+    side_by_side: Option<bool>,
+    /// Width the view lines were built for; `None` forces a rebuild.
+    rendered_width: Option<u16>,
+    confirm_revert: bool,
+    /// Comment being typed for the selected hunk.
+    comment: Option<String>,
+    status: Option<Line<'static>>,
+    root: PathBuf,
+    cwd: PathBuf,
+    app_event_tx: AppEventSender,
     is_done: bool,
 }
 
-impl StaticOverlay {
-    pub(crate) fn with_title(lines: Vec<Line<'static>>, title: String) -> Self {
+impl DiffOverlay {
+    pub(crate) fn new(
+        working_tree_text: String,
+        turn_diff: Option<&str>,
+        cwd: PathBuf,
+        app_event_tx: AppEventSender,
+    ) -> Self {
+        let working_tree = parse_unified_diff(&working_tree_text);
+        let turns = turn_diff.map(parse_unified_diff).unwrap_or_default();
+        let source = if working_tree.is_empty() && !turns.is_empty() {
+            DiffSource::Turns
+        } else {
+            DiffSource::WorkingTree
+        };
         Self {
-            view: PagerView::new(lines, title, 0),
+            view: PagerView::new(Vec::new(), "D I F F".to_string(), 0),
+            working_tree_text,
+            working_tree,
+            turns,
+            source,
+            mode: DiffMode::Files,
+            selected_file: 0,
+            selected_hunk: 0,
+            hunk_starts: Vec::new(),
+            side_by_side: None,
+            rendered_width: None,
+            confirm_revert: false,
+            comment: None,
+            status: None,
+            root: get_git_repo_root(&cwd).unwrap_or_else(|| cwd.clone()),
+            cwd,
+            app_event_tx,
             is_done: false,
         }
     }
 
+    /// Replace the working tree diff, e.g. after a hunk was staged or
+    /// reverted, keeping the open file when it still has changes.
+    pub(crate) fn set_working_tree_diff(&mut self, text: String) {
+        let open_path = self.selected().map(|file| file.path.clone());
+        self.working_tree = parse_unified_diff(&text);
+        self.working_tree_text = text;
+        if self.source != DiffSource::WorkingTree {
+            return;
+        }
+        match open_path.and_then(|path| self.working_tree.iter().position(|f| f.path == path)) {
+            Some(idx) => self.selected_file = idx,
+            None => {
+                self.mode = DiffMode::Files;
+                self.selected_file = self.selected_file.min(self.files().len().saturating_sub(1));
+            }
+        }
+        self.selected_hunk = self.selected_hunk.min(
+            self.selected()
+                .map_or(0, |f| f.hunks.len().saturating_sub(1)),
+        );
+        self.rendered_width = None;
+    }
+
+    pub(crate) fn set_status(&mut self, status: Line<'static>) {
+        self.status = Some(status);
+    }
+
+    fn files(&self) -> &[DiffFile] {
+        match self.source {
+            DiffSource::WorkingTree => &self.working_tree,
+            DiffSource::Turns => &self.turns,
+        }
+    }
+
+    fn selected(&self) -> Option<&DiffFile> {
+        self.files().get(self.selected_file)
+    }
+
+    fn is_side_by_side(&self, width: u16) -> bool {
+        self.side_by_side.unwrap_or(width >= SIDE_BY_SIDE_MIN_WIDTH)
+    }
+
+    fn file_changes(&self, files: &[DiffFile]) -> Vec<(PathBuf, FileChange)> {
+        files
+            .iter()
+            .map(|file| file.to_file_change(&self.root))
+            .collect()
+    }
+
+    fn rebuild(&mut self, width: u16) {
+        let mut lines: Vec<Line<'static>> = Vec::new();
+        let mut hunk_starts = Vec::new();
+        let source = match self.source {
+            DiffSource::WorkingTree => "Working tree",
+            DiffSource::Turns => "Agent changes",
+        };
+        if self.selected().is_none() {
+            self.mode = DiffMode::Files;
+        }
+        match (self.mode, self.selected()) {
+            (DiffMode::Hunks, Some(file)) => {
+                let change = file.to_file_change(&self.root);
+                lines.extend(create_diff_file_list(&[change], &self.cwd));
+                lines.push("".into());
+                if file.binary {
+                    lines.push("  Binary file not shown.".italic().into());
+                } else if file.hunks.is_empty() {
+                    lines.push("  No content changes.".italic().into());
+                }
+                let side_by_side = self.is_side_by_side(width);
+                for idx in 0..file.hunks.len() {
+                    if idx > 0 {
+                        lines.push("".into());
+                    }
+                    hunk_starts.push(lines.len());
+                    lines.push(Line::from(file.hunks[idx].header.clone()).cyan());
+                    let unified_diff = file.unified_diff(Some(idx));
+                    lines.extend(if side_by_side {
+                        render_side_by_side_diff(&file.path, &unified_diff, width as usize)
+                    } else {
+                        render_unified_diff(&file.path, &unified_diff, width as usize)
+                    });
+                }
+            }
+            _ => {
+                let files = self.files();
+                if files.is_empty() {
+                    let text = self.working_tree_text.trim();
+                    if self.source == DiffSource::WorkingTree && !text.is_empty() {
+                        lines.extend(text.lines().map(|line| Line::from(line.to_string())));
+                    } else {
+                        lines.push("No changes detected.".italic().into());
+                    }
+                } else {
+                    let (added, removed) = files.iter().fold((0, 0), |(a, r), file| {
+                        let (fa, fr) = file.line_counts();
+                        (a + fa, r + fr)
+                    });
+                    let noun = if files.len() == 1 { "file" } else { "files" };
+                    lines.push(Line::from(vec![
+                        "• ".into(),
+                        source.bold(),
+                        format!(" {} {noun} ", files.len()).into(),
+                        Span::styled(format!("+{added}"), theme::palette().added()),
+                        " ".into(),
+                        Span::styled(format!("-{removed}"), theme::palette().removed()),
+                    ]));
+                    lines.push("".into());
+                    lines.extend(create_diff_file_list(&self.file_changes(files), &self.cwd));
+                }
+            }
+        }
+        self.view.lines = lines;
+        self.hunk_starts = hunk_starts;
+        self.rendered_width = Some(width);
+        self.update_selection_highlight();
+    }
+
+    /// Line in the view that shows the current selection.
+    fn selected_line(&self) -> Option<usize> {
+        match self.mode {
+            DiffMode::Files if !self.files().is_empty() => {
+                Some(FILE_LIST_OFFSET + self.selected_file)
+            }
+            DiffMode::Files => None,
+            DiffMode::Hunks => self.hunk_starts.get(self.selected_hunk).copied(),
+        }
+    }
+
+    fn update_selection_highlight(&mut self) {
+        let mut highlights = HashMap::new();
+        if let Some(line) = self.selected_line()
+            && let Some(text) = self.view.lines.get(line)
+        {
+            let len = text.spans.iter().map(|s| s.content.len()).sum();
+            highlights.insert(
+                line,
+                vec![(0..len, Style::default().add_modifier(Modifier::REVERSED))],
+            );
+        }
+        self.view.set_line_highlights(highlights);
+    }
+
+    /// Scroll the selection into view. Hunks are brought to the top so as
+    /// much of them as possible is visible.
+    fn reveal_selection(&mut self) {
+        let Some(line) = self.selected_line() else {
+            return;
+        };
+        let top = self.view.top_line().unwrap_or(0);
+        let height = self.view.last_content_height.unwrap_or(0);
+        if self.mode == DiffMode::Hunks || line < top || line >= top + height {
+            self.view.scroll_to_line(line);
+        }
+    }
+
+    fn select(&mut self, forward: bool) {
+        let (current, len) = match self.mode {
+            DiffMode::Files => (self.selected_file, self.files().len()),
+            DiffMode::Hunks => (self.selected_hunk, self.hunk_starts.len()),
+        };
+        if len == 0 {
+            return;
+        }
+        let next = if forward {
+            (current + 1).min(len - 1)
+        } else {
+            current.saturating_sub(1)
+        };
+        match self.mode {
+            DiffMode::Files => self.selected_file = next,
+            DiffMode::Hunks => self.selected_hunk = next,
+        }
+        self.update_selection_highlight();
+        self.reveal_selection();
+    }
+
+    fn set_mode(&mut self, mode: DiffMode) {
+        if mode == DiffMode::Hunks && self.selected().is_none() {
+            return;
+        }
+        self.mode = mode;
+        self.selected_hunk = 0;
+        self.rendered_width = None;
+        self.view.scroll_offset = 0;
+        if mode == DiffMode::Files && self.selected_file > 0 {
+            self.view
+                .scroll_to_line(FILE_LIST_OFFSET + self.selected_file);
+        }
+    }
+
+    fn toggle_source(&mut self) {
+        let (next, files) = match self.source {
+            DiffSource::WorkingTree => (DiffSource::Turns, &self.turns),
+            DiffSource::Turns => (DiffSource::WorkingTree, &self.working_tree),
+        };
+        if next == DiffSource::Turns && files.is_empty() {
+            self.status = Some(
+                "No changes from the agent in this session yet."
+                    .dim()
+                    .into(),
+            );
+            return;
+        }
+        self.source = next;
+        self.selected_file = 0;
+        self.set_mode(DiffMode::Files);
+    }
+
+    fn apply_selected_hunk(&mut self, action: HunkAction) {
+        if self.source != DiffSource::WorkingTree {
+            self.status = Some(
+                "Only working tree changes can be staged or reverted."
+                    .dim()
+                    .into(),
+            );
+            return;
+        }
+        let Some(patch) = self
+            .selected()
+            .and_then(|file| file.hunk_patch(self.selected_hunk))
+        else {
+            return;
+        };
+        if action == HunkAction::Revert && !self.confirm_revert {
+            self.confirm_revert = true;
+            self.status = Some(Line::from(vec![
+                "Press ".into(),
+                "r".cyan(),
+                " again to revert this hunk in the working tree.".into(),
+            ]));
+            return;
+        }
+        self.status = None;
+        self.confirm_revert = false;
+        self.app_event_tx
+            .send(AppEvent::ApplyDiffHunk { patch, action });
+    }
+
+    fn submit_comment(&mut self, comment: String) {
+        let Some(file) = self.selected() else {
+            return;
+        };
+        let comment = comment.trim();
+        if comment.is_empty() {
+            return;
+        }
+        let hunk = file.unified_diff(Some(self.selected_hunk));
+        self.app_event_tx.send(AppEvent::SubmitUserMessage(format!(
+            "{comment}\n\n```diff\n{hunk}```"
+        )));
+        self.is_done = true;
+    }
+
+    fn handle_comment_input(&mut self, key_event: KeyEvent) {
+        let Some(comment) = self.comment.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Enter => {
+                let comment = self.comment.take().unwrap_or_default();
+                self.submit_comment(comment);
+            }
+            KeyCode::Esc => self.comment = None,
+            KeyCode::Backspace => {
+                comment.pop();
+            }
+            KeyCode::Char(c)
+                if !key_event
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                comment.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle a key press. Returns `false` for keys left to the pager.
+    fn handle_key(&mut self, key_event: KeyEvent) -> bool {
+        if self.comment.is_some() {
+            self.handle_comment_input(key_event);
+            return true;
+        }
+        let confirm_revert = std::mem::take(&mut self.confirm_revert);
+        match (self.mode, key_event.code) {
+            (_, KeyCode::Char('q')) => self.is_done = true,
+            (_, KeyCode::Char('c')) if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.is_done = true
+            }
+            (_, KeyCode::Char('t')) => self.toggle_source(),
+            (DiffMode::Files, KeyCode::Esc) => self.is_done = true,
+            (DiffMode::Files, KeyCode::Up | KeyCode::Char('k')) => self.select(false),
+            (DiffMode::Files, KeyCode::Down | KeyCode::Char('j')) => self.select(true),
+            (DiffMode::Files, KeyCode::Enter | KeyCode::Right | KeyCode::Char('l')) => {
+                self.set_mode(DiffMode::Hunks)
+            }
+            (DiffMode::Hunks, KeyCode::Esc | KeyCode::Left | KeyCode::Char('h')) => {
+                self.set_mode(DiffMode::Files)
+            }
+            (DiffMode::Hunks, KeyCode::Char(']')) => self.select(true),
+            (DiffMode::Hunks, KeyCode::Char('[')) => self.select(false),
+            (DiffMode::Hunks, KeyCode::Char('v')) => {
+                let width = self.rendered_width.unwrap_or(0);
+                self.side_by_side = Some(!self.is_side_by_side(width));
+                self.rendered_width = None;
+                if let Some(line) = self.selected_line() {
+                    self.view.scroll_to_line(line);
+                }
+            }
+            (DiffMode::Hunks, KeyCode::Char('s')) => self.apply_selected_hunk(HunkAction::Stage),
+            (DiffMode::Hunks, KeyCode::Char('r')) => {
+                self.confirm_revert = confirm_revert;
+                self.apply_selected_hunk(HunkAction::Revert);
+            }
+            (DiffMode::Hunks, KeyCode::Char('c')) if self.selected_line().is_some() => {
+                self.comment = Some(String::new())
+            }
+            _ => return false,
+        }
+        true
+    }
+
     fn render_hints(&self, area: Rect, buf: &mut Buffer) {
         let line1 = Rect::new(area.x, area.y, area.width, 1);
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
+        let line3 = Rect::new(area.x, area.y.saturating_add(2), area.width, 1);
         render_key_hints(line1, buf, PAGER_KEY_HINTS);
-        let pairs = [("q", "quit")];
+        let source = match self.source {
+            DiffSource::WorkingTree => "agent changes",
+            DiffSource::Turns => "working tree",
+        };
+        let pairs: Vec<(&str, &str)> = match self.mode {
+            DiffMode::Files => vec![
+                ("q", "quit"),
+                ("j/k", "select"),
+                ("⏎", "open"),
+                ("t", source),
+            ],
+            DiffMode::Hunks => vec![
+                ("q", "quit"),
+                ("←", "files"),
+                ("[/]", "hunk"),
+                ("s", "stage"),
+                ("r", "revert"),
+                ("c", "comment"),
+                ("v", "side by side"),
+            ],
+        };
         render_key_hints(line2, buf, &pairs);
+
+        if let Some(comment) = &self.comment {
+            let spans: Vec<Span<'static>> =
+                vec![" comment: ".cyan(), comment.clone().into(), "▏".dim()];
+            Paragraph::new(Line::from(spans)).render_ref(line3, buf);
+        } else if let Some(status) = &self.status {
+            let mut spans: Vec<Span<'static>> = vec![" ".into()];
+            spans.extend(status.spans.iter().cloned());
+            Paragraph::new(Line::from(spans)).render_ref(line3, buf);
+        }
     }
 
     pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
         let top_h = area.height.saturating_sub(3);
         let top = Rect::new(area.x, area.y, area.width, top_h);
         let bottom = Rect::new(area.x, area.y + top_h, area.width, 3);
+        if self.rendered_width != Some(top.width) {
+            self.rebuild(top.width);
+        }
         self.view.render(top, buf);
         self.render_hints(bottom, buf);
     }
 }
 
-impl StaticOverlay {
+impl DiffOverlay {
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match event {
-            TuiEvent::Key(key_event) => match key_event {
-                KeyEvent {
-                    code: KeyCode::Char('q'),
-                    kind: KeyEventKind::Press,
-                    ..
-                }
-                | KeyEvent {
-                    code: KeyCode::Char('c'),
-                    modifiers: crossterm::event::KeyModifiers::CONTROL,
-                    kind: KeyEventKind::Press,
-                    ..
-                } => {
-                    self.is_done = true;
+            TuiEvent::Key(key_event)
+                if matches!(key_event.kind, KeyEventKind::Press | KeyEventKind::Repeat) =>
+            {
+                if self.handle_key(key_event) {
+                    tui.frame_requester().schedule_frame();
                     Ok(())
+                } else {
+                    self.view.handle_key_event(tui, key_event)
                 }
-                other => self.view.handle_key_event(tui, other),
-            },
+            }
             TuiEvent::Draw => {
                 tui.draw(u16::MAX, |frame| {
                     self.render(frame.area(), frame.buffer);
@@ -536,7 +1415,8 @@ mod tests {
 
     #[test]
     fn edit_prev_hint_is_visible() {
-        let mut overlay = TranscriptOverlay::new(vec![Line::from("hello")]);
+        let mut overlay =
+            TranscriptOverlay::new(vec![Line::from("hello")], Vec::new(), Keymap::default());
 
         // Render into a small buffer and assert the backtrack hint is present
         let area = Rect::new(0, 0, 40, 10);
@@ -560,11 +1440,11 @@ mod tests {
     #[test]
     fn transcript_overlay_snapshot_basic() {
         // Prepare a transcript overlay with a few lines
-        let mut overlay = TranscriptOverlay::new(vec![
-            Line::from("alpha"),
-            Line::from("beta"),
-            Line::from("gamma"),
-        ]);
+        let mut overlay = TranscriptOverlay::new(
+            vec![Line::from("alpha"), Line::from("beta"), Line::from("gamma")],
+            Vec::new(),
+            Keymap::default(),
+        );
         let mut term = Terminal::new(TestBackend::new(40, 10)).expect("term");
         term.draw(|f| overlay.render(f.area(), f.buffer_mut()))
             .expect("draw");
@@ -573,8 +1453,11 @@ mod tests {
 
     #[test]
     fn transcript_overlay_keeps_scroll_pinned_at_bottom() {
-        let mut overlay =
-            TranscriptOverlay::new((0..20).map(|i| Line::from(format!("line{i}"))).collect());
+        let mut overlay = TranscriptOverlay::new(
+            (0..20).map(|i| Line::from(format!("line{i}"))).collect(),
+            Vec::new(),
+            Keymap::default(),
+        );
         let mut term = Terminal::new(TestBackend::new(40, 12)).expect("term");
         term.draw(|f| overlay.render(f.area(), f.buffer_mut()))
             .expect("draw");
@@ -584,37 +1467,152 @@ mod tests {
             "expected initial render to leave view at bottom"
         );
 
-        overlay.insert_lines(vec!["tail".into()]);
+        overlay.insert_cell(vec!["tail".into()], None);
 
         assert_eq!(overlay.view.scroll_offset, usize::MAX);
     }
 
     #[test]
     fn transcript_overlay_preserves_manual_scroll_position() {
-        let mut overlay =
-            TranscriptOverlay::new((0..20).map(|i| Line::from(format!("line{i}"))).collect());
+        let mut overlay = TranscriptOverlay::new(
+            (0..20).map(|i| Line::from(format!("line{i}"))).collect(),
+            Vec::new(),
+            Keymap::default(),
+        );
         let mut term = Terminal::new(TestBackend::new(40, 12)).expect("term");
         term.draw(|f| overlay.render(f.area(), f.buffer_mut()))
             .expect("draw");
 
         overlay.view.scroll_offset = 0;
 
-        overlay.insert_lines(vec!["tail".into()]);
+        overlay.insert_cell(vec!["tail".into()], None);
 
         assert_eq!(overlay.view.scroll_offset, 0);
     }
 
+    fn text_of(lines: &[Line<'static>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
     #[test]
-    fn static_overlay_snapshot_basic() {
-        // Prepare a static overlay with a few lines and a title
-        let mut overlay = StaticOverlay::with_title(
-            vec!["one".into(), "two".into(), "three".into()],
-            "S T A T I C".to_string(),
+    fn search_highlights_matches_and_steps_through_them() {
+        let mut overlay = TranscriptOverlay::new(
+            vec!["alpha beta".into(), "gamma".into(), "Beta blocker".into()],
+            Vec::new(),
+            Keymap::default(),
         );
-        let mut term = Terminal::new(TestBackend::new(40, 10)).expect("term");
-        term.draw(|f| overlay.render(f.area(), f.buffer_mut()))
-            .expect("draw");
-        assert_snapshot!(term.backend());
+        overlay.search.query = "beta".to_string();
+        overlay.refresh_matches();
+        assert_eq!(
+            overlay.search.matches,
+            vec![
+                SearchMatch {
+                    line: 0,
+                    range: 6..10
+                },
+                SearchMatch {
+                    line: 2,
+                    range: 0..4
+                },
+            ]
+        );
+
+        overlay.step_match(true);
+        assert_eq!(overlay.search.current, Some(0));
+        overlay.step_match(true);
+        assert_eq!(overlay.search.current, Some(1));
+        overlay.step_match(true);
+        assert_eq!(overlay.search.current, Some(0));
+        overlay.step_match(false);
+        assert_eq!(overlay.search.current, Some(1));
+
+        overlay.view.ensure_wrapped(40);
+        let (wrapped, _) = overlay.view.cached();
+        assert_eq!(text_of(wrapped)[0], "alpha beta");
+        let hit = wrapped[0]
+            .spans
+            .iter()
+            .find(|s| s.content == "beta")
+            .expect("match is split into its own span");
+        assert!(hit.style.add_modifier.contains(Modifier::REVERSED));
+        let current = wrapped[2]
+            .spans
+            .iter()
+            .find(|s| s.content == "Beta")
+            .expect("current match is split into its own span");
+        assert_eq!(current.style.fg, Some(Color::Cyan));
+
+        // An upper-case letter makes the search case-sensitive.
+        overlay.search.query = "Beta".to_string();
+        overlay.refresh_matches();
+        assert_eq!(overlay.search.matches.len(), 1);
+        assert_eq!(overlay.search.current, None);
+    }
+
+    #[test]
+    fn filter_and_cell_jumps_follow_cell_boundaries() {
+        let lines: Vec<Line<'static>> = ["user", "fix it", "", "codex", "done", "", "$ ls", "out"]
+            .into_iter()
+            .map(Line::from)
+            .collect();
+        let cells = vec![
+            TranscriptCell {
+                start: 0,
+                kind: HistoryCellKind::User,
+            },
+            TranscriptCell {
+                start: 3,
+                kind: HistoryCellKind::Agent,
+            },
+            TranscriptCell {
+                start: 6,
+                kind: HistoryCellKind::Command,
+            },
+        ];
+        let mut overlay = TranscriptOverlay::new(lines, cells, Keymap::default());
+        overlay.view.scroll_offset = 0;
+        overlay.view.ensure_wrapped(40);
+
+        overlay.step_cell(true);
+        assert_eq!(overlay.selected_line(), Some(3));
+        overlay.step_cell(true);
+        assert_eq!(overlay.selected_line(), Some(6));
+        overlay.step_cell(false);
+        assert_eq!(overlay.selected_line(), Some(3));
+
+        // all -> user messages -> agent messages -> commands
+        for _ in 0..3 {
+            overlay.cycle_filter();
+        }
+        assert_eq!(overlay.selected_line(), None);
+        overlay.view.ensure_wrapped(40);
+        let (wrapped, _) = overlay.view.cached();
+        assert_eq!(text_of(wrapped), vec!["$ ls", "out"]);
+
+        // Only visible cells are searched.
+        overlay.search.query = "o".to_string();
+        overlay.refresh_matches();
+        assert_eq!(
+            overlay.search.matches,
+            vec![SearchMatch {
+                line: 7,
+                range: 0..1
+            }]
+        );
+        overlay.clear_filter();
+        assert_eq!(overlay.search.matches.len(), 3);
+
+        overlay.insert_cell(
+            vec!["".into(), "codex".into(), "more output".into()],
+            Some(TranscriptCell {
+                start: 9,
+                kind: HistoryCellKind::Agent,
+            }),
+        );
+        assert_eq!(overlay.search.matches.len(), 6);
     }
 
     #[test]
@@ -663,4 +1661,118 @@ mod tests {
             "wrapped length should grow or stay same after append"
         );
     }
+
+    const WORKING_TREE_DIFF: &str = "\
+diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1,2 +1,2 @@
+-one
++uno
+ two
+@@ -10,1 +10,2 @@
+ ten
++eleven
+diff --git a/b.txt b/b.txt
+new file mode 100644
+--- /dev/null
++++ b/b.txt
+@@ -0,0 +1 @@
++bee
+";
+
+    fn diff_overlay(
+        turn_diff: Option<&str>,
+    ) -> (DiffOverlay, tokio::sync::mpsc::UnboundedReceiver<AppEvent>) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let overlay = DiffOverlay::new(
+            WORKING_TREE_DIFF.to_string(),
+            turn_diff,
+            PathBuf::from("/repo"),
+            AppEventSender::new(tx),
+        );
+        (overlay, rx)
+    }
+
+    fn press(overlay: &mut DiffOverlay, code: KeyCode) -> bool {
+        overlay.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn diff_overlay_lists_files_and_opens_hunks() {
+        let turn_diff =
+            "diff --git a/c.txt b/c.txt\n--- a/c.txt\n+++ b/c.txt\n@@ -1 +1 @@\n-c\n+see\n";
+        let (mut overlay, _rx) = diff_overlay(Some(turn_diff));
+        overlay.rebuild(80);
+        let text = text_of(&overlay.view.lines);
+        assert_eq!(text[0], "• Working tree 2 files +3 -1");
+        assert!(text[2].ends_with("a.txt (+2 -1)"), "{text:?}");
+        assert!(text[3].ends_with("b.txt (+1 -0)"), "{text:?}");
+
+        assert!(press(&mut overlay, KeyCode::Down));
+        assert_eq!(overlay.selected_file, 1);
+        assert!(press(&mut overlay, KeyCode::Up));
+        assert!(press(&mut overlay, KeyCode::Enter));
+        overlay.rebuild(80);
+        assert_eq!(overlay.mode, DiffMode::Hunks);
+        let text = text_of(&overlay.view.lines);
+        assert_eq!(overlay.hunk_starts.len(), 2);
+        assert_eq!(text[overlay.hunk_starts[0]], "@@ -1,2 +1,2 @@");
+        assert_eq!(text[overlay.hunk_starts[1]], "@@ -10,1 +10,2 @@");
+
+        assert!(press(&mut overlay, KeyCode::Char(']')));
+        assert_eq!(overlay.selected_hunk, 1);
+        // Pager keys are left to the view.
+        assert!(!press(&mut overlay, KeyCode::PageDown));
+        assert!(press(&mut overlay, KeyCode::Esc));
+        assert_eq!(overlay.mode, DiffMode::Files);
+
+        assert!(press(&mut overlay, KeyCode::Char('t')));
+        overlay.rebuild(80);
+        assert_eq!(
+            text_of(&overlay.view.lines)[0],
+            "• Agent changes 1 file +1 -1"
+        );
+    }
+
+    #[test]
+    fn diff_overlay_stages_reverts_and_comments_on_hunks() {
+        let (mut overlay, mut rx) = diff_overlay(None);
+        overlay.rebuild(80);
+        press(&mut overlay, KeyCode::Enter);
+        overlay.rebuild(80);
+
+        press(&mut overlay, KeyCode::Char('s'));
+        let Ok(AppEvent::ApplyDiffHunk { patch, action }) = rx.try_recv() else {
+            panic!("expected ApplyDiffHunk");
+        };
+        assert_eq!(action, HunkAction::Stage);
+        assert_eq!(
+            patch,
+            "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n-one\n+uno\n two\n"
+        );
+
+        // Reverting asks for confirmation first.
+        press(&mut overlay, KeyCode::Char('r'));
+        assert!(rx.try_recv().is_err());
+        press(&mut overlay, KeyCode::Char('r'));
+        let Ok(AppEvent::ApplyDiffHunk { action, .. }) = rx.try_recv() else {
+            panic!("expected ApplyDiffHunk");
+        };
+        assert_eq!(action, HunkAction::Revert);
+
+        press(&mut overlay, KeyCode::Char('c'));
+        for c in "why?".chars() {
+            press(&mut overlay, KeyCode::Char(c));
+        }
+        press(&mut overlay, KeyCode::Enter);
+        let Ok(AppEvent::SubmitUserMessage(text)) = rx.try_recv() else {
+            panic!("expected SubmitUserMessage");
+        };
+        assert_eq!(
+            text,
+            "why?\n\n```diff\n--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n-one\n+uno\n two\n```"
+        );
+        assert!(overlay.is_done());
+    }
 }
@@ -1,7 +1,27 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
 use codex_core::bash::try_parse_bash;
+use ratatui::style::Style;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
+use syntect::parsing::ParseState;
+use syntect::parsing::Scope;
+use syntect::parsing::ScopeStack;
+use syntect::parsing::SyntaxReference;
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Number of highlighted code blocks kept in memory. Streaming re-renders the
+/// whole message on every committed line, so the cache mostly serves to resume
+/// highlighting of a growing block instead of starting over.
+const HIGHLIGHT_CACHE_CAPACITY: usize = 32;
+
+/// Code larger than this is rendered without highlighting to keep rendering
+/// responsive.
+const MAX_HIGHLIGHT_BYTES: usize = 256 * 1024;
 
 /// Convert the full bash script into per-line styled content by first
 /// computing operator-dimmed spans across the entire script, then splitting
@@ -78,6 +98,158 @@ pub(crate) fn highlight_bash_to_lines(script: &str) -> Vec<Line<'static>> {
     lines
 }
 
+/// Highlight `code` written in `lang`, which may be a fenced code block info
+/// string (`rust`, `ts title="x"`) or a file extension (`rs`, `py`). Returns
+/// one line per source line, or `None` when the language is unknown so callers
+/// can fall back to plain rendering.
+pub(crate) fn highlight_code_to_lines(code: &str, lang: &str) -> Option<Vec<Line<'static>>> {
+    if code.len() > MAX_HIGHLIGHT_BYTES {
+        return None;
+    }
+    let syntax_set = syntax_set();
+    let syntax = find_syntax(syntax_set, lang)?;
+    let lang = syntax.name.as_str();
+
+    let mut cache = highlight_cache().lock().ok()?;
+    if let Some(entry) = cache.iter().find(|e| e.lang == lang && e.code == code) {
+        return Some(entry.lines.clone());
+    }
+
+    // Resume from a cached prefix of this block (the common case while a
+    // code block is streaming in) rather than re-highlighting from the top.
+    let resume_from = cache.iter().position(|e| {
+        e.lang == lang && e.code.ends_with('\n') && code.starts_with(e.code.as_str())
+    });
+    let (mut lines, mut parse_state, mut scopes, rest) =
+        match resume_from.and_then(|i| cache.remove(i)) {
+            Some(entry) => {
+                let rest = &code[entry.code.len()..];
+                (entry.lines, entry.parse_state, entry.scopes, rest)
+            }
+            None => (Vec::new(), ParseState::new(syntax), ScopeStack::new(), code),
+        };
+
+    for source_line in LinesWithEndings::from(rest) {
+        lines.push(highlight_line(
+            source_line,
+            &mut parse_state,
+            &mut scopes,
+            syntax_set,
+        )?);
+    }
+
+    if cache.len() >= HIGHLIGHT_CACHE_CAPACITY {
+        cache.pop_front();
+    }
+    cache.push_back(CachedHighlight {
+        lang: lang.to_string(),
+        code: code.to_string(),
+        lines: lines.clone(),
+        parse_state,
+        scopes,
+    });
+    Some(lines)
+}
+
+struct CachedHighlight {
+    lang: String,
+    code: String,
+    lines: Vec<Line<'static>>,
+    /// Parser state after the last line of `code`, used to resume.
+    parse_state: ParseState,
+    scopes: ScopeStack,
+}
+
+fn highlight_cache() -> &'static Mutex<VecDeque<CachedHighlight>> {
+    static CACHE: OnceLock<Mutex<VecDeque<CachedHighlight>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(VecDeque::with_capacity(HIGHLIGHT_CACHE_CAPACITY)))
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn find_syntax<'a>(syntax_set: &'a SyntaxSet, lang: &str) -> Option<&'a SyntaxReference> {
+    // Info strings may carry attributes after the language: `rust,ignore`,
+    // `ts title="a.ts"`, `{.python}`.
+    let token = lang
+        .trim()
+        .trim_start_matches(['{', '.'])
+        .split([',', ' ', '}'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    // The bundled grammars have no TypeScript; JavaScript is a close match.
+    let token = match token.as_str() {
+        "" | "text" | "plain" | "plaintext" => return None,
+        "ts" | "typescript" | "tsx" | "mts" | "cts" | "jsx" => "js",
+        "sh" | "shell" | "zsh" | "console" => "bash",
+        "py" | "python3" => "python",
+        other => other,
+    };
+    syntax_set.find_syntax_by_token(token)
+}
+
+fn highlight_line(
+    source_line: &str,
+    parse_state: &mut ParseState,
+    scopes: &mut ScopeStack,
+    syntax_set: &SyntaxSet,
+) -> Option<Line<'static>> {
+    let ops = parse_state.parse_line(source_line, syntax_set).ok()?;
+    let text = source_line.trim_end_matches(['\n', '\r']);
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut push = |start: usize, end: usize, style: Style| {
+        let end = end.min(text.len());
+        if start >= end {
+            return;
+        }
+        let piece = &text[start..end];
+        match spans.last_mut() {
+            Some(last) if last.style == style => last.content.to_mut().push_str(piece),
+            _ => spans.push(Span::styled(piece.to_string(), style)),
+        }
+    };
+    let mut last = 0;
+    for (offset, op) in ops {
+        push(last, offset, style_for_scopes(scopes));
+        last = offset;
+        scopes.apply(&op).ok()?;
+    }
+    push(last, text.len(), style_for_scopes(scopes));
+    Some(Line::from(spans))
+}
+
+/// Map the innermost recognised scope to a style from the palette in
+/// `tui/styles.md`: keywords magenta, strings green, literals and types cyan,
+/// comments dim.
+fn style_for_scopes(scopes: &ScopeStack) -> Style {
+    static RULES: OnceLock<Vec<(Scope, Style)>> = OnceLock::new();
+    let rules = RULES.get_or_init(|| {
+        [
+            ("comment", Style::new().dim()),
+            ("string", Style::new().green()),
+            ("constant.numeric", Style::new().cyan()),
+            ("constant.character", Style::new().cyan()),
+            ("constant.language", Style::new().cyan()),
+            ("entity.name.type", Style::new().cyan()),
+            ("support.type", Style::new().cyan()),
+            ("keyword", Style::new().magenta()),
+            ("storage", Style::new().magenta()),
+        ]
+        .into_iter()
+        .filter_map(|(scope, style)| Scope::new(scope).ok().map(|scope| (scope, style)))
+        .collect()
+    });
+    for scope in scopes.as_slice().iter().rev() {
+        if let Some((_, style)) = rules.iter().find(|(rule, _)| rule.is_prefix_of(*scope)) {
+            return *style;
+        }
+    }
+    Style::default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,4 +314,50 @@ mod tests {
         assert!(!dimmed.contains(&"\"".to_string()));
         assert!(!dimmed.contains(&"'".to_string()));
     }
+
+    fn line_text(line: &Line<'_>) -> String {
+        line.spans.iter().map(|sp| sp.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn highlights_rust_keywords_strings_and_comments() {
+        let code = "fn main() {\n    // greet\n    println!(\"hi\");\n}\n";
+        let lines = highlight_code_to_lines(code, "rust").expect("rust is supported");
+        let texts: Vec<String> = lines.iter().map(line_text).collect();
+        assert_eq!(
+            texts,
+            vec!["fn main() {", "    // greet", "    println!(\"hi\");", "}"]
+        );
+
+        let style_of = |needle: &str| {
+            lines
+                .iter()
+                .flat_map(|l| l.spans.iter())
+                .find(|sp| sp.content.contains(needle))
+                .map(|sp| sp.style)
+                .expect("span present")
+        };
+        assert_eq!(style_of("fn"), Style::new().magenta());
+        assert_eq!(style_of("greet"), Style::new().dim());
+        assert_eq!(style_of("hi"), Style::new().green());
+    }
+
+    #[test]
+    fn resolves_info_strings_and_unknown_languages() {
+        assert!(highlight_code_to_lines("let x = 1;\n", "ts title=\"a.ts\"").is_some());
+        assert!(highlight_code_to_lines("x = 1\n", "py").is_some());
+        assert!(highlight_code_to_lines("anything\n", "text").is_none());
+        assert!(highlight_code_to_lines("anything\n", "no-such-language").is_none());
+    }
+
+    #[test]
+    fn growing_block_matches_full_highlight() {
+        let full = "/* multi\nline */\nlet s = \"x\";\n";
+        let first = "/* multi\n";
+        let partial = highlight_code_to_lines(first, "js").expect("js is supported");
+        let resumed = highlight_code_to_lines(full, "js").expect("js is supported");
+        assert_eq!(partial[..], resumed[..1]);
+        // The second line is still inside the block comment.
+        assert_eq!(resumed[1].spans[0].style, Style::new().dim());
+    }
 }
@@ -7,6 +7,9 @@ use codex_core::ConversationItem;
 use codex_core::ConversationsPage;
 use codex_core::Cursor;
 use codex_core::RolloutRecorder;
+use codex_core::SessionSearchHit;
+use codex_core::SessionSearchQuery;
+use codex_core::search_sessions;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -28,6 +31,7 @@ use codex_protocol::protocol::InputMessageKind;
 use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 
 const PAGE_SIZE: usize = 25;
+const SEARCH_LIMIT: usize = 50;
 
 #[derive(Debug, Clone)]
 pub enum ResumeSelection {
@@ -38,7 +42,8 @@ pub enum ResumeSelection {
 
 /// Interactive session picker that lists recorded rollout files with simple
 /// search and pagination. Shows the first user input as the preview, relative
-/// time (e.g., "5 seconds ago"), and the absolute path.
+/// time (e.g., "5 seconds ago"), and the absolute path. Typing filters the
+/// current page; Tab runs a full-text search across all sessions.
 pub async fn run_resume_picker(tui: &mut Tui, codex_home: &Path) -> Result<ResumeSelection> {
     let alt = AltScreenGuard::enter(tui);
     let mut state = PickerState::new(codex_home.to_path_buf(), alt.tui.frame_requester());
@@ -97,6 +102,8 @@ struct PickerState {
     selected: usize,
     // search
     query: String,
+    /// True while `all_rows` holds full-text search results rather than a page.
+    searching_all: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -129,6 +136,7 @@ impl PickerState {
             filtered_rows: Vec::new(),
             selected: 0,
             query: String::new(),
+            searching_all: false,
         }
     }
 
@@ -163,14 +171,18 @@ impl PickerState {
                 }
                 self.request_frame();
             }
-            KeyCode::Left | KeyCode::Char('a') => {
+            KeyCode::Left | KeyCode::Char('a') if !self.searching_all => {
                 self.prev_page().await?;
             }
-            KeyCode::Right | KeyCode::Char('d') => {
+            KeyCode::Right | KeyCode::Char('d') if !self.searching_all => {
                 self.next_page().await?;
             }
+            KeyCode::Tab => {
+                self.search_all().await?;
+            }
             KeyCode::Backspace => {
                 self.query.pop();
+                self.leave_search_all().await?;
                 self.apply_filter();
             }
             KeyCode::Char(c) => {
@@ -181,6 +193,7 @@ impl PickerState {
                     && !key.modifiers.contains(crossterm::event::KeyModifiers::ALT)
                 {
                     self.query.push(c);
+                    self.leave_search_all().await?;
                     self.apply_filter();
                 }
             }
@@ -234,8 +247,42 @@ impl PickerState {
         Ok(())
     }
 
+    /// Replace the listed rows with full-text matches for the current query
+    /// across every recorded session.
+    async fn search_all(&mut self) -> Result<()> {
+        if self.query.trim().is_empty() {
+            return Ok(());
+        }
+        let query = SessionSearchQuery {
+            text: self.query.clone(),
+            limit: SEARCH_LIMIT,
+            ..Default::default()
+        };
+        let hits = search_sessions(&self.codex_home, &query).await?;
+        self.searching_all = true;
+        self.all_rows = hits.iter().map(hit_to_row).collect();
+        self.filtered_rows = self.all_rows.clone();
+        self.selected = 0;
+        self.request_frame();
+        Ok(())
+    }
+
+    /// Editing the query after a full-text search returns to the paged list.
+    async fn leave_search_all(&mut self) -> Result<()> {
+        if self.searching_all {
+            self.searching_all = false;
+            let anchor = self.pagination.current_anchor.clone();
+            self.load_page(anchor.as_ref()).await?;
+        }
+        Ok(())
+    }
+
     fn apply_filter(&mut self) {
-        if self.query.is_empty() {
+        if self.searching_all {
+            // Search results already match the query; the preview shows the
+            // matching snippet, which need not contain it verbatim.
+            self.filtered_rows = self.all_rows.clone();
+        } else if self.query.is_empty() {
             self.filtered_rows = self.all_rows.clone();
         } else {
             let q = self.query.to_lowercase();
@@ -278,6 +325,24 @@ fn head_to_row(item: &ConversationItem) -> Row {
     }
 }
 
+fn hit_to_row(hit: &SessionSearchHit) -> Row {
+    let ts = hit
+        .timestamp
+        .as_deref()
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|parsed| parsed.with_timezone(&Utc));
+    let preview = hit
+        .matches
+        .first()
+        .map(|m| m.snippet.clone())
+        .unwrap_or_default();
+    Row {
+        path: hit.path.clone(),
+        preview,
+        ts,
+    }
+}
+
 fn preview_from_head(head: &[serde_json::Value]) -> Option<String> {
     head.iter()
         .filter_map(|value| serde_json::from_value::<ResponseItem>(value.clone()).ok())
@@ -338,6 +403,8 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
         // Search line
         let q = if state.query.is_empty() {
             "Type to search".dim().to_string()
+        } else if state.searching_all {
+            format!("Search all sessions: {}", state.query)
         } else {
             format!("Search: {}", state.query)
         };
@@ -357,7 +424,9 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
             "←/a".into(),
             " prev  ".dim(),
             "→/d".into(),
-            " next".dim(),
+            " next  ".dim(),
+            "Tab".into(),
+            " search all sessions".dim(),
         ]
         .into();
         frame.render_widget_ref(hint_line, hint);
@@ -494,4 +563,28 @@ mod tests {
         assert!(rows[0].preview.contains('A'));
         assert!(rows[1].preview.contains('B'));
     }
+
+    #[test]
+    fn search_hit_row_uses_first_snippet() {
+        let hit = SessionSearchHit {
+            path: PathBuf::from("/tmp/c.jsonl"),
+            timestamp: Some("2025-01-03T00:00:00.000Z".to_string()),
+            cwd: None,
+            git_branch: None,
+            matches: vec![
+                codex_core::SearchMatch {
+                    kind: codex_core::SearchMatchKind::Command,
+                    snippet: "cargo test".to_string(),
+                },
+                codex_core::SearchMatch {
+                    kind: codex_core::SearchMatchKind::AgentMessage,
+                    snippet: "tests pass".to_string(),
+                },
+            ],
+        };
+        let row = hit_to_row(&hit);
+        assert_eq!(row.path, PathBuf::from("/tmp/c.jsonl"));
+        assert_eq!(row.preview, "cargo test");
+        assert!(row.ts.is_some());
+    }
 }
@@ -7,6 +7,9 @@ use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::text::Span;
 
+use crate::terminal_background::Background;
+use crate::theme;
+
 static PROCESS_START: OnceLock<Instant> = OnceLock::new();
 
 fn elapsed_since_start() -> Duration {
@@ -30,6 +33,7 @@ pub(crate) fn shimmer_spans(text: &str) -> Vec<Span<'static>> {
         .map(|level| level.has_16m)
         .unwrap_or(false);
     let band_half_width = 3.0;
+    let palette = theme::palette();
 
     let mut spans: Vec<Span<'static>> = Vec::with_capacity(chars.len());
     for (i, ch) in chars.iter().enumerate() {
@@ -45,7 +49,20 @@ pub(crate) fn shimmer_spans(text: &str) -> Vec<Span<'static>> {
         };
         let brightness = 0.4 + 0.6 * t;
         let level = (brightness * 255.0).clamp(0.0, 255.0) as u8;
-        let style = if has_true_color {
+        let style = if palette.high_contrast {
+            // Never fade text out; only emphasize the center of the band.
+            if level < 224 {
+                Style::default()
+            } else {
+                Style::default().add_modifier(Modifier::BOLD)
+            }
+        } else if has_true_color {
+            // On a light background the default foreground is dark, so the
+            // band sweeps towards black instead of white.
+            let level = match palette.background {
+                Background::Dark => level,
+                Background::Light => 255 - level,
+            };
             // Allow custom RGB colors, as the implementation is thoughtfully
             // adjusting the level of the default foreground color.
             #[allow(clippy::disallowed_methods)]
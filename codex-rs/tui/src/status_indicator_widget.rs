@@ -4,6 +4,8 @@
 use std::time::Duration;
 use std::time::Instant;
 
+use codex_core::keymap::KeyChord;
+use codex_core::keymap::Keymap;
 use codex_core::protocol::Op;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
@@ -28,6 +30,8 @@ pub(crate) struct StatusIndicatorWidget {
     elapsed_running: Duration,
     last_resume_at: Instant,
     is_paused: bool,
+    /// Key advertised for interrupting the running task.
+    interrupt_key: KeyChord,
     app_event_tx: AppEventSender,
     frame_requester: FrameRequester,
 }
@@ -57,12 +61,17 @@ impl StatusIndicatorWidget {
             elapsed_running: Duration::ZERO,
             last_resume_at: Instant::now(),
             is_paused: false,
+            interrupt_key: Keymap::default().interrupt,
 
             app_event_tx,
             frame_requester,
         }
     }
 
+    pub(crate) fn set_interrupt_key(&mut self, key: KeyChord) {
+        self.interrupt_key = key;
+    }
+
     pub fn desired_height(&self, width: u16) -> u16 {
         // Status line + optional blank line + wrapped queued messages (up to 3 lines per message)
         // + optional ellipsis line per truncated message + 1 spacer line
@@ -165,7 +174,7 @@ impl WidgetRef for StatusIndicatorWidget {
         spans.extend(vec![
             " ".into(),
             format!("({pretty_elapsed} • ").dim(),
-            "Esc".dim().bold(),
+            key_hint::chord_label(self.interrupt_key).dim().bold(),
             " to interrupt)".dim(),
         ]);
 
@@ -0,0 +1,287 @@
+//! A small tree-sitter index of the functions and types defined in a
+//! workspace, used to resolve `@fn:name` and `@type:name` mentions.
+//!
+//! The index is built on first use by walking the workspace (respecting
+//! `.gitignore`) and parsing every file in a supported language. It only
+//! records names and line ranges; it does not try to resolve imports or
+//! scopes.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use ignore::WalkBuilder;
+use tree_sitter::Language;
+use tree_sitter::Parser;
+use tree_sitter::Query;
+use tree_sitter::QueryCursor;
+use tree_sitter::StreamingIterator;
+
+/// Files larger than this are not indexed.
+const MAX_INDEXED_FILE_BYTES: u64 = 512 * 1024;
+/// Stop walking the workspace after this many indexed files.
+const MAX_INDEXED_FILES: usize = 5_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SymbolKind {
+    /// Functions, methods and macros.
+    Function,
+    /// Structs, enums, classes, traits, interfaces and type aliases.
+    Type,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SymbolDef {
+    pub(crate) kind: SymbolKind,
+    pub(crate) name: String,
+    /// Path relative to the indexed root.
+    pub(crate) path: PathBuf,
+    /// 1-based, inclusive.
+    pub(crate) start_line: usize,
+    pub(crate) end_line: usize,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct SymbolIndex {
+    symbols: Vec<SymbolDef>,
+}
+
+impl SymbolIndex {
+    pub(crate) fn build(root: &Path) -> Self {
+        let mut symbols = Vec::new();
+        let mut indexers: HashMap<SymbolLanguage, Option<Indexer>> = HashMap::new();
+        let mut indexed = 0;
+        for entry in WalkBuilder::new(root).build().flatten() {
+            if indexed >= MAX_INDEXED_FILES {
+                break;
+            }
+            let path = entry.path();
+            let Some(lang) = SymbolLanguage::for_path(path) else {
+                continue;
+            };
+            let indexable = entry
+                .metadata()
+                .is_ok_and(|meta| meta.is_file() && meta.len() <= MAX_INDEXED_FILE_BYTES);
+            if !indexable {
+                continue;
+            }
+            let Some(indexer) = indexers.entry(lang).or_insert_with(|| Indexer::new(lang)) else {
+                continue;
+            };
+            let Ok(source) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            indexed += 1;
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            symbols.extend(indexer.index(relative, &source));
+        }
+        Self { symbols }
+    }
+
+    /// Definitions of `name`, files closer to the root first.
+    pub(crate) fn lookup(&self, kind: SymbolKind, name: &str) -> Vec<&SymbolDef> {
+        let mut found: Vec<&SymbolDef> = self
+            .symbols
+            .iter()
+            .filter(|sym| sym.kind == kind && sym.name == name)
+            .collect();
+        found.sort_by_key(|sym| (sym.path.components().count(), sym.path.clone()));
+        found
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SymbolLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+impl SymbolLanguage {
+    fn for_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Rust),
+            "py" => Some(Self::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    fn language(self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    /// Query capturing definitions as `@function` or `@type`, with their
+    /// name as `@name`.
+    fn query_source(self) -> &'static str {
+        match self {
+            Self::Rust => {
+                r#"
+                (function_item name: (identifier) @name) @function
+                (function_signature_item name: (identifier) @name) @function
+                (macro_definition name: (identifier) @name) @function
+                (struct_item name: (type_identifier) @name) @type
+                (enum_item name: (type_identifier) @name) @type
+                (union_item name: (type_identifier) @name) @type
+                (trait_item name: (type_identifier) @name) @type
+                (type_item name: (type_identifier) @name) @type
+                "#
+            }
+            Self::Python => {
+                r#"
+                (function_definition name: (identifier) @name) @function
+                (class_definition name: (identifier) @name) @type
+                "#
+            }
+            Self::JavaScript => {
+                r#"
+                (function_declaration name: (identifier) @name) @function
+                (generator_function_declaration name: (identifier) @name) @function
+                (method_definition name: (property_identifier) @name) @function
+                (variable_declarator
+                  name: (identifier) @name
+                  value: [(arrow_function) (function_expression)]) @function
+                (class_declaration name: (identifier) @name) @type
+                "#
+            }
+            Self::TypeScript | Self::Tsx => {
+                r#"
+                (function_declaration name: (identifier) @name) @function
+                (method_definition name: (property_identifier) @name) @function
+                (variable_declarator
+                  name: (identifier) @name
+                  value: [(arrow_function) (function_expression)]) @function
+                (class_declaration name: (type_identifier) @name) @type
+                (abstract_class_declaration name: (type_identifier) @name) @type
+                (interface_declaration name: (type_identifier) @name) @type
+                (type_alias_declaration name: (type_identifier) @name) @type
+                (enum_declaration name: (identifier) @name) @type
+                "#
+            }
+            Self::Go => {
+                r#"
+                (function_declaration name: (identifier) @name) @function
+                (method_declaration name: (field_identifier) @name) @function
+                (type_spec name: (type_identifier) @name) @type
+                "#
+            }
+        }
+    }
+}
+
+/// A parser and definitions query for one language.
+struct Indexer {
+    parser: Parser,
+    query: Query,
+}
+
+impl Indexer {
+    /// `None` if the grammar or query fails to load.
+    fn new(lang: SymbolLanguage) -> Option<Self> {
+        let language = lang.language();
+        let query = Query::new(&language, lang.query_source()).ok()?;
+        let mut parser = Parser::new();
+        parser.set_language(&language).ok()?;
+        Some(Self { parser, query })
+    }
+
+    fn index(&mut self, path: &Path, source: &str) -> Vec<SymbolDef> {
+        let Some(tree) = self.parser.parse(source, None) else {
+            return Vec::new();
+        };
+        let bytes = source.as_bytes();
+        let mut symbols = Vec::new();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.query, tree.root_node(), bytes);
+        while let Some(m) = matches.next() {
+            let mut name = None;
+            let mut definition = None;
+            for capture in m.captures.iter() {
+                match self.query.capture_names()[capture.index as usize] {
+                    "name" => name = capture.node.utf8_text(bytes).ok(),
+                    "function" => definition = Some((SymbolKind::Function, capture.node)),
+                    "type" => definition = Some((SymbolKind::Type, capture.node)),
+                    _ => {}
+                }
+            }
+            if let (Some(name), Some((kind, node))) = (name, definition) {
+                symbols.push(SymbolDef {
+                    kind,
+                    name: name.to_string(),
+                    path: path.to_path_buf(),
+                    start_line: node.start_position().row + 1,
+                    end_line: node.end_position().row + 1,
+                });
+            }
+        }
+        symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn indexes_rust_functions_and_types() {
+        let source = "\
+struct Parser;
+
+impl Parser {
+    fn parse_command(&self) {
+        todo!()
+    }
+}
+";
+        let mut indexer = Indexer::new(SymbolLanguage::Rust).expect("rust grammar");
+        let symbols = indexer.index(Path::new("src/lib.rs"), source);
+        assert_eq!(
+            symbols,
+            vec![
+                SymbolDef {
+                    kind: SymbolKind::Type,
+                    name: "Parser".to_string(),
+                    path: PathBuf::from("src/lib.rs"),
+                    start_line: 1,
+                    end_line: 1,
+                },
+                SymbolDef {
+                    kind: SymbolKind::Function,
+                    name: "parse_command".to_string(),
+                    path: PathBuf::from("src/lib.rs"),
+                    start_line: 4,
+                    end_line: 6,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn indexes_python_definitions() {
+        let source = "class Shell:\n    def run(self):\n        pass\n";
+        let mut indexer = Indexer::new(SymbolLanguage::Python).expect("python grammar");
+        let symbols = indexer.index(Path::new("shell.py"), source);
+        let names: Vec<(SymbolKind, &str)> = symbols
+            .iter()
+            .map(|sym| (sym.kind, sym.name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![(SymbolKind::Type, "Shell"), (SymbolKind::Function, "run")]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Detect whether the terminal has a light or a dark background.
+//!
+//! The terminal is asked for its background color with an OSC 11 query,
+//! followed by a primary device attributes (DA1) request. Every terminal
+//! answers DA1, so we stop reading as soon as that reply arrives instead of
+//! waiting for the timeout when OSC 11 is not supported. If the terminal does
+//! not report a color, `COLORFGBG` (set by rxvt, Konsole and others) is used
+//! as a fallback.
+
+use std::time::Duration;
+
+/// How long to wait for the terminal to answer before giving up.
+#[cfg_attr(not(unix), allow(dead_code))]
+const QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Background {
+    Dark,
+    Light,
+}
+
+/// Best-effort detection of the terminal background. Must be called before
+/// the TUI starts reading input, since the reply arrives on the input stream.
+pub(crate) fn detect() -> Option<Background> {
+    query_osc11().or_else(|| {
+        std::env::var("COLORFGBG")
+            .ok()
+            .and_then(|value| parse_colorfgbg(&value))
+    })
+}
+
+#[cfg(unix)]
+fn query_osc11() -> Option<Background> {
+    use crossterm::terminal::disable_raw_mode;
+    use crossterm::terminal::enable_raw_mode;
+    use crossterm::terminal::is_raw_mode_enabled;
+
+    let mut tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    // The reply is not newline-terminated, so it can only be read in raw mode.
+    let was_raw = is_raw_mode_enabled().unwrap_or(false);
+    if !was_raw {
+        enable_raw_mode().ok()?;
+    }
+    let response = read_query_response(&mut tty);
+    if !was_raw {
+        let _ = disable_raw_mode();
+    }
+    parse_osc11_response(&response?)
+}
+
+#[cfg(not(unix))]
+fn query_osc11() -> Option<Background> {
+    None
+}
+
+#[cfg(unix)]
+fn read_query_response(tty: &mut std::fs::File) -> Option<Vec<u8>> {
+    use std::io::Read;
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+    use std::time::Instant;
+
+    tty.write_all(b"\x1b]11;?\x1b\\\x1b[c").ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    let mut response = Vec::new();
+    let mut buf = [0u8; 256];
+    while !contains_device_attributes(&response) {
+        let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+            break;
+        };
+        let mut pollfd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `pollfd` is a valid, exclusively borrowed pollfd for the
+        // duration of the call and we pass a count of exactly one.
+        let ready = unsafe { libc::poll(&mut pollfd, 1, remaining.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            break;
+        }
+        match tty.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => response.extend_from_slice(&buf[..n]),
+        }
+    }
+    Some(response)
+}
+
+/// Whether `bytes` contains a complete DA1 reply (`ESC [ ? ... c`).
+#[cfg_attr(not(unix), allow(dead_code))]
+fn contains_device_attributes(bytes: &[u8]) -> bool {
+    bytes
+        .windows(3)
+        .position(|window| window == b"\x1b[?")
+        .is_some_and(|start| bytes[start..].contains(&b'c'))
+}
+
+/// Parse a reply such as `ESC ] 11 ; rgb:ffff/ffff/dddd ESC \`.
+fn parse_osc11_response(bytes: &[u8]) -> Option<Background> {
+    let text = String::from_utf8_lossy(bytes);
+    let body = &text[text.find("]11;")? + 4..];
+    let spec = &body[..body.find(['\x07', '\x1b']).unwrap_or(body.len())];
+    let spec = spec
+        .strip_prefix("rgb:")
+        .or_else(|| spec.strip_prefix("rgba:"))?;
+    let mut channels = spec.split('/').map(parse_channel);
+    let (r, g, b) = (channels.next()??, channels.next()??, channels.next()??);
+    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    Some(if luminance > 0.5 {
+        Background::Light
+    } else {
+        Background::Dark
+    })
+}
+
+/// Parse one 1–4 digit hex channel into the range `0.0..=1.0`.
+fn parse_channel(hex: &str) -> Option<f32> {
+    if hex.is_empty() || hex.len() > 4 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let max = (1u32 << (4 * hex.len())) - 1;
+    Some(value as f32 / max as f32)
+}
+
+/// `COLORFGBG` is `fg;bg` (or `fg;default;bg`) with ANSI color indices.
+fn parse_colorfgbg(value: &str) -> Option<Background> {
+    let bg: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+    Some(if matches!(bg, 7 | 15) {
+        Background::Light
+    } else {
+        Background::Dark
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_osc11_replies() {
+        assert_eq!(
+            parse_osc11_response(b"\x1b]11;rgb:ffff/ffff/ffff\x1b\\\x1b[?62;22c"),
+            Some(Background::Light)
+        );
+        assert_eq!(
+            parse_osc11_response(b"\x1b]11;rgb:1e1e/1e1e/2e2e\x07"),
+            Some(Background::Dark)
+        );
+        assert_eq!(
+            parse_osc11_response(b"\x1b]11;rgba:fd/f6/e3/ff\x1b\\"),
+            Some(Background::Light)
+        );
+        // Only the DA1 reply: the terminal does not support OSC 11.
+        assert_eq!(parse_osc11_response(b"\x1b[?1;2c"), None);
+        assert_eq!(parse_osc11_response(b"\x1b]11;rgb:zz/00/00\x07"), None);
+    }
+
+    #[test]
+    fn detects_device_attributes_reply() {
+        assert!(!contains_device_attributes(b"\x1b]11;rgb:0/0/0\x1b\\"));
+        assert!(!contains_device_attributes(
+            b"\x1b]11;rgb:0/0/0\x1b\\\x1b[?6"
+        ));
+        assert!(contains_device_attributes(
+            b"\x1b]11;rgb:0/0/0\x1b\\\x1b[?62;c"
+        ));
+    }
+
+    #[test]
+    fn parses_colorfgbg() {
+        assert_eq!(parse_colorfgbg("15;0"), Some(Background::Dark));
+        assert_eq!(parse_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(parse_colorfgbg("0;default;7"), Some(Background::Light));
+        assert_eq!(parse_colorfgbg("default"), None);
+    }
+}
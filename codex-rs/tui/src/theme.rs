@@ -0,0 +1,309 @@
+//! Semantic colors used across the TUI.
+//!
+//! Rendering code asks the active [`Palette`] for the style of a role (added
+//! or removed diff lines, commands, reasoning, errors) instead of hardcoding
+//! a color. The palette is picked at startup from `tui.theme`, the detected
+//! terminal background and the `[tui.colors]` overrides, and again when those
+//! settings change in `config.toml`.
+
+use std::sync::RwLock;
+
+use codex_core::config::Config;
+use codex_core::theme::ThemeColor;
+use codex_core::theme::ThemeColors;
+use codex_core::theme::ThemeName;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+
+use crate::terminal_background;
+use crate::terminal_background::Background;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Palette {
+    /// Background the palette is designed for. Used by effects that derive
+    /// their colors from the default foreground, such as the shimmer.
+    pub(crate) background: Background,
+    /// Avoid dimmed text and render colored roles in bold.
+    pub(crate) high_contrast: bool,
+    pub(crate) added: Color,
+    pub(crate) removed: Color,
+    pub(crate) command: Color,
+    pub(crate) reasoning: Color,
+    pub(crate) error: Color,
+    /// Color of secondary text (gutters, inline code). `None` dims the
+    /// default foreground instead, which many terminals render too faintly
+    /// on a light background.
+    pub(crate) secondary: Option<Color>,
+}
+
+impl Palette {
+    pub(crate) const DARK: Palette = Palette {
+        background: Background::Dark,
+        high_contrast: false,
+        added: Color::Green,
+        removed: Color::Red,
+        command: Color::Magenta,
+        reasoning: Color::Magenta,
+        error: Color::Red,
+        secondary: None,
+    };
+
+    /// The standard ANSI green and magenta are too pale to read on a white
+    /// background, so the light palette uses darker shades from the
+    /// 256-color table.
+    pub(crate) const LIGHT: Palette = Palette {
+        background: Background::Light,
+        high_contrast: false,
+        // dark green, dark red, dark magenta and mid gray
+        added: Color::Indexed(28),
+        removed: Color::Indexed(124),
+        command: Color::Indexed(90),
+        reasoning: Color::Indexed(90),
+        error: Color::Indexed(124),
+        secondary: Some(Color::Indexed(243)),
+    };
+
+    /// Bright ANSI variants stand out on dark backgrounds but wash out on
+    /// light ones, where the regular variants are the darker choice.
+    pub(crate) const fn high_contrast(background: Background) -> Palette {
+        match background {
+            Background::Dark => Palette {
+                background,
+                high_contrast: true,
+                added: Color::LightGreen,
+                removed: Color::LightRed,
+                command: Color::LightMagenta,
+                reasoning: Color::LightMagenta,
+                error: Color::LightRed,
+                secondary: None,
+            },
+            Background::Light => Palette {
+                background,
+                high_contrast: true,
+                ..Palette::LIGHT
+            },
+        }
+    }
+
+    /// Resolve the palette for `name`, using `detected` when the theme depends
+    /// on the terminal background, then apply the user's overrides.
+    pub(crate) fn resolve(
+        name: ThemeName,
+        detected: Option<Background>,
+        overrides: &ThemeColors,
+    ) -> Palette {
+        let background = detected.unwrap_or(Background::Dark);
+        let mut palette = match name {
+            ThemeName::Auto => match background {
+                Background::Dark => Palette::DARK,
+                Background::Light => Palette::LIGHT,
+            },
+            ThemeName::Dark => Palette::DARK,
+            ThemeName::Light => Palette::LIGHT,
+            ThemeName::HighContrast => Palette::high_contrast(background),
+        };
+        let roles = [
+            (&mut palette.added, overrides.added),
+            (&mut palette.removed, overrides.removed),
+            (&mut palette.command, overrides.command),
+            (&mut palette.reasoning, overrides.reasoning),
+            (&mut palette.error, overrides.error),
+        ];
+        for (slot, color) in roles {
+            if let Some(color) = color {
+                *slot = to_color(color);
+            }
+        }
+        palette
+    }
+
+    pub(crate) fn added(&self) -> Style {
+        self.role(self.added)
+    }
+
+    pub(crate) fn removed(&self) -> Style {
+        self.role(self.removed)
+    }
+
+    pub(crate) fn command(&self) -> Style {
+        self.role(self.command)
+    }
+
+    pub(crate) fn reasoning(&self) -> Style {
+        self.role(self.reasoning)
+    }
+
+    pub(crate) fn error(&self) -> Style {
+        self.role(self.error)
+    }
+
+    /// Style for secondary text such as line-number gutters.
+    pub(crate) fn secondary(&self) -> Style {
+        if self.high_contrast {
+            Style::default()
+        } else {
+            self.muted()
+        }
+    }
+
+    /// Style for `inline code` in rendered markdown.
+    pub(crate) fn inline_code(&self) -> Style {
+        if self.high_contrast {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            self.muted()
+        }
+    }
+
+    fn muted(&self) -> Style {
+        match self.secondary {
+            Some(color) => Style::default().fg(color),
+            None => Style::default().add_modifier(Modifier::DIM),
+        }
+    }
+
+    fn role(&self, color: Color) -> Style {
+        let style = Style::default().fg(color);
+        if self.high_contrast {
+            style.add_modifier(Modifier::BOLD)
+        } else {
+            style
+        }
+    }
+}
+
+static PALETTE: RwLock<Palette> = RwLock::new(Palette::DARK);
+
+/// The active palette.
+pub(crate) fn palette() -> Palette {
+    PALETTE
+        .read()
+        .map(|palette| *palette)
+        .unwrap_or(Palette::DARK)
+}
+
+pub(crate) fn set_palette(palette: Palette) {
+    if let Ok(mut active) = PALETTE.write() {
+        *active = palette;
+    }
+}
+
+/// Pick the palette for `config`, querying the terminal background only when
+/// the theme depends on it. Must run before the TUI starts reading input.
+pub(crate) fn init(config: &Config) {
+    let detected = match config.tui_theme {
+        ThemeName::Auto | ThemeName::HighContrast => terminal_background::detect(),
+        ThemeName::Dark | ThemeName::Light => None,
+    };
+    set_palette(Palette::resolve(
+        config.tui_theme,
+        detected,
+        &config.tui_colors,
+    ));
+}
+
+/// Pick the palette again after `tui.theme` or `[tui.colors]` changed in
+/// `config.toml`. The terminal cannot be queried while the TUI reads input,
+/// so themes that follow the background reuse the current palette's.
+pub(crate) fn reload(config: &Config) {
+    set_palette(Palette::resolve(
+        config.tui_theme,
+        Some(palette().background),
+        &config.tui_colors,
+    ));
+}
+
+fn to_color(color: ThemeColor) -> Color {
+    match color {
+        ThemeColor::Default => Color::Reset,
+        ThemeColor::Black => Color::Black,
+        ThemeColor::Red => Color::Red,
+        ThemeColor::Green => Color::Green,
+        ThemeColor::Yellow => Color::Yellow,
+        ThemeColor::Blue => Color::Blue,
+        ThemeColor::Magenta => Color::Magenta,
+        ThemeColor::Cyan => Color::Cyan,
+        ThemeColor::Gray => Color::Gray,
+        ThemeColor::DarkGray => Color::DarkGray,
+        ThemeColor::LightRed => Color::LightRed,
+        ThemeColor::LightGreen => Color::LightGreen,
+        ThemeColor::LightYellow => Color::LightYellow,
+        ThemeColor::LightBlue => Color::LightBlue,
+        ThemeColor::LightMagenta => Color::LightMagenta,
+        ThemeColor::LightCyan => Color::LightCyan,
+        ThemeColor::White => Color::White,
+        // Custom colors are an explicit user choice.
+        #[allow(clippy::disallowed_methods)]
+        ThemeColor::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn auto_follows_detected_background() {
+        let none = ThemeColors::default();
+        assert_eq!(
+            Palette::resolve(ThemeName::Auto, None, &none),
+            Palette::DARK
+        );
+        assert_eq!(
+            Palette::resolve(ThemeName::Auto, Some(Background::Light), &none),
+            Palette::LIGHT
+        );
+        assert_eq!(
+            Palette::resolve(ThemeName::Dark, Some(Background::Light), &none),
+            Palette::DARK
+        );
+        assert_eq!(
+            Palette::resolve(ThemeName::HighContrast, None, &none).added,
+            Color::LightGreen
+        );
+    }
+
+    #[test]
+    fn light_palette_is_readable_on_a_light_background() {
+        let (dark, light) = (Palette::DARK, Palette::LIGHT);
+        for (role, dark_color, light_color) in [
+            ("added", dark.added, light.added),
+            ("removed", dark.removed, light.removed),
+            ("command", dark.command, light.command),
+            ("reasoning", dark.reasoning, light.reasoning),
+        ] {
+            assert_ne!(
+                dark_color, light_color,
+                "{role} is the same in both palettes"
+            );
+        }
+        assert_eq!(
+            dark.secondary(),
+            Style::default().add_modifier(Modifier::DIM)
+        );
+        assert_eq!(light.secondary(), Style::default().fg(Color::Indexed(243)));
+        assert_eq!(light.inline_code(), light.secondary());
+    }
+
+    #[test]
+    fn overrides_apply_on_top_of_theme() {
+        let overrides = ThemeColors {
+            added: Some(ThemeColor::Blue),
+            error: Some(ThemeColor::Default),
+            ..ThemeColors::default()
+        };
+        let palette = Palette::resolve(ThemeName::HighContrast, None, &overrides);
+        assert_eq!(palette.added, Color::Blue);
+        assert_eq!(palette.error, Color::Reset);
+        assert_eq!(palette.removed, Color::LightRed);
+        assert_eq!(
+            palette.added(),
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(palette.secondary(), Style::default());
+    }
+}
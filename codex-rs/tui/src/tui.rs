@@ -3,12 +3,15 @@ use std::io::Stdout;
 use std::io::stdout;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::atomic::AtomicBool;
 #[cfg(unix)]
 use std::sync::atomic::AtomicU8;
 #[cfg(unix)]
 use std::sync::atomic::AtomicU16;
 use std::sync::atomic::Ordering;
+use std::task::Poll;
+use std::task::Waker;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -156,6 +159,17 @@ pub enum TuiEvent {
     Draw,
 }
 
+/// The crossterm input stream read by [`Tui::event_stream`]. It is dropped
+/// while another program owns the terminal, since its reader would otherwise
+/// keep consuming that program's keystrokes.
+#[derive(Default)]
+struct TerminalInput {
+    /// `None` while input is paused.
+    events: Option<crossterm::event::EventStream>,
+    /// Woken when input resumes.
+    waker: Option<Waker>,
+}
+
 pub struct Tui {
     frame_schedule_tx: tokio::sync::mpsc::UnboundedSender<Instant>,
     draw_tx: tokio::sync::broadcast::Sender<()>,
@@ -170,6 +184,7 @@ pub struct Tui {
     alt_screen_active: Arc<AtomicBool>,
     // True when terminal/tab is focused; updated internally from crossterm events
     terminal_focused: Arc<AtomicBool>,
+    input: Arc<Mutex<TerminalInput>>,
 }
 
 #[cfg(unix)]
@@ -288,6 +303,7 @@ impl Tui {
             suspend_cursor_y: Arc::new(AtomicU16::new(0)),
             alt_screen_active: Arc::new(AtomicBool::new(false)),
             terminal_focused: Arc::new(AtomicBool::new(true)),
+            input: Arc::new(Mutex::new(TerminalInput::default())),
         }
     }
 
@@ -298,8 +314,8 @@ impl Tui {
     }
 
     pub fn event_stream(&self) -> Pin<Box<dyn Stream<Item = TuiEvent> + Send + 'static>> {
-        use tokio_stream::StreamExt;
-        let mut crossterm_events = crossterm::event::EventStream::new();
+        self.resume_input();
+        let input = self.input.clone();
         let mut draw_rx = self.draw_tx.subscribe();
         #[cfg(unix)]
         let resume_pending = self.resume_pending.clone();
@@ -310,8 +326,22 @@ impl Tui {
         let terminal_focused = self.terminal_focused.clone();
         let event_stream = async_stream::stream! {
             loop {
+                // Lock only while polling, so input can be paused while this
+                // stream is suspended.
+                let next_crossterm_event = std::future::poll_fn(|cx| {
+                    let Ok(mut input) = input.lock() else {
+                        return Poll::Ready(None);
+                    };
+                    match input.events.as_mut() {
+                        Some(events) => Pin::new(events).poll_next(cx),
+                        None => {
+                            input.waker = Some(cx.waker().clone());
+                            Poll::Pending
+                        }
+                    }
+                });
                 select! {
-                    Some(Ok(event)) = crossterm_events.next() => {
+                    Some(Ok(event)) = next_crossterm_event => {
                         match event {
                             crossterm::event::Event::Key(key_event) => {
                                 #[cfg(unix)]
@@ -470,6 +500,48 @@ impl Tui {
         Ok(())
     }
 
+    /// Stop reading terminal input until [`Tui::resume_input`].
+    fn pause_input(&self) {
+        if let Ok(mut input) = self.input.lock() {
+            input.events = None;
+        }
+    }
+
+    /// Start (or restart) reading terminal input.
+    fn resume_input(&self) {
+        if let Ok(mut input) = self.input.lock() {
+            if input.events.is_none() {
+                input.events = Some(crossterm::event::EventStream::new());
+            }
+            if let Some(waker) = input.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Hand the terminal to `f` (e.g. to run an external editor) and restore
+    /// the TUI afterwards. `f` runs on a blocking thread, on the alternate
+    /// screen with raw mode and the other input modes disabled, so the inline
+    /// history in the normal scrollback is left untouched. Terminal input is
+    /// not read while `f` runs, so every keystroke goes to `f`'s program.
+    pub async fn with_released_terminal<R: Send + 'static>(
+        &mut self,
+        f: impl FnOnce() -> R + Send + 'static,
+    ) -> Result<R> {
+        execute!(self.terminal.backend_mut(), EnterAlternateScreen)?;
+        restore()?;
+        self.pause_input();
+        let result = tokio::task::spawn_blocking(f).await;
+        self.resume_input();
+        set_modes()?;
+        let result = result.map_err(std::io::Error::other)?;
+        execute!(self.terminal.backend_mut(), LeaveAlternateScreen)?;
+        // Force a full redraw of the viewport on the next frame.
+        self.terminal.clear()?;
+        self.frame_requester().schedule_frame();
+        Ok(result)
+    }
+
     pub fn insert_history_lines(&mut self, lines: Vec<Line<'static>>) {
         self.pending_history_lines.extend(lines);
         self.frame_requester().schedule_frame();
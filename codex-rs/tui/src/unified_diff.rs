@@ -0,0 +1,359 @@
+//! Split `git diff` output into files and hunks.
+//!
+//! The diff viewer needs more structure than the flat text `git diff` prints:
+//! it lists files with their line counts, renders one file at a time and
+//! hands single hunks back to `git apply` to stage or revert them. Paths are
+//! kept exactly as they appear in the diff, i.e. relative to the repository
+//! root.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::protocol::FileChange;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiffFileStatus {
+    Added,
+    Deleted,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiffHunk {
+    /// The `@@ -a,b +c,d @@` line, including any trailing section heading.
+    pub(crate) header: String,
+    /// Body lines, each starting with ` `, `+`, `-` or `\`.
+    pub(crate) lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiffFile {
+    pub(crate) path: PathBuf,
+    /// Previous path when the file was renamed.
+    pub(crate) old_path: Option<PathBuf>,
+    pub(crate) status: DiffFileStatus,
+    pub(crate) binary: bool,
+    pub(crate) hunks: Vec<DiffHunk>,
+    /// Lines from `diff --git` through `+++`, replayed in front of a hunk so
+    /// `git apply` knows which file it touches.
+    header: Vec<String>,
+}
+
+impl DiffFile {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            old_path: None,
+            status: DiffFileStatus::Modified,
+            binary: false,
+            hunks: Vec::new(),
+            header: Vec::new(),
+        }
+    }
+
+    /// Number of added and removed lines.
+    pub(crate) fn line_counts(&self) -> (usize, usize) {
+        self.hunks.iter().flat_map(|hunk| hunk.lines.iter()).fold(
+            (0, 0),
+            |(added, removed), line| match line.as_bytes().first() {
+                Some(b'+') => (added + 1, removed),
+                Some(b'-') => (added, removed + 1),
+                _ => (added, removed),
+            },
+        )
+    }
+
+    /// A minimal `---`/`+++` diff containing only hunk `idx`, or every hunk
+    /// when `idx` is `None`.
+    pub(crate) fn unified_diff(&self, idx: Option<usize>) -> String {
+        let path = self.path.display();
+        let old_path = self.old_path.as_deref().unwrap_or(&self.path).display();
+        let mut out = format!("--- a/{old_path}\n+++ b/{path}\n");
+        for (i, hunk) in self.hunks.iter().enumerate() {
+            if idx.is_none_or(|idx| idx == i) {
+                push_hunk(&mut out, hunk);
+            }
+        }
+        out
+    }
+
+    /// A patch for `git apply` that contains the file header and hunk `idx`.
+    pub(crate) fn hunk_patch(&self, idx: usize) -> Option<String> {
+        let hunk = self.hunks.get(idx)?;
+        let mut out = String::new();
+        for line in &self.header {
+            out.push_str(line);
+            out.push('\n');
+        }
+        push_hunk(&mut out, hunk);
+        Some(out)
+    }
+
+    /// The file as a [`FileChange`] keyed by its path under `root`, so it can
+    /// be rendered like any other patch.
+    pub(crate) fn to_file_change(&self, root: &Path) -> (PathBuf, FileChange) {
+        let side_content = |sign: u8| -> String {
+            self.hunks
+                .iter()
+                .flat_map(|hunk| hunk.lines.iter())
+                .filter(|line| line.as_bytes().first() == Some(&sign))
+                .map(|line| format!("{}\n", &line[1..]))
+                .collect()
+        };
+        match self.status {
+            DiffFileStatus::Added => (
+                root.join(&self.path),
+                FileChange::Add {
+                    content: side_content(b'+'),
+                    mode: None,
+                },
+            ),
+            DiffFileStatus::Deleted => (
+                root.join(&self.path),
+                FileChange::Delete {
+                    content: side_content(b'-'),
+                },
+            ),
+            DiffFileStatus::Modified => {
+                let old_path = self.old_path.as_deref().unwrap_or(&self.path);
+                (
+                    root.join(old_path),
+                    FileChange::Update {
+                        unified_diff: self.unified_diff(None),
+                        move_path: self.old_path.as_ref().map(|_| root.join(&self.path)),
+                        mode: None,
+                    },
+                )
+            }
+        }
+    }
+}
+
+fn push_hunk(out: &mut String, hunk: &DiffHunk) {
+    out.push_str(&hunk.header);
+    out.push('\n');
+    for line in &hunk.lines {
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+/// Parse the output of `git diff` (or any concatenation of unified diffs)
+/// into files. Color escapes are not supported.
+pub(crate) fn parse_unified_diff(text: &str) -> Vec<DiffFile> {
+    let mut files: Vec<DiffFile> = Vec::new();
+    let mut current: Option<DiffFile> = None;
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            files.extend(current.take());
+            let path = rest
+                .rfind(" b/")
+                .map(|idx| PathBuf::from(&rest[idx + 3..]))
+                .unwrap_or_default();
+            let mut file = DiffFile::new(path);
+            file.header.push(line.to_string());
+            current = Some(file);
+            continue;
+        }
+        // Plain unified diffs have no `diff --git` line: a `---`/`+++` pair
+        // after the last hunk starts the next file.
+        let is_plain = current.as_ref().is_none_or(|file| {
+            !file.hunks.is_empty()
+                && !file
+                    .header
+                    .first()
+                    .is_some_and(|first| first.starts_with("diff --git "))
+        });
+        if is_plain
+            && line.starts_with("--- ")
+            && lines.peek().is_some_and(|next| next.starts_with("+++ "))
+        {
+            files.extend(current.take());
+            current = Some(DiffFile::new(PathBuf::new()));
+        }
+        let Some(file) = current.as_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            file.hunks.push(DiffHunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+            });
+            continue;
+        }
+        if let Some(hunk) = file.hunks.last_mut() {
+            match line.as_bytes().first() {
+                Some(b' ' | b'+' | b'-' | b'\\') => hunk.lines.push(line.to_string()),
+                // Some tools strip the trailing space of empty context lines.
+                None => hunk.lines.push(" ".to_string()),
+                Some(_) => {}
+            }
+            continue;
+        }
+
+        file.header.push(line.to_string());
+        if line.starts_with("new file mode") {
+            file.status = DiffFileStatus::Added;
+        } else if line.starts_with("deleted file mode") {
+            file.status = DiffFileStatus::Deleted;
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            file.old_path = Some(PathBuf::from(from));
+        } else if let Some(to) = line.strip_prefix("rename to ") {
+            file.path = PathBuf::from(to);
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        } else if let Some(old) = line.strip_prefix("--- ") {
+            match header_path(old, "a/") {
+                None => file.status = DiffFileStatus::Added,
+                Some(old) if file.path.as_os_str().is_empty() => file.path = old,
+                Some(_) => {}
+            }
+        } else if let Some(new) = line.strip_prefix("+++ ") {
+            match header_path(new, "b/") {
+                None => file.status = DiffFileStatus::Deleted,
+                Some(new) => file.path = new,
+            }
+        }
+    }
+    files.extend(current);
+    files
+}
+
+/// Path named by a `---`/`+++` header, or `None` for `/dev/null`.
+fn header_path(value: &str, prefix: &str) -> Option<PathBuf> {
+    let value = value.split('\t').next().unwrap_or(value);
+    if value == "/dev/null" {
+        return None;
+    }
+    Some(PathBuf::from(value.strip_prefix(prefix).unwrap_or(value)))
+}
+
+/// All files of `files` as changes keyed by their path under `root`.
+pub(crate) fn to_file_changes(files: &[DiffFile], root: &Path) -> HashMap<PathBuf, FileChange> {
+    files.iter().map(|file| file.to_file_change(root)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const GIT_DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@ mod a;
+ fn one() {}
+-fn two() {}
++fn deux() {}
+ fn three() {}
+@@ -10,2 +10,3 @@ fn ten() {}
+ fn ten() {}
++fn eleven() {}
+ fn twelve() {}
+diff --git a/notes.txt b/notes.txt
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/notes.txt
+@@ -0,0 +1,2 @@
++hello
++world
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+index 4444444..0000000
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+diff --git a/a.md b/b.md
+similarity index 90%
+rename from a.md
+rename to b.md
+";
+
+    #[test]
+    fn parses_files_hunks_and_statuses() {
+        let files = parse_unified_diff(GIT_DIFF);
+        let summary: Vec<_> = files
+            .iter()
+            .map(|f| {
+                (
+                    f.path.display().to_string(),
+                    f.status,
+                    f.hunks.len(),
+                    f.line_counts(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "src/lib.rs".to_string(),
+                    DiffFileStatus::Modified,
+                    2,
+                    (2, 1)
+                ),
+                ("notes.txt".to_string(), DiffFileStatus::Added, 1, (2, 0)),
+                ("old.txt".to_string(), DiffFileStatus::Deleted, 1, (0, 1)),
+                ("b.md".to_string(), DiffFileStatus::Modified, 0, (0, 0)),
+            ]
+        );
+        assert_eq!(files[3].old_path, Some(PathBuf::from("a.md")));
+        assert_eq!(files[0].hunks[1].header, "@@ -10,2 +10,3 @@ fn ten() {}");
+    }
+
+    #[test]
+    fn hunk_patch_keeps_file_header_and_single_hunk() {
+        let files = parse_unified_diff(GIT_DIFF);
+        assert_eq!(
+            files[0].hunk_patch(1).as_deref(),
+            Some(
+                "diff --git a/src/lib.rs b/src/lib.rs\n\
+                 index 1111111..2222222 100644\n\
+                 --- a/src/lib.rs\n\
+                 +++ b/src/lib.rs\n\
+                 @@ -10,2 +10,3 @@ fn ten() {}\n \
+                 fn ten() {}\n\
+                 +fn eleven() {}\n \
+                 fn twelve() {}\n"
+            )
+        );
+        assert_eq!(files[0].hunk_patch(2), None);
+    }
+
+    #[test]
+    fn converts_to_file_changes() {
+        let files = parse_unified_diff(GIT_DIFF);
+        let root = Path::new("/repo");
+        let changes = to_file_changes(&files, root);
+        assert_eq!(
+            changes.get(Path::new("/repo/notes.txt")),
+            Some(&FileChange::Add {
+                content: "hello\nworld\n".to_string(),
+                mode: None,
+            })
+        );
+        assert_eq!(
+            changes.get(Path::new("/repo/a.md")),
+            Some(&FileChange::Update {
+                unified_diff: "--- a/a.md\n+++ b/b.md\n".to_string(),
+                move_path: Some(PathBuf::from("/repo/b.md")),
+                mode: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_plain_unified_diffs() {
+        let files = parse_unified_diff("--- a/x.txt\n+++ b/x.txt\n@@ -1 +1 @@\n-a\n+b\n");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, PathBuf::from("x.txt"));
+        assert_eq!(files[0].line_counts(), (1, 1));
+    }
+}
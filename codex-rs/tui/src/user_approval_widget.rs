@@ -6,14 +6,21 @@
 //! UI to Rust using [`ratatui`]. The goal is feature‑parity for the keyboard
 //! driven workflow – a fully‑fledged visual match is not required.
 
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::LazyLock;
 
+use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
+use codex_core::protocol::PatchComment;
+use codex_core::protocol::RejectedChange;
 use codex_core::protocol::ReviewDecision;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::prelude::*;
@@ -28,9 +35,13 @@ use ratatui::widgets::Wrap;
 
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
+use crate::diff_render::display_path_for;
+use crate::diff_render::file_change_note;
+use crate::diff_render::numbered_diff_lines;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::history_cell;
 use crate::text_formatting::truncate_text;
+use crate::theme;
 
 /// Request coming from the agent that needs user approval.
 pub(crate) enum ApprovalRequest {
@@ -43,6 +54,9 @@ pub(crate) enum ApprovalRequest {
         id: String,
         reason: Option<String>,
         grant_root: Option<PathBuf>,
+        /// The proposed changes, so lines can be commented on.
+        changes: HashMap<PathBuf, FileChange>,
+        cwd: PathBuf,
     },
 }
 
@@ -53,7 +67,16 @@ struct SelectOption {
     label: Line<'static>,
     description: &'static str,
     key: KeyCode,
-    decision: ReviewDecision,
+    action: SelectAction,
+}
+
+#[derive(Clone, Copy)]
+enum SelectAction {
+    Decide(ReviewDecision),
+    /// Enter *review* mode to comment on the patch before denying it.
+    RequestChanges,
+    /// Enter *pick* mode to approve only some files or hunks.
+    PickParts,
 }
 
 static COMMAND_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
@@ -62,19 +85,19 @@ static COMMAND_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
             label: Line::from(vec!["Y".underlined(), "es".into()]),
             description: "Approve and run the command",
             key: KeyCode::Char('y'),
-            decision: ReviewDecision::Approved,
+            action: SelectAction::Decide(ReviewDecision::Approved),
         },
         SelectOption {
             label: Line::from(vec!["A".underlined(), "lways".into()]),
             description: "Approve the command for the remainder of this session",
             key: KeyCode::Char('a'),
-            decision: ReviewDecision::ApprovedForSession,
+            action: SelectAction::Decide(ReviewDecision::ApprovedForSession),
         },
         SelectOption {
             label: Line::from(vec!["N".underlined(), "o, provide feedback".into()]),
             description: "Do not run the command; provide feedback",
             key: KeyCode::Char('n'),
-            decision: ReviewDecision::Abort,
+            action: SelectAction::Decide(ReviewDecision::Abort),
         },
     ]
 });
@@ -85,17 +108,348 @@ static PATCH_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
             label: Line::from(vec!["Y".underlined(), "es".into()]),
             description: "Approve and apply the changes",
             key: KeyCode::Char('y'),
-            decision: ReviewDecision::Approved,
+            action: SelectAction::Decide(ReviewDecision::Approved),
         },
         SelectOption {
             label: Line::from(vec!["N".underlined(), "o, provide feedback".into()]),
             description: "Do not apply the changes; provide feedback",
             key: KeyCode::Char('n'),
-            decision: ReviewDecision::Abort,
+            action: SelectAction::Decide(ReviewDecision::Abort),
+        },
+        SelectOption {
+            label: Line::from(vec!["R".underlined(), "equest changes".into()]),
+            description: "Comment on specific lines and send the comments back",
+            key: KeyCode::Char('r'),
+            action: SelectAction::RequestChanges,
+        },
+        SelectOption {
+            label: Line::from(vec!["S".underlined(), "elect files".into()]),
+            description: "Apply only the files or hunks you pick",
+            key: KeyCode::Char('s'),
+            action: SelectAction::PickParts,
         },
     ]
 });
 
+/// Rows of the patch listed in *review* mode.
+const REVIEW_VISIBLE_ROWS: usize = 10;
+
+/// One row of the patch in *review* mode: a file header or a diff line.
+struct ReviewLine {
+    path: PathBuf,
+    /// Line number the row is anchored to; `None` for file headers.
+    line: Option<u64>,
+    /// Diff sign (`+`, `-` or ` `); `None` for file headers.
+    sign: Option<char>,
+    text: String,
+}
+
+/// State of the *review* mode, where the user comments on lines of the
+/// proposed patch before requesting changes.
+struct PatchReview {
+    lines: Vec<ReviewLine>,
+    cursor: usize,
+    /// Comments by row index.
+    comments: BTreeMap<usize, String>,
+    /// Comment being typed for the row under the cursor.
+    input: Option<String>,
+}
+
+impl PatchReview {
+    fn new(changes: &HashMap<PathBuf, FileChange>, cwd: &Path) -> Self {
+        let mut paths: Vec<&PathBuf> = changes.keys().collect();
+        paths.sort();
+        let mut lines = Vec::new();
+        for path in paths {
+            let header = |text: String| ReviewLine {
+                path: path.clone(),
+                line: None,
+                sign: None,
+                text,
+            };
+            let change = &changes[path];
+            let diff_lines = match change {
+                FileChange::Add { content, .. } => {
+                    lines.push(header(with_note(
+                        format!("{} (new)", display_path_for(path, cwd)),
+                        change,
+                    )));
+                    numbered_content('+', content)
+                }
+                FileChange::AddBinary { .. } | FileChange::AddSymlink { .. } => {
+                    lines.push(header(with_note(
+                        format!("{} (new)", display_path_for(path, cwd)),
+                        change,
+                    )));
+                    Vec::new()
+                }
+                FileChange::Delete { content } => {
+                    lines.push(header(format!("{} (deleted)", display_path_for(path, cwd))));
+                    numbered_content('-', content)
+                }
+                FileChange::Update {
+                    unified_diff,
+                    move_path,
+                    ..
+                } => {
+                    let mut text = display_path_for(path, cwd);
+                    if let Some(move_path) = move_path {
+                        text.push_str(&format!(" → {}", display_path_for(move_path, cwd)));
+                    }
+                    lines.push(header(with_note(text, change)));
+                    numbered_diff_lines(unified_diff)
+                }
+            };
+            lines.extend(diff_lines.into_iter().map(|(sign, line, text)| ReviewLine {
+                path: path.clone(),
+                line: Some(line as u64),
+                sign: Some(sign),
+                text,
+            }));
+        }
+        Self {
+            lines,
+            cursor: 0,
+            comments: BTreeMap::new(),
+            input: None,
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let last = self.lines.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    /// First row of the window shown around the cursor.
+    fn window_start(&self) -> usize {
+        let max_start = self.lines.len().saturating_sub(REVIEW_VISIBLE_ROWS);
+        self.cursor
+            .saturating_sub(REVIEW_VISIBLE_ROWS / 2)
+            .min(max_start)
+    }
+
+    fn patch_comments(&self) -> Vec<PatchComment> {
+        self.comments
+            .iter()
+            .filter_map(|(idx, body)| {
+                let row = self.lines.get(*idx)?;
+                Some(PatchComment {
+                    path: row.path.clone(),
+                    line: row.line,
+                    snippet: row.sign.map(|sign| format!("{sign}{}", row.text)),
+                    body: body.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// One row in *pick* mode: a whole file or one hunk of an updated file.
+struct PatchPart {
+    path: PathBuf,
+    /// Index of the hunk in the file's unified diff; `None` for file rows.
+    hunk: Option<usize>,
+    label: String,
+    added: usize,
+    removed: usize,
+}
+
+/// State of the *pick* mode, where the user chooses which files and hunks
+/// of the patch to apply.
+struct PatchSelection {
+    parts: Vec<PatchPart>,
+    accepted: Vec<bool>,
+    cursor: usize,
+}
+
+impl PatchSelection {
+    fn new(changes: &HashMap<PathBuf, FileChange>, cwd: &Path) -> Self {
+        let mut paths: Vec<&PathBuf> = changes.keys().collect();
+        paths.sort();
+        let mut parts = Vec::new();
+        for path in paths {
+            let display = display_path_for(path, cwd);
+            let change = &changes[path];
+            match change {
+                FileChange::Add { content, .. } => parts.push(PatchPart {
+                    path: path.clone(),
+                    hunk: None,
+                    label: with_note(format!("{display} (new)"), change),
+                    added: content.lines().count(),
+                    removed: 0,
+                }),
+                FileChange::AddBinary { .. } | FileChange::AddSymlink { .. } => {
+                    parts.push(PatchPart {
+                        path: path.clone(),
+                        hunk: None,
+                        label: with_note(format!("{display} (new)"), change),
+                        added: 0,
+                        removed: 0,
+                    })
+                }
+                FileChange::Delete { content } => parts.push(PatchPart {
+                    path: path.clone(),
+                    hunk: None,
+                    label: format!("{display} (deleted)"),
+                    added: 0,
+                    removed: content.lines().count(),
+                }),
+                FileChange::Update {
+                    unified_diff,
+                    move_path,
+                    ..
+                } => {
+                    let hunks = split_hunks(unified_diff);
+                    let mut label = display;
+                    if let Some(move_path) = move_path {
+                        label.push_str(&format!(" → {}", display_path_for(move_path, cwd)));
+                    }
+                    parts.push(PatchPart {
+                        path: path.clone(),
+                        hunk: None,
+                        label: with_note(label, change),
+                        added: hunks.iter().map(|(_, added, _)| added).sum(),
+                        removed: hunks.iter().map(|(_, _, removed)| removed).sum(),
+                    });
+                    parts.extend(hunks.into_iter().enumerate().map(
+                        |(idx, (header, added, removed))| PatchPart {
+                            path: path.clone(),
+                            hunk: Some(idx),
+                            label: header,
+                            added,
+                            removed,
+                        },
+                    ));
+                }
+            }
+        }
+        let accepted = vec![true; parts.len()];
+        Self {
+            parts,
+            accepted,
+            cursor: 0,
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let last = self.parts.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    fn window_start(&self) -> usize {
+        let max_start = self.parts.len().saturating_sub(REVIEW_VISIBLE_ROWS);
+        self.cursor
+            .saturating_sub(REVIEW_VISIBLE_ROWS / 2)
+            .min(max_start)
+    }
+
+    /// Indices of the hunk rows belonging to the file row at `idx`.
+    fn hunk_rows(&self, idx: usize) -> std::ops::Range<usize> {
+        let end = self.parts[idx + 1..]
+            .iter()
+            .position(|part| part.hunk.is_none())
+            .map_or(self.parts.len(), |offset| idx + 1 + offset);
+        idx + 1..end
+    }
+
+    /// Whether the row at `idx` is accepted: `Some(true)` or `Some(false)`
+    /// when all of it is, `None` for a partly accepted file.
+    fn state(&self, idx: usize) -> Option<bool> {
+        let hunks = self.hunk_rows(idx);
+        if self.parts[idx].hunk.is_some() || hunks.is_empty() {
+            return Some(self.accepted[idx]);
+        }
+        let accepted = self.accepted[hunks.clone()].iter().filter(|a| **a).count();
+        match accepted {
+            0 => Some(false),
+            n if n == hunks.len() => Some(true),
+            _ => None,
+        }
+    }
+
+    fn toggle(&mut self) {
+        let idx = self.cursor;
+        if self.parts[idx].hunk.is_some() {
+            self.accepted[idx] = !self.accepted[idx];
+            return;
+        }
+        let value = self.state(idx) != Some(true);
+        self.accepted[idx] = value;
+        for row in self.hunk_rows(idx) {
+            self.accepted[row] = value;
+        }
+    }
+
+    fn toggle_all(&mut self) {
+        let value = !self.accepted.iter().all(|a| *a);
+        self.accepted.iter_mut().for_each(|a| *a = value);
+    }
+
+    fn nothing_accepted(&self) -> bool {
+        (0..self.parts.len())
+            .filter(|idx| self.parts[*idx].hunk.is_none())
+            .all(|idx| self.state(idx) == Some(false))
+    }
+
+    fn rejected_changes(&self) -> Vec<RejectedChange> {
+        let mut rejected = Vec::new();
+        for (idx, part) in self.parts.iter().enumerate() {
+            if part.hunk.is_some() {
+                continue;
+            }
+            match self.state(idx) {
+                Some(true) => {}
+                Some(false) => rejected.push(RejectedChange {
+                    path: part.path.clone(),
+                    hunks: Vec::new(),
+                }),
+                None => rejected.push(RejectedChange {
+                    path: part.path.clone(),
+                    hunks: self
+                        .hunk_rows(idx)
+                        .filter(|row| !self.accepted[*row])
+                        .filter_map(|row| self.parts[row].hunk)
+                        .collect(),
+                }),
+            }
+        }
+        rejected
+    }
+}
+
+/// The hunks of `unified_diff` as `(header, added, removed)`.
+fn split_hunks(unified_diff: &str) -> Vec<(String, usize, usize)> {
+    let mut hunks: Vec<(String, usize, usize)> = Vec::new();
+    for line in unified_diff.lines() {
+        if line.starts_with("@@") {
+            hunks.push((line.to_string(), 0, 0));
+        } else if let Some((_, added, removed)) = hunks.last_mut() {
+            match line.as_bytes().first() {
+                Some(b'+') => *added += 1,
+                Some(b'-') => *removed += 1,
+                _ => {}
+            }
+        }
+    }
+    hunks
+}
+
+/// Append what the change does besides editing text, e.g. `(mode 755)`.
+fn with_note(label: String, change: &FileChange) -> String {
+    match file_change_note(change) {
+        Some(note) => format!("{label} ({note})"),
+        None => label,
+    }
+}
+
+fn numbered_content(sign: char, content: &str) -> Vec<(char, usize, String)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, text)| (sign, i + 1, text.to_string()))
+        .collect()
+}
+
 /// A modal prompting the user to approve or deny the pending request.
 pub(crate) struct UserApprovalWidget {
     approval_request: ApprovalRequest,
@@ -106,6 +460,12 @@ pub(crate) struct UserApprovalWidget {
     /// Currently selected index in *select* mode.
     selected_option: usize,
 
+    /// Set while the user comments on the patch (*review* mode).
+    review: Option<PatchReview>,
+
+    /// Set while the user picks the parts of the patch to apply (*pick* mode).
+    selection: Option<PatchSelection>,
+
     /// Set to `true` once a decision has been sent – the parent view can then
     /// remove this widget from its queue.
     done: bool,
@@ -153,6 +513,8 @@ impl UserApprovalWidget {
             app_event_tx,
             confirmation_prompt,
             selected_option: 0,
+            review: None,
+            selection: None,
             done: false,
         }
     }
@@ -169,7 +531,13 @@ impl UserApprovalWidget {
     /// was consumed—callers can assume it always is.
     pub(crate) fn handle_key_event(&mut self, key: KeyEvent) {
         if key.kind == KeyEventKind::Press {
-            self.handle_select_key(key);
+            if self.review.is_some() {
+                self.handle_review_key(key);
+            } else if self.selection.is_some() {
+                self.handle_pick_key(key);
+            } else {
+                self.handle_select_key(key);
+            }
         }
     }
 
@@ -200,7 +568,7 @@ impl UserApprovalWidget {
             }
             KeyCode::Enter => {
                 let opt = &self.select_options[self.selected_option];
-                self.send_decision(opt.decision);
+                self.select(opt.action);
             }
             KeyCode::Esc => {
                 self.send_decision(ReviewDecision::Abort);
@@ -212,12 +580,178 @@ impl UserApprovalWidget {
                     .iter()
                     .find(|opt| Self::normalize_keycode(opt.key) == normalized)
                 {
-                    self.send_decision(opt.decision);
+                    self.select(opt.action);
                 }
             }
         }
     }
 
+    fn select(&mut self, action: SelectAction) {
+        match action {
+            SelectAction::Decide(decision) => self.send_decision(decision),
+            SelectAction::RequestChanges => {
+                if let ApprovalRequest::ApplyPatch { changes, cwd, .. } = &self.approval_request {
+                    self.review = Some(PatchReview::new(changes, cwd));
+                }
+            }
+            SelectAction::PickParts => {
+                if let ApprovalRequest::ApplyPatch { changes, cwd, .. } = &self.approval_request {
+                    self.selection = Some(PatchSelection::new(changes, cwd));
+                }
+            }
+        }
+    }
+
+    fn handle_pick_key(&mut self, key_event: KeyEvent) {
+        let Some(selection) = self.selection.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => selection.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => selection.move_cursor(1),
+            KeyCode::PageUp => selection.move_cursor(-(REVIEW_VISIBLE_ROWS as isize)),
+            KeyCode::PageDown => selection.move_cursor(REVIEW_VISIBLE_ROWS as isize),
+            KeyCode::Char(' ') => selection.toggle(),
+            KeyCode::Char('a') => selection.toggle_all(),
+            KeyCode::Enter => {
+                if selection.nothing_accepted() {
+                    self.send_decision(ReviewDecision::Denied);
+                } else {
+                    let rejected = selection.rejected_changes();
+                    self.send_partial_approval(rejected);
+                }
+            }
+            KeyCode::Esc => self.selection = None,
+            _ => {}
+        }
+    }
+
+    /// Approve the patch except for the `rejected` parts.
+    fn send_partial_approval(&mut self, rejected: Vec<RejectedChange>) {
+        let ApprovalRequest::ApplyPatch { id, cwd, .. } = &self.approval_request else {
+            return;
+        };
+        if !rejected.is_empty() {
+            let mut lines: Vec<Line<'static>> = vec![Line::from(vec![
+                "✔ ".fg(Color::Green),
+                "You ".into(),
+                "approved".bold(),
+                " the patch except:".into(),
+            ])];
+            for change in &rejected {
+                let mut text = display_path_for(&change.path, cwd);
+                if !change.hunks.is_empty() {
+                    let hunks: Vec<String> = change
+                        .hunks
+                        .iter()
+                        .map(|idx| (idx + 1).to_string())
+                        .collect();
+                    let noun = if hunks.len() == 1 { "hunk" } else { "hunks" };
+                    text.push_str(&format!(" ({noun} {})", hunks.join(", ")));
+                }
+                lines.push(Line::from(vec!["  └ ".dim(), text.into()]));
+            }
+            self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                history_cell::new_user_approval_decision(lines),
+            )));
+        }
+        self.app_event_tx.send(AppEvent::CodexOp(Op::PatchApproval {
+            id: id.clone(),
+            decision: ReviewDecision::Approved,
+            comments: Vec::new(),
+            rejected,
+        }));
+        self.done = true;
+    }
+
+    fn handle_review_key(&mut self, key_event: KeyEvent) {
+        let Some(review) = self.review.as_mut() else {
+            return;
+        };
+        if let Some(input) = review.input.as_mut() {
+            match key_event.code {
+                KeyCode::Enter => {
+                    let body = review.input.take().unwrap_or_default();
+                    if body.trim().is_empty() {
+                        review.comments.remove(&review.cursor);
+                    } else {
+                        review.comments.insert(review.cursor, body);
+                    }
+                }
+                KeyCode::Esc => review.input = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c)
+                    if !key_event
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                {
+                    input.push(c);
+                }
+                _ => {}
+            }
+            return;
+        }
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => review.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => review.move_cursor(1),
+            KeyCode::PageUp => review.move_cursor(-(REVIEW_VISIBLE_ROWS as isize)),
+            KeyCode::PageDown => review.move_cursor(REVIEW_VISIBLE_ROWS as isize),
+            KeyCode::Enter | KeyCode::Char('c') => {
+                let existing = review.comments.get(&review.cursor).cloned();
+                review.input = Some(existing.unwrap_or_default());
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                review.comments.remove(&review.cursor);
+            }
+            KeyCode::Char('s') if !review.comments.is_empty() => {
+                let comments = review.patch_comments();
+                self.send_review_comments(comments);
+            }
+            KeyCode::Esc => self.review = None,
+            _ => {}
+        }
+    }
+
+    /// Deny the patch and send `comments` back to the model.
+    fn send_review_comments(&mut self, comments: Vec<PatchComment>) {
+        let ApprovalRequest::ApplyPatch { id, cwd, .. } = &self.approval_request else {
+            return;
+        };
+        let noun = if comments.len() == 1 {
+            "comment"
+        } else {
+            "comments"
+        };
+        let mut lines: Vec<Line<'static>> = vec![Line::from(vec![
+            "✗ ".fg(Color::Red),
+            "You ".into(),
+            "requested changes".bold(),
+            format!(" to the proposed patch with {} {noun}", comments.len()).into(),
+        ])];
+        for comment in &comments {
+            let anchor = match comment.line {
+                Some(line) => format!("{}:{line}", display_path_for(&comment.path, cwd)),
+                None => display_path_for(&comment.path, cwd),
+            };
+            lines.push(Line::from(vec!["  └ ".dim(), anchor.dim()]));
+            for body in comment.body.lines() {
+                lines.push(Line::from(format!("    {body}")));
+            }
+        }
+        self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+            history_cell::new_user_approval_decision(lines),
+        )));
+        self.app_event_tx.send(AppEvent::CodexOp(Op::PatchApproval {
+            id: id.clone(),
+            decision: ReviewDecision::Denied,
+            comments,
+            rejected: Vec::new(),
+        }));
+        self.done = true;
+    }
+
     fn send_decision(&mut self, decision: ReviewDecision) {
         self.send_decision_with_feedback(decision, String::new())
     }
@@ -304,6 +838,8 @@ impl UserApprovalWidget {
             ApprovalRequest::ApplyPatch { id, .. } => Op::PatchApproval {
                 id: id.clone(),
                 decision,
+                comments: Vec::new(),
+                rejected: Vec::new(),
             },
         };
 
@@ -318,6 +854,14 @@ impl UserApprovalWidget {
     }
 
     pub(crate) fn desired_height(&self, width: u16) -> u16 {
+        if let Some(review) = &self.review {
+            // Title, patch rows, comment line and key hints.
+            return review.lines.len().min(REVIEW_VISIBLE_ROWS) as u16 + 3;
+        }
+        if let Some(selection) = &self.selection {
+            // Title, rows and key hints.
+            return selection.parts.len().min(REVIEW_VISIBLE_ROWS) as u16 + 2;
+        }
         // Reserve space for:
         // - 1 title line ("Allow command?" or "Apply changes?")
         // - 1 buttons line (options rendered horizontally on a single row)
@@ -326,8 +870,159 @@ impl UserApprovalWidget {
     }
 }
 
+impl UserApprovalWidget {
+    fn render_review(&self, review: &PatchReview, area: Rect, buf: &mut Buffer) {
+        let inner = area.inner(Margin::new(1, 0));
+        let mut lines: Vec<Line<'static>> = vec![Line::from(vec![
+            "Request changes".bold(),
+            format!(
+                "  {} comment{}",
+                review.comments.len(),
+                if review.comments.len() == 1 { "" } else { "s" }
+            )
+            .dim(),
+        ])];
+        let palette = theme::palette();
+        let start = review.window_start();
+        for (idx, row) in review
+            .lines
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(REVIEW_VISIBLE_ROWS)
+        {
+            let cursor = if idx == review.cursor {
+                "› ".cyan()
+            } else {
+                "  ".into()
+            };
+            let marker = if review.comments.contains_key(&idx) {
+                "● ".cyan()
+            } else {
+                "  ".into()
+            };
+            let content = match (row.sign, row.line) {
+                (Some(sign), Some(line)) => {
+                    let style = match sign {
+                        '+' => palette.added(),
+                        '-' => palette.removed(),
+                        _ => Style::default(),
+                    };
+                    vec![
+                        Span::styled(format!("{line:>5} "), palette.secondary()),
+                        Span::styled(format!("{sign}{}", row.text), style),
+                    ]
+                }
+                _ => vec![row.text.clone().bold()],
+            };
+            let mut spans = vec![cursor, marker];
+            spans.extend(content);
+            let mut line = Line::from(spans);
+            if idx == review.cursor {
+                line = line.reversed();
+            }
+            lines.push(line);
+        }
+        if let Some(input) = &review.input {
+            lines.push(Line::from(vec![
+                "comment: ".cyan(),
+                input.clone().into(),
+                "▏".dim(),
+            ]));
+        } else if let Some(comment) = review.comments.get(&review.cursor) {
+            lines.push(Line::from(vec!["↳ ".dim(), comment.clone().italic()]));
+        } else {
+            lines.push(Line::from(""));
+        }
+        let hints = if review.input.is_some() {
+            "⏎ save comment   esc cancel"
+        } else if review.comments.is_empty() {
+            "↑/↓ move   ⏎ comment   esc back"
+        } else {
+            "↑/↓ move   ⏎ edit comment   d delete   s send and deny patch   esc back"
+        };
+        lines.push(Line::from(hints).dim());
+        Paragraph::new(lines).render(inner, buf);
+
+        Block::bordered()
+            .border_type(BorderType::QuadrantOutside)
+            .border_style(Style::default().fg(Color::Cyan))
+            .borders(Borders::LEFT)
+            .render_ref(Rect::new(0, area.y, 1, area.height), buf);
+    }
+}
+
+impl UserApprovalWidget {
+    fn render_selection(&self, selection: &PatchSelection, area: Rect, buf: &mut Buffer) {
+        let inner = area.inner(Margin::new(1, 0));
+        let palette = theme::palette();
+        let mut lines: Vec<Line<'static>> = vec![Line::from(vec![
+            "Select files".bold(),
+            "  unchecked files and hunks are not applied".dim(),
+        ])];
+        let start = selection.window_start();
+        for (idx, part) in selection
+            .parts
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(REVIEW_VISIBLE_ROWS)
+        {
+            let cursor = if idx == selection.cursor {
+                "› ".cyan()
+            } else {
+                "  ".into()
+            };
+            let checkbox = match selection.state(idx) {
+                Some(true) => "[x] ",
+                Some(false) => "[ ] ",
+                None => "[~] ",
+            };
+            let indent = if part.hunk.is_some() { "    " } else { "" };
+            let label = if part.hunk.is_some() {
+                part.label.clone().dim()
+            } else {
+                part.label.clone().bold()
+            };
+            let mut line = Line::from(vec![
+                cursor,
+                indent.into(),
+                checkbox.into(),
+                label,
+                " ".into(),
+                Span::styled(format!("+{}", part.added), palette.added()),
+                " ".into(),
+                Span::styled(format!("-{}", part.removed), palette.removed()),
+            ]);
+            if idx == selection.cursor {
+                line = line.reversed();
+            }
+            lines.push(line);
+        }
+        lines.push(
+            Line::from("↑/↓ move   space toggle   a toggle all   ⏎ apply selected   esc back")
+                .dim(),
+        );
+        Paragraph::new(lines).render(inner, buf);
+
+        Block::bordered()
+            .border_type(BorderType::QuadrantOutside)
+            .border_style(Style::default().fg(Color::Cyan))
+            .borders(Borders::LEFT)
+            .render_ref(Rect::new(0, area.y, 1, area.height), buf);
+    }
+}
+
 impl WidgetRef for &UserApprovalWidget {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(review) = &self.review {
+            self.render_review(review, area, buf);
+            return;
+        }
+        if let Some(selection) = &self.selection {
+            self.render_selection(selection, area, buf);
+            return;
+        }
         let prompt_height = self.get_confirmation_prompt_height(area.width);
         let [prompt_chunk, response_chunk] = Layout::default()
             .direction(Direction::Vertical)
@@ -445,4 +1140,138 @@ mod tests {
             })
         )));
     }
+
+    #[test]
+    fn select_files_approves_only_picked_parts() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let mut changes = HashMap::new();
+        changes.insert(
+            PathBuf::from("/repo/a.rs"),
+            FileChange::Update {
+                unified_diff: "@@ -1,2 +1,2 @@\n x\n-y\n+Y\n@@ -9,2 +9,2 @@\n p\n-q\n+Q\n"
+                    .to_string(),
+                move_path: None,
+                mode: None,
+            },
+        );
+        changes.insert(
+            PathBuf::from("/repo/b.rs"),
+            FileChange::Add {
+                content: "new\n".to_string(),
+                mode: None,
+            },
+        );
+        let req = ApprovalRequest::ApplyPatch {
+            id: "4".to_string(),
+            reason: None,
+            grant_root: None,
+            changes,
+            cwd: PathBuf::from("/repo"),
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        let press = |widget: &mut UserApprovalWidget, code: KeyCode| {
+            widget.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+        };
+
+        press(&mut widget, KeyCode::Char('s'));
+        // Rows: a.rs, its two hunks, b.rs. Reject the second hunk and b.rs.
+        press(&mut widget, KeyCode::Down);
+        press(&mut widget, KeyCode::Down);
+        press(&mut widget, KeyCode::Char(' '));
+        press(&mut widget, KeyCode::Down);
+        press(&mut widget, KeyCode::Char(' '));
+        press(&mut widget, KeyCode::Enter);
+        assert!(widget.is_complete());
+
+        let mut rejected = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::PatchApproval {
+                decision: ReviewDecision::Approved,
+                rejected: sent,
+                ..
+            }) = ev
+            {
+                rejected = Some(sent);
+            }
+        }
+        assert_eq!(
+            rejected,
+            Some(vec![
+                RejectedChange {
+                    path: PathBuf::from("/repo/a.rs"),
+                    hunks: vec![1],
+                },
+                RejectedChange {
+                    path: PathBuf::from("/repo/b.rs"),
+                    hunks: Vec::new(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn request_changes_sends_anchored_comments() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let mut changes = HashMap::new();
+        changes.insert(
+            PathBuf::from("/repo/src/lib.rs"),
+            FileChange::Update {
+                unified_diff: "@@ -1,2 +1,2 @@\n fn one() {}\n-fn two() {}\n+fn deux() {}\n"
+                    .to_string(),
+                move_path: None,
+                mode: None,
+            },
+        );
+        let req = ApprovalRequest::ApplyPatch {
+            id: "3".to_string(),
+            reason: None,
+            grant_root: None,
+            changes,
+            cwd: PathBuf::from("/repo"),
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        let press = |widget: &mut UserApprovalWidget, code: KeyCode| {
+            widget.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+        };
+
+        press(&mut widget, KeyCode::Char('r'));
+        // Sending without comments does nothing.
+        press(&mut widget, KeyCode::Char('s'));
+        assert!(!widget.is_complete());
+
+        // Rows: file header, ` fn one`, `-fn two`, `+fn deux`.
+        press(&mut widget, KeyCode::Down);
+        press(&mut widget, KeyCode::Down);
+        press(&mut widget, KeyCode::Down);
+        press(&mut widget, KeyCode::Enter);
+        for c in "keep two".chars() {
+            press(&mut widget, KeyCode::Char(c));
+        }
+        press(&mut widget, KeyCode::Enter);
+        press(&mut widget, KeyCode::Char('s'));
+        assert!(widget.is_complete());
+
+        let mut comments = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::PatchApproval {
+                decision: ReviewDecision::Denied,
+                comments: sent,
+                ..
+            }) = ev
+            {
+                comments = Some(sent);
+            }
+        }
+        assert_eq!(
+            comments,
+            Some(vec![PatchComment {
+                path: PathBuf::from("/repo/src/lib.rs"),
+                line: Some(2),
+                snippet: Some("+fn deux() {}".to_string()),
+                body: "keep two".to_string(),
+            }])
+        );
+    }
 }